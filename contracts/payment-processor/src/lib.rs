@@ -0,0 +1,799 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short, vec,
+    Address, Env, Vec, String, token
+};
+
+pub use payment_core::{
+    BlockedPayment, ComplianceConfig, ComplianceProvider, Error, PaymentDetails, PaymentRecord,
+    SpendingLimits,
+};
+use payment_core::{
+    compliance_config, enforce_spending_limits, extend_persistent, find_blocked,
+    record_blocked_payment, screen_address, set_allowlisted, set_compliance_config,
+    set_denylisted, split_fee,
+};
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    PaymentCounter,
+    Payment(u64),
+    BusinessConfig(Address),
+    AuthorizedAddresses,
+    OrderPayment(Address, String),
+    Admin,
+    SpendingLimits,
+    BusinessLimits(Address),
+    LimitExempt(Address),
+    PayerSpend(Address),
+    BusinessSpend(Address),
+    BlockedPayment(Address, String),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BusinessConfig {
+    pub fee_rate: i128, // Fee as basis points (100 = 1%)
+    pub min_amount: i128,
+    pub max_amount: i128,
+    pub is_active: bool,
+}
+
+#[contract]
+pub struct PaymentContract;
+
+#[contractimpl]
+impl PaymentContract {
+    /// Initialize the contract with authorized addresses
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        authorized_addresses: Vec<Address>,
+    ) -> Result<(), Error> {
+        // Ensure the admin is authenticated
+        admin.require_auth();
+        
+        // Set authorized addresses for payment processing
+        env.storage().instance().set(&DataKey::AuthorizedAddresses, &authorized_addresses);
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        
+        // Initialize payment counter
+        env.storage().instance().set(&DataKey::PaymentCounter, &0u64);
+        
+        Ok(())
+    }
+
+    /// Configure business settings
+    pub fn configure_business(
+        env: Env,
+        business_address: Address,
+        fee_rate: i128,
+        min_amount: i128,
+        max_amount: i128,
+    ) -> Result<(), Error> {
+        business_address.require_auth();
+        
+        let config = BusinessConfig {
+            fee_rate,
+            min_amount,
+            max_amount,
+            is_active: true,
+        };
+        
+        env.storage().instance().set(&DataKey::BusinessConfig(business_address.clone()), &config);
+        
+        Ok(())
+    }
+
+    /// Process XLM payment. Returns 0 if screening blocked the payment.
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_xlm_payment(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        amount: i128,
+        business_name: String,
+        customer_name: String,
+        order_id: String,
+    ) -> Result<u64, Error> {
+        // Authenticate sender
+        sender.require_auth();
+        
+        // Validate authorized addresses
+        let authorized_addresses: Vec<Address> = env.storage().instance()
+            .get(&DataKey::AuthorizedAddresses)
+            .ok_or(Error::ContractNotInitialized)?;
+        
+        if !authorized_addresses.contains(&recipient) {
+            return Err(Error::NotAuthorized);
+        }
+        
+        // Validate business configuration
+        let business_config: BusinessConfig = env.storage().instance()
+            .get(&DataKey::BusinessConfig(recipient.clone()))
+            .ok_or(Error::BusinessNotFound)?;
+        
+        if !business_config.is_active {
+            return Err(Error::BusinessNotActive);
+        }
+        
+        if amount < business_config.min_amount || amount > business_config.max_amount {
+            return Err(Error::AmountOutOfRange);
+        }
+        
+        // Reject retried submissions for an order that was already paid
+        let order_key = DataKey::OrderPayment(recipient.clone(), order_id.clone());
+        if env.storage().persistent().has(&order_key) {
+            return Err(Error::DuplicateOrder);
+        }
+        
+        // Screen both sides of the payment before anything is written, as a
+        // blocked payment returns normally and would keep those writes
+        if !Self::screen_payment(&env, &sender, &recipient, &order_id) {
+            return Ok(0);
+        }
+        
+        Self::enforce_spending_limits(&env, &recipient, &sender, amount)?;
+        
+        // Transfer XLM (native asset)
+        // Note: In Soroban, native XLM transfers are handled differently
+        // This is a simplified representation
+        
+        // Create payment record
+        let payment_counter: u64 = env.storage().instance()
+            .get(&DataKey::PaymentCounter)
+            .unwrap_or(0);
+        
+        let payment_id = payment_counter + 1;
+        
+        let payment_details = PaymentDetails {
+            amount,
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            token_address: env.current_contract_address(),
+            business_name,
+            customer_name,
+            order_id,
+        };
+        
+        let payment_record = PaymentRecord {
+            payment_id,
+            details: payment_details,
+            timestamp: env.ledger().timestamp(),
+            status: symbol_short!("COMPLETE"),
+        };
+        
+        // Store payment record
+        Self::store_payment(&env, &payment_record, &order_key);
+        
+        // Emit event
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("xlm")),
+            (payment_id, sender, recipient, amount)
+        );
+        
+        Ok(payment_id)
+    }
+
+    /// Process token payment (for assets like USDC on Stellar). Returns 0 if
+    /// screening blocked the payment.
+    #[allow(clippy::too_many_arguments)]
+    pub fn process_token_payment(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token_address: Address,
+        amount: i128,
+        business_name: String,
+        customer_name: String,
+        order_id: String,
+    ) -> Result<u64, Error> {
+        // Authenticate sender
+        sender.require_auth();
+        
+        // Validate authorized addresses
+        let authorized_addresses: Vec<Address> = env.storage().instance()
+            .get(&DataKey::AuthorizedAddresses)
+            .ok_or(Error::ContractNotInitialized)?;
+        
+        if !authorized_addresses.contains(&recipient) {
+            return Err(Error::NotAuthorized);
+        }
+        
+        // Validate business configuration
+        let business_config: BusinessConfig = env.storage().instance()
+            .get(&DataKey::BusinessConfig(recipient.clone()))
+            .ok_or(Error::BusinessNotFound)?;
+        
+        if !business_config.is_active {
+            return Err(Error::BusinessNotActive);
+        }
+        
+        if amount < business_config.min_amount || amount > business_config.max_amount {
+            return Err(Error::AmountOutOfRange);
+        }
+        
+        // Reject retried submissions for an order that was already paid
+        let order_key = DataKey::OrderPayment(recipient.clone(), order_id.clone());
+        if env.storage().persistent().has(&order_key) {
+            return Err(Error::DuplicateOrder);
+        }
+        
+        // Screen both sides of the payment before anything is written, as a
+        // blocked payment returns normally and would keep those writes
+        if !Self::screen_payment(&env, &sender, &recipient, &order_id) {
+            return Ok(0);
+        }
+        
+        Self::enforce_spending_limits(&env, &recipient, &sender, amount)?;
+        
+        // Get token client
+        let token_client = token::Client::new(&env, &token_address);
+        
+        // Calculate fee
+        let (fee, net_amount) = split_fee(amount, business_config.fee_rate);
+        
+        // Transfer tokens
+        token_client.transfer(&sender, &recipient, &net_amount);
+        
+        // Transfer fee if applicable
+        if fee > 0 {
+            // Transfer fee to contract or fee collector
+            token_client.transfer(&sender, &env.current_contract_address(), &fee);
+        }
+        
+        // Create payment record
+        let payment_counter: u64 = env.storage().instance()
+            .get(&DataKey::PaymentCounter)
+            .unwrap_or(0);
+        
+        let payment_id = payment_counter + 1;
+        
+        let payment_details = PaymentDetails {
+            amount,
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            token_address: token_address.clone(),
+            business_name,
+            customer_name,
+            order_id,
+        };
+        
+        let payment_record = PaymentRecord {
+            payment_id,
+            details: payment_details,
+            timestamp: env.ledger().timestamp(),
+            status: symbol_short!("COMPLETE"),
+        };
+        
+        // Store payment record
+        Self::store_payment(&env, &payment_record, &order_key);
+        
+        // Emit event
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("token")),
+            (payment_id, sender, recipient, amount)
+        );
+
+        // Fees are collected by the contract itself
+        if fee > 0 {
+            env.events().publish(
+                (symbol_short!("payment"), symbol_short!("fee")),
+                (payment_id, env.current_contract_address(), token_address, fee)
+            );
+        }
+
+        Ok(payment_id)
+    }
+
+    /// Get payment details
+    pub fn get_payment(env: Env, payment_id: u64) -> Option<PaymentRecord> {
+        env.storage().persistent().get(&DataKey::Payment(payment_id))
+    }
+
+    /// Get the payment recorded for a business order
+    pub fn get_payment_by_order_id(
+        env: Env,
+        business_address: Address,
+        order_id: String,
+    ) -> Option<PaymentRecord> {
+        let payment_id: u64 = env.storage().persistent()
+            .get(&DataKey::OrderPayment(business_address, order_id))?;
+        
+        env.storage().persistent().get(&DataKey::Payment(payment_id))
+    }
+
+    /// Get business configuration
+    pub fn get_business_config(env: Env, business_address: Address) -> Option<BusinessConfig> {
+        env.storage().instance().get(&DataKey::BusinessConfig(business_address))
+    }
+
+    /// Get authorized addresses
+    pub fn get_authorized_addresses(env: Env) -> Option<Vec<Address>> {
+        env.storage().instance().get(&DataKey::AuthorizedAddresses)
+    }
+
+    /// Update business status
+    pub fn update_business_status(
+        env: Env,
+        business_address: Address,
+        is_active: bool,
+    ) -> Result<(), Error> {
+        business_address.require_auth();
+        
+        let mut config: BusinessConfig = env.storage().instance()
+            .get(&DataKey::BusinessConfig(business_address.clone()))
+            .ok_or(Error::BusinessNotFound)?;
+        
+        config.is_active = is_active;
+        
+        env.storage().instance().set(&DataKey::BusinessConfig(business_address), &config);
+        
+        Ok(())
+    }
+
+    /// Get payment counter
+    pub fn get_payment_counter(env: Env) -> u64 {
+        env.storage().instance().get(&DataKey::PaymentCounter).unwrap_or(0)
+    }
+
+    /// Set the default spending limits for every business (admin only)
+    pub fn set_spending_limits(env: Env, limits: SpendingLimits) -> Result<(), Error> {
+        let admin: Address = env.storage().instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::ContractNotInitialized)?;
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::SpendingLimits, &limits);
+        
+        Ok(())
+    }
+
+    /// Override the default spending limits for one business (admin only)
+    pub fn set_business_limits(
+        env: Env,
+        business_address: Address,
+        limits: SpendingLimits,
+    ) -> Result<(), Error> {
+        let admin: Address = env.storage().instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::ContractNotInitialized)?;
+        admin.require_auth();
+        
+        env.storage().instance().set(&DataKey::BusinessLimits(business_address), &limits);
+        
+        Ok(())
+    }
+
+    /// Exempt a sender from per-payment and per-payer limits (admin only)
+    pub fn set_limit_exemption(env: Env, sender: Address, exempt: bool) -> Result<(), Error> {
+        let admin: Address = env.storage().instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::ContractNotInitialized)?;
+        admin.require_auth();
+        
+        let key = DataKey::LimitExempt(sender);
+        if exempt {
+            env.storage().persistent().set(&key, &true);
+            extend_persistent(&env, &key);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        
+        Ok(())
+    }
+
+    /// Choose where compliance decisions come from (admin only).
+    /// Cached decisions are reused until the next ledger or config change.
+    pub fn set_compliance_config(env: Env, config: ComplianceConfig) -> Result<(), Error> {
+        let admin: Address = env.storage().instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::ContractNotInitialized)?;
+        admin.require_auth();
+        
+        set_compliance_config(&env, &config);
+        
+        Ok(())
+    }
+
+    /// Get the compliance configuration, if screening is configured
+    pub fn get_compliance_config(env: Env) -> Option<ComplianceConfig> {
+        compliance_config(&env)
+    }
+
+    /// Add or remove an address on the on-contract denylist (admin only)
+    pub fn set_denylisted(env: Env, address: Address, listed: bool) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        set_denylisted(&env, &address, listed);
+        
+        Ok(())
+    }
+
+    /// Add or remove an address on the on-contract allowlist (admin only)
+    pub fn set_allowlisted(env: Env, address: Address, listed: bool) -> Result<(), Error> {
+        Self::require_admin(&env)?;
+        set_allowlisted(&env, &address, listed);
+        
+        Ok(())
+    }
+
+    /// Screen an address
+    pub fn check_compliance(env: Env, address: Address) -> bool {
+        screen_address(&env, &address)
+    }
+
+    /// Get the last attempt to pay a business order that was refused by screening
+    pub fn get_blocked_payment(
+        env: Env,
+        business_address: Address,
+        order_id: String,
+    ) -> Option<BlockedPayment> {
+        env.storage().persistent().get(&DataKey::BlockedPayment(business_address, order_id))
+    }
+
+    /// Get the spending limits that apply to a business
+    pub fn get_spending_limits(env: Env, business_address: Address) -> SpendingLimits {
+        env.storage().instance()
+            .get(&DataKey::BusinessLimits(business_address))
+            .or_else(|| env.storage().instance().get(&DataKey::SpendingLimits))
+            .unwrap_or_default()
+    }
+}
+
+impl PaymentContract {
+    fn require_admin(env: &Env) -> Result<(), Error> {
+        let admin: Address = env.storage().instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::ContractNotInitialized)?;
+        admin.require_auth();
+        
+        Ok(())
+    }
+
+    // Screens both sides of a payment. A blocked payment is recorded under
+    // its business order and announced rather than failed.
+    fn screen_payment(env: &Env, sender: &Address, recipient: &Address, order_id: &String) -> bool {
+        let parties = vec![env, sender.clone(), recipient.clone()];
+        let Some(blocked_address) = find_blocked(env, &parties) else {
+            return true;
+        };
+        
+        let key = DataKey::BlockedPayment(recipient.clone(), order_id.clone());
+        record_blocked_payment(env, &key, (recipient.clone(), order_id.clone()), sender, &blocked_address);
+        false
+    }
+
+    // Payments and their order index grow without bound, so they live in
+    // persistent storage rather than being loaded with the instance
+    fn store_payment(env: &Env, payment_record: &PaymentRecord, order_key: &DataKey) {
+        let payment_key = DataKey::Payment(payment_record.payment_id);
+        env.storage().persistent().set(&payment_key, payment_record);
+        env.storage().persistent().set(order_key, &payment_record.payment_id);
+        extend_persistent(env, &payment_key);
+        extend_persistent(env, order_key);
+
+        env.storage().instance().set(&DataKey::PaymentCounter, &payment_record.payment_id);
+    }
+
+    fn enforce_spending_limits(
+        env: &Env,
+        business_address: &Address,
+        sender: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let limits = Self::get_spending_limits(env.clone(), business_address.clone());
+        let exempt_key = DataKey::LimitExempt(sender.clone());
+        let payer_exempt = env.storage().persistent().has(&exempt_key);
+        if payer_exempt {
+            extend_persistent(env, &exempt_key);
+        }
+        enforce_spending_limits(
+            env,
+            &limits,
+            amount,
+            &DataKey::PayerSpend(sender.clone()),
+            &DataKey::BusinessSpend(business_address.clone()),
+            payer_exempt,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{testutils::{storage::Persistent as _, Address as _, Events, Ledger}, token::StellarAssetClient, vec, Address, Env, IntoVal};
+
+    #[test]
+    fn test_initialize_contract() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+        
+        let admin = Address::generate(&env);
+        let authorized_addresses = vec![&env, Address::generate(&env), Address::generate(&env)];
+        
+        env.mock_all_auths();
+        client.initialize(&admin, &authorized_addresses);
+        
+        let retrieved_addresses = client.get_authorized_addresses();
+        assert_eq!(retrieved_addresses.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_payment_before_initialize() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+
+        let result = client.try_process_token_payment(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &1000,
+            &String::from_str(&env, "Test Store"),
+            &String::from_str(&env, "Alice"),
+            &String::from_str(&env, "ORDER-1"),
+        );
+        assert_eq!(result, Err(Ok(Error::ContractNotInitialized)));
+    }
+
+    #[test]
+    fn test_payment_validation_errors() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let business = Address::generate(&env);
+        let outsider = Address::generate(&env);
+        let sender = Address::generate(&env);
+        let token_address = Address::generate(&env);
+        client.initialize(&admin, &vec![&env, business.clone()]);
+
+        let business_name = String::from_str(&env, "Test Store");
+        let customer_name = String::from_str(&env, "Alice");
+        let order_id = String::from_str(&env, "ORDER-1");
+
+        // Recipient outside the authorized set
+        let result = client.try_process_token_payment(
+            &sender, &outsider, &token_address, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+        // Authorized recipient without a business configuration
+        let result = client.try_process_token_payment(
+            &sender, &business, &token_address, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(result, Err(Ok(Error::BusinessNotFound)));
+
+        client.configure_business(&business, &250, &100, &10_000);
+
+        // Amount outside the configured range
+        let result = client.try_process_token_payment(
+            &sender, &business, &token_address, &50, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(result, Err(Ok(Error::AmountOutOfRange)));
+
+        // Inactive business
+        client.update_business_status(&business, &false);
+        let result = client.try_process_token_payment(
+            &sender, &business, &token_address, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(result, Err(Ok(Error::BusinessNotActive)));
+    }
+
+    #[test]
+    fn test_update_unconfigured_business() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+
+        let result = client.try_update_business_status(&Address::generate(&env), &false);
+        assert_eq!(result, Err(Ok(Error::BusinessNotFound)));
+    }
+
+    #[test]
+    fn test_duplicate_order_rejected() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let business = Address::generate(&env);
+        let other_business = Address::generate(&env);
+        let sender = Address::generate(&env);
+        client.initialize(&admin, &vec![&env, business.clone(), other_business.clone()]);
+        client.configure_business(&business, &250, &100, &10_000);
+        client.configure_business(&other_business, &250, &100, &10_000);
+
+        let token_address = env.register_stellar_asset_contract_v2(admin).address();
+        StellarAssetClient::new(&env, &token_address).mint(&sender, &10_000);
+        let token_client = token::Client::new(&env, &token_address);
+
+        let business_name = String::from_str(&env, "Test Store");
+        let customer_name = String::from_str(&env, "Alice");
+        let order_id = String::from_str(&env, "ORDER-1");
+
+        let payment_id = client.process_token_payment(
+            &sender, &business, &token_address, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(token_client.balance(&sender), 9_000);
+        assert_eq!(token_client.balance(&contract_id), 25);
+        let event = env.events().all().last().unwrap();
+        assert_eq!(event.1, (symbol_short!("payment"), symbol_short!("fee")).into_val(&env));
+        let data: (u64, Address, Address, i128) = event.2.into_val(&env);
+        assert_eq!(data, (payment_id, contract_id.clone(), token_address.clone(), 25));
+
+        // A retried submission must not charge the customer again
+        let result = client.try_process_token_payment(
+            &sender, &business, &token_address, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(result, Err(Ok(Error::DuplicateOrder)));
+        let result = client.try_process_xlm_payment(
+            &sender, &business, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(result, Err(Ok(Error::DuplicateOrder)));
+        assert_eq!(token_client.balance(&sender), 9_000);
+        assert_eq!(client.get_payment_counter(), 1);
+
+        let record = client.get_payment_by_order_id(&business, &order_id).unwrap();
+        assert_eq!(record.payment_id, payment_id);
+        assert_eq!(record.details.order_id, order_id);
+        assert_eq!(client.get_payment_by_order_id(&business, &String::from_str(&env, "ORDER-2")), None);
+
+        // Records outlive the instance's TTL
+        env.as_contract(&contract_id, || {
+            let storage = env.storage().persistent();
+            assert_eq!(storage.get_ttl(&DataKey::Payment(payment_id)), payment_core::PERSISTENT_TTL);
+            assert_eq!(
+                storage.get_ttl(&DataKey::OrderPayment(business.clone(), order_id.clone())),
+                payment_core::PERSISTENT_TTL
+            );
+        });
+
+        // Order IDs are scoped to the receiving business
+        client.process_token_payment(
+            &sender, &other_business, &token_address, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(client.get_payment_counter(), 2);
+    }
+
+    #[test]
+    fn test_spending_limits() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+        env.ledger().set_timestamp(10_000);
+
+        let admin = Address::generate(&env);
+        let business = Address::generate(&env);
+        let sender = Address::generate(&env);
+        client.initialize(&admin, &vec![&env, business.clone()]);
+        client.configure_business(&business, &0, &1, &100_000);
+        client.set_spending_limits(&SpendingLimits {
+            max_payment_amount: Some(5_000),
+            payer_daily_limit: Some(8_000),
+            business_weekly_limit: Some(20_000),
+            ..Default::default()
+        });
+
+        let token_address = env.register_stellar_asset_contract_v2(admin).address();
+        StellarAssetClient::new(&env, &token_address).mint(&sender, &100_000);
+
+        let business_name = String::from_str(&env, "Test Store");
+        let customer_name = String::from_str(&env, "Alice");
+        let pay = |amount: i128, order: &str| {
+            client.try_process_token_payment(
+                &sender, &business, &token_address, &amount, &business_name, &customer_name,
+                &String::from_str(&env, order),
+            )
+        };
+
+        assert_eq!(pay(5_001, "ORDER-1"), Err(Ok(Error::PaymentLimitExceeded)));
+        assert!(pay(5_000, "ORDER-1").is_ok());
+        assert_eq!(pay(3_001, "ORDER-2"), Err(Ok(Error::PayerDailyLimitExceeded)));
+        assert!(pay(3_000, "ORDER-2").is_ok());
+
+        // The daily window rolls forward with ledger time
+        env.ledger().set_timestamp(10_000 + 86_400);
+        assert!(pay(5_000, "ORDER-3").is_ok());
+
+        // Exempt senders still count towards the business cap
+        client.set_limit_exemption(&sender, &true);
+        assert!(pay(6_000, "ORDER-4").is_ok());
+        assert_eq!(pay(1_001, "ORDER-5"), Err(Ok(Error::BusinessWeeklyLimitExceeded)));
+
+        client.set_business_limits(&business, &SpendingLimits::default());
+        assert!(pay(1_001, "ORDER-5").is_ok());
+        assert_eq!(client.get_spending_limits(&business), SpendingLimits::default());
+    }
+
+    #[test]
+    fn test_compliance_denylist() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let business = Address::generate(&env);
+        let sender = Address::generate(&env);
+        client.initialize(&admin, &vec![&env, business.clone()]);
+        client.configure_business(&business, &0, &1, &100_000);
+        client.set_compliance_config(&ComplianceConfig {
+            provider: ComplianceProvider::OnContract,
+            require_allowlist: false,
+        });
+        client.set_denylisted(&sender, &true);
+
+        let token_address = env.register_stellar_asset_contract_v2(admin).address();
+        StellarAssetClient::new(&env, &token_address).mint(&sender, &10_000);
+
+        let business_name = String::from_str(&env, "Test Store");
+        let customer_name = String::from_str(&env, "Alice");
+        let order_id = String::from_str(&env, "ORDER-1");
+
+        // Blocked payments return normally so the record and event are kept
+        let payment_id = client.process_token_payment(
+            &sender, &business, &token_address, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(payment_id, 0);
+        assert_eq!(token::Client::new(&env, &token_address).balance(&sender), 10_000);
+        let blocked = client.get_blocked_payment(&business, &order_id).unwrap();
+        assert_eq!(blocked.payer, sender);
+        assert_eq!(blocked.blocked_address, sender);
+        let event = env.events().all().last().unwrap();
+        assert_eq!(event.1, (symbol_short!("payment"), symbol_short!("blocked")).into_val(&env));
+
+        let payment_id = client.process_xlm_payment(
+            &sender, &business, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(payment_id, 0);
+        assert_eq!(client.get_payment_counter(), 0);
+        assert!(!client.check_compliance(&sender));
+
+        client.set_denylisted(&sender, &false);
+        assert!(client.check_compliance(&sender));
+        client.process_token_payment(
+            &sender, &business, &token_address, &1000, &business_name, &customer_name, &order_id,
+        );
+        assert_eq!(client.get_payment_counter(), 1);
+    }
+
+    #[test]
+    fn test_compliance_config_change_resets_cache() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let sender = Address::generate(&env);
+        client.initialize(&admin, &vec![&env]);
+        client.set_compliance_config(&ComplianceConfig {
+            provider: ComplianceProvider::OnContract,
+            require_allowlist: false,
+        });
+        assert!(client.check_compliance(&sender));
+
+        // Same ledger, but the decision cached above no longer applies
+        client.set_compliance_config(&ComplianceConfig {
+            provider: ComplianceProvider::OnContract,
+            require_allowlist: true,
+        });
+        assert!(!client.check_compliance(&sender));
+    }
+}
@@ -0,0 +1,100 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, log, symbol_short, Address, BytesN, Env,
+};
+
+pub use payment_core::{evm_payment_id, EvmPayment};
+
+/// Errors returned by the registry. The shared payment `Error` enum is at
+/// the 50 case limit of contract specs, so the registry has its own codes.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    ContractNotInitialized = 1,
+    InvalidAmount = 2,
+    /// The unified ID does not match the payment's origin
+    InvalidMirrorId = 3,
+    AlreadyMirrored = 4,
+    PaymentNotFound = 5,
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Relayer,
+    Payment(BytesN<32>),
+}
+
+#[contract]
+pub struct PaymentRegistry;
+
+#[contractimpl]
+impl PaymentRegistry {
+    /// Initialize the registry with the relayer allowed to record payments
+    pub fn initialize(env: Env, admin: Address, relayer: Address) -> Result<(), Error> {
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Relayer, &relayer);
+
+        log!(&env, "Registry initialized with relayer: {}", relayer);
+        Ok(())
+    }
+
+    /// Replace the relayer (admin only)
+    pub fn set_relayer(env: Env, relayer: Address) -> Result<(), Error> {
+        let admin: Address = env.storage().instance()
+            .get(&DataKey::Admin)
+            .ok_or(Error::ContractNotInitialized)?;
+        admin.require_auth();
+
+        env.storage().instance().set(&DataKey::Relayer, &relayer);
+        Ok(())
+    }
+
+    /// Record a payment made on an EVM chain. Each unified ID is recorded
+    /// once, so a relayer retrying a delivery cannot mirror a payment twice.
+    pub fn record_payment(env: Env, payment: EvmPayment) -> Result<(), Error> {
+        let relayer: Address = env.storage().instance()
+            .get(&DataKey::Relayer)
+            .ok_or(Error::ContractNotInitialized)?;
+        relayer.require_auth();
+
+        let id = evm_payment_id(&env, payment.chain_id, &payment.contract, &payment.payment_id);
+        if payment.id != id {
+            return Err(Error::InvalidMirrorId);
+        }
+        if payment.amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        let key = DataKey::Payment(id.clone());
+        if env.storage().persistent().has(&key) {
+            return Err(Error::AlreadyMirrored);
+        }
+
+        let payment = EvmPayment { recorded_at: env.ledger().timestamp(), ..payment };
+        env.storage().persistent().set(&key, &payment);
+
+        env.events().publish(
+            (symbol_short!("mirror"), symbol_short!("record")),
+            (id, payment.chain_id, payment.payment_id, payment.amount)
+        );
+
+        log!(&env, "Mirrored payment from chain {}", payment.chain_id);
+        Ok(())
+    }
+
+    /// Get a mirrored payment by its unified ID
+    pub fn get_payment(env: Env, id: BytesN<32>) -> Result<EvmPayment, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Payment(id))
+            .ok_or(Error::PaymentNotFound)
+    }
+
+    pub fn is_mirrored(env: Env, id: BytesN<32>) -> bool {
+        env.storage().persistent().has(&DataKey::Payment(id))
+    }
+}
@@ -0,0 +1,78 @@
+use payment_registry::{evm_payment_id, Error, EvmPayment, PaymentRegistry, PaymentRegistryClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, String, U256,
+};
+
+fn payment(env: &Env, payment_id: u32) -> EvmPayment {
+    let contract = BytesN::from_array(env, &[0xaa; 20]);
+    let payment_id = U256::from_u32(env, payment_id);
+    EvmPayment {
+        id: evm_payment_id(env, 43_114, &contract, &payment_id),
+        chain_id: 43_114,
+        contract,
+        payment_id,
+        sender: BytesN::from_array(env, &[1; 20]),
+        recipient: BytesN::from_array(env, &[2; 20]),
+        amount: 25_000_000,
+        order_id: String::from_str(env, "ORDER-1"),
+        transaction_hash: BytesN::from_array(env, &[3; 32]),
+        recorded_at: 0,
+    }
+}
+
+#[test]
+fn test_payment_is_mirrored_once() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentRegistry);
+    let client = PaymentRegistryClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(10_000);
+    client.initialize(&Address::generate(&env), &Address::generate(&env));
+    let payment = payment(&env, 1);
+
+    assert!(!client.is_mirrored(&payment.id));
+    client.record_payment(&payment);
+    assert!(client.is_mirrored(&payment.id));
+    assert_eq!(client.get_payment(&payment.id), EvmPayment { recorded_at: 10_000, ..payment.clone() });
+
+    assert_eq!(client.try_record_payment(&payment), Err(Ok(Error::AlreadyMirrored)));
+}
+
+#[test]
+fn test_unified_ids_are_distinct() {
+    let env = Env::default();
+    let first = payment(&env, 1);
+    let second = payment(&env, 2);
+    assert_ne!(first.id, second.id);
+
+    let other_chain = evm_payment_id(&env, 1, &first.contract, &first.payment_id);
+    assert_ne!(other_chain, first.id);
+}
+
+#[test]
+fn test_record_payment_errors() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentRegistry);
+    let client = PaymentRegistryClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.ledger().set_timestamp(10_000);
+    client.initialize(&Address::generate(&env), &Address::generate(&env));
+
+    let forged = EvmPayment { id: payment(&env, 2).id, ..payment(&env, 1) };
+    assert_eq!(client.try_record_payment(&forged), Err(Ok(Error::InvalidMirrorId)));
+
+    let empty = EvmPayment { amount: 0, ..payment(&env, 1) };
+    assert_eq!(client.try_record_payment(&empty), Err(Ok(Error::InvalidAmount)));
+
+    let unknown = BytesN::from_array(&env, &[9; 32]);
+    assert_eq!(client.try_get_payment(&unknown), Err(Ok(Error::PaymentNotFound)));
+
+    let uninitialized = PaymentRegistryClient::new(&env, &env.register_contract(None, PaymentRegistry));
+    assert_eq!(
+        uninitialized.try_record_payment(&payment(&env, 1)),
+        Err(Ok(Error::ContractNotInitialized))
+    );
+}
@@ -0,0 +1,99 @@
+use soroban_sdk::{contractimpl, log, vec, Address, Env};
+
+use crate::{BusinessConfig, DataKey, PaymentContract, PaymentContractClient};
+use payment_core::{
+    compliance_config, find_blocked, record_blocked_payment, screen_address, set_allowlisted,
+    set_compliance_config, set_denylisted, BlockedPayment, ComplianceConfig, Error, PaymentRequest,
+};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Choose where compliance decisions come from (contract owner only).
+    /// Cached decisions are reused until the next ledger or config change.
+    pub fn set_compliance_config(env: Env, caller: Address, config: ComplianceConfig) -> Result<(), Error> {
+        caller.require_auth();
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        set_compliance_config(&env, &config);
+        Ok(())
+    }
+
+    /// Get the compliance configuration, if screening is configured
+    pub fn get_compliance_config(env: Env) -> Option<ComplianceConfig> {
+        compliance_config(&env)
+    }
+
+    /// Add or remove an address on the on-contract denylist (contract owner only)
+    pub fn set_denylisted(env: Env, caller: Address, address: Address, listed: bool) -> Result<(), Error> {
+        Self::require_contract_owner(&env, &caller)?;
+        set_denylisted(&env, &address, listed);
+        Ok(())
+    }
+
+    /// Add or remove an address on the on-contract allowlist (contract owner only)
+    pub fn set_allowlisted(env: Env, caller: Address, address: Address, listed: bool) -> Result<(), Error> {
+        Self::require_contract_owner(&env, &caller)?;
+        set_allowlisted(&env, &address, listed);
+        Ok(())
+    }
+
+    /// Screen an address
+    pub fn check_compliance(env: Env, address: Address) -> bool {
+        screen_address(&env, &address)
+    }
+
+    /// Get the last attempt to pay a request that was refused by screening
+    pub fn get_blocked_payment(env: Env, payment_id: u64) -> Option<BlockedPayment> {
+        env.storage().persistent().get(&DataKey::BlockedPayment(payment_id))
+    }
+}
+
+impl PaymentContract {
+    fn require_contract_owner(env: &Env, caller: &Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if *caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+        Ok(())
+    }
+
+    // Screens everyone a payment moves funds between. A blocked payment is
+    // recorded and announced rather than failed.
+    pub(crate) fn screen_payment(
+        env: &Env,
+        payment_request: &PaymentRequest,
+        payer: &Address,
+        business_config: &BusinessConfig,
+    ) -> bool {
+        let mut parties = vec![env, payer.clone(), payment_request.requester.clone()];
+        for share in payment_request.splits.iter() {
+            parties.push_back(share.recipient);
+        }
+        if payment_request.fee_percentage > 0 {
+            parties.push_back(business_config.fee_recipient.clone());
+        }
+
+        let Some(blocked_address) = find_blocked(env, &parties) else {
+            return true;
+        };
+
+        let key = DataKey::BlockedPayment(payment_request.id);
+        record_blocked_payment(env, &key, payment_request.id, payer, &blocked_address);
+        log!(env, "Payment {} blocked by compliance screening", payment_request.id);
+        false
+    }
+}
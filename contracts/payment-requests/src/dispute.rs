@@ -0,0 +1,297 @@
+use soroban_sdk::{contractimpl, symbol_short, Address, BytesN, Env, log};
+
+use crate::{DataKey, PaymentContract, PaymentContractClient};
+use payment_core::{
+    screen_address, Dispute, DisputeConfig, DisputeOutcome, DisputeStatus, Error, PaymentRequest, PaymentStatus,
+};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Set the arbiter, deadlines and hold period for disputes (contract owner only)
+    pub fn set_dispute_config(env: Env, caller: Address, config: DisputeConfig) -> Result<(), Error> {
+        caller.require_auth();
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        if config.response_period == 0 || config.ruling_period == 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().instance().set(&DataKey::DisputeConfig, &config);
+        Ok(())
+    }
+
+    /// Get the dispute configuration, if disputes are enabled
+    pub fn get_dispute_config(env: Env) -> Option<DisputeConfig> {
+        env.storage().instance().get(&DataKey::DisputeConfig)
+    }
+
+    /// Challenge a paid request. The payer's unrefunded contribution is
+    /// disputed and the merchant must respond before the response deadline.
+    /// Proceeds the contract holds for the request are escrowed up to the
+    /// disputed amount, so they cannot be paid out while the dispute is open.
+    /// Each payer of a request may open their own dispute.
+    pub fn open_dispute(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+
+        let config = Self::dispute_config(&env)?;
+
+        let payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        match payment_request.status {
+            PaymentStatus::Completed | PaymentStatus::PartiallyPaid => {},
+            _ => return Err(Error::InvalidPaymentStatus),
+        }
+
+        let dispute_key = DataKey::Dispute(payment_id, payer.clone());
+        if env.storage().persistent().has(&dispute_key) {
+            return Err(Error::DisputeAlreadyExists);
+        }
+
+        let disputed_amount = Self::refundable_amount(&env, payment_id, &payer);
+        if disputed_amount <= 0 {
+            return Err(Error::NotAuthorized);
+        }
+
+        let escrowed_amount = Self::take_held_proceeds(&env, &payment_request, disputed_amount);
+
+        let now = env.ledger().timestamp();
+        let dispute = Dispute {
+            payment_id,
+            payer: payer.clone(),
+            disputed_amount,
+            payer_evidence: evidence_hash,
+            merchant_evidence: BytesN::from_array(&env, &[0; 32]),
+            opened_at: now,
+            response_deadline: now + config.response_period,
+            ruling_deadline: 0,
+            status: DisputeStatus::Open,
+            refund_amount: 0,
+            refund_paid: 0,
+            escrowed_amount,
+        };
+        env.storage().persistent().set(&dispute_key, &dispute);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("open")),
+            (payment_id, payer, disputed_amount)
+        );
+
+        log!(&env, "Dispute opened on payment {}", payment_id);
+        Ok(())
+    }
+
+    /// Answer a payer's dispute with the merchant's evidence (requester only)
+    pub fn respond_to_dispute(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+        evidence_hash: BytesN<32>,
+    ) -> Result<(), Error> {
+        let config = Self::dispute_config(&env)?;
+        let mut dispute = Self::get_dispute(env.clone(), payment_id, payer.clone())?;
+
+        let payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+        payment_request.requester.require_auth();
+
+        if dispute.status != DisputeStatus::Open {
+            return Err(Error::InvalidDisputeStatus);
+        }
+
+        let now = env.ledger().timestamp();
+        if now > dispute.response_deadline {
+            return Err(Error::DeadlineExpired);
+        }
+
+        dispute.merchant_evidence = evidence_hash;
+        dispute.ruling_deadline = now + config.ruling_period;
+        dispute.status = DisputeStatus::Responded;
+        env.storage().persistent().set(&DataKey::Dispute(payment_id, payer.clone()), &dispute);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("respond")),
+            (payment_id, payer)
+        );
+        Ok(())
+    }
+
+    /// Rule on a dispute (arbiter only). The arbiter may rule before the
+    /// merchant responds, but not once a deadline has passed.
+    pub fn resolve_dispute(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+        outcome: DisputeOutcome,
+    ) -> Result<(), Error> {
+        let config = Self::dispute_config(&env)?;
+        config.arbiter.require_auth();
+
+        let dispute = Self::get_dispute(env.clone(), payment_id, payer)?;
+
+        let now = env.ledger().timestamp();
+        let deadline = match dispute.status {
+            DisputeStatus::Open => dispute.response_deadline,
+            DisputeStatus::Responded => dispute.ruling_deadline,
+            DisputeStatus::Resolved(_) => return Err(Error::InvalidDisputeStatus),
+        };
+        if now > deadline {
+            return Err(Error::DeadlineExpired);
+        }
+
+        if let DisputeOutcome::PartialRefund(amount) = outcome {
+            if amount <= 0 || amount > dispute.disputed_amount {
+                return Err(Error::InvalidAmount);
+            }
+        }
+
+        Self::close_dispute(&env, dispute, outcome)
+    }
+
+    /// Apply the default outcome once a deadline was missed. A merchant who
+    /// never responds loses the dispute; if the arbiter never rules, the
+    /// payment stands. Anyone may call this.
+    pub fn finalize_dispute(env: Env, payment_id: u64, payer: Address) -> Result<(), Error> {
+        let dispute = Self::get_dispute(env.clone(), payment_id, payer)?;
+
+        let now = env.ledger().timestamp();
+        let outcome = match dispute.status {
+            DisputeStatus::Open if now > dispute.response_deadline => DisputeOutcome::Refund,
+            DisputeStatus::Responded if now > dispute.ruling_deadline => DisputeOutcome::Rejected,
+            DisputeStatus::Resolved(_) => return Err(Error::InvalidDisputeStatus),
+            _ => return Err(Error::DisputeDeadlineNotReached),
+        };
+
+        Self::close_dispute(&env, dispute, outcome)
+    }
+
+    /// Pay what is still owed on a resolved dispute (payer, requester or
+    /// arbiter). Proceeds the contract holds for the request are used first;
+    /// the requester's wallet covers the rest only when the requester calls.
+    pub fn pay_dispute_refund(env: Env, payment_id: u64, payer: Address, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let config = Self::dispute_config(&env)?;
+        let mut dispute = Self::get_dispute(env.clone(), payment_id, payer.clone())?;
+
+        let payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        let requester_authorized = caller == payment_request.requester;
+        if caller != payer && caller != config.arbiter && !requester_authorized {
+            return Err(Error::NotAuthorized);
+        }
+
+        let outstanding = dispute.refund_amount - dispute.refund_paid;
+        if !matches!(dispute.status, DisputeStatus::Resolved(_)) || outstanding <= 0 {
+            return Err(Error::InvalidDisputeStatus);
+        }
+
+        Self::return_held_proceeds(&env, &payment_request, dispute.escrowed_amount);
+        dispute.escrowed_amount = 0;
+
+        let from_held = outstanding.min(Self::held_proceeds(&env, &payment_request));
+        if from_held > 0 {
+            Self::pay_refund(&env, payment_id, &payer, from_held, false)?;
+            dispute.refund_paid += from_held;
+        }
+
+        let rest = outstanding - from_held;
+        if rest > 0 && requester_authorized {
+            Self::pay_refund(&env, payment_id, &payer, rest, true)?;
+            dispute.refund_paid += rest;
+        } else if from_held == 0 {
+            return Err(Error::InsufficientBalance);
+        }
+
+        env.storage().persistent().set(&DataKey::Dispute(payment_id, payer), &dispute);
+        Ok(())
+    }
+
+    /// Get the dispute a payer raised against a payment
+    pub fn get_dispute(env: Env, payment_id: u64, payer: Address) -> Result<Dispute, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Dispute(payment_id, payer))
+            .ok_or(Error::DisputeNotFound)
+    }
+}
+
+impl PaymentContract {
+    fn dispute_config(env: &Env) -> Result<DisputeConfig, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::DisputeConfig)
+            .ok_or(Error::DisputeNotConfigured)
+    }
+
+    // Seconds a request's proceeds stay in the contract after a payment
+    pub(crate) fn dispute_hold_period(env: &Env) -> u64 {
+        env.storage()
+            .instance()
+            .get::<_, DisputeConfig>(&DataKey::DisputeConfig)
+            .map_or(0, |config| config.hold_period)
+    }
+
+    // Records the outcome and refunds immediately from the request's held
+    // proceeds, the escrow included, as far as they go; the rest stays owed
+    // until `pay_dispute_refund`
+    fn close_dispute(env: &Env, mut dispute: Dispute, outcome: DisputeOutcome) -> Result<(), Error> {
+        let payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(dispute.payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        // Voluntary refunds made while the dispute was open count towards it
+        let refund_amount = dispute
+            .refund_for(&outcome)
+            .min(Self::refundable_amount(env, dispute.payment_id, &dispute.payer));
+
+        let escrowed = dispute.escrowed_amount;
+        Self::return_held_proceeds(env, &payment_request, escrowed);
+        dispute.escrowed_amount = 0;
+        let held = Self::held_proceeds(env, &payment_request);
+
+        if refund_amount > 0 && screen_address(env, &dispute.payer) {
+            let payable = refund_amount.min(held);
+            if payable > 0 && Self::pay_refund(env, dispute.payment_id, &dispute.payer, payable, false)? {
+                dispute.refund_paid = payable;
+            }
+        } else if refund_amount > 0 {
+            // A payer blocked by compliance screening is owed the refund
+            // until cleared, and the escrow stays with it
+            dispute.escrowed_amount = Self::take_held_proceeds(env, &payment_request, refund_amount.min(escrowed));
+        }
+
+        dispute.refund_amount = refund_amount;
+        dispute.status = DisputeStatus::Resolved(outcome);
+        env.storage().persistent().set(&DataKey::Dispute(dispute.payment_id, dispute.payer.clone()), &dispute);
+
+        env.events().publish(
+            (symbol_short!("dispute"), symbol_short!("resolved")),
+            (dispute.payment_id, dispute.payer.clone(), refund_amount)
+        );
+
+        log!(env, "Dispute on payment {} resolved, refund {}", dispute.payment_id, refund_amount);
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+use soroban_sdk::{contractimpl, symbol_short, token::Client as TokenClient, Address, BytesN, Env, log};
+
+use crate::{DataKey, PaymentAmount, PaymentContract, PaymentContractClient};
+use payment_core::{intent_message, Error, PaymentIntent, PaymentRequest, RequestKind};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Register the ed25519 key whose signatures authorize the payer's intents.
+    /// The payer must also approve this contract to spend the intent's token.
+    pub fn set_intent_key(env: Env, payer: Address, public_key: BytesN<32>) {
+        payer.require_auth();
+        env.storage().persistent().set(&DataKey::IntentKey(payer), &public_key);
+    }
+
+    /// Get the key registered to sign a payer's intents
+    pub fn get_intent_key(env: Env, payer: Address) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::IntentKey(payer))
+    }
+
+    /// Get the nonce the payer's next intent must carry
+    pub fn get_intent_nonce(env: Env, payer: Address) -> u64 {
+        env.storage().persistent().get(&DataKey::IntentNonce(payer)).unwrap_or(0)
+    }
+
+    /// Execute a payment the payer signed off-chain. Any relayer may submit
+    /// it; funds are drawn from the payer's allowance to this contract. An
+    /// invalid signature aborts the call.
+    ///
+    /// The signed amount is paid as an installment, or as the chosen amount of
+    /// an open-amount request. For a tippable request it covers the request
+    /// and the tip. For a fiat priced request it is the most the payer pays:
+    /// the request is quoted now and only the quoted amount is drawn.
+    pub fn execute_payment_with_signature(
+        env: Env,
+        intent: PaymentIntent,
+        signature: BytesN<64>,
+    ) -> Result<(), Error> {
+        let public_key: BytesN<32> = env.storage()
+            .persistent()
+            .get(&DataKey::IntentKey(intent.payer.clone()))
+            .ok_or(Error::IntentKeyNotSet)?;
+
+        if env.ledger().timestamp() > intent.expiry {
+            return Err(Error::DeadlineExpired);
+        }
+
+        // Intents are single-use and consumed in order
+        let nonce_key = DataKey::IntentNonce(intent.payer.clone());
+        let nonce: u64 = env.storage().persistent().get(&nonce_key).unwrap_or(0);
+        if intent.nonce != nonce {
+            return Err(Error::InvalidNonce);
+        }
+
+        let contract_address = env.current_contract_address();
+        env.crypto().ed25519_verify(
+            &public_key,
+            &intent_message(&env, &contract_address, &intent),
+            &signature,
+        );
+        env.storage().persistent().set(&nonce_key, &(nonce + 1));
+
+        if intent.amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let payment_request: PaymentRequest = Self::get_payment_request(env.clone(), intent.payment_id)?;
+        let (amount, draw_amount) = match payment_request.kind {
+            RequestKind::OpenAmount(_) => (PaymentAmount::OpenAmount(intent.amount), intent.amount),
+            RequestKind::Tippable => (PaymentAmount::Tip(intent.amount - payment_request.amount), intent.amount),
+            RequestKind::FiatPriced => {
+                let quote = Self::quote_fiat_amount(&env, payment_request.amount, &intent.token_address)?;
+                if quote.token_amount > intent.amount {
+                    return Err(Error::SlippageExceeded);
+                }
+                (PaymentAmount::Full, quote.token_amount)
+            }
+            _ => (PaymentAmount::Installment(intent.amount), intent.amount),
+        };
+
+        // A blocked intent is spent without moving funds
+        let business_config = Self::get_business_config(env.clone(), payment_request.business_name.clone())?;
+        if !Self::screen_payment(&env, &payment_request, &intent.payer, &business_config) {
+            return Ok(());
+        }
+
+        // Pull the amount due into the contract, then pay from there
+        let token_client = TokenClient::new(&env, &intent.token_address);
+        if token_client.allowance(&intent.payer, &contract_address) < draw_amount {
+            return Err(Error::InsufficientAllowance);
+        }
+        if token_client.balance(&intent.payer) < draw_amount {
+            return Err(Error::InsufficientBalance);
+        }
+        token_client.transfer_from(&contract_address, &intent.payer, &contract_address, &draw_amount);
+
+        // Screening passed above, so this only fails if the decision changed
+        if !Self::settle_payment(
+            &env,
+            intent.payment_id,
+            &intent.payer,
+            &contract_address,
+            &intent.token_address,
+            amount,
+        )? {
+            return Err(Error::AddressBlocked);
+        }
+
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("intent")),
+            (intent.payment_id, intent.payer, intent.nonce)
+        );
+
+        log!(&env, "Signed payment {} executed", intent.payment_id);
+        Ok(())
+    }
+}
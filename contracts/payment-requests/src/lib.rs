@@ -0,0 +1,883 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contractimpl, contracttype, contractmeta, symbol_short,
+    Address, Env, Map, String, Vec, log,
+    token::Client as TokenClient,
+};
+
+pub use payment_core::{
+    intent_message, AmountBounds, Asset, BlockedPayment, ComplianceConfig, ComplianceProvider,
+    Dispute, DisputeConfig, DisputeOutcome, DisputeStatus, Error, FeeBasis, FiatQuote, Invoice,
+    InvoiceTotals, LineItem, OracleConfig, PaymentHistory, PaymentIntent, PaymentRequest,
+    PaymentStatus, PriceData, RampKind, RampRecord, RampStatus, RequestKind, SettlementConfig,
+    SettlementRecord, SpendingLimits, SplitShare, Stream, StreamStatus,
+};
+use payment_core::{
+    allocate_splits, invoice_totals, is_valid_basis_points, split_fee, validate_splits,
+};
+
+mod compliance;
+mod dispute;
+mod intent;
+mod limits;
+mod oracle;
+mod ramp;
+mod refund;
+mod settlement;
+mod stream;
+mod swap;
+
+// Contract metadata
+contractmeta!(
+    key = "Description",
+    val = "Multi-Chain Payment Platform - Stellar XLM Payment Contract"
+);
+
+#[derive(Clone)]
+#[contracttype]
+pub enum DataKey {
+    PaymentRequest(u64),
+    BusinessConfig(String),
+    PaymentHistory(Address),
+    AuthorizedAddresses,
+    ContractOwner,
+    FeeConfig,
+    NativeToken,
+    PaymentCounter,
+    Invoice(u64),
+    MerchantReference(String, String),
+    Contributions(u64),
+    PaymentToken(u64),
+    StreamCounter,
+    Stream(u64),
+    StreamHeld(u64),
+    SwapAdapter,
+    OracleConfig,
+    FiatQuote(u64),
+    SettlementConfig(String),
+    MerchantBalance(String, Address),
+    HeldProceeds(u64),
+    HeldUntil(u64),
+    UnsettledPayments(String, Address),
+    LastSettlement(String, Address),
+    SettlementCounter,
+    Settlement(u64),
+    Refunds(u64),
+    DisputeConfig,
+    Dispute(u64, Address),
+    SpendingLimits,
+    BusinessLimits(String),
+    LimitExempt(Address),
+    PayerSpend(Address),
+    BusinessSpend(String),
+    BlockedPayment(u64),
+    IntentKey(Address),
+    IntentNonce(Address),
+    Ramps(u64),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BusinessConfig {
+    pub name: String,
+    pub owner: Address,
+    pub fee_recipient: Address,
+    pub default_fee_percentage: u32,
+    pub is_active: bool,
+    pub fee_basis: FeeBasis,
+}
+
+// How a call to `settle_payment` wants to pay a request
+enum PaymentAmount {
+    Full,
+    Installment(i128),
+    OpenAmount(i128),
+    Tip(i128),
+}
+
+#[contract]
+pub struct PaymentContract;
+
+#[contractimpl]
+impl PaymentContract {
+    /// Initialize the contract with owner, default configurations and the
+    /// native XLM asset contract used by `execute_xlm_payment`
+    pub fn initialize(
+        env: Env,
+        owner: Address,
+        default_fee_percentage: u32,
+        native_token: Address,
+    ) -> Result<(), Error> {
+        if !is_valid_basis_points(default_fee_percentage as i128) {
+            return Err(Error::InvalidFeePercentage);
+        }
+
+        owner.require_auth();
+
+        // Set contract owner
+        env.storage().instance().set(&DataKey::ContractOwner, &owner);
+        
+        // Set default fee configuration
+        env.storage().instance().set(&DataKey::FeeConfig, &default_fee_percentage);
+
+        // Set native asset contract
+        env.storage().instance().set(&DataKey::NativeToken, &native_token);
+
+        log!(&env, "Contract initialized with owner: {}", owner);
+        Ok(())
+    }
+
+    /// Register a new business for payment processing
+    pub fn register_business(
+        env: Env,
+        business_name: String,
+        business_owner: Address,
+        fee_recipient: Address,
+        fee_percentage: u32,
+    ) -> Result<(), Error> {
+        business_owner.require_auth();
+
+        if !is_valid_basis_points(fee_percentage as i128) {
+            return Err(Error::InvalidFeePercentage);
+        }
+
+        let business_config = BusinessConfig {
+            name: business_name.clone(),
+            owner: business_owner,
+            fee_recipient,
+            default_fee_percentage: fee_percentage,
+            is_active: true,
+            fee_basis: FeeBasis::Total,
+        };
+
+        env.storage().persistent().set(&DataKey::BusinessConfig(business_name.clone()), &business_config);
+
+        env.events().publish(
+            (symbol_short!("business"), symbol_short!("register")),
+            (business_name, business_config.owner, business_config.fee_recipient, fee_percentage)
+        );
+        Ok(())
+    }
+
+    /// Create a new payment request
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_payment_request(
+        env: Env,
+        amount: i128,
+        business_name: String,
+        description: String,
+        denomination: String,
+        authorized_addresses: Vec<Address>,
+        requester: Address,
+        custom_fee_percentage: Option<u32>,
+    ) -> Result<u64, Error> {
+        requester.require_auth();
+
+        let payment_request = Self::new_payment_request(
+            &env,
+            RequestKind::Fixed,
+            amount,
+            business_name,
+            description,
+            denomination,
+            authorized_addresses,
+            requester,
+            custom_fee_percentage,
+        )?;
+        let payment_id = payment_request.id;
+
+        env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+
+        log!(&env, "Payment request created with ID: {}", payment_id);
+        Ok(payment_id)
+    }
+
+    /// Create a request where the payer chooses the amount within optional bounds
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_open_payment_request(
+        env: Env,
+        business_name: String,
+        description: String,
+        denomination: String,
+        authorized_addresses: Vec<Address>,
+        requester: Address,
+        custom_fee_percentage: Option<u32>,
+        bounds: AmountBounds,
+    ) -> Result<u64, Error> {
+        requester.require_auth();
+
+        if bounds.min_amount.is_some_and(|min| min <= 0)
+            || bounds.max_amount.is_some_and(|max| max <= 0)
+        {
+            return Err(Error::InvalidAmount);
+        }
+
+        if let (Some(min), Some(max)) = (bounds.min_amount, bounds.max_amount) {
+            if min > max {
+                return Err(Error::InvalidAmount);
+            }
+        }
+
+        // The amount is only known once the payer picks it
+        let payment_request = Self::new_payment_request(
+            &env,
+            RequestKind::OpenAmount(bounds),
+            0,
+            business_name,
+            description,
+            denomination,
+            authorized_addresses,
+            requester,
+            custom_fee_percentage,
+        )?;
+        let payment_id = payment_request.id;
+
+        env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+
+        log!(&env, "Open payment request created with ID: {}", payment_id);
+        Ok(payment_id)
+    }
+
+    /// Create a fixed-amount request that accepts a tip on top
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_tippable_request(
+        env: Env,
+        amount: i128,
+        business_name: String,
+        description: String,
+        denomination: String,
+        authorized_addresses: Vec<Address>,
+        requester: Address,
+        custom_fee_percentage: Option<u32>,
+    ) -> Result<u64, Error> {
+        requester.require_auth();
+
+        let payment_request = Self::new_payment_request(
+            &env,
+            RequestKind::Tippable,
+            amount,
+            business_name,
+            description,
+            denomination,
+            authorized_addresses,
+            requester,
+            custom_fee_percentage,
+        )?;
+        let payment_id = payment_request.id;
+
+        env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+
+        log!(&env, "Tippable payment request created with ID: {}", payment_id);
+        Ok(payment_id)
+    }
+
+    /// Create a payment request whose amount is computed from an invoice
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_invoice_request(
+        env: Env,
+        business_name: String,
+        description: String,
+        denomination: String,
+        authorized_addresses: Vec<Address>,
+        requester: Address,
+        custom_fee_percentage: Option<u32>,
+        invoice: Invoice,
+    ) -> Result<u64, Error> {
+        requester.require_auth();
+
+        if invoice.due_date < env.ledger().timestamp() {
+            return Err(Error::InvalidInvoice);
+        }
+
+        let totals = invoice_totals(&invoice)?;
+
+        // Merchant references are unique per business so the ERP can reconcile
+        let reference_key = DataKey::MerchantReference(
+            business_name.clone(),
+            invoice.merchant_reference.clone(),
+        );
+        if env.storage().persistent().has(&reference_key) {
+            return Err(Error::DuplicateMerchantReference);
+        }
+
+        let payment_request = Self::new_payment_request(
+            &env,
+            RequestKind::Fixed,
+            totals.total,
+            business_name,
+            description,
+            denomination,
+            authorized_addresses,
+            requester,
+            custom_fee_percentage,
+        )?;
+        let payment_id = payment_request.id;
+
+        env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+        env.storage().persistent().set(&DataKey::Invoice(payment_id), &invoice);
+        env.storage().persistent().set(&reference_key, &payment_id);
+
+        log!(&env, "Invoice request created with ID: {}", payment_id);
+        Ok(payment_id)
+    }
+
+    /// Execute payment from one of the authorized addresses. If a party fails
+    /// compliance screening nothing is paid and the attempt is recorded (see
+    /// `get_blocked_payment`); the same holds for the other payment calls.
+    pub fn execute_payment(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+        token_address: Address,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+
+        if Self::settle_payment(&env, payment_id, &payer, &payer, &token_address, PaymentAmount::Full)? {
+            log!(&env, "Payment {} executed successfully", payment_id);
+        }
+        Ok(())
+    }
+
+    /// Pay part of a request; the request completes once the full amount is paid
+    pub fn execute_partial_payment(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+
+        if Self::settle_payment(&env, payment_id, &payer, &payer, &token_address, PaymentAmount::Installment(amount))? {
+            log!(&env, "Installment of {} paid towards payment {}", amount, payment_id);
+        }
+        Ok(())
+    }
+
+    /// Pay an open-amount request with an amount chosen by the payer
+    pub fn execute_open_payment(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+        token_address: Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+
+        if Self::settle_payment(&env, payment_id, &payer, &payer, &token_address, PaymentAmount::OpenAmount(amount))? {
+            log!(&env, "Open payment {} executed for {}", payment_id, amount);
+        }
+        Ok(())
+    }
+
+    /// Pay a tippable request in full and add a tip for the requester
+    pub fn execute_payment_with_tip(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+        token_address: Address,
+        tip: i128,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+
+        if Self::settle_payment(&env, payment_id, &payer, &payer, &token_address, PaymentAmount::Tip(tip))? {
+            log!(&env, "Payment {} executed with tip {}", payment_id, tip);
+        }
+        Ok(())
+    }
+
+    /// Execute XLM payment (native Stellar asset)
+    pub fn execute_xlm_payment(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+    ) -> Result<(), Error> {
+        payer.require_auth();
+
+        let native_token: Address = env.storage()
+            .instance()
+            .get(&DataKey::NativeToken)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if Self::settle_payment(&env, payment_id, &payer, &payer, &native_token, PaymentAmount::Full)? {
+            log!(&env, "XLM Payment {} executed successfully", payment_id);
+        }
+        Ok(())
+    }
+
+    /// Get payment request details
+    pub fn get_payment_request(env: Env, payment_id: u64) -> Result<PaymentRequest, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)
+    }
+
+    /// Get the invoice attached to a payment request
+    pub fn get_invoice(env: Env, payment_id: u64) -> Result<Invoice, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Invoice(payment_id))
+            .ok_or(Error::PaymentNotFound)
+    }
+
+    /// Get the payable breakdown of the invoice attached to a payment request
+    pub fn get_invoice_totals(env: Env, payment_id: u64) -> Result<InvoiceTotals, Error> {
+        invoice_totals(&Self::get_invoice(env, payment_id)?)
+    }
+
+    /// Look up a payment request by the merchant reference on its invoice
+    pub fn get_payment_by_merchant_ref(
+        env: Env,
+        business_name: String,
+        merchant_reference: String,
+    ) -> Result<PaymentRequest, Error> {
+        let payment_id: u64 = env.storage()
+            .persistent()
+            .get(&DataKey::MerchantReference(business_name, merchant_reference))
+            .ok_or(Error::PaymentNotFound)?;
+
+        Self::get_payment_request(env, payment_id)
+    }
+
+    /// Get business configuration
+    pub fn get_business_config(env: Env, business_name: String) -> Result<BusinessConfig, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BusinessConfig(business_name))
+            .ok_or(Error::BusinessNotFound)
+    }
+
+    /// Get the amount each payer has contributed towards a request
+    pub fn get_payment_contributions(env: Env, payment_id: u64) -> Map<Address, i128> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Contributions(payment_id))
+            .unwrap_or(Map::new(&env))
+    }
+
+    /// Get payment history for an address
+    pub fn get_payment_history(env: Env, address: Address) -> PaymentHistory {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PaymentHistory(address))
+            .unwrap_or(PaymentHistory {
+                total_payments: 0,
+                total_amount: 0,
+                last_payment_id: 0,
+            })
+    }
+
+    /// Cancel a payment request (only by requester or contract owner)
+    pub fn cancel_payment_request(env: Env, payment_id: u64, caller: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let mut payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        // Verify caller is authorized to cancel
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != payment_request.requester && caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        // Verify payment can be cancelled
+        match payment_request.status {
+            PaymentStatus::Pending => {},
+            PaymentStatus::Completed => return Err(Error::PaymentAlreadyCompleted),
+            PaymentStatus::PartiallyPaid => return Err(Error::InvalidPaymentStatus),
+            _ => return Err(Error::PaymentNotFound),
+        }
+
+        // Update payment status
+        payment_request.status = PaymentStatus::Cancelled;
+        env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("cancel")),
+            payment_id
+        );
+
+        log!(&env, "Payment request {} cancelled", payment_id);
+        Ok(())
+    }
+
+    /// Update business status (activate/deactivate)
+    pub fn update_business_status(
+        env: Env,
+        business_name: String,
+        is_active: bool,
+        caller: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let mut business_config: BusinessConfig = env.storage()
+            .persistent()
+            .get(&DataKey::BusinessConfig(business_name.clone()))
+            .ok_or(Error::BusinessNotFound)?;
+
+        // Verify caller is business owner or contract owner
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != business_config.owner && caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        business_config.is_active = is_active;
+        env.storage().persistent().set(&DataKey::BusinessConfig(business_name.clone()), &business_config);
+
+        env.events().publish(
+            (symbol_short!("business"), symbol_short!("status")),
+            (business_name, is_active)
+        );
+
+        Ok(())
+    }
+
+    /// Split the net proceeds of a pending request between several payees
+    pub fn set_payment_splits(
+        env: Env,
+        payment_id: u64,
+        splits: Vec<SplitShare>,
+        caller: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let mut payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        if caller != payment_request.requester {
+            return Err(Error::NotAuthorized);
+        }
+
+        // Splits are fixed once the first payment lands
+        if payment_request.status != PaymentStatus::Pending {
+            return Err(Error::InvalidPaymentStatus);
+        }
+
+        validate_splits(&splits)?;
+
+        payment_request.splits = splits;
+        env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+
+        Ok(())
+    }
+
+    /// Choose whether tips are charged the business fee (business or contract owner)
+    pub fn set_fee_basis(
+        env: Env,
+        business_name: String,
+        fee_basis: FeeBasis,
+        caller: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let mut business_config: BusinessConfig = env.storage()
+            .persistent()
+            .get(&DataKey::BusinessConfig(business_name.clone()))
+            .ok_or(Error::BusinessNotFound)?;
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != business_config.owner && caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        business_config.fee_basis = fee_basis;
+        env.storage().persistent().set(&DataKey::BusinessConfig(business_name), &business_config);
+
+        Ok(())
+    }
+
+    // Private helper that validates and builds a pending payment request and
+    // announces its ID
+    #[allow(clippy::too_many_arguments)]
+    fn new_payment_request(
+        env: &Env,
+        kind: RequestKind,
+        amount: i128,
+        business_name: String,
+        description: String,
+        denomination: String,
+        authorized_addresses: Vec<Address>,
+        requester: Address,
+        custom_fee_percentage: Option<u32>,
+    ) -> Result<PaymentRequest, Error> {
+        let is_open = matches!(kind, RequestKind::OpenAmount(_));
+        if (is_open && amount != 0) || (!is_open && amount <= 0) {
+            return Err(Error::InvalidAmount);
+        }
+
+        if authorized_addresses.is_empty() {
+            return Err(Error::InvalidAddress);
+        }
+
+        // Verify business exists and is active
+        let business_config: BusinessConfig = env.storage()
+            .persistent()
+            .get(&DataKey::BusinessConfig(business_name.clone()))
+            .ok_or(Error::BusinessNotFound)?;
+
+        if !business_config.is_active {
+            return Err(Error::BusinessNotActive);
+        }
+
+        // Determine fee percentage
+        let fee_percentage = custom_fee_percentage.unwrap_or(business_config.default_fee_percentage);
+        
+        if !is_valid_basis_points(fee_percentage as i128) {
+            return Err(Error::InvalidFeePercentage);
+        }
+
+        // Generate unique payment ID
+        let payment_id = env.storage()
+            .instance()
+            .get::<_, u64>(&DataKey::PaymentCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage().instance().set(&DataKey::PaymentCounter, &payment_id);
+
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("create")),
+            (payment_id, business_name.clone(), requester.clone(), amount, fee_percentage)
+        );
+
+        Ok(PaymentRequest {
+            id: payment_id,
+            amount,
+            business_name,
+            description,
+            denomination,
+            authorized_addresses,
+            requester,
+            timestamp: env.ledger().timestamp(),
+            status: PaymentStatus::Pending,
+            fee_percentage,
+            amount_paid: 0,
+            kind,
+            tip_amount: 0,
+            splits: Vec::new(env),
+        })
+    }
+
+    // Private helper that moves funds for an open request and records the
+    // contribution. Tokens are taken from `funds_from`, which is the payer
+    // unless the contract already holds the funds (e.g. after a swap).
+    // Returns false, having changed nothing but the blocked record, when a
+    // party fails screening.
+    fn settle_payment(
+        env: &Env,
+        payment_id: u64,
+        payer: &Address,
+        funds_from: &Address,
+        token_address: &Address,
+        amount: PaymentAmount,
+    ) -> Result<bool, Error> {
+        let mut payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        // Verify payment is still open
+        match payment_request.status {
+            PaymentStatus::Pending | PaymentStatus::PartiallyPaid => {},
+            PaymentStatus::Completed => return Err(Error::PaymentAlreadyCompleted),
+            _ => return Err(Error::PaymentNotFound),
+        }
+
+        // Verify payer is authorized
+        if !payment_request.authorized_addresses.contains(payer) {
+            return Err(Error::NotAuthorized);
+        }
+
+        // Get business configuration
+        let business_config: BusinessConfig = env.storage()
+            .persistent()
+            .get(&DataKey::BusinessConfig(payment_request.business_name.clone()))
+            .ok_or(Error::BusinessNotFound)?;
+
+        // Screen before anything is written, as a blocked payment returns
+        // normally and would keep those writes
+        if !Self::screen_payment(env, &payment_request, payer, &business_config) {
+            return Ok(false);
+        }
+
+        // All installments must be paid in the same token
+        let token_key = DataKey::PaymentToken(payment_id);
+        match env.storage().persistent().get::<_, Address>(&token_key) {
+            Some(paid_token) if paid_token != *token_address => return Err(Error::TokenMismatch),
+            Some(_) => {},
+            None => env.storage().persistent().set(&token_key, token_address),
+        }
+
+        // Work out the base amount and tip for this kind of request
+        let (amount, tip) = match (&payment_request.kind, amount) {
+            (RequestKind::OpenAmount(bounds), PaymentAmount::OpenAmount(amount)) => {
+                if bounds.min_amount.is_some_and(|min| amount < min)
+                    || bounds.max_amount.is_some_and(|max| amount > max)
+                {
+                    return Err(Error::AmountOutOfRange);
+                }
+                payment_request.amount = amount;
+                (amount, 0)
+            }
+            (RequestKind::OpenAmount(_), _) | (_, PaymentAmount::OpenAmount(_)) => {
+                return Err(Error::InvalidRequestKind)
+            }
+            (RequestKind::FiatPriced, PaymentAmount::Full) => {
+                let quote = Self::quote_fiat_amount(env, payment_request.amount, token_address)?;
+                env.storage().persistent().set(&DataKey::FiatQuote(payment_id), &quote);
+                payment_request.amount = quote.token_amount;
+                (quote.token_amount, 0)
+            }
+            (RequestKind::FiatPriced, _) => return Err(Error::InvalidRequestKind),
+            (RequestKind::Tippable, PaymentAmount::Tip(tip)) => {
+                if tip < 0 {
+                    return Err(Error::InvalidAmount);
+                }
+                (payment_request.amount, tip)
+            }
+            (RequestKind::Tippable, PaymentAmount::Installment(_)) | (_, PaymentAmount::Tip(_)) => {
+                return Err(Error::InvalidRequestKind)
+            }
+            (_, PaymentAmount::Installment(amount)) => (amount, 0),
+            (_, PaymentAmount::Full) => (payment_request.amount - payment_request.amount_paid, 0),
+        };
+
+        // Overpayments are rejected rather than silently kept
+        let outstanding = payment_request.amount - payment_request.amount_paid;
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > outstanding {
+            return Err(Error::Overpayment);
+        }
+
+        // Calculate fee and net amount. The fee is taken from the running total so
+        // installments add up to the same fee as a single full payment.
+        let fee_percentage = payment_request.fee_percentage as i128;
+        let paid_after = payment_request.amount_paid + amount;
+        let mut fee_amount = split_fee(paid_after, fee_percentage).0
+            - split_fee(payment_request.amount_paid, fee_percentage).0;
+        if business_config.fee_basis == FeeBasis::Total {
+            fee_amount += split_fee(tip, fee_percentage).0;
+        }
+        let gross_amount = amount + tip;
+        let net_amount = gross_amount - fee_amount;
+
+        Self::enforce_spending_limits(env, &payment_request.business_name, payer, gross_amount)?;
+
+        // Initialize token client
+        let token_client = TokenClient::new(env, token_address);
+
+        // Check payer balance
+        if token_client.balance(funds_from) < gross_amount {
+            return Err(Error::InsufficientBalance);
+        }
+
+        // Execute transfers
+        if payment_request.splits.is_empty() {
+            let contract_address = env.current_contract_address();
+            let settlement_enabled = Self::is_settlement_enabled(env, &payment_request.business_name);
+            let hold_period = Self::dispute_hold_period(env);
+            let on_hold = (settlement_enabled || hold_period > 0)
+                && env.storage().persistent().has(&DataKey::HeldUntil(payment_id));
+            if settlement_enabled && !on_hold {
+                // Hold proceeds in the contract until the next payout
+                if net_amount > 0 && *funds_from != contract_address {
+                    token_client.transfer(funds_from, &contract_address, &net_amount);
+                }
+                Self::accrue_proceeds(env, &payment_request.business_name, token_address, payment_id, net_amount);
+            } else if hold_period > 0 || on_hold {
+                // Hold proceeds for the dispute window, so a ruling can be
+                // refunded without the requester
+                if net_amount > 0 && *funds_from != contract_address {
+                    token_client.transfer(funds_from, &contract_address, &net_amount);
+                }
+                Self::hold_proceeds(env, payment_id, net_amount, hold_period);
+            } else if net_amount > 0 {
+                token_client.transfer(funds_from, &payment_request.requester, &net_amount);
+            }
+        } else {
+            for (recipient, leg_amount) in allocate_splits(env, net_amount, &payment_request.splits) {
+                if leg_amount > 0 {
+                    token_client.transfer(funds_from, &recipient, &leg_amount);
+                }
+                env.events().publish(
+                    (symbol_short!("payment"), symbol_short!("split")),
+                    (payment_id, recipient, leg_amount)
+                );
+            }
+        }
+
+        if fee_amount > 0 {
+            token_client.transfer(funds_from, &business_config.fee_recipient, &fee_amount);
+            env.events().publish(
+                (symbol_short!("payment"), symbol_short!("fee")),
+                (payment_id, business_config.fee_recipient, token_address.clone(), fee_amount)
+            );
+        }
+
+        // Record the payer's contribution towards the base amount
+        let contributions_key = DataKey::Contributions(payment_id);
+        let mut contributions: Map<Address, i128> = env.storage()
+            .persistent()
+            .get(&contributions_key)
+            .unwrap_or(Map::new(env));
+        let contributed = contributions.get(payer.clone()).unwrap_or(0);
+        contributions.set(payer.clone(), contributed + amount);
+        env.storage().persistent().set(&contributions_key, &contributions);
+
+        // Update payment status
+        payment_request.amount_paid = paid_after;
+        payment_request.tip_amount += tip;
+        payment_request.status = if paid_after == payment_request.amount {
+            PaymentStatus::Completed
+        } else {
+            PaymentStatus::PartiallyPaid
+        };
+        env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("paid")),
+            (payment_id, payer.clone(), token_address.clone(), amount, tip, payment_request.status)
+        );
+
+        // Update payment history
+        Self::update_payment_history(env, payer, payment_id, gross_amount);
+
+        Ok(true)
+    }
+
+    // Private helper function to update payment history
+    fn update_payment_history(env: &Env, payer: &Address, payment_id: u64, amount: i128) {
+        let mut history = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentHistory(payer.clone()))
+            .unwrap_or(PaymentHistory {
+                total_payments: 0,
+                total_amount: 0,
+                last_payment_id: 0,
+            });
+
+        history.total_payments += 1;
+        history.total_amount += amount;
+        history.last_payment_id = payment_id;
+
+        env.storage().persistent().set(&DataKey::PaymentHistory(payer.clone()), &history);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,118 @@
+use soroban_sdk::{contractimpl, Address, Env, String, Vec};
+
+use crate::{DataKey, PaymentContract, PaymentContractClient};
+use payment_core::{enforce_spending_limits, window_total, Error, SpendBucket, SpendingLimits};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Set the default spending limits for every business (contract owner only)
+    pub fn set_spending_limits(env: Env, caller: Address, limits: SpendingLimits) -> Result<(), Error> {
+        caller.require_auth();
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        env.storage().instance().set(&DataKey::SpendingLimits, &limits);
+        Ok(())
+    }
+
+    /// Override the default spending limits for one business (contract owner only)
+    pub fn set_business_limits(
+        env: Env,
+        business_name: String,
+        limits: SpendingLimits,
+        caller: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        if !env.storage().persistent().has(&DataKey::BusinessConfig(business_name.clone())) {
+            return Err(Error::BusinessNotFound);
+        }
+
+        env.storage().persistent().set(&DataKey::BusinessLimits(business_name), &limits);
+        Ok(())
+    }
+
+    /// Exempt a payer from per-payment and per-payer limits (contract owner only)
+    pub fn set_limit_exemption(env: Env, caller: Address, payer: Address, exempt: bool) -> Result<(), Error> {
+        caller.require_auth();
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        if exempt {
+            env.storage().persistent().set(&DataKey::LimitExempt(payer), &true);
+        } else {
+            env.storage().persistent().remove(&DataKey::LimitExempt(payer));
+        }
+        Ok(())
+    }
+
+    /// Get the spending limits that apply to a business
+    pub fn get_spending_limits(env: Env, business_name: String) -> SpendingLimits {
+        env.storage()
+            .persistent()
+            .get(&DataKey::BusinessLimits(business_name))
+            .or_else(|| env.storage().instance().get(&DataKey::SpendingLimits))
+            .unwrap_or_default()
+    }
+
+    /// Get a payer's volume over the last `window` seconds
+    pub fn get_payer_volume(env: Env, payer: Address, window: u64) -> i128 {
+        let spend: Vec<SpendBucket> = env.storage()
+            .persistent()
+            .get(&DataKey::PayerSpend(payer))
+            .unwrap_or(Vec::new(&env));
+        window_total(&spend, env.ledger().timestamp(), window)
+    }
+
+    /// Get a business's volume over the last `window` seconds
+    pub fn get_business_volume(env: Env, business_name: String, window: u64) -> i128 {
+        let spend: Vec<SpendBucket> = env.storage()
+            .persistent()
+            .get(&DataKey::BusinessSpend(business_name))
+            .unwrap_or(Vec::new(&env));
+        window_total(&spend, env.ledger().timestamp(), window)
+    }
+}
+
+impl PaymentContract {
+    pub(crate) fn enforce_spending_limits(
+        env: &Env,
+        business_name: &String,
+        payer: &Address,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let limits = Self::get_spending_limits(env.clone(), business_name.clone());
+        let payer_exempt = env.storage().persistent().has(&DataKey::LimitExempt(payer.clone()));
+        enforce_spending_limits(
+            env,
+            &limits,
+            amount,
+            &DataKey::PayerSpend(payer.clone()),
+            &DataKey::BusinessSpend(business_name.clone()),
+            payer_exempt,
+        )
+    }
+}
@@ -0,0 +1,126 @@
+use soroban_sdk::{contractimpl, Address, Env, String, Vec, log};
+
+use crate::{DataKey, PaymentContract, PaymentContractClient};
+use payment_core::{
+    fiat_to_token_amount, is_valid_basis_points, price_deviation_bps, Asset, Error, FiatQuote,
+    OracleConfig, PriceOracleClient, RequestKind,
+};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Configure the price feed for fiat-denominated requests (contract owner only)
+    pub fn set_oracle_config(env: Env, caller: Address, config: OracleConfig) -> Result<(), Error> {
+        caller.require_auth();
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        if !is_valid_basis_points(config.max_deviation_bps as i128) {
+            return Err(Error::InvalidOracleConfig);
+        }
+
+        env.storage().instance().set(&DataKey::OracleConfig, &config);
+        Ok(())
+    }
+
+    /// Get the price feed configuration
+    pub fn get_oracle_config(env: Env) -> Option<OracleConfig> {
+        env.storage().instance().get(&DataKey::OracleConfig)
+    }
+
+    /// Create a request priced in the oracle's fiat currency. `fiat_amount`
+    /// uses the same 7-decimal scale as token amounts.
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_fiat_payment_request(
+        env: Env,
+        fiat_amount: i128,
+        business_name: String,
+        description: String,
+        denomination: String,
+        authorized_addresses: Vec<Address>,
+        requester: Address,
+        custom_fee_percentage: Option<u32>,
+    ) -> Result<u64, Error> {
+        requester.require_auth();
+
+        let config: OracleConfig = env.storage()
+            .instance()
+            .get(&DataKey::OracleConfig)
+            .ok_or(Error::OracleNotSet)?;
+
+        // The feed can only price requests in its own quote currency
+        if denomination != config.denomination {
+            return Err(Error::TokenMismatch);
+        }
+
+        let payment_request = Self::new_payment_request(
+            &env,
+            RequestKind::FiatPriced,
+            fiat_amount,
+            business_name,
+            description,
+            denomination,
+            authorized_addresses,
+            requester,
+            custom_fee_percentage,
+        )?;
+        let payment_id = payment_request.id;
+
+        env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+
+        log!(&env, "Fiat payment request created with ID: {}", payment_id);
+        Ok(payment_id)
+    }
+
+    /// Get the conversion used to pay a fiat-denominated request
+    pub fn get_fiat_quote(env: Env, payment_id: u64) -> Result<FiatQuote, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::FiatQuote(payment_id))
+            .ok_or(Error::PaymentNotFound)
+    }
+}
+
+impl PaymentContract {
+    // Converts a fiat amount to `token_address` units at the current oracle
+    // price, rejecting stale or jumpy prices
+    pub(crate) fn quote_fiat_amount(
+        env: &Env,
+        fiat_amount: i128,
+        token_address: &Address,
+    ) -> Result<FiatQuote, Error> {
+        let config: OracleConfig = env.storage()
+            .instance()
+            .get(&DataKey::OracleConfig)
+            .ok_or(Error::OracleNotSet)?;
+        let oracle = PriceOracleClient::new(env, &config.oracle);
+        let asset = Asset::Stellar(token_address.clone());
+
+        let latest = oracle.lastprice(&asset).ok_or(Error::PriceUnavailable)?;
+        if env.ledger().timestamp().saturating_sub(latest.timestamp) > config.max_age {
+            return Err(Error::StalePrice);
+        }
+
+        // Compare with the previous record to catch sudden jumps
+        if let Some(previous) = oracle.prices(&asset, &2).and_then(|prices| prices.get(1)) {
+            if price_deviation_bps(previous.price, latest.price) > config.max_deviation_bps as i128 {
+                return Err(Error::PriceDeviation);
+            }
+        }
+
+        let token_amount = fiat_to_token_amount(fiat_amount, latest.price, oracle.decimals())?;
+        Ok(FiatQuote {
+            fiat_amount,
+            token_address: token_address.clone(),
+            price: latest.price,
+            price_timestamp: latest.timestamp,
+            token_amount,
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use soroban_sdk::{contractimpl, symbol_short, Address, Env, Vec, log};
+
+use crate::{DataKey, PaymentContract, PaymentContractClient};
+use payment_core::{Error, PaymentRequest, RampRecord};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Record the progress of an anchor deposit, withdrawal or send made for a
+    /// payment request (requester or an authorized payer). Updates replace the
+    /// record with the same anchor and anchor ID until it is final, and only
+    /// the address that recorded it first may make them.
+    pub fn record_ramp(env: Env, payment_id: u64, caller: Address, record: RampRecord) -> Result<(), Error> {
+        caller.require_auth();
+
+        let payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        if caller != payment_request.requester && !payment_request.authorized_addresses.contains(&caller) {
+            return Err(Error::NotAuthorized);
+        }
+        if record.amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        let record = RampRecord {
+            recorded_by: caller.clone(),
+            updated_at: env.ledger().timestamp(),
+            ..record
+        };
+        let mut ramps = Self::get_ramps(env.clone(), payment_id);
+        let existing = ramps
+            .iter()
+            .position(|ramp| ramp.anchor == record.anchor && ramp.anchor_id == record.anchor_id);
+        match existing {
+            Some(index) => {
+                let existing = ramps.get_unchecked(index as u32);
+                if existing.recorded_by != caller {
+                    return Err(Error::NotAuthorized);
+                }
+                if existing.status.is_final() {
+                    return Err(Error::RampAlreadyFinal);
+                }
+                ramps.set(index as u32, record.clone());
+            }
+            None => ramps.push_back(record.clone()),
+        }
+        env.storage().persistent().set(&DataKey::Ramps(payment_id), &ramps);
+
+        env.events().publish(
+            (symbol_short!("ramp"), symbol_short!("update")),
+            (payment_id, record.kind, record.anchor_id, record.status)
+        );
+
+        log!(&env, "Ramp for payment {} updated", payment_id);
+        Ok(())
+    }
+
+    /// Get the anchor transactions recorded for a payment request
+    pub fn get_ramps(env: Env, payment_id: u64) -> Vec<RampRecord> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Ramps(payment_id))
+            .unwrap_or(Vec::new(&env))
+    }
+}
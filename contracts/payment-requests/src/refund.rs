@@ -0,0 +1,114 @@
+use soroban_sdk::{contractimpl, symbol_short, token::Client as TokenClient, Address, Env, Map, log};
+
+use crate::{DataKey, PaymentContract, PaymentContractClient};
+use payment_core::{ensure_compliant, Error, PaymentRequest, PaymentStatus};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Return part or all of a payer's contribution (requester only)
+    pub fn refund_payment(env: Env, payment_id: u64, payer: Address, amount: i128) -> Result<(), Error> {
+        let payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        payment_request.requester.require_auth();
+
+        if amount <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+        if amount > Self::refundable_amount(&env, payment_id, &payer) {
+            return Err(Error::RefundExceedsPayment);
+        }
+
+        Self::pay_refund(&env, payment_id, &payer, amount, true)?;
+        Ok(())
+    }
+
+    /// Get the amounts refunded to each payer of a request
+    pub fn get_payment_refunds(env: Env, payment_id: u64) -> Map<Address, i128> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Refunds(payment_id))
+            .unwrap_or(Map::new(&env))
+    }
+}
+
+impl PaymentContract {
+    // A payer's contribution that has not been refunded yet
+    pub(crate) fn refundable_amount(env: &Env, payment_id: u64, payer: &Address) -> i128 {
+        let contributed = Self::get_payment_contributions(env.clone(), payment_id)
+            .get(payer.clone())
+            .unwrap_or(0);
+        let refunded = Self::get_payment_refunds(env.clone(), payment_id)
+            .get(payer.clone())
+            .unwrap_or(0);
+        contributed - refunded
+    }
+
+    // Sends a refund to a payer, drawing on proceeds the contract still holds
+    // for the request before the requester's wallet. Returns false without
+    // moving funds when held proceeds fall short and the requester has not
+    // authorized the call.
+    pub(crate) fn pay_refund(
+        env: &Env,
+        payment_id: u64,
+        payer: &Address,
+        amount: i128,
+        requester_authorized: bool,
+    ) -> Result<bool, Error> {
+        let mut payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        match payment_request.status {
+            PaymentStatus::Completed | PaymentStatus::PartiallyPaid => {},
+            _ => return Err(Error::InvalidPaymentStatus),
+        }
+
+        ensure_compliant(env, payer)?;
+
+        let token_address: Address = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentToken(payment_id))
+            .ok_or(Error::InvalidPaymentStatus)?;
+        let token_client = TokenClient::new(env, &token_address);
+
+        if Self::held_proceeds(env, &payment_request) >= amount {
+            token_client.transfer(&env.current_contract_address(), payer, &amount);
+            Self::take_held_proceeds(env, &payment_request, amount);
+        } else if requester_authorized {
+            if token_client.balance(&payment_request.requester) < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            token_client.transfer(&payment_request.requester, payer, &amount);
+        } else {
+            return Ok(false);
+        }
+
+        let refunds_key = DataKey::Refunds(payment_id);
+        let mut refunds: Map<Address, i128> = env.storage()
+            .persistent()
+            .get(&refunds_key)
+            .unwrap_or(Map::new(env));
+        let refunded = refunds.get(payer.clone()).unwrap_or(0);
+        refunds.set(payer.clone(), refunded + amount);
+        env.storage().persistent().set(&refunds_key, &refunds);
+
+        // A request whose whole base amount went back to its payers is refunded
+        let total_refunded: i128 = refunds.values().iter().sum();
+        if total_refunded == payment_request.amount_paid {
+            payment_request.status = PaymentStatus::Refunded;
+            env.storage().persistent().set(&DataKey::PaymentRequest(payment_id), &payment_request);
+        }
+
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("refund")),
+            (payment_id, payer.clone(), amount)
+        );
+
+        log!(env, "Payment {} refunded {}", payment_id, amount);
+        Ok(true)
+    }
+}
@@ -0,0 +1,289 @@
+use soroban_sdk::{contractimpl, symbol_short, token::Client as TokenClient, Address, Env, String, Vec, log};
+
+use crate::{BusinessConfig, DataKey, PaymentContract, PaymentContractClient};
+use payment_core::{
+    ensure_compliant, is_settlement_due, Error, PaymentRequest, SettlementConfig, SettlementRecord,
+};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Turn batched settlement on or off for a business (business or contract owner)
+    pub fn set_settlement_config(
+        env: Env,
+        business_name: String,
+        config: SettlementConfig,
+        caller: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+
+        let business_config: BusinessConfig = env.storage()
+            .persistent()
+            .get(&DataKey::BusinessConfig(business_name.clone()))
+            .ok_or(Error::BusinessNotFound)?;
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != business_config.owner && caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        if config.threshold <= 0 {
+            return Err(Error::InvalidAmount);
+        }
+
+        env.storage().persistent().set(&DataKey::SettlementConfig(business_name), &config);
+        Ok(())
+    }
+
+    /// Get the settlement configuration of a business
+    pub fn get_settlement_config(env: Env, business_name: String) -> Option<SettlementConfig> {
+        env.storage().persistent().get(&DataKey::SettlementConfig(business_name))
+    }
+
+    /// Get the proceeds held for a business in a token, not counting those
+    /// escrowed for open disputes
+    pub fn get_merchant_balance(env: Env, business_name: String, token_address: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::MerchantBalance(business_name, token_address))
+            .unwrap_or(0)
+    }
+
+    /// Get the proceeds of one request the contract still holds, not counting
+    /// those escrowed for open disputes
+    pub fn get_held_proceeds(env: Env, payment_id: u64) -> i128 {
+        env.storage()
+            .persistent()
+            .get::<_, PaymentRequest>(&DataKey::PaymentRequest(payment_id))
+            .map_or(0, |payment_request| Self::held_proceeds(&env, &payment_request))
+    }
+
+    /// Pay a request's held proceeds to the requester once its dispute window
+    /// has passed. Anyone may trigger this; proceeds waiting for settlement
+    /// are paid out by `settle` instead. Returns the amount released.
+    pub fn release_proceeds(env: Env, payment_id: u64) -> Result<i128, Error> {
+        let payment_request: PaymentRequest = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentRequest(payment_id))
+            .ok_or(Error::PaymentNotFound)?;
+
+        let held_until: u64 = env.storage()
+            .persistent()
+            .get(&DataKey::HeldUntil(payment_id))
+            .ok_or(Error::InvalidPaymentStatus)?;
+        if env.ledger().timestamp() < held_until {
+            return Err(Error::SettlementNotDue);
+        }
+
+        let held = Self::held_proceeds(&env, &payment_request);
+        if held <= 0 {
+            return Ok(0);
+        }
+
+        ensure_compliant(&env, &payment_request.requester)?;
+
+        let token_address: Address = env.storage()
+            .persistent()
+            .get(&DataKey::PaymentToken(payment_id))
+            .ok_or(Error::InvalidPaymentStatus)?;
+        TokenClient::new(&env, &token_address)
+            .transfer(&env.current_contract_address(), &payment_request.requester, &held);
+        env.storage().persistent().set(&DataKey::HeldProceeds(payment_id), &0i128);
+
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("release")),
+            (payment_id, payment_request.requester, held)
+        );
+        Ok(held)
+    }
+
+    /// Get the payments whose proceeds are waiting for the next payout
+    pub fn get_unsettled_payments(env: Env, business_name: String, token_address: Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::UnsettledPayments(business_name, token_address))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Pay out a business's accumulated proceeds in one token once the
+    /// schedule or threshold allows it. Anyone may trigger this; funds only
+    /// ever go to the configured payout address. Returns the settlement ID.
+    pub fn settle(env: Env, business_name: String, token_address: Address) -> Result<u64, Error> {
+        let config: SettlementConfig = env.storage()
+            .persistent()
+            .get(&DataKey::SettlementConfig(business_name.clone()))
+            .ok_or(Error::SettlementNotConfigured)?;
+
+        let balance_key = DataKey::MerchantBalance(business_name.clone(), token_address.clone());
+        let last_key = DataKey::LastSettlement(business_name.clone(), token_address.clone());
+        let unsettled_key = DataKey::UnsettledPayments(business_name.clone(), token_address.clone());
+
+        let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        let last_settled_at: u64 = env.storage().persistent().get(&last_key).unwrap_or(0);
+        let now = env.ledger().timestamp();
+
+        if !is_settlement_due(&config, balance, last_settled_at, now) {
+            return Err(Error::SettlementNotDue);
+        }
+
+        ensure_compliant(&env, &config.payout_address)?;
+
+        TokenClient::new(&env, &token_address)
+            .transfer(&env.current_contract_address(), &config.payout_address, &balance);
+
+        // Generate unique settlement ID
+        let settlement_id = env.storage()
+            .instance()
+            .get::<_, u64>(&DataKey::SettlementCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage().instance().set(&DataKey::SettlementCounter, &settlement_id);
+
+        let record = SettlementRecord {
+            id: settlement_id,
+            business_name: business_name.clone(),
+            token_address,
+            payout_address: config.payout_address,
+            amount: balance,
+            payment_ids: env.storage().persistent().get(&unsettled_key).unwrap_or(Vec::new(&env)),
+            timestamp: now,
+        };
+        env.storage().persistent().set(&DataKey::Settlement(settlement_id), &record);
+        env.storage().persistent().set(&balance_key, &0i128);
+        env.storage().persistent().set(&last_key, &now);
+        env.storage().persistent().remove(&unsettled_key);
+
+        env.events().publish(
+            (symbol_short!("settle"), business_name),
+            (settlement_id, record.token_address, balance)
+        );
+
+        log!(&env, "Settlement {} paid out {}", settlement_id, balance);
+        Ok(settlement_id)
+    }
+
+    /// Get a settlement record
+    pub fn get_settlement(env: Env, settlement_id: u64) -> Result<SettlementRecord, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Settlement(settlement_id))
+            .ok_or(Error::SettlementNotFound)
+    }
+}
+
+impl PaymentContract {
+    // Returns true when a business's proceeds should be held for batching
+    pub(crate) fn is_settlement_enabled(env: &Env, business_name: &String) -> bool {
+        env.storage()
+            .persistent()
+            .get::<_, SettlementConfig>(&DataKey::SettlementConfig(business_name.clone()))
+            .is_some_and(|config| config.enabled)
+    }
+
+    // Credits proceeds already held by the contract to a business balance
+    pub(crate) fn accrue_proceeds(
+        env: &Env,
+        business_name: &String,
+        token_address: &Address,
+        payment_id: u64,
+        amount: i128,
+    ) {
+        let balance_key = DataKey::MerchantBalance(business_name.clone(), token_address.clone());
+        let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+        env.storage().persistent().set(&balance_key, &(balance + amount));
+
+        // The first payout window opens with the first proceeds
+        let last_key = DataKey::LastSettlement(business_name.clone(), token_address.clone());
+        if !env.storage().persistent().has(&last_key) {
+            env.storage().persistent().set(&last_key, &env.ledger().timestamp());
+        }
+
+        // Installments of the same request are listed once. What a request
+        // held before its last settlement was paid out with it.
+        let unsettled_key = DataKey::UnsettledPayments(business_name.clone(), token_address.clone());
+        let mut unsettled: Vec<u64> = env.storage().persistent().get(&unsettled_key).unwrap_or(Vec::new(env));
+        if unsettled.contains(payment_id) {
+            Self::add_held_proceeds(env, payment_id, amount);
+        } else {
+            unsettled.push_back(payment_id);
+            env.storage().persistent().set(&unsettled_key, &unsettled);
+            env.storage().persistent().set(&DataKey::HeldProceeds(payment_id), &amount);
+        }
+    }
+
+    // Keeps proceeds of a request without settlement in the contract until
+    // its dispute window has passed. Each installment restarts the window.
+    pub(crate) fn hold_proceeds(env: &Env, payment_id: u64, amount: i128, hold_period: u64) {
+        Self::add_held_proceeds(env, payment_id, amount);
+        env.storage()
+            .persistent()
+            .set(&DataKey::HeldUntil(payment_id), &(env.ledger().timestamp() + hold_period));
+    }
+
+    fn add_held_proceeds(env: &Env, payment_id: u64, amount: i128) {
+        let held_key = DataKey::HeldProceeds(payment_id);
+        let held: i128 = env.storage().persistent().get(&held_key).unwrap_or(0);
+        env.storage().persistent().set(&held_key, &(held + amount));
+    }
+
+    // A request's proceeds still in the contract. Those waiting for
+    // settlement count only until the business's next payout.
+    pub(crate) fn held_proceeds(env: &Env, payment_request: &PaymentRequest) -> i128 {
+        if let Some(DataKey::MerchantBalance(business_name, token_address)) =
+            Self::settlement_balance_key(env, payment_request)
+        {
+            if !Self::get_unsettled_payments(env.clone(), business_name, token_address).contains(payment_request.id) {
+                return 0;
+            }
+        }
+        env.storage().persistent().get(&DataKey::HeldProceeds(payment_request.id)).unwrap_or(0)
+    }
+
+    // Takes up to `amount` of a request's held proceeds out of its business
+    // balance as well, if they wait for settlement. Returns the amount taken.
+    pub(crate) fn take_held_proceeds(env: &Env, payment_request: &PaymentRequest, amount: i128) -> i128 {
+        let held = Self::held_proceeds(env, payment_request);
+        let taken = amount.min(held).max(0);
+        if taken == 0 {
+            return 0;
+        }
+        env.storage().persistent().set(&DataKey::HeldProceeds(payment_request.id), &(held - taken));
+
+        if let Some(balance_key) = Self::settlement_balance_key(env, payment_request) {
+            let balance: i128 = env.storage().persistent().get(&balance_key).unwrap_or(0);
+            env.storage().persistent().set(&balance_key, &(balance - taken));
+        }
+        taken
+    }
+
+    // Puts proceeds taken with `take_held_proceeds` back where they came from
+    pub(crate) fn return_held_proceeds(env: &Env, payment_request: &PaymentRequest, amount: i128) {
+        if amount <= 0 {
+            return;
+        }
+        let token_address: Option<Address> = env.storage().persistent().get(&DataKey::PaymentToken(payment_request.id));
+        match (Self::settlement_balance_key(env, payment_request), token_address) {
+            (Some(_), Some(token_address)) => Self::accrue_proceeds(
+                env,
+                &payment_request.business_name,
+                &token_address,
+                payment_request.id,
+                amount,
+            ),
+            _ => Self::add_held_proceeds(env, payment_request.id, amount),
+        }
+    }
+
+    // The business balance a request's held proceeds are part of, unless
+    // they are held for its dispute window instead
+    fn settlement_balance_key(env: &Env, payment_request: &PaymentRequest) -> Option<DataKey> {
+        if env.storage().persistent().has(&DataKey::HeldUntil(payment_request.id)) {
+            return None;
+        }
+        let token_address: Address = env.storage().persistent().get(&DataKey::PaymentToken(payment_request.id))?;
+        Some(DataKey::MerchantBalance(payment_request.business_name.clone(), token_address))
+    }
+}
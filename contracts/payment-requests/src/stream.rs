@@ -0,0 +1,178 @@
+use soroban_sdk::{contractimpl, symbol_short, token::Client as TokenClient, Address, Env, log};
+
+use crate::{DataKey, PaymentContract, PaymentContractClient};
+use payment_core::{ensure_compliant, is_valid_schedule, screen_address, vested_amount, Error, Stream, StreamStatus};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Lock a deposit that vests to the recipient between start and end time
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_stream(
+        env: Env,
+        sender: Address,
+        recipient: Address,
+        token_address: Address,
+        deposit: i128,
+        start_time: u64,
+        cliff_time: u64,
+        end_time: u64,
+    ) -> Result<u64, Error> {
+        sender.require_auth();
+
+        if !is_valid_schedule(deposit, start_time, cliff_time, end_time) || sender == recipient {
+            return Err(Error::InvalidStream);
+        }
+
+        ensure_compliant(&env, &sender)?;
+        ensure_compliant(&env, &recipient)?;
+
+        // Generate unique stream ID
+        let stream_id = env.storage()
+            .instance()
+            .get::<_, u64>(&DataKey::StreamCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage().instance().set(&DataKey::StreamCounter, &stream_id);
+
+        // Escrow the full deposit in the contract
+        TokenClient::new(&env, &token_address)
+            .transfer(&sender, &env.current_contract_address(), &deposit);
+
+        let stream = Stream {
+            id: stream_id,
+            sender: sender.clone(),
+            recipient: recipient.clone(),
+            token_address,
+            deposit,
+            withdrawn: 0,
+            start_time,
+            cliff_time,
+            end_time,
+            status: StreamStatus::Active,
+        };
+        env.storage().persistent().set(&DataKey::Stream(stream_id), &stream);
+
+        env.events().publish(
+            (symbol_short!("stream"), symbol_short!("create")),
+            (stream_id, sender, recipient, deposit)
+        );
+
+        log!(&env, "Stream created with ID: {}", stream_id);
+        Ok(stream_id)
+    }
+
+    /// Withdraw everything that has vested so far, or what a cancelled stream
+    /// held back while the recipient was blocked; returns the amount paid out
+    pub fn withdraw_from_stream(env: Env, stream_id: u64) -> Result<i128, Error> {
+        let mut stream = Self::get_stream(env.clone(), stream_id)?;
+        stream.recipient.require_auth();
+
+        let held_key = DataKey::StreamHeld(stream_id);
+        if stream.status == StreamStatus::Cancelled && env.storage().persistent().has(&held_key) {
+            ensure_compliant(&env, &stream.recipient)?;
+
+            let held: i128 = env.storage().persistent().get(&held_key).unwrap();
+            TokenClient::new(&env, &stream.token_address)
+                .transfer(&env.current_contract_address(), &stream.recipient, &held);
+            env.storage().persistent().remove(&held_key);
+
+            env.events().publish(
+                (symbol_short!("stream"), symbol_short!("withdraw")),
+                (stream_id, stream.recipient, held)
+            );
+            return Ok(held);
+        }
+
+        if stream.status != StreamStatus::Active {
+            return Err(Error::StreamNotActive);
+        }
+
+        ensure_compliant(&env, &stream.recipient)?;
+
+        let amount = vested_amount(&stream, env.ledger().timestamp()) - stream.withdrawn;
+        if amount <= 0 {
+            return Ok(0);
+        }
+
+        TokenClient::new(&env, &stream.token_address)
+            .transfer(&env.current_contract_address(), &stream.recipient, &amount);
+        stream.withdrawn += amount;
+
+        if stream.withdrawn == stream.deposit {
+            stream.status = StreamStatus::Depleted;
+        }
+        env.storage().persistent().set(&DataKey::Stream(stream_id), &stream);
+
+        env.events().publish(
+            (symbol_short!("stream"), symbol_short!("withdraw")),
+            (stream_id, stream.recipient, amount)
+        );
+        Ok(amount)
+    }
+
+    /// Stop a stream: the recipient receives what has vested and the sender
+    /// reclaims the rest. If the recipient fails screening, what vested stays
+    /// in the contract until they can withdraw it. Returns the amount
+    /// refunded to the sender.
+    pub fn cancel_stream(env: Env, stream_id: u64) -> Result<i128, Error> {
+        let mut stream = Self::get_stream(env.clone(), stream_id)?;
+        stream.sender.require_auth();
+
+        if stream.status != StreamStatus::Active {
+            return Err(Error::StreamNotActive);
+        }
+
+        // Only the refund goes to the sender
+        ensure_compliant(&env, &stream.sender)?;
+
+        let vested = vested_amount(&stream, env.ledger().timestamp());
+        let mut owed = vested - stream.withdrawn;
+        let refund = stream.deposit - vested;
+
+        let token_client = TokenClient::new(&env, &stream.token_address);
+        if owed > 0 && !screen_address(&env, &stream.recipient) {
+            env.storage().persistent().set(&DataKey::StreamHeld(stream_id), &owed);
+            env.events().publish(
+                (symbol_short!("stream"), symbol_short!("held")),
+                (stream_id, stream.recipient.clone(), owed)
+            );
+            owed = 0;
+        }
+        if owed > 0 {
+            token_client.transfer(&env.current_contract_address(), &stream.recipient, &owed);
+        }
+        if refund > 0 {
+            token_client.transfer(&env.current_contract_address(), &stream.sender, &refund);
+        }
+
+        stream.withdrawn = vested;
+        stream.status = StreamStatus::Cancelled;
+        env.storage().persistent().set(&DataKey::Stream(stream_id), &stream);
+
+        env.events().publish(
+            (symbol_short!("stream"), symbol_short!("cancel")),
+            (stream_id, owed, refund)
+        );
+        Ok(refund)
+    }
+
+    /// Get stream details
+    pub fn get_stream(env: Env, stream_id: u64) -> Result<Stream, Error> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Stream(stream_id))
+            .ok_or(Error::StreamNotFound)
+    }
+
+    /// Get the amount the recipient could withdraw right now
+    pub fn get_stream_balance(env: Env, stream_id: u64) -> Result<i128, Error> {
+        let stream = Self::get_stream(env.clone(), stream_id)?;
+        if stream.status == StreamStatus::Cancelled {
+            return Ok(env.storage().persistent().get(&DataKey::StreamHeld(stream_id)).unwrap_or(0));
+        }
+        if stream.status != StreamStatus::Active {
+            return Ok(0);
+        }
+        Ok(vested_amount(&stream, env.ledger().timestamp()) - stream.withdrawn)
+    }
+}
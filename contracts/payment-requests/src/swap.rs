@@ -0,0 +1,113 @@
+use soroban_sdk::{contractimpl, symbol_short, token::Client as TokenClient, Address, Env, log};
+
+use crate::{DataKey, PaymentAmount, PaymentContract, PaymentContractClient};
+use payment_core::{Error, PaymentRequest, PaymentStatus, RequestKind, SwapAdapterClient};
+
+#[contractimpl]
+impl PaymentContract {
+    /// Set the swap adapter used for cross-currency payments (contract owner only)
+    pub fn set_swap_adapter(env: Env, caller: Address, adapter: Address) -> Result<(), Error> {
+        caller.require_auth();
+
+        let contract_owner: Address = env.storage()
+            .instance()
+            .get(&DataKey::ContractOwner)
+            .ok_or(Error::ContractNotInitialized)?;
+
+        if caller != contract_owner {
+            return Err(Error::NotAuthorized);
+        }
+
+        env.storage().instance().set(&DataKey::SwapAdapter, &adapter);
+        Ok(())
+    }
+
+    /// Get the configured swap adapter
+    pub fn get_swap_adapter(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::SwapAdapter)
+    }
+
+    /// Pay the outstanding balance of a request in `source_token` while the
+    /// requester receives `receive_token`. The payer spends at most
+    /// `max_source_amount` and the call fails once `deadline` has passed.
+    /// Returns the amount of `source_token` spent, which is zero when the
+    /// payment is blocked by compliance screening.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_swap_payment(
+        env: Env,
+        payment_id: u64,
+        payer: Address,
+        source_token: Address,
+        receive_token: Address,
+        max_source_amount: i128,
+        deadline: u64,
+    ) -> Result<i128, Error> {
+        payer.require_auth();
+
+        if env.ledger().timestamp() > deadline {
+            return Err(Error::DeadlineExpired);
+        }
+
+        let payment_request: PaymentRequest = Self::get_payment_request(env.clone(), payment_id)?;
+
+        // Only the fixed outstanding balance can be quoted up front
+        match payment_request.status {
+            PaymentStatus::Pending | PaymentStatus::PartiallyPaid => {},
+            PaymentStatus::Completed => return Err(Error::PaymentAlreadyCompleted),
+            _ => return Err(Error::PaymentNotFound),
+        }
+        if let RequestKind::OpenAmount(_) | RequestKind::FiatPriced = payment_request.kind {
+            return Err(Error::InvalidRequestKind);
+        }
+        let amount_out = payment_request.amount - payment_request.amount_paid;
+
+        // A blocked payment must be caught before the swap moves any funds
+        let business_config = Self::get_business_config(env.clone(), payment_request.business_name.clone())?;
+        if !Self::screen_payment(&env, &payment_request, &payer, &business_config) {
+            return Ok(0);
+        }
+
+        let adapter_address: Address = env.storage()
+            .instance()
+            .get(&DataKey::SwapAdapter)
+            .ok_or(Error::SwapAdapterNotSet)?;
+        let adapter = SwapAdapterClient::new(&env, &adapter_address);
+
+        // Enforce the payer's slippage bound on the quote
+        let amount_in = adapter.quote_in(&source_token, &receive_token, &amount_out);
+        if amount_in <= 0 || amount_in > max_source_amount {
+            return Err(Error::SlippageExceeded);
+        }
+
+        // The contract also holds other businesses' proceeds and dispute
+        // escrow, so only what the swap actually delivered is counted
+        let contract_address = env.current_contract_address();
+        let receive_client = TokenClient::new(&env, &receive_token);
+        let balance_before = receive_client.balance(&contract_address);
+        TokenClient::new(&env, &source_token).transfer(&payer, &adapter_address, &amount_in);
+        adapter.swap(&source_token, &receive_token, &amount_in, &amount_out, &contract_address);
+        let received = receive_client.balance(&contract_address) - balance_before;
+        if received < amount_out {
+            return Err(Error::SlippageExceeded);
+        }
+
+        // Screening passed above, so this only fails if the decision changed
+        if !Self::settle_payment(&env, payment_id, &payer, &contract_address, &receive_token, PaymentAmount::Full)? {
+            return Err(Error::AddressBlocked);
+        }
+
+        // Anything the pool returned above the quote belongs to the payer
+        let surplus = received - amount_out;
+        if surplus > 0 {
+            receive_client.transfer(&contract_address, &payer, &surplus);
+        }
+
+        env.events().publish(
+            (symbol_short!("payment"), symbol_short!("swap")),
+            (payment_id, source_token, amount_in, receive_token, amount_out)
+        );
+
+        log!(&env, "Swap payment {} executed for {} source tokens", payment_id, amount_in);
+        Ok(amount_in)
+    }
+}
@@ -0,0 +1,78 @@
+//! Contract, business and funded payer shared by the integration tests.
+#![allow(dead_code)]
+
+use payment_requests::{PaymentContract, PaymentContractClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env, String,
+};
+
+pub struct Setup<'a> {
+    pub env: Env,
+    pub client: PaymentContractClient<'a>,
+    pub token: TokenClient<'a>,
+    pub owner: Address,
+    pub business_name: String,
+    pub business_owner: Address,
+    pub fee_recipient: Address,
+    pub payer: Address,
+    pub requester: Address,
+}
+
+impl<'a> Setup<'a> {
+    /// An initialized contract at timestamp 10_000 with "Test Store"
+    /// charging `business_fee` basis points, and a payer holding 100_000
+    /// of a token the contract owner issues
+    pub fn new(business_fee: u32) -> Setup<'a> {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+
+        env.mock_all_auths();
+        env.ledger().set_timestamp(10_000);
+
+        let owner = Address::generate(&env);
+        client.initialize(&owner, &250u32, &Address::generate(&env));
+
+        let business_name = String::from_str(&env, "Test Store");
+        let business_owner = Address::generate(&env);
+        let fee_recipient = Address::generate(&env);
+        client.register_business(&business_name, &business_owner, &fee_recipient, &business_fee);
+
+        let token = TokenClient::new(&env, &env.register_stellar_asset_contract_v2(owner.clone()).address());
+        let payer = Address::generate(&env);
+        let requester = Address::generate(&env);
+
+        let setup = Setup {
+            env,
+            client,
+            token,
+            owner,
+            business_name,
+            business_owner,
+            fee_recipient,
+            payer,
+            requester,
+        };
+        setup.mint(&setup.payer, 100_000);
+        setup
+    }
+
+    pub fn mint(&self, to: &Address, amount: i128) {
+        StellarAssetClient::new(&self.env, &self.token.address).mint(to, &amount);
+    }
+
+    /// A fixed-amount request of the store that only the payer may pay
+    pub fn create_request(&self, amount: i128) -> u64 {
+        self.client.create_payment_request(
+            &amount,
+            &self.business_name,
+            &String::from_str(&self.env, "Order"),
+            &String::from_str(&self.env, "USDC"),
+            &vec![&self.env, self.payer.clone()],
+            &self.requester,
+            &None,
+        )
+    }
+}
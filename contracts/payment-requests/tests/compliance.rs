@@ -0,0 +1,257 @@
+mod common;
+
+use common::Setup;
+use payment_requests::{BlockedPayment, ComplianceConfig, ComplianceProvider, Error, PaymentStatus};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Events, Ledger},
+    vec, Address, Env, IntoVal, String,
+};
+
+/// Stand-in for an external screening service that counts its lookups.
+#[contract]
+pub struct TestScreen;
+
+#[contractimpl]
+impl TestScreen {
+    pub fn block(env: Env, address: Address) {
+        env.storage().instance().set(&address, &true);
+    }
+
+    pub fn lookups(env: Env) -> u32 {
+        env.storage().instance().get(&symbol_short!("lookups")).unwrap_or(0)
+    }
+
+    pub fn is_allowed(env: Env, address: Address) -> bool {
+        let lookups = Self::lookups(env.clone()) + 1;
+        env.storage().instance().set(&symbol_short!("lookups"), &lookups);
+        !env.storage().instance().has(&address)
+    }
+}
+
+fn blocked_address(setup: &Setup, payment_id: u64) -> Option<Address> {
+    setup.client.get_blocked_payment(&payment_id).map(|blocked| blocked.blocked_address)
+}
+
+#[test]
+fn test_on_contract_denylist() {
+    let setup = Setup::new(0);
+    let Setup { env, client, token, owner, payer, requester, .. } = &setup;
+
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    });
+    client.set_denylisted(owner, payer, &true);
+
+    // The attempt is recorded instead of failing, and nothing is paid
+    let payment_id = setup.create_request(1_000);
+    client.execute_payment(&payment_id, payer, &token.address);
+    assert_eq!(token.balance(payer), 100_000);
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Pending);
+    assert_eq!(blocked_address(&setup, payment_id), Some(payer.clone()));
+
+    // Sanctioned merchants cannot receive funds either
+    client.set_denylisted(owner, payer, &false);
+    client.set_denylisted(owner, requester, &true);
+    client.execute_payment(&payment_id, payer, &token.address);
+    assert_eq!(token.balance(payer), 100_000);
+    assert_eq!(blocked_address(&setup, payment_id), Some(requester.clone()));
+
+    client.set_denylisted(owner, requester, &false);
+    client.execute_payment(&payment_id, payer, &token.address);
+    assert_eq!(token.balance(requester), 1_000);
+
+    // Streams are screened too
+    let stranger = Address::generate(env);
+    client.set_denylisted(owner, &stranger, &true);
+    let result = client.try_create_stream(payer, &stranger, &token.address, &1_000, &10_000, &10_000, &20_000);
+    assert_eq!(result, Err(Ok(Error::AddressBlocked)));
+}
+
+#[test]
+fn test_fee_recipient_screened() {
+    let setup = Setup::new(0);
+    let Setup { env, client, token, owner, payer, requester, .. } = &setup;
+
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    });
+    let business_name = String::from_str(env, "Fee Store");
+    let fee_recipient = Address::generate(env);
+    client.register_business(&business_name, &Address::generate(env), &fee_recipient, &100u32);
+    client.set_denylisted(owner, &fee_recipient, &true);
+
+    let payment_id = client.create_payment_request(
+        &1_000,
+        &business_name,
+        &String::from_str(env, "Order"),
+        &String::from_str(env, "USDC"),
+        &vec![env, payer.clone()],
+        requester,
+        &None,
+    );
+    client.execute_payment(&payment_id, payer, &token.address);
+    assert_eq!(token.balance(payer), 100_000);
+    assert_eq!(blocked_address(&setup, payment_id), Some(fee_recipient.clone()));
+
+    client.set_denylisted(owner, &fee_recipient, &false);
+    client.execute_payment(&payment_id, payer, &token.address);
+    assert_eq!(token.balance(&fee_recipient), 10);
+}
+
+#[test]
+fn test_cancel_stream_screened() {
+    let setup = Setup::new(0);
+    let Setup { env, client, token, owner, payer, .. } = &setup;
+
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    });
+    let recipient = Address::generate(env);
+    let stream_id = client.create_stream(payer, &recipient, &token.address, &1_000, &10_000, &10_000, &20_000);
+    env.ledger().set_timestamp(15_000);
+
+    // A sender blocked after the stream started cannot reclaim the rest
+    client.set_denylisted(owner, payer, &true);
+    let result = client.try_cancel_stream(&stream_id);
+    assert_eq!(result, Err(Ok(Error::AddressBlocked)));
+    assert_eq!(token.balance(payer), 99_000);
+
+    // A blocked recipient does not stop the refund; what vested is held back
+    client.set_denylisted(owner, payer, &false);
+    client.set_denylisted(owner, &recipient, &true);
+    assert_eq!(client.cancel_stream(&stream_id), 500);
+    assert_eq!(token.balance(payer), 99_500);
+    assert_eq!(token.balance(&recipient), 0);
+    assert_eq!(token.balance(&client.address), 500);
+    assert_eq!(client.get_stream_balance(&stream_id), 500);
+    let result = client.try_withdraw_from_stream(&stream_id);
+    assert_eq!(result, Err(Ok(Error::AddressBlocked)));
+
+    // Once cleared, the recipient collects the held amount exactly once
+    client.set_denylisted(owner, &recipient, &false);
+    assert_eq!(client.withdraw_from_stream(&stream_id), 500);
+    assert_eq!(token.balance(&recipient), 500);
+    assert_eq!(client.get_stream_balance(&stream_id), 0);
+    let result = client.try_withdraw_from_stream(&stream_id);
+    assert_eq!(result, Err(Ok(Error::StreamNotActive)));
+}
+
+#[test]
+fn test_allowlist_required() {
+    let setup = Setup::new(0);
+    let Setup { client, token, owner, payer, requester, .. } = &setup;
+
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: true,
+    });
+    client.set_allowlisted(owner, payer, &true);
+
+    let payment_id = setup.create_request(1_000);
+    client.execute_payment(&payment_id, payer, &token.address);
+    assert_eq!(blocked_address(&setup, payment_id), Some(requester.clone()));
+
+    client.set_allowlisted(owner, requester, &true);
+    client.execute_payment(&payment_id, payer, &token.address);
+    assert_eq!(token.balance(payer), 99_000);
+}
+
+#[test]
+fn test_external_provider_cached_per_ledger() {
+    let setup = Setup::new(0);
+    let Setup { env, client, token, owner, payer, requester, .. } = &setup;
+
+    let screen = TestScreenClient::new(env, &env.register_contract(None, TestScreen));
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::External(screen.address.clone()),
+        require_allowlist: false,
+    });
+
+    // Payer and requester are each looked up once per ledger
+    let first = setup.create_request(1_000);
+    let second = setup.create_request(1_000);
+    client.execute_payment(&first, payer, &token.address);
+    client.execute_payment(&second, payer, &token.address);
+    assert_eq!(screen.lookups(), 2);
+
+    env.ledger().set_sequence_number(env.ledger().sequence() + 1);
+    screen.block(requester);
+    let third = setup.create_request(1_000);
+    client.execute_payment(&third, payer, &token.address);
+    assert_eq!(blocked_address(&setup, third), Some(requester.clone()));
+    assert_eq!(token.balance(payer), 98_000);
+}
+
+#[test]
+fn test_config_change_clears_cache() {
+    let setup = Setup::new(0);
+    let Setup { client, token, owner, payer, requester, .. } = &setup;
+
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    });
+    assert!(client.check_compliance(payer));
+
+    // A stricter config applies within the ledger the earlier decision was made
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: true,
+    });
+    assert!(!client.check_compliance(payer));
+
+    let payment_id = setup.create_request(1_000);
+    client.execute_payment(&payment_id, payer, &token.address);
+    assert_eq!(blocked_address(&setup, payment_id), Some(payer.clone()));
+    assert_eq!(token.balance(requester), 0);
+}
+
+#[test]
+fn test_blocked_event() {
+    let setup = Setup::new(0);
+    let Setup { env, client, token, owner, payer, requester, .. } = &setup;
+
+    assert!(client.check_compliance(payer));
+
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    });
+    client.set_denylisted(owner, requester, &true);
+    assert!(!client.check_compliance(requester));
+
+    // The event survives because the blocked payment does not fail the call
+    let payment_id = setup.create_request(1_000);
+    env.ledger().set_timestamp(12_000);
+    client.execute_payment(&payment_id, payer, &token.address);
+
+    let topics = (symbol_short!("payment"), symbol_short!("blocked"));
+    let events = env.events().all();
+    assert_eq!(
+        events.slice(events.len() - 1..),
+        vec![
+            env,
+            (
+                client.address.clone(),
+                topics.into_val(env),
+                (payment_id, payer.clone(), requester.clone()).into_val(env)
+            )
+        ]
+    );
+    assert_eq!(
+        client.get_blocked_payment(&payment_id),
+        Some(BlockedPayment {
+            payer: payer.clone(),
+            blocked_address: requester.clone(),
+            ledger: env.ledger().sequence(),
+            timestamp: 12_000,
+        })
+    );
+
+    let result = client.try_set_denylisted(&Address::generate(env), payer, &false);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+}
@@ -0,0 +1,372 @@
+mod common;
+
+use common::Setup;
+use payment_requests::{
+    ComplianceConfig, ComplianceProvider, DisputeConfig, DisputeOutcome, DisputeStatus, Error,
+    PaymentStatus, SettlementConfig,
+};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, Env, String, Vec,
+};
+
+// The requester also holds funds to refund from its own wallet
+fn setup<'a>() -> Setup<'a> {
+    let setup = Setup::new(100);
+    let Setup { env, client, owner, requester, .. } = &setup;
+    client.set_dispute_config(
+        owner,
+        &DisputeConfig {
+            arbiter: Address::generate(env),
+            response_period: 3_600,
+            ruling_period: 7_200,
+            hold_period: 0,
+        },
+    );
+    setup.mint(requester, 100_000);
+    setup
+}
+
+fn pay(setup: &Setup, amount: i128) -> u64 {
+    let payment_id = setup.create_request(amount);
+    setup.client.execute_payment(&payment_id, &setup.payer, &setup.token.address);
+    payment_id
+}
+
+fn evidence(env: &Env, byte: u8) -> BytesN<32> {
+    BytesN::from_array(env, &[byte; 32])
+}
+
+#[test]
+fn test_arbiter_partial_refund() {
+    let setup = setup();
+    let Setup { env, client, token, payer, requester, .. } = &setup;
+
+    let payment_id = pay(&setup, 1_000);
+    client.open_dispute(&payment_id, payer, &evidence(env, 1));
+
+    let dispute = client.get_dispute(&payment_id, payer);
+    assert_eq!(dispute.status, DisputeStatus::Open);
+    assert_eq!(dispute.disputed_amount, 1_000);
+    assert_eq!(dispute.response_deadline, 10_000 + 3_600);
+
+    env.ledger().set_timestamp(11_000);
+    client.respond_to_dispute(&payment_id, payer, &evidence(env, 2));
+    let dispute = client.get_dispute(&payment_id, payer);
+    assert_eq!(dispute.status, DisputeStatus::Responded);
+    assert_eq!(dispute.merchant_evidence, evidence(env, 2));
+    assert_eq!(dispute.ruling_deadline, 11_000 + 7_200);
+
+    client.resolve_dispute(&payment_id, payer, &DisputeOutcome::PartialRefund(400));
+    let dispute = client.get_dispute(&payment_id, payer);
+    assert_eq!(dispute.status, DisputeStatus::Resolved(DisputeOutcome::PartialRefund(400)));
+    assert_eq!(dispute.refund_amount, 400);
+
+    // Proceeds already left the contract, so the merchant owes the refund
+    assert_eq!(dispute.refund_paid, 0);
+    client.pay_dispute_refund(&payment_id, payer, requester);
+    assert_eq!(client.get_dispute(&payment_id, payer).refund_paid, 400);
+    assert_eq!(token.balance(payer), 100_000 - 1_000 + 400);
+    assert_eq!(token.balance(requester), 100_000 + 990 - 400);
+    assert_eq!(client.get_payment_refunds(&payment_id).get(payer.clone()), Some(400));
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Completed);
+
+    let result = client.try_pay_dispute_refund(&payment_id, payer, requester);
+    assert_eq!(result, Err(Ok(Error::InvalidDisputeStatus)));
+}
+
+#[test]
+fn test_unanswered_dispute_defaults_to_refund() {
+    let setup = setup();
+    let Setup { env, client, token, payer, requester, .. } = &setup;
+
+    let payment_id = pay(&setup, 1_000);
+    client.open_dispute(&payment_id, payer, &evidence(env, 1));
+
+    let result = client.try_finalize_dispute(&payment_id, payer);
+    assert_eq!(result, Err(Ok(Error::DisputeDeadlineNotReached)));
+
+    env.ledger().set_timestamp(10_000 + 3_601);
+    let result = client.try_respond_to_dispute(&payment_id, payer, &evidence(env, 2));
+    assert_eq!(result, Err(Ok(Error::DeadlineExpired)));
+    let result = client.try_resolve_dispute(&payment_id, payer, &DisputeOutcome::Rejected);
+    assert_eq!(result, Err(Ok(Error::DeadlineExpired)));
+
+    client.finalize_dispute(&payment_id, payer);
+    let dispute = client.get_dispute(&payment_id, payer);
+    assert_eq!(dispute.status, DisputeStatus::Resolved(DisputeOutcome::Refund));
+    assert_eq!(dispute.refund_amount, 1_000);
+
+    client.pay_dispute_refund(&payment_id, payer, requester);
+    assert_eq!(token.balance(payer), 100_000);
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Refunded);
+
+    let result = client.try_finalize_dispute(&payment_id, payer);
+    assert_eq!(result, Err(Ok(Error::InvalidDisputeStatus)));
+}
+
+#[test]
+fn test_missed_ruling_defaults_to_rejected() {
+    let setup = setup();
+    let Setup { env, client, token, payer, requester, .. } = &setup;
+
+    let payment_id = pay(&setup, 1_000);
+    client.open_dispute(&payment_id, payer, &evidence(env, 1));
+    client.respond_to_dispute(&payment_id, payer, &evidence(env, 2));
+
+    env.ledger().set_timestamp(10_000 + 7_201);
+    client.finalize_dispute(&payment_id, payer);
+
+    let dispute = client.get_dispute(&payment_id, payer);
+    assert_eq!(dispute.status, DisputeStatus::Resolved(DisputeOutcome::Rejected));
+    assert_eq!(dispute.refund_amount, 0);
+    assert_eq!(token.balance(payer), 99_000);
+
+    let result = client.try_pay_dispute_refund(&payment_id, payer, requester);
+    assert_eq!(result, Err(Ok(Error::InvalidDisputeStatus)));
+}
+
+#[test]
+fn test_refund_from_held_proceeds() {
+    let setup = setup();
+    let Setup { env, client, token, business_name, business_owner, payer, requester, .. } = &setup;
+
+    client.set_settlement_config(
+        business_name,
+        &SettlementConfig {
+            enabled: true,
+            payout_address: Address::generate(env),
+            interval: 86_400,
+            threshold: 5_000,
+        },
+        business_owner,
+    );
+
+    let payment_id = pay(&setup, 1_000);
+    client.open_dispute(&payment_id, payer, &evidence(env, 1));
+    client.resolve_dispute(&payment_id, payer, &DisputeOutcome::PartialRefund(300));
+
+    // Refunded straight from the balance the contract holds for the business
+    let dispute = client.get_dispute(&payment_id, payer);
+    assert_eq!(dispute.refund_paid, 300);
+    assert_eq!(token.balance(payer), 99_300);
+    assert_eq!(token.balance(requester), 100_000);
+    assert_eq!(client.get_merchant_balance(business_name, &token.address), 690);
+}
+
+#[test]
+fn test_escrow_survives_merchant_withdrawal() {
+    let setup = setup();
+    let Setup { env, client, token, business_name, business_owner, payer, requester, .. } = &setup;
+
+    let payout_address = Address::generate(env);
+    client.set_settlement_config(
+        business_name,
+        &SettlementConfig {
+            enabled: true,
+            payout_address: payout_address.clone(),
+            interval: 86_400,
+            threshold: 500,
+        },
+        business_owner,
+    );
+
+    // Two payments net of the 1% fee are held; the disputed one's proceeds
+    // are escrowed, and nothing from the other payment
+    let disputed = pay(&setup, 1_000);
+    pay(&setup, 1_000);
+    client.open_dispute(&disputed, payer, &evidence(env, 1));
+    assert_eq!(client.get_dispute(&disputed, payer).escrowed_amount, 990);
+    assert_eq!(client.get_merchant_balance(business_name, &token.address), 990);
+    assert_eq!(client.get_held_proceeds(&disputed), 0);
+
+    // The merchant withdraws everything it can and never answers
+    client.settle(business_name, &token.address);
+    assert_eq!(token.balance(&payout_address), 990);
+    assert_eq!(client.get_merchant_balance(business_name, &token.address), 0);
+
+    env.ledger().set_timestamp(10_000 + 3_601);
+    client.finalize_dispute(&disputed, payer);
+
+    // The default refund is paid from the escrow without the requester
+    let dispute = client.get_dispute(&disputed, payer);
+    assert_eq!(dispute.refund_amount, 1_000);
+    assert_eq!(dispute.refund_paid, 990);
+    assert_eq!(dispute.escrowed_amount, 0);
+    assert_eq!(token.balance(payer), 100_000 - 2_000 + 990);
+    assert_eq!(token.balance(&client.address), 0);
+
+    // The fee part is owed by the requester, and only they can pay it
+    let result = client.try_pay_dispute_refund(&disputed, payer, payer);
+    assert_eq!(result, Err(Ok(Error::InsufficientBalance)));
+    client.pay_dispute_refund(&disputed, payer, requester);
+    assert_eq!(token.balance(payer), 100_000 - 1_000);
+    assert_eq!(token.balance(requester), 100_000 - 10);
+    assert_eq!(client.get_payment_request(&disputed).status, PaymentStatus::Refunded);
+
+    let result = client.try_pay_dispute_refund(&disputed, payer, requester);
+    assert_eq!(result, Err(Ok(Error::InvalidDisputeStatus)));
+}
+
+#[test]
+fn test_proceeds_held_for_dispute_window() {
+    let setup = setup();
+    let Setup { env, client, token, owner, payer, requester, .. } = &setup;
+
+    let arbiter = client.get_dispute_config().unwrap().arbiter;
+    client.set_dispute_config(
+        owner,
+        &DisputeConfig {
+            arbiter: arbiter.clone(),
+            response_period: 3_600,
+            ruling_period: 7_200,
+            hold_period: 86_400,
+        },
+    );
+
+    // A merchant without settlement is paid once the window has passed
+    let disputed = pay(&setup, 1_000);
+    let undisputed = pay(&setup, 1_000);
+    assert_eq!(token.balance(requester), 100_000);
+    assert_eq!(client.get_held_proceeds(&disputed), 990);
+    let result = client.try_release_proceeds(&undisputed);
+    assert_eq!(result, Err(Ok(Error::SettlementNotDue)));
+
+    // A blocked payer keeps the escrow until cleared, then anyone involved
+    // can pay it out without the requester
+    client.open_dispute(&disputed, payer, &evidence(env, 1));
+    client.set_compliance_config(owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    });
+    client.set_denylisted(owner, payer, &true);
+    client.resolve_dispute(&disputed, payer, &DisputeOutcome::PartialRefund(400));
+    assert_eq!(client.get_dispute(&disputed, payer).escrowed_amount, 400);
+    client.set_denylisted(owner, payer, &false);
+    client.pay_dispute_refund(&disputed, payer, &arbiter);
+    assert_eq!(client.get_dispute(&disputed, payer).refund_paid, 400);
+    assert_eq!(token.balance(payer), 100_000 - 2_000 + 400);
+
+    let result = client.try_pay_dispute_refund(&disputed, payer, payer);
+    assert_eq!(result, Err(Ok(Error::InvalidDisputeStatus)));
+
+    // Refunds only draw on what is held for that request; the rest comes
+    // from the requester even though the contract holds other proceeds
+    client.refund_payment(&disputed, payer, &600);
+    assert_eq!(token.balance(requester), 100_000 - 600);
+    assert_eq!(client.get_held_proceeds(&disputed), 590);
+    assert_eq!(client.get_held_proceeds(&undisputed), 990);
+
+    env.ledger().set_timestamp(10_000 + 86_400);
+    assert_eq!(client.release_proceeds(&disputed), 590);
+    assert_eq!(client.release_proceeds(&undisputed), 990);
+    assert_eq!(client.release_proceeds(&undisputed), 0);
+    assert_eq!(token.balance(requester), 100_000 - 600 + 590 + 990);
+    assert_eq!(token.balance(&client.address), 0);
+}
+
+#[test]
+fn test_each_payer_disputes_their_own_share() {
+    let setup = setup();
+    let Setup { env, client, token, business_name, payer, requester, .. } = &setup;
+
+    let other_payer = Address::generate(env);
+    setup.mint(&other_payer, 100_000);
+    let mut authorized_addresses = Vec::new(env);
+    authorized_addresses.push_back(payer.clone());
+    authorized_addresses.push_back(other_payer.clone());
+    let payment_id = client.create_payment_request(
+        &1_000,
+        business_name,
+        &String::from_str(env, "Order"),
+        &String::from_str(env, "USDC"),
+        &authorized_addresses,
+        requester,
+        &None,
+    );
+    client.execute_partial_payment(&payment_id, payer, &token.address, &600);
+    client.execute_partial_payment(&payment_id, &other_payer, &token.address, &400);
+
+    // One payer's dispute does not stop the other from opening theirs
+    client.open_dispute(&payment_id, payer, &evidence(env, 1));
+    client.open_dispute(&payment_id, &other_payer, &evidence(env, 2));
+    assert_eq!(client.get_dispute(&payment_id, payer).disputed_amount, 600);
+    assert_eq!(client.get_dispute(&payment_id, &other_payer).disputed_amount, 400);
+
+    // A full refund only returns what that payer contributed
+    client.resolve_dispute(&payment_id, &other_payer, &DisputeOutcome::Refund);
+    client.pay_dispute_refund(&payment_id, &other_payer, requester);
+    assert_eq!(token.balance(&other_payer), 100_000);
+    assert_eq!(token.balance(payer), 99_400);
+    assert_eq!(client.get_dispute(&payment_id, payer).status, DisputeStatus::Open);
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Completed);
+
+    let result = client.try_resolve_dispute(&payment_id, payer, &DisputeOutcome::PartialRefund(601));
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_voluntary_refund() {
+    let setup = setup();
+    let Setup { env, client, token, payer, .. } = &setup;
+
+    let payment_id = pay(&setup, 1_000);
+    client.refund_payment(&payment_id, payer, &600);
+    assert_eq!(token.balance(payer), 99_600);
+
+    let result = client.try_refund_payment(&payment_id, payer, &401);
+    assert_eq!(result, Err(Ok(Error::RefundExceedsPayment)));
+    let result = client.try_refund_payment(&payment_id, &Address::generate(env), &1);
+    assert_eq!(result, Err(Ok(Error::RefundExceedsPayment)));
+
+    // Only the unrefunded part can be disputed, and a full refund caps the ruling
+    client.open_dispute(&payment_id, payer, &evidence(env, 1));
+    assert_eq!(client.get_dispute(&payment_id, payer).disputed_amount, 400);
+    client.refund_payment(&payment_id, payer, &400);
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Refunded);
+
+    client.resolve_dispute(&payment_id, payer, &DisputeOutcome::Refund);
+    assert_eq!(client.get_dispute(&payment_id, payer).refund_amount, 0);
+    assert_eq!(token.balance(payer), 100_000);
+}
+
+#[test]
+fn test_dispute_errors() {
+    let setup = setup();
+    let Setup { env, client, payer, .. } = &setup;
+
+    let result = client.try_open_dispute(&99, payer, &evidence(env, 1));
+    assert_eq!(result, Err(Ok(Error::PaymentNotFound)));
+    let result = client.try_get_dispute(&99, payer);
+    assert_eq!(result, Err(Ok(Error::DisputeNotFound)));
+
+    let payment_id = pay(&setup, 1_000);
+    let result = client.try_open_dispute(&payment_id, &Address::generate(env), &evidence(env, 1));
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    client.open_dispute(&payment_id, payer, &evidence(env, 1));
+    let result = client.try_open_dispute(&payment_id, payer, &evidence(env, 1));
+    assert_eq!(result, Err(Ok(Error::DisputeAlreadyExists)));
+
+    let result = client.try_resolve_dispute(&payment_id, payer, &DisputeOutcome::PartialRefund(1_001));
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+}
+
+#[test]
+fn test_disputes_require_config() {
+    let Setup { env, client, .. } = Setup::new(100);
+
+    let result = client.try_open_dispute(&1, &Address::generate(&env), &evidence(&env, 1));
+    assert_eq!(result, Err(Ok(Error::DisputeNotConfigured)));
+
+    let result = client.try_set_dispute_config(
+        &Address::generate(&env),
+        &DisputeConfig {
+            arbiter: Address::generate(&env),
+            response_period: 3_600,
+            ruling_period: 7_200,
+            hold_period: 0,
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+}
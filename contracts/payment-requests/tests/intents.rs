@@ -0,0 +1,171 @@
+mod common;
+
+use ed25519_dalek::{Signer, SigningKey};
+use common::Setup;
+use payment_requests::{intent_message, AmountBounds, Error, PaymentIntent, PaymentStatus};
+use rand::rngs::OsRng;
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, BytesN, String, Vec,
+};
+
+// One-time wallet session: the payer registers the returned signing key
+// and approves the contract
+fn setup<'a>() -> (Setup<'a>, SigningKey) {
+    let setup = Setup::new(0);
+    let Setup { env, client, token, payer, .. } = &setup;
+
+    let signing_key = SigningKey::generate(&mut OsRng);
+    client.set_intent_key(payer, &BytesN::from_array(env, &signing_key.verifying_key().to_bytes()));
+    token.approve(payer, &client.address, &50_000, &1_000);
+    (setup, signing_key)
+}
+
+fn intent(setup: &Setup, payment_id: u64, amount: i128, nonce: u64) -> PaymentIntent {
+    PaymentIntent {
+        payment_id,
+        payer: setup.payer.clone(),
+        token_address: setup.token.address.clone(),
+        amount,
+        nonce,
+        expiry: 20_000,
+    }
+}
+
+fn sign(setup: &Setup, key: &SigningKey, intent: &PaymentIntent) -> BytesN<64> {
+    let message = intent_message(&setup.env, &setup.client.address, intent);
+    let message: std::vec::Vec<u8> = message.iter().collect();
+    BytesN::from_array(&setup.env, &key.sign(&message).to_bytes())
+}
+
+#[test]
+fn test_relayed_signed_payment() {
+    let (setup, signing_key) = setup();
+    let Setup { env, client, token, payer, requester, .. } = &setup;
+
+    let payment_id = setup.create_request(1_000);
+    let intent = intent(&setup, payment_id, 1_000, 0);
+    let signature = sign(&setup, &signing_key, &intent);
+
+    // Only the relayer's transaction is needed from here on
+    env.set_auths(&[]);
+    client.execute_payment_with_signature(&intent, &signature);
+
+    assert_eq!(token.balance(payer), 99_000);
+    assert_eq!(token.balance(requester), 1_000);
+    assert_eq!(token.allowance(payer, &client.address), 49_000);
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Completed);
+    assert_eq!(client.get_intent_nonce(payer), 1);
+
+    // Replaying the same intent is rejected
+    let result = client.try_execute_payment_with_signature(&intent, &signature);
+    assert_eq!(result, Err(Ok(Error::InvalidNonce)));
+}
+
+#[test]
+fn test_installments_and_open_amounts() {
+    let (setup, signing_key) = setup();
+    let Setup { env, client, token, business_name, payer, requester, .. } = &setup;
+
+    let payment_id = setup.create_request(1_000);
+    for nonce in 0..2 {
+        let intent = intent(&setup, payment_id, 500, nonce);
+        client.execute_payment_with_signature(&intent, &sign(&setup, &signing_key, &intent));
+    }
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Completed);
+
+    let mut authorized_addresses = Vec::new(env);
+    authorized_addresses.push_back(payer.clone());
+    let open_id = client.create_open_payment_request(
+        business_name,
+        &String::from_str(env, "Donation"),
+        &String::from_str(env, "USDC"),
+        &authorized_addresses,
+        requester,
+        &None,
+        &AmountBounds { min_amount: Some(100), max_amount: None },
+    );
+    let intent = intent(&setup, open_id, 750, 2);
+    client.execute_payment_with_signature(&intent, &sign(&setup, &signing_key, &intent));
+    assert_eq!(client.get_payment_request(&open_id).amount_paid, 750);
+    assert_eq!(token.balance(requester), 1_750);
+}
+
+#[test]
+fn test_tippable_request() {
+    let (setup, signing_key) = setup();
+    let Setup { env, client, token, business_name, payer, requester, .. } = &setup;
+
+    let payment_id = client.create_tippable_request(
+        &1_000,
+        business_name,
+        &String::from_str(env, "Dinner"),
+        &String::from_str(env, "USDC"),
+        &soroban_sdk::vec![env, payer.clone()],
+        requester,
+        &None,
+    );
+
+    // The signed amount must cover the request itself
+    let short = intent(&setup, payment_id, 900, 0);
+    let result = client.try_execute_payment_with_signature(&short, &sign(&setup, &signing_key, &short));
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    // Whatever it signs above the request is the tip
+    let intent = intent(&setup, payment_id, 1_200, 0);
+    client.execute_payment_with_signature(&intent, &sign(&setup, &signing_key, &intent));
+
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.status, PaymentStatus::Completed);
+    assert_eq!(payment_request.tip_amount, 200);
+    assert_eq!(token.balance(payer), 98_800);
+    assert_eq!(token.balance(requester), 1_200);
+}
+
+#[test]
+fn test_intent_errors() {
+    let (setup, signing_key) = setup();
+    let Setup { env, client, token, payer, .. } = &setup;
+
+    let payment_id = setup.create_request(1_000);
+
+    let future = intent(&setup, payment_id, 1_000, 1);
+    let result = client.try_execute_payment_with_signature(&future, &sign(&setup, &signing_key, &future));
+    assert_eq!(result, Err(Ok(Error::InvalidNonce)));
+
+    let mut over_allowance = intent(&setup, payment_id, 1_000, 0);
+    over_allowance.amount = 60_000;
+    let signature = sign(&setup, &signing_key, &over_allowance);
+    let result = client.try_execute_payment_with_signature(&over_allowance, &signature);
+    assert_eq!(result, Err(Ok(Error::InsufficientAllowance)));
+
+    let overpay = intent(&setup, payment_id, 1_001, 0);
+    let result = client.try_execute_payment_with_signature(&overpay, &sign(&setup, &signing_key, &overpay));
+    assert_eq!(result, Err(Ok(Error::Overpayment)));
+
+    let valid = intent(&setup, payment_id, 1_000, 0);
+    let signature = sign(&setup, &signing_key, &valid);
+
+    // A tampered amount no longer matches the signature
+    let mut tampered = valid.clone();
+    tampered.amount = 999;
+    assert!(client.try_execute_payment_with_signature(&tampered, &signature).is_err());
+
+    // Signed by a key the payer never registered
+    let other_key = SigningKey::generate(&mut OsRng);
+    let forged = sign(&setup, &other_key, &valid);
+    assert!(client.try_execute_payment_with_signature(&valid, &forged).is_err());
+
+    env.ledger().set_timestamp(20_001);
+    let result = client.try_execute_payment_with_signature(&valid, &signature);
+    assert_eq!(result, Err(Ok(Error::DeadlineExpired)));
+
+    let mut stranger = valid.clone();
+    stranger.payer = Address::generate(env);
+    let result = client.try_execute_payment_with_signature(&stranger, &signature);
+    assert_eq!(result, Err(Ok(Error::IntentKeyNotSet)));
+
+    // Failed attempts consume nothing
+    assert_eq!(client.get_intent_nonce(payer), 0);
+    assert_eq!(token.balance(payer), 100_000);
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::Setup;
+use payment_requests::{Error, SpendingLimits};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, String,
+};
+
+const DAY: u64 = 86_400;
+
+fn pay(setup: &Setup, payer: &Address, amount: i128) -> Result<u64, Error> {
+    let Setup { env, client, token, business_name, .. } = setup;
+    let payment_id = client.create_payment_request(
+        &amount,
+        business_name,
+        &String::from_str(env, "Order"),
+        &String::from_str(env, "USDC"),
+        &vec![env, payer.clone()],
+        &Address::generate(env),
+        &None,
+    );
+    match client.try_execute_payment(&payment_id, payer, &token.address) {
+        Ok(_) => Ok(payment_id),
+        Err(Ok(error)) => Err(error),
+        Err(Err(_)) => panic!("unexpected host error"),
+    }
+}
+
+#[test]
+fn test_payer_limits_roll_with_ledger_time() {
+    let setup = Setup::new(0);
+    let Setup { env, client, token, owner, payer, .. } = &setup;
+
+    client.set_spending_limits(owner, &SpendingLimits {
+        max_payment_amount: Some(2_000),
+        payer_daily_limit: Some(3_000),
+        payer_weekly_limit: Some(5_000),
+        ..Default::default()
+    });
+
+    assert_eq!(pay(&setup, payer, 2_001), Err(Error::PaymentLimitExceeded));
+    assert!(pay(&setup, payer, 2_000).is_ok());
+    assert_eq!(pay(&setup, payer, 1_001), Err(Error::PayerDailyLimitExceeded));
+    assert!(pay(&setup, payer, 1_000).is_ok());
+    assert_eq!(client.get_payer_volume(payer, &DAY), 3_000);
+
+    // Rejected payments move no funds and record no volume
+    assert_eq!(token.balance(payer), 97_000);
+
+    env.ledger().set_timestamp(10_000 + DAY);
+    assert_eq!(client.get_payer_volume(payer, &DAY), 0);
+    assert_eq!(pay(&setup, payer, 2_001), Err(Error::PaymentLimitExceeded));
+    assert!(pay(&setup, payer, 2_000).is_ok());
+    assert_eq!(pay(&setup, payer, 1), Err(Error::PayerWeeklyLimitExceeded));
+
+    // Other payers have their own windows
+    let other = Address::generate(env);
+    setup.mint(&other, 10_000);
+    assert!(pay(&setup, &other, 2_000).is_ok());
+
+    env.ledger().set_timestamp(10_000 + 7 * DAY);
+    assert!(pay(&setup, payer, 2_000).is_ok());
+}
+
+#[test]
+fn test_business_limits_and_overrides() {
+    let setup = Setup::new(0);
+    let Setup { env, client, owner, business_name, payer, .. } = &setup;
+
+    client.set_spending_limits(owner, &SpendingLimits {
+        max_payment_amount: Some(1_000),
+        business_daily_limit: Some(4_000),
+        ..Default::default()
+    });
+
+    // An exempt payer skips payer caps but not the business cap
+    client.set_limit_exemption(owner, payer, &true);
+    assert!(pay(&setup, payer, 3_000).is_ok());
+
+    let other = Address::generate(env);
+    setup.mint(&other, 10_000);
+    assert_eq!(pay(&setup, &other, 1_000 + 1), Err(Error::PaymentLimitExceeded));
+    assert!(pay(&setup, &other, 1_000).is_ok());
+    assert_eq!(pay(&setup, &other, 1), Err(Error::BusinessDailyLimitExceeded));
+    assert_eq!(client.get_business_volume(business_name, &DAY), 4_000);
+
+    client.set_limit_exemption(owner, payer, &false);
+    assert_eq!(pay(&setup, payer, 1_001), Err(Error::PaymentLimitExceeded));
+
+    // A business override replaces the defaults
+    let limits = SpendingLimits {
+        business_weekly_limit: Some(10_000),
+        ..Default::default()
+    };
+    client.set_business_limits(business_name, &limits, owner);
+    assert_eq!(client.get_spending_limits(business_name), limits);
+    assert!(pay(&setup, payer, 6_000).is_ok());
+    assert_eq!(pay(&setup, payer, 1), Err(Error::BusinessWeeklyLimitExceeded));
+}
+
+#[test]
+fn test_limit_admin_errors() {
+    let setup = Setup::new(0);
+    let Setup { env, client, owner, payer, .. } = &setup;
+
+    let stranger = Address::generate(env);
+    let result = client.try_set_spending_limits(&stranger, &SpendingLimits::default());
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    let result = client.try_set_limit_exemption(&stranger, payer, &true);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    let result = client.try_set_business_limits(
+        &String::from_str(env, "Unknown"),
+        &SpendingLimits::default(),
+        owner,
+    );
+    assert_eq!(result, Err(Ok(Error::BusinessNotFound)));
+}
@@ -0,0 +1,219 @@
+mod common;
+
+use ed25519_dalek::{Signer, SigningKey};
+use common::Setup;
+use payment_requests::{
+    intent_message, Asset, Error, OracleConfig, PaymentIntent, PaymentStatus, PriceData,
+};
+use rand::rngs::OsRng;
+use soroban_sdk::{contract, contractimpl, vec, BytesN, Env, String, Vec};
+
+/// Stand-in for a SEP-40 price feed; prices are stored newest first.
+#[contract]
+pub struct TestOracle;
+
+#[contractimpl]
+impl TestOracle {
+    pub fn set_prices(env: Env, asset: Asset, prices: Vec<PriceData>) {
+        env.storage().instance().set(&asset, &prices);
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        let prices: Vec<PriceData> = env.storage().instance().get(&asset)?;
+        prices.get(0)
+    }
+
+    pub fn prices(env: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+        let prices: Vec<PriceData> = env.storage().instance().get(&asset)?;
+        Some(prices.slice(0..records.min(prices.len())))
+    }
+}
+
+// 0.125 USD per token with 14 decimals
+const PRICE: i128 = 12_500_000_000_000;
+
+struct OracleSetup<'a> {
+    setup: Setup<'a>,
+    oracle: TestOracleClient<'a>,
+    payment_id: u64,
+}
+
+fn setup<'a>() -> OracleSetup<'a> {
+    let setup = Setup::new(0);
+    let Setup { env, client, owner, business_name, payer, requester, .. } = &setup;
+    setup.mint(payer, 100_000_000_000);
+
+    let oracle = TestOracleClient::new(env, &env.register_contract(None, TestOracle));
+    client.set_oracle_config(
+        owner,
+        &OracleConfig {
+            oracle: oracle.address.clone(),
+            denomination: String::from_str(env, "USD"),
+            max_age: 300,
+            max_deviation_bps: 1_000,
+        },
+    );
+
+    // 25.00 USD
+    let payment_id = client.create_fiat_payment_request(
+        &250_000_000i128,
+        business_name,
+        &String::from_str(env, "Subscription"),
+        &String::from_str(env, "USD"),
+        &vec![env, payer.clone()],
+        requester,
+        &None,
+    );
+
+    OracleSetup { setup, oracle, payment_id }
+}
+
+#[test]
+fn test_fiat_request_converted_at_execution() {
+    let OracleSetup { setup: Setup { env, client, token, payer, requester, .. }, oracle, payment_id } = setup();
+
+    oracle.set_prices(
+        &Asset::Stellar(token.address.clone()),
+        &vec![
+            &env,
+            PriceData { price: PRICE, timestamp: 9_900 },
+            PriceData { price: PRICE - PRICE / 50, timestamp: 9_600 },
+        ],
+    );
+
+    client.execute_payment(&payment_id, &payer, &token.address);
+
+    // 25 USD / 0.125 = 200 tokens
+    assert_eq!(token.balance(&requester), 2_000_000_000);
+
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.status, PaymentStatus::Completed);
+    assert_eq!(payment_request.amount, 2_000_000_000);
+
+    let quote = client.get_fiat_quote(&payment_id);
+    assert_eq!(quote.fiat_amount, 250_000_000);
+    assert_eq!(quote.price, PRICE);
+    assert_eq!(quote.price_timestamp, 9_900);
+    assert_eq!(quote.token_amount, 2_000_000_000);
+}
+
+#[test]
+fn test_fiat_request_paid_with_intent() {
+    let OracleSetup { setup: Setup { env, client, token, payer, requester, .. }, oracle, payment_id } = setup();
+    oracle.set_prices(
+        &Asset::Stellar(token.address.clone()),
+        &vec![&env, PriceData { price: PRICE, timestamp: 9_900 }],
+    );
+
+    let signing_key = SigningKey::generate(&mut OsRng);
+    client.set_intent_key(&payer, &BytesN::from_array(&env, &signing_key.verifying_key().to_bytes()));
+    token.approve(&payer, &client.address, &3_000_000_000, &1_000);
+    let sign = |intent: &PaymentIntent| {
+        let message: std::vec::Vec<u8> = intent_message(&env, &client.address, intent).iter().collect();
+        BytesN::from_array(&env, &signing_key.sign(&message).to_bytes())
+    };
+
+    // The signed amount caps what the quote may come to
+    let mut intent = PaymentIntent {
+        payment_id,
+        payer: payer.clone(),
+        token_address: token.address.clone(),
+        amount: 1_999_999_999,
+        nonce: 0,
+        expiry: 20_000,
+    };
+    let result = client.try_execute_payment_with_signature(&intent, &sign(&intent));
+    assert_eq!(result, Err(Ok(Error::SlippageExceeded)));
+
+    // Only the quoted 200 tokens are drawn
+    intent.amount = 2_500_000_000;
+    client.execute_payment_with_signature(&intent, &sign(&intent));
+    assert_eq!(token.balance(&requester), 2_000_000_000);
+    assert_eq!(token.balance(&payer), 98_000_100_000);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Completed);
+    assert_eq!(client.get_fiat_quote(&payment_id).token_amount, 2_000_000_000);
+}
+
+#[test]
+fn test_fiat_request_price_checks() {
+    let OracleSetup { setup: Setup { env, client, token, payer, .. }, oracle, payment_id } = setup();
+    let asset = Asset::Stellar(token.address.clone());
+
+    // No price published for the token
+    let result = client.try_execute_payment(&payment_id, &payer, &token.address);
+    assert_eq!(result, Err(Ok(Error::PriceUnavailable)));
+
+    // Older than max_age
+    oracle.set_prices(&asset, &vec![&env, PriceData { price: PRICE, timestamp: 9_000 }]);
+    let result = client.try_execute_payment(&payment_id, &payer, &token.address);
+    assert_eq!(result, Err(Ok(Error::StalePrice)));
+
+    // Jumped 20% since the previous record
+    oracle.set_prices(
+        &asset,
+        &vec![
+            &env,
+            PriceData { price: PRICE, timestamp: 9_950 },
+            PriceData { price: PRICE * 100 / 120, timestamp: 9_650 },
+        ],
+    );
+    let result = client.try_execute_payment(&payment_id, &payer, &token.address);
+    assert_eq!(result, Err(Ok(Error::PriceDeviation)));
+
+    // Fiat requests are paid in one go
+    let result = client.try_execute_partial_payment(&payment_id, &payer, &token.address, &1_000);
+    assert_eq!(result, Err(Ok(Error::InvalidRequestKind)));
+
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Pending);
+    assert_eq!(client.try_get_fiat_quote(&payment_id), Err(Ok(Error::PaymentNotFound)));
+}
+
+#[test]
+fn test_fiat_request_requires_matching_feed() {
+    let Setup { env, client, business_name, payer, requester, .. } = Setup::new(0);
+
+    let result = client.try_create_fiat_payment_request(
+        &250_000_000i128,
+        &business_name,
+        &String::from_str(&env, "Subscription"),
+        &String::from_str(&env, "USD"),
+        &vec![&env, payer],
+        &requester,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::OracleNotSet)));
+}
+
+#[test]
+fn test_oracle_config_checks() {
+    let OracleSetup { setup: Setup { env, client, owner, business_name, payer, requester, .. }, oracle, .. } = setup();
+
+    // Deviation beyond 100%
+    let result = client.try_set_oracle_config(
+        &owner,
+        &OracleConfig {
+            oracle: oracle.address.clone(),
+            denomination: String::from_str(&env, "USD"),
+            max_age: 300,
+            max_deviation_bps: 10_001,
+        },
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidOracleConfig)));
+
+    // The feed quotes USD only
+    let result = client.try_create_fiat_payment_request(
+        &250_000_000i128,
+        &business_name,
+        &String::from_str(&env, "Subscription"),
+        &String::from_str(&env, "EUR"),
+        &vec![&env, payer.clone()],
+        &requester,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::TokenMismatch)));
+}
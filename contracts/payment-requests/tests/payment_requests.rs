@@ -0,0 +1,804 @@
+use payment_requests::{
+    AmountBounds, Error, FeeBasis, Invoice, LineItem, PaymentContract, PaymentContractClient,
+    PaymentStatus, SplitShare,
+};
+use soroban_sdk::{
+    symbol_short,
+    testutils::{Address as _, Events},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, Env, IntoVal, String, Vec,
+};
+
+// Test contract initialization
+#[test]
+fn test_initialize_contract() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let default_fee = 250u32; // 2.5%
+
+    env.mock_all_auths();
+    
+    let result = client.try_initialize(&owner, &default_fee, &Address::generate(&env));
+    assert_eq!(result, Ok(Ok(())));
+}
+
+#[test]
+fn test_initialize_with_invalid_fee() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    let owner = Address::generate(&env);
+    let invalid_fee = 10001u32; // > 100%
+
+    env.mock_all_auths();
+    
+    let result = client.try_initialize(&owner, &invalid_fee, &Address::generate(&env));
+    assert_eq!(result, Err(Ok(Error::InvalidFeePercentage)));
+}
+
+#[test]
+fn test_register_business() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    // Initialize contract
+    let owner = Address::generate(&env);
+    let default_fee = 250u32;
+    env.mock_all_auths();
+    client.initialize(&owner, &default_fee, &Address::generate(&env));
+
+    // Register business
+    let business_name = String::from_str(&env, "Test Store");
+    let business_owner = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let fee_percentage = 300u32; // 3%
+
+    let result = client.try_register_business(
+        &business_name,
+        &business_owner,
+        &fee_recipient,
+        &fee_percentage,
+    );
+    assert_eq!(result, Ok(Ok(())));
+
+    // Verify business was registered
+    let business_config = client.get_business_config(&business_name);
+    assert_eq!(business_config.name, business_name);
+    assert_eq!(business_config.owner, business_owner);
+    assert_eq!(business_config.fee_recipient, fee_recipient);
+    assert_eq!(business_config.default_fee_percentage, fee_percentage);
+    assert!(business_config.is_active);
+}
+
+#[test]
+fn test_create_payment_request() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    // Initialize contract
+    let owner = Address::generate(&env);
+    let default_fee = 250u32;
+    env.mock_all_auths();
+    client.initialize(&owner, &default_fee, &Address::generate(&env));
+
+    // Register business
+    let business_name = String::from_str(&env, "Test Store");
+    let business_owner = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let fee_percentage = 300u32;
+
+    client.register_business(
+        &business_name,
+        &business_owner,
+        &fee_recipient,
+        &fee_percentage,
+    );
+
+    // Create payment request
+    let amount = 1000000i128; // 100 XLM (in stroops)
+    let description = String::from_str(&env, "Test payment");
+    let denomination = String::from_str(&env, "XLM");
+    let requester = Address::generate(&env);
+    
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(Address::generate(&env));
+    authorized_addresses.push_back(Address::generate(&env));
+    authorized_addresses.push_back(Address::generate(&env));
+
+    let payment_id = client.create_payment_request(
+        &amount,
+        &business_name,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &None,
+    );
+
+    // Verify payment request
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.amount, amount);
+    assert_eq!(payment_request.business_name, business_name);
+    assert_eq!(payment_request.description, description);
+    assert_eq!(payment_request.denomination, denomination);
+    assert_eq!(payment_request.requester, requester);
+    assert_eq!(payment_request.authorized_addresses, authorized_addresses);
+    assert_eq!(payment_request.fee_percentage, fee_percentage);
+    
+    match payment_request.status {
+        PaymentStatus::Pending => {},
+        _ => panic!("Payment should be pending"),
+    }
+}
+
+#[test]
+fn test_create_payment_request_with_custom_fee() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    // Initialize and setup business
+    let owner = Address::generate(&env);
+    let default_fee = 250u32;
+    env.mock_all_auths();
+    client.initialize(&owner, &default_fee, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Test Store");
+    let business_owner = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    let fee_percentage = 300u32;
+
+    client.register_business(
+        &business_name,
+        &business_owner,
+        &fee_recipient,
+        &fee_percentage,
+    );
+
+    // Create payment request with custom fee
+    let amount = 1000000i128;
+    let description = String::from_str(&env, "Test payment");
+    let denomination = String::from_str(&env, "XLM");
+    let requester = Address::generate(&env);
+    let custom_fee = 500u32; // 5%
+    
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(Address::generate(&env));
+
+    let payment_id = client.create_payment_request(
+        &amount,
+        &business_name,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &Some(custom_fee),
+    );
+
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.fee_percentage, custom_fee);
+}
+
+#[test]
+fn test_register_business_with_invalid_fee() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.initialize(&Address::generate(&env), &250u32, &Address::generate(&env));
+
+    let result = client.try_register_business(
+        &String::from_str(&env, "Test Store"),
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &10001u32,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidFeePercentage)));
+}
+
+#[test]
+fn test_create_payment_request_errors() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.initialize(&Address::generate(&env), &250u32, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Test Store");
+    let business_owner = Address::generate(&env);
+    client.register_business(
+        &business_name,
+        &business_owner,
+        &Address::generate(&env),
+        &300u32,
+    );
+
+    let description = String::from_str(&env, "Test payment");
+    let denomination = String::from_str(&env, "XLM");
+    let requester = Address::generate(&env);
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(Address::generate(&env));
+
+    // Non-positive amount
+    let result = client.try_create_payment_request(
+        &0i128,
+        &business_name,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    // No authorized payers
+    let result = client.try_create_payment_request(
+        &1000i128,
+        &business_name,
+        &description,
+        &denomination,
+        &Vec::new(&env),
+        &requester,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidAddress)));
+
+    // Unknown business
+    let result = client.try_create_payment_request(
+        &1000i128,
+        &String::from_str(&env, "Unknown Store"),
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::BusinessNotFound)));
+
+    // Custom fee above 100%
+    let result = client.try_create_payment_request(
+        &1000i128,
+        &business_name,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &Some(10001u32),
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidFeePercentage)));
+
+    // Deactivated business
+    client.update_business_status(&business_name, &false, &business_owner);
+    let result = client.try_create_payment_request(
+        &1000i128,
+        &business_name,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(Error::BusinessNotActive)));
+}
+
+#[test]
+fn test_payment_request_lifecycle_errors() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.initialize(&Address::generate(&env), &250u32, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Test Store");
+    client.register_business(
+        &business_name,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &300u32,
+    );
+
+    let requester = Address::generate(&env);
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(Address::generate(&env));
+
+    let payment_id = client.create_payment_request(
+        &1000i128,
+        &business_name,
+        &String::from_str(&env, "Test payment"),
+        &String::from_str(&env, "XLM"),
+        &authorized_addresses,
+        &requester,
+        &None,
+    );
+
+    // Unknown payment
+    let result = client.try_get_payment_request(&(payment_id + 1));
+    assert_eq!(result, Err(Ok(Error::PaymentNotFound)));
+
+    // Payer outside the authorized set
+    let result = client.try_execute_payment(
+        &payment_id,
+        &Address::generate(&env),
+        &Address::generate(&env),
+    );
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    // Only the requester or contract owner may cancel
+    let result = client.try_cancel_payment_request(&payment_id, &Address::generate(&env));
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+}
+
+fn sample_invoice(env: &Env, merchant_reference: &str) -> Invoice {
+    Invoice {
+        line_items: vec![
+            env,
+            LineItem {
+                sku: String::from_str(env, "WIDGET"),
+                quantity: 3,
+                unit_price: 1_000,
+            },
+            LineItem {
+                sku: String::from_str(env, "GADGET"),
+                quantity: 1,
+                unit_price: 500,
+            },
+        ],
+        tax_basis_points: 1_000, // 10%
+        discount: 500,
+        due_date: 86_400,
+        merchant_reference: String::from_str(env, merchant_reference),
+    }
+}
+
+#[test]
+fn test_create_invoice_request() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.initialize(&Address::generate(&env), &250u32, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Test Store");
+    client.register_business(
+        &business_name,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &300u32,
+    );
+
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(Address::generate(&env));
+    let invoice = sample_invoice(&env, "ERP-0001");
+
+    let payment_id = client.create_invoice_request(
+        &business_name,
+        &String::from_str(&env, "Invoice ERP-0001"),
+        &String::from_str(&env, "USDC"),
+        &authorized_addresses,
+        &Address::generate(&env),
+        &None,
+        &invoice,
+    );
+
+    // (3 * 1000 + 500 - 500) * 1.10
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.amount, 3_300);
+    assert_eq!(payment_request.status, PaymentStatus::Pending);
+    assert_eq!(client.get_invoice(&payment_id), invoice);
+
+    let totals = client.get_invoice_totals(&payment_id);
+    assert_eq!(totals.subtotal, 3_500);
+    assert_eq!(totals.tax, 300);
+    assert_eq!(totals.total, 3_300);
+
+    let by_reference = client.get_payment_by_merchant_ref(
+        &business_name,
+        &String::from_str(&env, "ERP-0001"),
+    );
+    assert_eq!(by_reference, payment_request);
+}
+
+#[test]
+fn test_invoice_request_errors() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    client.initialize(&Address::generate(&env), &250u32, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Test Store");
+    let other_business = String::from_str(&env, "Other Store");
+    for name in [&business_name, &other_business] {
+        client.register_business(
+            name,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &300u32,
+        );
+    }
+
+    let description = String::from_str(&env, "Invoice");
+    let denomination = String::from_str(&env, "USDC");
+    let requester = Address::generate(&env);
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(Address::generate(&env));
+
+    client.create_invoice_request(
+        &business_name,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &None,
+        &sample_invoice(&env, "ERP-0001"),
+    );
+
+    // Merchant references are unique per business
+    let result = client.try_create_invoice_request(
+        &business_name,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &None,
+        &sample_invoice(&env, "ERP-0001"),
+    );
+    assert_eq!(result, Err(Ok(Error::DuplicateMerchantReference)));
+
+    let result = client.try_create_invoice_request(
+        &other_business,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &None,
+        &sample_invoice(&env, "ERP-0001"),
+    );
+    assert!(result.is_ok());
+
+    // Discount larger than the subtotal
+    let mut invoice = sample_invoice(&env, "ERP-0002");
+    invoice.discount = 10_000;
+    let result = client.try_create_invoice_request(
+        &business_name,
+        &description,
+        &denomination,
+        &authorized_addresses,
+        &requester,
+        &None,
+        &invoice,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidInvoice)));
+
+    // Unknown reference
+    let result = client.try_get_payment_by_merchant_ref(
+        &business_name,
+        &String::from_str(&env, "ERP-9999"),
+    );
+    assert_eq!(result, Err(Ok(Error::PaymentNotFound)));
+}
+
+#[test]
+fn test_installment_payments() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &250u32, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Test Store");
+    let fee_recipient = Address::generate(&env);
+    client.register_business(
+        &business_name,
+        &Address::generate(&env),
+        &fee_recipient,
+        &300u32, // 3%
+    );
+
+    let token_address = env.register_stellar_asset_contract_v2(owner.clone()).address();
+    let other_token = env.register_stellar_asset_contract_v2(owner).address();
+    let token = TokenClient::new(&env, &token_address);
+    let first_payer = Address::generate(&env);
+    let second_payer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_address).mint(&first_payer, &10_000);
+    StellarAssetClient::new(&env, &token_address).mint(&second_payer, &10_000);
+    StellarAssetClient::new(&env, &other_token).mint(&second_payer, &10_000);
+
+    let requester = Address::generate(&env);
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(first_payer.clone());
+    authorized_addresses.push_back(second_payer.clone());
+
+    let payment_id = client.create_payment_request(
+        &1_000i128,
+        &business_name,
+        &String::from_str(&env, "B2B invoice"),
+        &String::from_str(&env, "USDC"),
+        &authorized_addresses,
+        &requester,
+        &None,
+    );
+
+    // First installment
+    client.execute_partial_payment(&payment_id, &first_payer, &token_address, &333);
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.status, PaymentStatus::PartiallyPaid);
+    assert_eq!(payment_request.amount_paid, 333);
+
+    // Installments must stay in the same token and within the outstanding balance
+    let result = client.try_execute_partial_payment(&payment_id, &second_payer, &other_token, &100);
+    assert_eq!(result, Err(Ok(Error::TokenMismatch)));
+    let result = client.try_execute_partial_payment(&payment_id, &second_payer, &token_address, &668);
+    assert_eq!(result, Err(Ok(Error::Overpayment)));
+    let result = client.try_execute_partial_payment(&payment_id, &second_payer, &token_address, &0);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    // A partially paid request can no longer be cancelled
+    let result = client.try_cancel_payment_request(&payment_id, &requester);
+    assert_eq!(result, Err(Ok(Error::InvalidPaymentStatus)));
+
+    // Second payer contributes, then the first one settles the remainder
+    client.execute_partial_payment(&payment_id, &second_payer, &token_address, &300);
+    client.execute_payment(&payment_id, &first_payer, &token_address);
+
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.status, PaymentStatus::Completed);
+    assert_eq!(payment_request.amount_paid, 1_000);
+
+    let contributions = client.get_payment_contributions(&payment_id);
+    assert_eq!(contributions.get(first_payer.clone()), Some(700));
+    assert_eq!(contributions.get(second_payer.clone()), Some(300));
+
+    // Installment fees add up to the fee of a single full payment
+    assert_eq!(token.balance(&fee_recipient), 30);
+    assert_eq!(token.balance(&requester), 970);
+    assert_eq!(token.balance(&first_payer), 9_300);
+    assert_eq!(token.balance(&second_payer), 9_700);
+
+    assert_eq!(client.get_payment_history(&first_payer).total_payments, 2);
+    assert_eq!(client.get_payment_history(&first_payer).total_amount, 700);
+
+    let result = client.try_execute_partial_payment(&payment_id, &second_payer, &token_address, &1);
+    assert_eq!(result, Err(Ok(Error::PaymentAlreadyCompleted)));
+}
+
+#[test]
+fn test_open_amount_request() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &250u32, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Donations");
+    let fee_recipient = Address::generate(&env);
+    client.register_business(&business_name, &Address::generate(&env), &fee_recipient, &100u32);
+
+    let token_address = env.register_stellar_asset_contract_v2(owner).address();
+    let token = TokenClient::new(&env, &token_address);
+    let donor = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_address).mint(&donor, &10_000);
+
+    let requester = Address::generate(&env);
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(donor.clone());
+
+    // Bounds must be positive and ordered
+    let result = client.try_create_open_payment_request(
+        &business_name,
+        &String::from_str(&env, "Donate"),
+        &String::from_str(&env, "USDC"),
+        &authorized_addresses,
+        &requester,
+        &None,
+        &AmountBounds { min_amount: Some(500), max_amount: Some(100) },
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    let payment_id = client.create_open_payment_request(
+        &business_name,
+        &String::from_str(&env, "Donate"),
+        &String::from_str(&env, "USDC"),
+        &authorized_addresses,
+        &requester,
+        &None,
+        &AmountBounds { min_amount: Some(100), max_amount: Some(5_000) },
+    );
+    assert_eq!(client.get_payment_request(&payment_id).amount, 0);
+
+    // The payer must choose an amount within the bounds
+    let result = client.try_execute_payment(&payment_id, &donor, &token_address);
+    assert_eq!(result, Err(Ok(Error::InvalidRequestKind)));
+    let result = client.try_execute_open_payment(&payment_id, &donor, &token_address, &50);
+    assert_eq!(result, Err(Ok(Error::AmountOutOfRange)));
+    let result = client.try_execute_open_payment(&payment_id, &donor, &token_address, &5_001);
+    assert_eq!(result, Err(Ok(Error::AmountOutOfRange)));
+
+    client.execute_open_payment(&payment_id, &donor, &token_address, &2_000);
+
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.status, PaymentStatus::Completed);
+    assert_eq!(payment_request.amount, 2_000);
+    assert_eq!(payment_request.amount_paid, 2_000);
+    assert_eq!(token.balance(&fee_recipient), 20);
+    assert_eq!(token.balance(&requester), 1_980);
+}
+
+#[test]
+fn test_tippable_request_fee_basis() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &250u32, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Cafe");
+    let business_owner = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    client.register_business(&business_name, &business_owner, &fee_recipient, &1_000u32); // 10%
+    assert_eq!(client.get_business_config(&business_name).fee_basis, FeeBasis::Total);
+
+    let token_address = env.register_stellar_asset_contract_v2(owner).address();
+    let token = TokenClient::new(&env, &token_address);
+    let customer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_address).mint(&customer, &10_000);
+
+    let requester = Address::generate(&env);
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(customer.clone());
+
+    let create = || {
+        client.create_tippable_request(
+            &1_000i128,
+            &business_name,
+            &String::from_str(&env, "Coffee"),
+            &String::from_str(&env, "USDC"),
+            &authorized_addresses,
+            &requester,
+            &None,
+        )
+    };
+
+    // Fee on base and tip
+    let payment_id = create();
+    let result = client.try_execute_partial_payment(&payment_id, &customer, &token_address, &500);
+    assert_eq!(result, Err(Ok(Error::InvalidRequestKind)));
+    let result = client.try_execute_payment_with_tip(&payment_id, &customer, &token_address, &-1);
+    assert_eq!(result, Err(Ok(Error::InvalidAmount)));
+
+    client.execute_payment_with_tip(&payment_id, &customer, &token_address, &200);
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.status, PaymentStatus::Completed);
+    assert_eq!(payment_request.tip_amount, 200);
+    assert_eq!(token.balance(&fee_recipient), 120);
+    assert_eq!(token.balance(&requester), 1_080);
+
+    // Fee on base only
+    client.set_fee_basis(&business_name, &FeeBasis::BaseOnly, &business_owner);
+    let payment_id = create();
+    client.execute_payment_with_tip(&payment_id, &customer, &token_address, &200);
+    assert_eq!(token.balance(&fee_recipient), 220);
+    assert_eq!(token.balance(&requester), 2_180);
+    assert_eq!(client.get_payment_history(&customer).total_amount, 2_400);
+
+    // Tips only apply to tippable requests
+    let fixed_id = client.create_payment_request(
+        &1_000i128,
+        &business_name,
+        &String::from_str(&env, "Beans"),
+        &String::from_str(&env, "USDC"),
+        &authorized_addresses,
+        &requester,
+        &None,
+    );
+    let result = client.try_execute_payment_with_tip(&fixed_id, &customer, &token_address, &100);
+    assert_eq!(result, Err(Ok(Error::InvalidRequestKind)));
+
+    let result = client.try_set_fee_basis(&business_name, &FeeBasis::Total, &Address::generate(&env));
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+}
+
+#[test]
+fn test_split_payment() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    let owner = Address::generate(&env);
+    client.initialize(&owner, &250u32, &Address::generate(&env));
+
+    let business_name = String::from_str(&env, "Marketplace");
+    let fee_recipient = Address::generate(&env);
+    client.register_business(&business_name, &Address::generate(&env), &fee_recipient, &100u32);
+
+    let token_address = env.register_stellar_asset_contract_v2(owner).address();
+    let token = TokenClient::new(&env, &token_address);
+    let buyer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token_address).mint(&buyer, &10_000);
+
+    let requester = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let affiliate = Address::generate(&env);
+    let platform = Address::generate(&env);
+    let mut authorized_addresses = Vec::new(&env);
+    authorized_addresses.push_back(buyer.clone());
+
+    let payment_id = client.create_payment_request(
+        &1_000i128,
+        &business_name,
+        &String::from_str(&env, "Order"),
+        &String::from_str(&env, "USDC"),
+        &authorized_addresses,
+        &requester,
+        &None,
+    );
+
+    // Shares must add up to 100%
+    let result = client.try_set_payment_splits(
+        &payment_id,
+        &vec![
+            &env,
+            SplitShare { recipient: seller.clone(), share_basis_points: 8_000 },
+            SplitShare { recipient: affiliate.clone(), share_basis_points: 1_000 },
+        ],
+        &requester,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidSplit)));
+
+    let splits = vec![
+        &env,
+        SplitShare { recipient: seller.clone(), share_basis_points: 8_000 },
+        SplitShare { recipient: affiliate.clone(), share_basis_points: 1_500 },
+        SplitShare { recipient: platform.clone(), share_basis_points: 500 },
+    ];
+    let result = client.try_set_payment_splits(&payment_id, &splits, &seller);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    client.set_payment_splits(&payment_id, &splits, &requester);
+    client.execute_payment(&payment_id, &buyer, &token_address);
+
+    // 1% fee, then 990 split 80/15/5 with the remainder on the last leg
+    assert_eq!(token.balance(&fee_recipient), 10);
+    assert_eq!(token.balance(&seller), 792);
+    assert_eq!(token.balance(&affiliate), 148);
+    assert_eq!(token.balance(&platform), 50);
+    assert_eq!(token.balance(&requester), 0);
+
+    let topics = (symbol_short!("payment"), symbol_short!("split"));
+    let mut split_events = Vec::new(&env);
+    for event in env.events().all().iter() {
+        if event.0 == contract_id && event.1 == topics.into_val(&env) {
+            split_events.push_back(event);
+        }
+    }
+    assert_eq!(
+        split_events,
+        vec![
+            &env,
+            (contract_id.clone(), topics.into_val(&env), (payment_id, seller, 792i128).into_val(&env)),
+            (contract_id.clone(), topics.into_val(&env), (payment_id, affiliate, 148i128).into_val(&env)),
+            (contract_id.clone(), topics.into_val(&env), (payment_id, platform, 50i128).into_val(&env)),
+        ]
+    );
+
+    let result = client.try_set_payment_splits(&payment_id, &splits, &requester);
+    assert_eq!(result, Err(Ok(Error::InvalidPaymentStatus)));
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::Setup;
+use payment_requests::{Error, RampKind, RampRecord, RampStatus};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    Address, String,
+};
+
+fn record(setup: &Setup, kind: RampKind, anchor_id: &str, status: RampStatus) -> RampRecord {
+    RampRecord {
+        kind,
+        anchor: String::from_str(&setup.env, "anchor.example"),
+        anchor_id: String::from_str(&setup.env, anchor_id),
+        token_address: setup.token.address.clone(),
+        amount: 1_000,
+        status,
+        stellar_transaction_id: String::from_str(&setup.env, ""),
+        recorded_by: setup.requester.clone(),
+        updated_at: 0,
+    }
+}
+
+#[test]
+fn test_ramp_updates_replace_pending_record() {
+    let setup = Setup::new(100);
+    let Setup { env, client, payer, requester, .. } = &setup;
+    let payment_id = setup.create_request(1_000);
+
+    client.record_ramp(&payment_id, payer, &record(&setup, RampKind::Deposit, "dep-1", RampStatus::Pending));
+    client.record_ramp(&payment_id, requester, &record(&setup, RampKind::Send, "send-1", RampStatus::Pending));
+
+    env.ledger().set_timestamp(12_000);
+    let mut completed = record(&setup, RampKind::Deposit, "dep-1", RampStatus::Completed);
+    completed.stellar_transaction_id = String::from_str(env, "abc123");
+    client.record_ramp(&payment_id, payer, &completed);
+
+    let ramps = client.get_ramps(&payment_id);
+    assert_eq!(ramps.len(), 2);
+    assert_eq!(
+        ramps.get(0).unwrap(),
+        RampRecord { recorded_by: payer.clone(), updated_at: 12_000, ..completed }
+    );
+    assert_eq!(ramps.get(1).unwrap().recorded_by, *requester);
+    assert_eq!(ramps.get(1).unwrap().status, RampStatus::Pending);
+    assert_eq!(ramps.get(1).unwrap().updated_at, 10_000);
+    assert!(client.get_ramps(&(payment_id + 1)).is_empty());
+}
+
+#[test]
+fn test_final_ramp_cannot_change() {
+    let setup = Setup::new(100);
+    let Setup { client, payer, .. } = &setup;
+    let payment_id = setup.create_request(1_000);
+
+    client.record_ramp(&payment_id, payer, &record(&setup, RampKind::Deposit, "dep-1", RampStatus::Failed));
+    let result = client.try_record_ramp(
+        &payment_id,
+        payer,
+        &record(&setup, RampKind::Deposit, "dep-1", RampStatus::Completed),
+    );
+    assert_eq!(result, Err(Ok(Error::RampAlreadyFinal)));
+}
+
+#[test]
+fn test_only_recorder_updates_ramp() {
+    let setup = Setup::new(100);
+    let Setup { client, payer, requester, .. } = &setup;
+    let payment_id = setup.create_request(1_000);
+
+    client.record_ramp(&payment_id, payer, &record(&setup, RampKind::Deposit, "dep-1", RampStatus::Pending));
+
+    // Another party to the request cannot mark the payer's ramp final
+    let failed = record(&setup, RampKind::Deposit, "dep-1", RampStatus::Failed);
+    let result = client.try_record_ramp(&payment_id, requester, &failed);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    let completed = record(&setup, RampKind::Deposit, "dep-1", RampStatus::Completed);
+    client.record_ramp(&payment_id, payer, &completed);
+    assert_eq!(client.get_ramps(&payment_id).get(0).unwrap().status, RampStatus::Completed);
+}
+
+#[test]
+fn test_record_ramp_errors() {
+    let setup = Setup::new(100);
+    let Setup { env, client, payer, .. } = &setup;
+    let payment_id = setup.create_request(1_000);
+    let pending = record(&setup, RampKind::Withdrawal, "wd-1", RampStatus::Pending);
+
+    let stranger = Address::generate(env);
+    assert_eq!(client.try_record_ramp(&payment_id, &stranger, &pending), Err(Ok(Error::NotAuthorized)));
+    assert_eq!(client.try_record_ramp(&99, payer, &pending), Err(Ok(Error::PaymentNotFound)));
+
+    let empty = RampRecord { amount: 0, ..pending };
+    assert_eq!(client.try_record_ramp(&payment_id, payer, &empty), Err(Ok(Error::InvalidAmount)));
+}
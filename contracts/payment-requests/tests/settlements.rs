@@ -0,0 +1,140 @@
+mod common;
+
+use common::Setup;
+use payment_requests::{Error, SettlementConfig};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger},
+    vec, Address, String, Vec,
+};
+
+// The store batches its proceeds to the returned payout address
+fn setup<'a>() -> (Setup<'a>, Address) {
+    let setup = Setup::new(100);
+    let payout_address = Address::generate(&setup.env);
+    setup.client.set_settlement_config(
+        &setup.business_name,
+        &SettlementConfig {
+            enabled: true,
+            payout_address: payout_address.clone(),
+            interval: 86_400,
+            threshold: 5_000,
+        },
+        &setup.business_owner,
+    );
+    (setup, payout_address)
+}
+
+fn pay(setup: &Setup, amount: i128) -> u64 {
+    let payment_id = setup.create_request(amount);
+    setup.client.execute_payment(&payment_id, &setup.payer, &setup.token.address);
+    payment_id
+}
+
+#[test]
+fn test_proceeds_accumulate_until_interval() {
+    let (setup, payout_address) = setup();
+    let Setup { env, client, token, business_name, requester, .. } = &setup;
+
+    let first = pay(&setup, 1_000);
+    let second = pay(&setup, 2_000);
+
+    // 1% fee goes out immediately, the rest is held
+    assert_eq!(token.balance(requester), 0);
+    assert_eq!(token.balance(&client.address), 2_970);
+    assert_eq!(client.get_merchant_balance(business_name, &token.address), 2_970);
+    assert_eq!(client.get_unsettled_payments(business_name, &token.address), vec![env, first, second]);
+
+    // Below threshold, so the payout waits for the interval to elapse
+    let result = client.try_settle(business_name, &token.address);
+    assert_eq!(result, Err(Ok(Error::SettlementNotDue)));
+
+    env.ledger().set_timestamp(10_000 + 86_400);
+    let settlement_id = client.settle(business_name, &token.address);
+    let record = client.get_settlement(&settlement_id);
+    assert_eq!(record.amount, 2_970);
+    assert_eq!(record.payment_ids, vec![env, first, second]);
+    assert_eq!(record.payout_address, payout_address);
+    assert_eq!(record.timestamp, 10_000 + 86_400);
+    assert_eq!(token.balance(&payout_address), 2_970);
+    assert_eq!(client.get_merchant_balance(business_name, &token.address), 0);
+    assert_eq!(client.get_unsettled_payments(business_name, &token.address), Vec::new(env));
+
+    let third = pay(&setup, 1_000);
+    let result = client.try_settle(business_name, &token.address);
+    assert_eq!(result, Err(Ok(Error::SettlementNotDue)));
+
+    env.ledger().set_timestamp(10_000 + 2 * 86_400);
+    let settlement_id = client.settle(business_name, &token.address);
+    let record = client.get_settlement(&settlement_id);
+    assert_eq!(record.amount, 990);
+    assert_eq!(record.payment_ids, vec![env, third]);
+    assert_eq!(token.balance(&payout_address), 3_960);
+}
+
+#[test]
+fn test_threshold_triggers_early_payout() {
+    let (setup, payout_address) = setup();
+    let Setup { client, token, business_name, .. } = &setup;
+
+    pay(&setup, 2_000);
+    let result = client.try_settle(business_name, &token.address);
+    assert_eq!(result, Err(Ok(Error::SettlementNotDue)));
+
+    // 1_980 + 3_960 crosses the 5_000 threshold before the interval ends
+    pay(&setup, 4_000);
+    client.settle(business_name, &token.address);
+    assert_eq!(token.balance(&payout_address), 1_980 + 3_960);
+}
+
+#[test]
+fn test_disabling_settlement_pays_requester_and_drains_balance() {
+    let (setup, payout_address) = setup();
+    let Setup { client, token, business_name, business_owner, requester, .. } = &setup;
+
+    pay(&setup, 1_000);
+    pay(&setup, 1_000);
+
+    client.set_settlement_config(
+        business_name,
+        &SettlementConfig {
+            enabled: false,
+            payout_address: payout_address.clone(),
+            interval: 86_400,
+            threshold: 5_000,
+        },
+        business_owner,
+    );
+
+    // New payments go straight to the requester again
+    pay(&setup, 1_000);
+    assert_eq!(token.balance(requester), 990);
+
+    // Leftover proceeds can be drained without waiting
+    client.settle(business_name, &token.address);
+    assert_eq!(token.balance(&payout_address), 1_980);
+    assert_eq!(token.balance(&client.address), 0);
+
+    let result = client.try_settle(business_name, &token.address);
+    assert_eq!(result, Err(Ok(Error::SettlementNotDue)));
+}
+
+#[test]
+fn test_settlement_configuration_errors() {
+    let (setup, payout_address) = setup();
+    let Setup { env, client, token, .. } = &setup;
+
+    let unknown = String::from_str(env, "Unknown Store");
+    let result = client.try_settle(&unknown, &token.address);
+    assert_eq!(result, Err(Ok(Error::SettlementNotConfigured)));
+
+    let config = SettlementConfig {
+        enabled: true,
+        payout_address: payout_address.clone(),
+        interval: 0,
+        threshold: 1,
+    };
+    let result = client.try_set_settlement_config(&setup.business_name, &config, &Address::generate(env));
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+
+    assert_eq!(client.try_get_settlement(&1), Err(Ok(Error::SettlementNotFound)));
+}
@@ -0,0 +1,463 @@
+//! Property tests running random sequences of calls against the contract and
+//! a model of it. Every call must succeed or fail as the model predicts, and
+//! after every call:
+//!
+//! - no tokens are created or lost
+//! - no request moves back to an earlier status or loses paid amount
+//! - the fee of a payment is never more than the payment
+//! - every payer's history adds up to what they contributed
+//!
+//! Set `PROPTEST_CASES` to run more sequences than the default 32.
+
+use payment_requests::{Error, PaymentContract, PaymentContractClient, PaymentStatus};
+use proptest::prelude::*;
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, String, Vec,
+};
+
+const BUSINESSES: usize = 2;
+const REQUESTERS: usize = 2;
+const PAYERS: usize = 3;
+const PAYER_BALANCES: [i128; PAYERS] = [20_000, 4_000, 0];
+// Calls name request IDs up to here, so some name requests that do not exist
+const MAX_PAYMENT_ID: u64 = 10;
+
+#[derive(Clone, Copy, Debug)]
+enum Caller {
+    Owner,
+    /// The requester of a request or the owner of a business
+    Party,
+    Stranger,
+}
+
+#[derive(Clone, Debug)]
+enum Op {
+    Initialize { fee: u32 },
+    Register { business: usize, fee: u32 },
+    Create { business: usize, amount: i128, requester: usize, payers: u8, custom_fee: Option<u32> },
+    Execute { payment_id: u64, payer: usize },
+    ExecutePartial { payment_id: u64, payer: usize, amount: i128 },
+    Cancel { payment_id: u64, caller: Caller },
+    UpdateBusinessStatus { business: usize, is_active: bool, caller: Caller },
+}
+
+fn caller() -> impl Strategy<Value = Caller> {
+    prop_oneof![Just(Caller::Owner), Just(Caller::Party), Just(Caller::Stranger)]
+}
+
+// Fees range a little past 100% so invalid ones come up too
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        1 => (0u32..=10_500).prop_map(|fee| Op::Initialize { fee }),
+        2 => (0..BUSINESSES, 0u32..=10_500).prop_map(|(business, fee)| Op::Register { business, fee }),
+        4 => (
+            0..BUSINESSES,
+            -10i128..=6_000,
+            0..REQUESTERS,
+            0u8..(1 << PAYERS),
+            proptest::option::of(0u32..=10_500),
+        )
+            .prop_map(|(business, amount, requester, payers, custom_fee)| Op::Create {
+                business,
+                amount,
+                requester,
+                payers,
+                custom_fee,
+            }),
+        4 => (1..=MAX_PAYMENT_ID, 0..PAYERS).prop_map(|(payment_id, payer)| Op::Execute { payment_id, payer }),
+        3 => (1..=MAX_PAYMENT_ID, 0..PAYERS, -10i128..=3_000)
+            .prop_map(|(payment_id, payer, amount)| Op::ExecutePartial { payment_id, payer, amount }),
+        2 => (1..=MAX_PAYMENT_ID, caller()).prop_map(|(payment_id, caller)| Op::Cancel { payment_id, caller }),
+        2 => (0..BUSINESSES, any::<bool>(), caller())
+            .prop_map(|(business, is_active, caller)| Op::UpdateBusinessStatus { business, is_active, caller }),
+    ]
+}
+
+#[derive(Clone, Copy)]
+struct ModelBusiness {
+    fee: u32,
+    is_active: bool,
+}
+
+#[derive(Clone)]
+struct ModelRequest {
+    business: usize,
+    amount: i128,
+    requester: usize,
+    payers: u8,
+    fee: u32,
+    status: PaymentStatus,
+    amount_paid: i128,
+    fees_paid: i128,
+}
+
+#[derive(Clone, Copy, Default)]
+struct ModelHistory {
+    total_payments: u64,
+    total_amount: i128,
+    last_payment_id: u64,
+}
+
+#[derive(Default)]
+struct Model {
+    initialized: bool,
+    businesses: [Option<ModelBusiness>; BUSINESSES],
+    requests: std::vec::Vec<ModelRequest>,
+    histories: [ModelHistory; PAYERS],
+}
+
+impl Model {
+    fn request(&self, payment_id: u64) -> Option<&ModelRequest> {
+        self.requests.get(payment_id.checked_sub(1)? as usize)
+    }
+}
+
+struct Harness<'a> {
+    env: Env,
+    client: PaymentContractClient<'a>,
+    token: TokenClient<'a>,
+    contract_id: Address,
+    owner: Address,
+    stranger: Address,
+    business_names: [String; BUSINESSES],
+    business_owners: [Address; BUSINESSES],
+    fee_recipients: [Address; BUSINESSES],
+    requesters: [Address; REQUESTERS],
+    payers: [Address; PAYERS],
+    model: Model,
+}
+
+fn harness<'a>() -> Harness<'a> {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+    env.mock_all_auths();
+    // A sequence makes more calls than one budget covers
+    env.budget().reset_unlimited();
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(&env)).address();
+    let token = TokenClient::new(&env, &token_address);
+    let payers = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    for (payer, balance) in payers.iter().zip(PAYER_BALANCES) {
+        if balance > 0 {
+            StellarAssetClient::new(&env, &token_address).mint(payer, &balance);
+        }
+    }
+
+    Harness {
+        client,
+        token,
+        contract_id,
+        owner: Address::generate(&env),
+        stranger: Address::generate(&env),
+        business_names: [String::from_str(&env, "Store A"), String::from_str(&env, "Store B")],
+        business_owners: [Address::generate(&env), Address::generate(&env)],
+        fee_recipients: [Address::generate(&env), Address::generate(&env)],
+        requesters: [Address::generate(&env), Address::generate(&env)],
+        payers,
+        model: Model::default(),
+        env,
+    }
+}
+
+impl Harness<'_> {
+    fn balances(&self) -> std::vec::Vec<i128> {
+        let holders = self.payers.iter()
+            .chain(&self.requesters)
+            .chain(&self.fee_recipients)
+            .chain([&self.contract_id, &self.owner, &self.stranger]);
+        holders.map(|holder| self.token.balance(holder)).collect()
+    }
+
+    fn caller(&self, caller: Caller, party: &Address) -> Address {
+        match caller {
+            Caller::Owner => self.owner.clone(),
+            Caller::Party => party.clone(),
+            Caller::Stranger => self.stranger.clone(),
+        }
+    }
+
+    // Runs one call, checking its outcome against the model and updating the model
+    fn apply(&mut self, op: &Op) {
+        match *op {
+            Op::Initialize { fee } => {
+                let result = outcome(self.client.try_initialize(&self.owner, &fee, &self.token.address));
+                if fee > 10_000 {
+                    assert_eq!(result, Err(Error::InvalidFeePercentage));
+                } else {
+                    assert_eq!(result, Ok(()));
+                    self.model.initialized = true;
+                }
+            }
+            Op::Register { business, fee } => {
+                let result = outcome(self.client.try_register_business(
+                    &self.business_names[business],
+                    &self.business_owners[business],
+                    &self.fee_recipients[business],
+                    &fee,
+                ));
+                if fee > 10_000 {
+                    assert_eq!(result, Err(Error::InvalidFeePercentage));
+                } else {
+                    assert_eq!(result, Ok(()));
+                    self.model.businesses[business] = Some(ModelBusiness { fee, is_active: true });
+                }
+            }
+            Op::Create { business, amount, requester, payers, custom_fee } => {
+                let mut authorized = Vec::new(&self.env);
+                for (index, payer) in self.payers.iter().enumerate() {
+                    if payers & (1 << index) != 0 {
+                        authorized.push_back(payer.clone());
+                    }
+                }
+                let result = outcome(self.client.try_create_payment_request(
+                    &amount,
+                    &self.business_names[business],
+                    &String::from_str(&self.env, "Order"),
+                    &String::from_str(&self.env, "USDC"),
+                    &authorized,
+                    &self.requesters[requester],
+                    &custom_fee,
+                ));
+
+                let config = self.model.businesses[business];
+                let fee = custom_fee.or(config.map(|config| config.fee)).unwrap_or(0);
+                let expected = if amount <= 0 {
+                    Err(Error::InvalidAmount)
+                } else if payers == 0 {
+                    Err(Error::InvalidAddress)
+                } else if config.is_none() {
+                    Err(Error::BusinessNotFound)
+                } else if config.is_some_and(|config| !config.is_active) {
+                    Err(Error::BusinessNotActive)
+                } else if fee > 10_000 {
+                    Err(Error::InvalidFeePercentage)
+                } else {
+                    Ok(self.model.requests.len() as u64 + 1)
+                };
+                assert_eq!(result, expected);
+                if expected.is_ok() {
+                    self.model.requests.push(ModelRequest {
+                        business,
+                        amount,
+                        requester,
+                        payers,
+                        fee,
+                        status: PaymentStatus::Pending,
+                        amount_paid: 0,
+                        fees_paid: 0,
+                    });
+                }
+            }
+            Op::Execute { payment_id, payer } => {
+                let payer_address = self.payers[payer].clone();
+                let balance = self.token.balance(&payer_address);
+                let result = outcome(self.client.try_execute_payment(&payment_id, &payer_address, &self.token.address));
+                self.check_payment(payment_id, payer, balance, None, result);
+            }
+            Op::ExecutePartial { payment_id, payer, amount } => {
+                let payer_address = self.payers[payer].clone();
+                let balance = self.token.balance(&payer_address);
+                let result = outcome(self.client.try_execute_partial_payment(
+                    &payment_id,
+                    &payer_address,
+                    &self.token.address,
+                    &amount,
+                ));
+                self.check_payment(payment_id, payer, balance, Some(amount), result);
+            }
+            Op::Cancel { payment_id, caller } => {
+                let request = self.model.request(payment_id).cloned();
+                let party = request.as_ref().map_or(&self.stranger, |request| &self.requesters[request.requester]);
+                let caller_address = self.caller(caller, party);
+                let result = outcome(self.client.try_cancel_payment_request(&payment_id, &caller_address));
+
+                let expected = match &request {
+                    None => Err(Error::PaymentNotFound),
+                    Some(_) if !self.model.initialized => Err(Error::ContractNotInitialized),
+                    Some(_) if matches!(caller, Caller::Stranger) => Err(Error::NotAuthorized),
+                    Some(request) => match request.status {
+                        PaymentStatus::Pending => Ok(()),
+                        PaymentStatus::Completed => Err(Error::PaymentAlreadyCompleted),
+                        PaymentStatus::PartiallyPaid => Err(Error::InvalidPaymentStatus),
+                        _ => Err(Error::PaymentNotFound),
+                    },
+                };
+                assert_eq!(result, expected);
+                if expected.is_ok() {
+                    self.model.requests[payment_id as usize - 1].status = PaymentStatus::Cancelled;
+                }
+            }
+            Op::UpdateBusinessStatus { business, is_active, caller } => {
+                let caller_address = self.caller(caller, &self.business_owners[business].clone());
+                let result = outcome(self.client.try_update_business_status(
+                    &self.business_names[business],
+                    &is_active,
+                    &caller_address,
+                ));
+
+                let expected = if self.model.businesses[business].is_none() {
+                    Err(Error::BusinessNotFound)
+                } else if !self.model.initialized {
+                    Err(Error::ContractNotInitialized)
+                } else if matches!(caller, Caller::Stranger) {
+                    Err(Error::NotAuthorized)
+                } else {
+                    Ok(())
+                };
+                assert_eq!(result, expected);
+                if expected.is_ok() {
+                    self.model.businesses[business].as_mut().unwrap().is_active = is_active;
+                }
+            }
+        }
+    }
+
+    // Checks a full (`installment` of `None`) or partial payment by a payer
+    // holding `balance` against the model
+    fn check_payment(
+        &mut self,
+        payment_id: u64,
+        payer: usize,
+        balance: i128,
+        installment: Option<i128>,
+        result: Result<(), Error>,
+    ) {
+        let Some(request) = self.model.request(payment_id).cloned() else {
+            assert_eq!(result, Err(Error::PaymentNotFound));
+            return;
+        };
+        let outstanding = request.amount - request.amount_paid;
+        let amount = installment.unwrap_or(outstanding);
+
+        let expected = match request.status {
+            PaymentStatus::Pending | PaymentStatus::PartiallyPaid => {
+                if request.payers & (1 << payer) == 0 {
+                    Err(Error::NotAuthorized)
+                } else if amount <= 0 {
+                    Err(Error::InvalidAmount)
+                } else if amount > outstanding {
+                    Err(Error::Overpayment)
+                } else if balance < amount {
+                    Err(Error::InsufficientBalance)
+                } else {
+                    Ok(())
+                }
+            }
+            PaymentStatus::Completed => Err(Error::PaymentAlreadyCompleted),
+            _ => Err(Error::PaymentNotFound),
+        };
+        assert_eq!(result, expected);
+        if expected.is_err() {
+            return;
+        }
+
+        let request = &mut self.model.requests[payment_id as usize - 1];
+        request.amount_paid += amount;
+        request.status = if request.amount_paid == request.amount {
+            PaymentStatus::Completed
+        } else {
+            PaymentStatus::PartiallyPaid
+        };
+        let history = &mut self.model.histories[payer];
+        history.total_payments += 1;
+        history.total_amount += amount;
+        history.last_payment_id = payment_id;
+    }
+
+    // Checks the invariants after `op`, given the balances before it
+    fn check_invariants(&mut self, op: &Op, before: &[i128], statuses: &[(PaymentStatus, i128)]) {
+        let after = self.balances();
+        let minted: i128 = PAYER_BALANCES.iter().sum();
+        assert_eq!(after.iter().sum::<i128>(), minted, "tokens created or lost by {op:?}");
+
+        // What a payment moved: the payer's loss is split between the
+        // requester and the fee recipient
+        if let Op::Execute { payment_id, payer } | Op::ExecutePartial { payment_id, payer, .. } = *op {
+            let paid = before[payer] - after[payer];
+            if paid > 0 {
+                let request = &self.model.requests[payment_id as usize - 1];
+                let requester = PAYERS + request.requester;
+                let fee_recipient = PAYERS + REQUESTERS + request.business;
+                let fee = after[fee_recipient] - before[fee_recipient];
+                assert!((0..=paid).contains(&fee), "fee {fee} of a {paid} payment");
+                assert_eq!(after[requester] - before[requester], paid - fee);
+                self.model.requests[payment_id as usize - 1].fees_paid += fee;
+            } else {
+                assert_eq!(after, before, "{op:?} moved funds without charging the payer");
+            }
+        } else {
+            assert_eq!(after, before, "{op:?} moved funds");
+        }
+
+        for (index, request) in self.model.requests.iter().enumerate() {
+            let payment_id = index as u64 + 1;
+            let stored = self.client.get_payment_request(&payment_id);
+            assert_eq!((stored.status, stored.amount_paid), (request.status, request.amount_paid));
+            assert_eq!(stored.fee_percentage, request.fee);
+            assert!(request.fees_paid <= request.amount_paid);
+
+            if let Some(&(status, amount_paid)) = statuses.get(index) {
+                assert!(stored.amount_paid >= amount_paid, "request {payment_id} lost paid amount");
+                let allowed = match status {
+                    PaymentStatus::Pending => true,
+                    PaymentStatus::PartiallyPaid => {
+                        matches!(stored.status, PaymentStatus::PartiallyPaid | PaymentStatus::Completed)
+                    }
+                    status => stored.status == status,
+                };
+                assert!(allowed, "request {payment_id} went from {status:?} to {:?}", stored.status);
+            }
+        }
+
+        for (payer, expected) in self.model.histories.iter().enumerate() {
+            let history = self.client.get_payment_history(&self.payers[payer]);
+            assert_eq!(
+                (history.total_payments, history.total_amount, history.last_payment_id),
+                (expected.total_payments, expected.total_amount, expected.last_payment_id),
+            );
+
+            // Contributions are tracked per request, the history per payer
+            let contributed: i128 = (1..=self.model.requests.len() as u64)
+                .filter_map(|payment_id| self.client.get_payment_contributions(&payment_id).get(self.payers[payer].clone()))
+                .sum();
+            assert_eq!(history.total_amount, contributed);
+        }
+    }
+
+    fn statuses(&self) -> std::vec::Vec<(PaymentStatus, i128)> {
+        (1..=self.model.requests.len() as u64)
+            .map(|payment_id| {
+                let stored = self.client.get_payment_request(&payment_id);
+                (stored.status, stored.amount_paid)
+            })
+            .collect()
+    }
+}
+
+// The contract's answer, failing the test on anything but a contract error
+fn outcome<T, C: std::fmt::Debug, I: std::fmt::Debug>(
+    result: Result<Result<T, C>, Result<Error, I>>,
+) -> Result<T, Error> {
+    match result {
+        Ok(value) => Ok(value.expect("return value converts")),
+        Err(error) => Err(error.expect("call fails with a contract error")),
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn test_random_call_sequences_keep_invariants(ops in proptest::collection::vec(op(), 1..32)) {
+        let mut harness = harness();
+        for op in &ops {
+            let before = harness.balances();
+            let statuses = harness.statuses();
+            harness.apply(op);
+            harness.check_invariants(op, &before, &statuses);
+        }
+    }
+}
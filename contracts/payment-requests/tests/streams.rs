@@ -0,0 +1,115 @@
+mod common;
+
+use common::Setup;
+use payment_requests::{Error, StreamStatus};
+use soroban_sdk::testutils::{Events, Ledger};
+
+fn setup<'a>() -> Setup<'a> {
+    let setup = Setup::new(0);
+    setup.env.ledger().set_timestamp(1_000);
+    setup
+}
+
+#[test]
+fn test_stream_vests_after_cliff() {
+    let Setup { env, client, token, payer: sender, requester: recipient, .. } = setup();
+
+    // 10_000 over 1_000 seconds with a 250 second cliff
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token.address,
+        &10_000,
+        &1_000,
+        &1_250,
+        &2_000,
+    );
+    assert_eq!(token.balance(&sender), 90_000);
+    assert_eq!(token.balance(&client.address), 10_000);
+
+    // Nothing is withdrawable before the cliff
+    env.ledger().set_timestamp(1_200);
+    assert_eq!(client.get_stream_balance(&stream_id), 0);
+    let events = env.events().all().len();
+    assert_eq!(client.withdraw_from_stream(&stream_id), 0);
+    assert_eq!(env.events().all().len(), events);
+
+    env.ledger().set_timestamp(1_500);
+    assert_eq!(client.get_stream_balance(&stream_id), 5_000);
+    assert_eq!(client.withdraw_from_stream(&stream_id), 5_000);
+    assert_eq!(token.balance(&recipient), 5_000);
+
+    env.ledger().set_timestamp(1_750);
+    assert_eq!(client.withdraw_from_stream(&stream_id), 2_500);
+
+    // Past the end the remainder is released and the stream is depleted
+    env.ledger().set_timestamp(5_000);
+    assert_eq!(client.withdraw_from_stream(&stream_id), 2_500);
+    assert_eq!(token.balance(&recipient), 10_000);
+    assert_eq!(token.balance(&client.address), 0);
+
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.withdrawn, 10_000);
+    assert_eq!(stream.status, StreamStatus::Depleted);
+    assert_eq!(client.try_withdraw_from_stream(&stream_id), Err(Ok(Error::StreamNotActive)));
+}
+
+#[test]
+fn test_cancel_stream_refunds_unvested() {
+    let Setup { env, client, token, payer: sender, requester: recipient, .. } = setup();
+
+    let stream_id = client.create_stream(
+        &sender,
+        &recipient,
+        &token.address,
+        &10_000,
+        &1_000,
+        &1_000,
+        &2_000,
+    );
+
+    env.ledger().set_timestamp(1_100);
+    assert_eq!(client.withdraw_from_stream(&stream_id), 1_000);
+
+    // Recipient keeps what vested, sender reclaims the rest
+    env.ledger().set_timestamp(1_400);
+    assert_eq!(client.cancel_stream(&stream_id), 6_000);
+    assert_eq!(token.balance(&recipient), 4_000);
+    assert_eq!(token.balance(&sender), 96_000);
+    assert_eq!(token.balance(&client.address), 0);
+
+    let stream = client.get_stream(&stream_id);
+    assert_eq!(stream.status, StreamStatus::Cancelled);
+    assert_eq!(client.get_stream_balance(&stream_id), 0);
+
+    env.ledger().set_timestamp(3_000);
+    assert_eq!(client.try_withdraw_from_stream(&stream_id), Err(Ok(Error::StreamNotActive)));
+    assert_eq!(client.try_cancel_stream(&stream_id), Err(Ok(Error::StreamNotActive)));
+}
+
+#[test]
+fn test_invalid_streams() {
+    let Setup { client, token, payer: sender, requester: recipient, .. } = setup();
+
+    // Cliff after the end
+    let result = client.try_create_stream(
+        &sender,
+        &recipient,
+        &token.address,
+        &10_000,
+        &1_000,
+        &3_000,
+        &2_000,
+    );
+    assert_eq!(result, Err(Ok(Error::InvalidStream)));
+
+    // Empty deposit
+    let result = client.try_create_stream(&sender, &recipient, &token.address, &0, &1_000, &1_000, &2_000);
+    assert_eq!(result, Err(Ok(Error::InvalidStream)));
+
+    // Streaming to yourself
+    let result = client.try_create_stream(&sender, &sender, &token.address, &10, &1_000, &1_000, &2_000);
+    assert_eq!(result, Err(Ok(Error::InvalidStream)));
+
+    assert_eq!(client.try_get_stream(&1), Err(Ok(Error::StreamNotFound)));
+}
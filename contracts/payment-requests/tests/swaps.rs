@@ -0,0 +1,173 @@
+mod common;
+
+use common::Setup;
+use payment_requests::{ComplianceConfig, ComplianceProvider, Error, PaymentStatus};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::Ledger,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env,
+};
+
+/// Constant-price pool standing in for an AMM: `rate` source tokens buy one
+/// unit of the destination token. A `short` pool delivers half of what it
+/// reports.
+#[contract]
+pub struct MockPool;
+
+#[contractimpl]
+impl MockPool {
+    pub fn set_rate(env: Env, rate: i128) {
+        env.storage().instance().set(&symbol_short!("rate"), &rate);
+    }
+
+    pub fn set_short(env: Env, short: bool) {
+        env.storage().instance().set(&symbol_short!("short"), &short);
+    }
+
+    pub fn quote_in(env: Env, _token_in: Address, _token_out: Address, amount_out: i128) -> i128 {
+        let rate: i128 = env.storage().instance().get(&symbol_short!("rate")).unwrap();
+        amount_out * rate
+    }
+
+    pub fn swap(
+        env: Env,
+        _token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_amount_out: i128,
+        to: Address,
+    ) -> i128 {
+        let rate: i128 = env.storage().instance().get(&symbol_short!("rate")).unwrap();
+        let amount_out = amount_in / rate;
+        assert!(amount_out >= min_amount_out, "slippage");
+        let short: bool = env.storage().instance().get(&symbol_short!("short")).unwrap_or(false);
+        let delivered = if short { amount_out / 2 } else { amount_out };
+        TokenClient::new(&env, &token_out).transfer(&env.current_contract_address(), &to, &delivered);
+        amount_out
+    }
+}
+
+struct SwapSetup<'a> {
+    setup: Setup<'a>,
+    pool: MockPoolClient<'a>,
+    usdc: TokenClient<'a>,
+    payment_id: u64,
+}
+
+// The payer holds the common token, called XLM here, and the pool sells USDC
+fn setup<'a>() -> SwapSetup<'a> {
+    let setup = Setup::new(100);
+    let env = &setup.env;
+    env.ledger().set_timestamp(1_000);
+
+    let usdc = TokenClient::new(env, &env.register_stellar_asset_contract_v2(setup.owner.clone()).address());
+    let pool = MockPoolClient::new(env, &env.register_contract(None, MockPool));
+    pool.set_rate(&10);
+    StellarAssetClient::new(env, &usdc.address).mint(&pool.address, &1_000_000);
+
+    let payment_id = setup.create_request(1_000);
+    SwapSetup { setup, pool, usdc, payment_id }
+}
+
+#[test]
+fn test_swap_payment() {
+    let SwapSetup {
+        setup: Setup { client, token: xlm, owner, payer, requester, fee_recipient, .. },
+        pool, usdc, payment_id,
+    } = setup();
+
+    client.set_swap_adapter(&owner, &pool.address);
+
+    let spent = client.execute_swap_payment(
+        &payment_id,
+        &payer,
+        &xlm.address,
+        &usdc.address,
+        &10_500,
+        &2_000,
+    );
+    assert_eq!(spent, 10_000);
+
+    // Payer spent XLM, merchant and fee recipient were paid in USDC
+    assert_eq!(xlm.balance(&payer), 90_000);
+    assert_eq!(xlm.balance(&pool.address), 10_000);
+    assert_eq!(usdc.balance(&requester), 990);
+    assert_eq!(usdc.balance(&fee_recipient), 10);
+    assert_eq!(usdc.balance(&client.address), 0);
+
+    let payment_request = client.get_payment_request(&payment_id);
+    assert_eq!(payment_request.status, PaymentStatus::Completed);
+    assert_eq!(client.get_payment_contributions(&payment_id).get(payer), Some(1_000));
+}
+
+#[test]
+fn test_swap_payment_bounds() {
+    let SwapSetup {
+        setup: Setup { env, client, token: xlm, owner, payer, .. },
+        pool, usdc, payment_id,
+    } = setup();
+
+    // No liquidity source configured yet
+    let result = client.try_execute_swap_payment(&payment_id, &payer, &xlm.address, &usdc.address, &10_500, &2_000);
+    assert_eq!(result, Err(Ok(Error::SwapAdapterNotSet)));
+
+    let result = client.try_set_swap_adapter(&payer, &pool.address);
+    assert_eq!(result, Err(Ok(Error::NotAuthorized)));
+    client.set_swap_adapter(&owner, &pool.address);
+    assert_eq!(client.get_swap_adapter(), Some(pool.address.clone()));
+
+    // Price moved against the payer beyond their bound
+    pool.set_rate(&11);
+    let result = client.try_execute_swap_payment(&payment_id, &payer, &xlm.address, &usdc.address, &10_500, &2_000);
+    assert_eq!(result, Err(Ok(Error::SlippageExceeded)));
+
+    // Deadline passed
+    env.ledger().set_timestamp(2_001);
+    let result = client.try_execute_swap_payment(&payment_id, &payer, &xlm.address, &usdc.address, &20_000, &2_000);
+    assert_eq!(result, Err(Ok(Error::DeadlineExpired)));
+
+    assert_eq!(xlm.balance(&payer), 100_000);
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Pending);
+}
+
+#[test]
+fn test_blocked_swap_moves_nothing() {
+    let SwapSetup {
+        setup: Setup { client, token: xlm, owner, payer, requester, .. },
+        pool, usdc, payment_id,
+    } = setup();
+
+    client.set_swap_adapter(&owner, &pool.address);
+    client.set_compliance_config(&owner, &ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    });
+    client.set_denylisted(&owner, &requester, &true);
+
+    let spent = client.execute_swap_payment(&payment_id, &payer, &xlm.address, &usdc.address, &10_500, &2_000);
+    assert_eq!(spent, 0);
+    assert_eq!(xlm.balance(&payer), 100_000);
+    assert_eq!(usdc.balance(&client.address), 0);
+    assert_eq!(client.get_blocked_payment(&payment_id).unwrap().blocked_address, requester);
+    assert_eq!(client.get_payment_request(&payment_id).status, PaymentStatus::Pending);
+}
+
+#[test]
+fn test_swap_counts_only_delivered_tokens() {
+    let SwapSetup {
+        setup: Setup { client, token: xlm, owner, payer, requester, .. },
+        pool, usdc, payment_id,
+    } = setup();
+
+    client.set_swap_adapter(&owner, &pool.address);
+    pool.set_short(&true);
+
+    // Proceeds held for other businesses must not make up the shortfall
+    StellarAssetClient::new(&client.env, &usdc.address).mint(&client.address, &5_000);
+    let result = client.try_execute_swap_payment(&payment_id, &payer, &xlm.address, &usdc.address, &10_500, &2_000);
+    assert_eq!(result, Err(Ok(Error::SlippageExceeded)));
+    assert_eq!(usdc.balance(&client.address), 5_000);
+    assert_eq!(usdc.balance(&requester), 0);
+    assert_eq!(xlm.balance(&payer), 100_000);
+}
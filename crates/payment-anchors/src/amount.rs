@@ -0,0 +1,60 @@
+//! Decimal amounts as anchors exchange them, e.g. `"100.5"`.
+
+/// `value` base units with `decimals` decimal places, without trailing zeros
+pub fn format(value: i128, decimals: u32) -> String {
+    let scale = 10i128.pow(decimals);
+    let sign = if value < 0 { "-" } else { "" };
+    let (units, fraction) = (value.abs() / scale, value.abs() % scale);
+    let fraction = format!("{fraction:0width$}", width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{sign}{units}")
+    } else {
+        format!("{sign}{units}.{fraction}")
+    }
+}
+
+/// Base units of a non-negative decimal amount, or `None` when it is
+/// malformed or more precise than `decimals`
+pub fn parse(amount: &str, decimals: u32) -> Option<i128> {
+    let (units, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if units.is_empty() || fraction.len() > decimals as usize {
+        return None;
+    }
+    if !units
+        .bytes()
+        .chain(fraction.bytes())
+        .all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+    let fraction = format!("{fraction:0<width$}", width = decimals as usize);
+    let scale = 10i128.checked_pow(decimals)?;
+    units
+        .parse::<i128>()
+        .ok()?
+        .checked_mul(scale)?
+        .checked_add(fraction.parse::<i128>().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_amounts_round_trip() {
+        assert_eq!(format(1_005_000_000, 7), "100.5");
+        assert_eq!(format(1_000_000_000, 7), "100");
+        assert_eq!(format(1, 7), "0.0000001");
+        assert_eq!(parse("100.5", 7), Some(1_005_000_000));
+        assert_eq!(parse("100", 7), Some(1_000_000_000));
+        assert_eq!(parse("0.0000001", 7), Some(1));
+    }
+
+    #[test]
+    fn test_malformed_amounts_are_rejected() {
+        for amount in ["", ".5", "1.00000001", "-1", "1e5", "1.2.3"] {
+            assert_eq!(parse(amount, 7), None, "{amount}");
+        }
+    }
+}
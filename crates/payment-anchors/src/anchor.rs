@@ -0,0 +1,152 @@
+//! HTTP plumbing and the transaction record shared by SEP-24 and SEP-31.
+
+use std::time::Duration;
+
+use payments_client::types::RampStatus;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::AnchorError;
+
+/// An anchor transaction as returned by `GET /transaction` (SEP-24) and
+/// `GET /transactions/:id` (SEP-31). Fields the service does not use are
+/// ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct AnchorTransaction {
+    pub id: String,
+    /// SEP-24/SEP-31 status, e.g. `pending_user_transfer_start`
+    pub status: String,
+    pub amount_in: Option<String>,
+    pub amount_out: Option<String>,
+    pub amount_fee: Option<String>,
+    pub stellar_transaction_id: Option<String>,
+    /// Where a withdrawal is paid once the anchor is ready for it
+    pub withdraw_anchor_account: Option<String>,
+    pub withdraw_memo: Option<String>,
+    pub withdraw_memo_type: Option<String>,
+    pub message: Option<String>,
+}
+
+impl AnchorTransaction {
+    /// Status of the ramp in contract state. Statuses the anchor can still
+    /// move on from are pending.
+    pub fn ramp_status(&self) -> RampStatus {
+        match self.status.as_str() {
+            "completed" => RampStatus::Completed,
+            "error" | "expired" | "refunded" | "no_market" | "too_small" | "too_large" => {
+                RampStatus::Failed
+            }
+            _ => RampStatus::Pending,
+        }
+    }
+
+    /// The anchor is waiting for the user's on-chain payment
+    pub fn awaits_payment(&self) -> bool {
+        matches!(
+            self.status.as_str(),
+            "pending_user_transfer_start" | "pending_sender"
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct TransactionResponse {
+    transaction: AnchorTransaction,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Authenticated requests against one anchor server
+#[derive(Clone)]
+pub(crate) struct Http {
+    agent: ureq::Agent,
+    server: String,
+    authorization: String,
+}
+
+impl Http {
+    pub(crate) fn new(server: &str, auth_token: &str) -> Http {
+        Http {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+            server: server.trim_end_matches('/').to_string(),
+            authorization: format!("Bearer {auth_token}"),
+        }
+    }
+
+    pub(crate) fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, AnchorError> {
+        let mut request = self
+            .agent
+            .get(&self.url(path))
+            .set("Authorization", &self.authorization);
+        for (name, value) in query {
+            request = request.query(name, value);
+        }
+        read(request.call())
+    }
+
+    pub(crate) fn post_form<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        form: &[(&str, &str)],
+    ) -> Result<T, AnchorError> {
+        read(
+            self.agent
+                .post(&self.url(path))
+                .set("Authorization", &self.authorization)
+                .send_form(form),
+        )
+    }
+
+    pub(crate) fn post_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<T, AnchorError> {
+        read(
+            self.agent
+                .post(&self.url(path))
+                .set("Authorization", &self.authorization)
+                .send_json(body),
+        )
+    }
+
+    pub(crate) fn transaction(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<AnchorTransaction, AnchorError> {
+        self.get::<TransactionResponse>(path, query)
+            .map(|response| response.transaction)
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.server)
+    }
+}
+
+fn read<T: DeserializeOwned>(
+    result: Result<ureq::Response, ureq::Error>,
+) -> Result<T, AnchorError> {
+    match result {
+        Ok(response) => response
+            .into_json()
+            .map_err(|error| AnchorError::Protocol(error.to_string())),
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            let message = serde_json::from_str::<ErrorResponse>(&body)
+                .map(|error| error.error)
+                .unwrap_or(body);
+            Err(AnchorError::Anchor { status, message })
+        }
+        Err(error) => Err(AnchorError::Http(error.to_string())),
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+
+use payments_client::{types::PaymentStatus, ClientError};
+
+#[derive(Debug)]
+pub enum AnchorError {
+    /// The anchor could not be reached
+    Http(String),
+    /// The anchor refused the request
+    Anchor {
+        status: u16,
+        message: String,
+    },
+    /// The anchor answered with something the service cannot use
+    Protocol(String),
+    /// The anchor is not configured for this SEP
+    Unsupported(&'static str),
+    /// The payment request is not in a state the ramp applies to
+    RequestState {
+        payment_id: u64,
+        status: PaymentStatus,
+    },
+    /// Open amount and fiat priced requests have no token amount to deposit
+    NoTokenAmount(u64),
+    InvalidAmount(i128),
+    Ledger(ClientError),
+}
+
+impl fmt::Display for AnchorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnchorError::Http(message) => write!(f, "http error: {message}"),
+            AnchorError::Anchor { status, message } => {
+                write!(f, "anchor returned {status}: {message}")
+            }
+            AnchorError::Protocol(message) => write!(f, "unexpected anchor response: {message}"),
+            AnchorError::Unsupported(sep) => write!(f, "anchor is not configured for {sep}"),
+            AnchorError::RequestState { payment_id, status } => {
+                write!(f, "payment request {payment_id} is {status:?}")
+            }
+            AnchorError::NoTokenAmount(payment_id) => {
+                write!(f, "payment request {payment_id} has no token amount")
+            }
+            AnchorError::InvalidAmount(amount) => write!(f, "invalid amount {amount}"),
+            AnchorError::Ledger(error) => write!(f, "ledger error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for AnchorError {}
+
+impl From<ClientError> for AnchorError {
+    fn from(error: ClientError) -> AnchorError {
+        AnchorError::Ledger(error)
+    }
+}
@@ -0,0 +1,204 @@
+//! What the ramp service needs from the network: reading and updating the
+//! contract, and sending classic payments to anchors.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ed25519_dalek::SigningKey;
+use payments_client::{
+    transaction,
+    types::{Address, PaymentRequest, RampRecord},
+    ClientError, PaymentsClient,
+};
+use stellar_strkey::{ed25519, Strkey};
+use stellar_xdr::curr::{
+    AccountId, AlphaNum12, AlphaNum4, Asset, AssetCode12, AssetCode4, Hash, Memo as XdrMemo,
+    MuxedAccount, Operation, OperationBody, PaymentOp, Preconditions, PublicKey, SequenceNumber,
+    Transaction, TransactionExt, Uint256,
+};
+
+use crate::{AnchorError, RampAsset};
+
+/// Memo an anchor asks payments to carry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Memo {
+    None,
+    Text(String),
+    Id(u64),
+    Hash([u8; 32]),
+}
+
+impl Memo {
+    /// Memo from an anchor's `memo` and `memo_type` fields; hash memos are
+    /// base64 encoded
+    pub fn parse(memo: Option<&str>, memo_type: Option<&str>) -> Result<Memo, AnchorError> {
+        let Some(memo) = memo else {
+            return Ok(Memo::None);
+        };
+        let invalid = || AnchorError::Protocol(format!("invalid {memo_type:?} memo {memo:?}"));
+        match memo_type.unwrap_or("text") {
+            "text" if memo.len() <= 28 => Ok(Memo::Text(memo.to_string())),
+            "id" => memo.parse().map(Memo::Id).map_err(|_| invalid()),
+            "hash" => STANDARD
+                .decode(memo)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(Memo::Hash)
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+pub trait Ledger {
+    /// Account the service ramps for; it pays deposits into the contract
+    /// and withdrawals to the anchor
+    fn account(&self) -> Address;
+
+    fn payment_request(&self, payment_id: u64) -> Result<PaymentRequest, ClientError>;
+
+    fn ramps(&self, payment_id: u64) -> Result<Vec<RampRecord>, ClientError>;
+
+    /// Record a ramp in contract state as [`Ledger::account`]
+    fn record_ramp(&self, payment_id: u64, record: &RampRecord) -> Result<(), ClientError>;
+
+    /// Pay a request in full from [`Ledger::account`]
+    fn execute_payment(&self, payment_id: u64, token: &Address) -> Result<(), ClientError>;
+
+    /// Pay `amount` of `asset` to the Stellar account `destination` and
+    /// return the transaction hash
+    fn pay(
+        &self,
+        destination: &str,
+        asset: &RampAsset,
+        amount: i128,
+        memo: &Memo,
+    ) -> Result<String, ClientError>;
+}
+
+/// The network through Soroban RPC, signing as a single ed25519 account
+pub struct RpcLedger {
+    client: PaymentsClient,
+    key: SigningKey,
+}
+
+impl RpcLedger {
+    pub fn new(client: PaymentsClient, key: SigningKey) -> RpcLedger {
+        RpcLedger { client, key }
+    }
+
+    pub fn client(&self) -> &PaymentsClient {
+        &self.client
+    }
+
+    fn public_key(&self) -> [u8; 32] {
+        self.key.verifying_key().to_bytes()
+    }
+}
+
+impl Ledger for RpcLedger {
+    fn account(&self) -> Address {
+        Address::Account(self.public_key())
+    }
+
+    fn payment_request(&self, payment_id: u64) -> Result<PaymentRequest, ClientError> {
+        let simulation = self
+            .client
+            .get_payment_request(&payment_id)
+            .simulate(&self.public_key())?;
+        Ok(simulation.result)
+    }
+
+    fn ramps(&self, payment_id: u64) -> Result<Vec<RampRecord>, ClientError> {
+        let simulation = self
+            .client
+            .get_ramps(&payment_id)
+            .simulate(&self.public_key())?;
+        Ok(simulation.result)
+    }
+
+    fn record_ramp(&self, payment_id: u64, record: &RampRecord) -> Result<(), ClientError> {
+        self.client
+            .record_ramp(&payment_id, &self.account(), record)
+            .submit(&self.key)
+    }
+
+    fn execute_payment(&self, payment_id: u64, token: &Address) -> Result<(), ClientError> {
+        self.client
+            .execute_payment(&payment_id, &self.account(), token)
+            .submit(&self.key)
+    }
+
+    fn pay(
+        &self,
+        destination: &str,
+        asset: &RampAsset,
+        amount: i128,
+        memo: &Memo,
+    ) -> Result<String, ClientError> {
+        let invalid = |what: &str| ClientError::Protocol(format!("invalid {what}"));
+        let destination = match Strkey::from_string(destination) {
+            Ok(Strkey::PublicKeyEd25519(ed25519::PublicKey(key))) => key,
+            _ => return Err(invalid("destination account")),
+        };
+        let amount = i64::try_from(amount).map_err(|_| invalid("payment amount"))?;
+
+        let source = self.public_key();
+        let sequence = self.client.rpc().get_account_sequence(&source)? + 1;
+        let operation = Operation {
+            source_account: None,
+            body: OperationBody::Payment(PaymentOp {
+                destination: MuxedAccount::Ed25519(Uint256(destination)),
+                asset: classic_asset(asset).ok_or_else(|| invalid("asset"))?,
+                amount,
+            }),
+        };
+        let transaction = Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256(source)),
+            fee: transaction::BASE_FEE,
+            seq_num: SequenceNumber(sequence),
+            cond: Preconditions::None,
+            memo: match memo {
+                Memo::None => XdrMemo::None,
+                Memo::Text(text) => XdrMemo::Text(text.as_str().try_into()?),
+                Memo::Id(id) => XdrMemo::Id(*id),
+                Memo::Hash(hash) => XdrMemo::Hash(Hash(*hash)),
+            },
+            operations: vec![operation].try_into()?,
+            ext: TransactionExt::V0,
+        };
+        let envelope = transaction::sign(transaction, self.client.network_passphrase(), &self.key)?;
+        let (hash, _) = self.client.send(&envelope)?;
+        Ok(hash)
+    }
+}
+
+fn classic_asset(asset: &RampAsset) -> Option<Asset> {
+    let Some(issuer) = &asset.issuer else {
+        return (asset.code == "native").then_some(Asset::Native);
+    };
+    let issuer = match Strkey::from_string(issuer).ok()? {
+        Strkey::PublicKeyEd25519(ed25519::PublicKey(key)) => {
+            AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key)))
+        }
+        _ => return None,
+    };
+    let code = asset.code.as_bytes();
+    match code.len() {
+        1..=4 => {
+            let mut padded = [0; 4];
+            padded[..code.len()].copy_from_slice(code);
+            Some(Asset::CreditAlphanum4(AlphaNum4 {
+                asset_code: AssetCode4(padded),
+                issuer,
+            }))
+        }
+        5..=12 => {
+            let mut padded = [0; 12];
+            padded[..code.len()].copy_from_slice(code);
+            Some(Asset::CreditAlphanum12(AlphaNum12 {
+                asset_code: AssetCode12(padded),
+                issuer,
+            }))
+        }
+        _ => None,
+    }
+}
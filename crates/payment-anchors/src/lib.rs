@@ -0,0 +1,370 @@
+//! Anchor ramps for payment requests.
+//!
+//! [`RampService`] starts SEP-24 interactive deposits and withdrawals and
+//! SEP-31 cross-border sends for a `PaymentRequest`, and records each one in
+//! the contract with `record_ramp`. [`RampService::poll`] follows the
+//! anchor's transactions and settles them on chain:
+//!
+//! - a completed deposit pays the request with `execute_payment` from the
+//!   deposited funds
+//! - a withdrawal is paid to the anchor's account once the anchor asks for
+//!   it
+//! - a send is paid to the receiving anchor when it starts
+//!
+//! Every status change is written back to the contract, so the ramps of a
+//! request can be read with `get_ramps` and a restarted service picks up
+//! where it left off with [`RampService::resume`].
+
+mod amount;
+mod anchor;
+mod error;
+pub mod ledger;
+mod sep24;
+mod sep31;
+
+use payments_client::{
+    types::{
+        Address, PaymentRequest, PaymentStatus, RampKind, RampRecord, RampStatus, RequestKind,
+    },
+    ClientError,
+};
+
+pub use anchor::AnchorTransaction;
+pub use error::AnchorError;
+pub use ledger::{Ledger, Memo, RpcLedger};
+pub use sep24::{Interactive, Sep24};
+pub use sep31::{Parties, SendInstructions, Sep31};
+
+/// An anchor and the SEPs the service uses it for
+#[derive(Clone)]
+pub struct Anchor {
+    /// Recorded as the ramp's `anchor`
+    pub home_domain: String,
+    pub sep24: Option<Sep24>,
+    pub sep31: Option<Sep31>,
+}
+
+/// The asset ramped, both as the anchor and as the contract know it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RampAsset {
+    /// Asset code, or `native` for lumens
+    pub code: String,
+    pub issuer: Option<String>,
+    /// Stellar asset contract of the asset
+    pub token: Address,
+    /// Decimal places of the anchor's amounts, 7 for classic assets
+    pub decimals: u32,
+}
+
+/// A ramp whose record changed during [`RampService::poll`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RampUpdate {
+    pub payment_id: u64,
+    pub record: RampRecord,
+}
+
+pub struct RampService<L> {
+    ledger: L,
+    anchor: Anchor,
+    asset: RampAsset,
+    pending: Vec<(u64, RampRecord)>,
+}
+
+impl<L: Ledger> RampService<L> {
+    pub fn new(ledger: L, anchor: Anchor, asset: RampAsset) -> RampService<L> {
+        RampService {
+            ledger,
+            anchor,
+            asset,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn ledger(&self) -> &L {
+        &self.ledger
+    }
+
+    /// Ramps being followed, by payment request
+    pub fn pending(&self) -> &[(u64, RampRecord)] {
+        &self.pending
+    }
+
+    /// Follow the pending ramps the ledger account has recorded with this
+    /// anchor for a request; only it may update them. Returns how many were
+    /// picked up.
+    pub fn resume(&mut self, payment_id: u64) -> Result<usize, AnchorError> {
+        let mut resumed = 0;
+        let account = self.ledger.account();
+        for record in self.ledger.ramps(payment_id)? {
+            let followed = self
+                .pending
+                .iter()
+                .any(|(id, pending)| *id == payment_id && pending.anchor_id == record.anchor_id);
+            if record.anchor == self.anchor.home_domain
+                && record.recorded_by == account
+                && record.status == RampStatus::Pending
+                && !followed
+            {
+                self.pending.push((payment_id, record));
+                resumed += 1;
+            }
+        }
+        Ok(resumed)
+    }
+
+    /// Deposit what is left to pay on a request into the ledger account.
+    /// The request is paid once the anchor completes the deposit.
+    pub fn start_deposit(&mut self, payment_id: u64) -> Result<Interactive, AnchorError> {
+        let request = self.request(
+            payment_id,
+            &[PaymentStatus::Pending, PaymentStatus::PartiallyPaid],
+        )?;
+        // Fiat priced requests are quoted in the token only when paid
+        if matches!(
+            request.kind,
+            RequestKind::OpenAmount(_) | RequestKind::FiatPriced
+        ) {
+            return Err(AnchorError::NoTokenAmount(payment_id));
+        }
+        let amount = request.amount - request.amount_paid;
+
+        let interactive = self.sep24()?.deposit(
+            &self.asset.code,
+            &self.ledger.account().to_string(),
+            &amount::format(amount, self.asset.decimals),
+        )?;
+        self.start(
+            payment_id,
+            RampKind::Deposit,
+            &interactive.id,
+            amount,
+            String::new(),
+        )?;
+        Ok(interactive)
+    }
+
+    /// Withdraw `amount` of a completed request's proceeds from the ledger
+    /// account
+    pub fn start_withdrawal(
+        &mut self,
+        payment_id: u64,
+        amount: i128,
+    ) -> Result<Interactive, AnchorError> {
+        self.check_payout(payment_id, amount)?;
+
+        let interactive = self.sep24()?.withdraw(
+            &self.asset.code,
+            &self.ledger.account().to_string(),
+            &amount::format(amount, self.asset.decimals),
+        )?;
+        self.start(
+            payment_id,
+            RampKind::Withdrawal,
+            &interactive.id,
+            amount,
+            String::new(),
+        )?;
+        Ok(interactive)
+    }
+
+    /// Send `amount` of a completed request's proceeds to a receiver abroad,
+    /// paying the receiving anchor right away. The send is recorded before
+    /// the payment, so a payment that fails leaves a pending ramp behind
+    /// rather than an unrecorded one that could be sent again.
+    pub fn start_send(
+        &mut self,
+        payment_id: u64,
+        amount: i128,
+        parties: &Parties,
+    ) -> Result<SendInstructions, AnchorError> {
+        self.check_payout(payment_id, amount)?;
+
+        let sep31 = self
+            .anchor
+            .sep31
+            .as_ref()
+            .ok_or(AnchorError::Unsupported("SEP-31"))?;
+        let instructions = sep31.send(
+            &amount::format(amount, self.asset.decimals),
+            &self.asset.code,
+            self.asset.issuer.as_deref(),
+            parties,
+        )?;
+        let memo = Memo::parse(
+            instructions.stellar_memo.as_deref(),
+            instructions.stellar_memo_type.as_deref(),
+        )?;
+        self.start(
+            payment_id,
+            RampKind::Send,
+            &instructions.id,
+            amount,
+            String::new(),
+        )?;
+
+        let hash = self
+            .ledger
+            .pay(&instructions.stellar_account_id, &self.asset, amount, &memo)?;
+        let index = self.pending.len() - 1;
+        self.pending[index].1.stellar_transaction_id = hash;
+        self.ledger
+            .record_ramp(payment_id, &self.pending[index].1)?;
+        Ok(instructions)
+    }
+
+    /// Check every pending ramp with its anchor, settle the ones the anchor
+    /// is done with and record the changes in the contract
+    pub fn poll(&mut self) -> Result<Vec<RampUpdate>, AnchorError> {
+        let mut updates = Vec::new();
+        let mut index = 0;
+        while index < self.pending.len() {
+            let (payment_id, record) = self.pending[index].clone();
+            let next = self.advance(payment_id, &record)?;
+            // Kept before recording, so a payment made for the ramp is not
+            // made again when recording fails
+            if next.status == RampStatus::Pending {
+                self.pending[index].1 = next.clone();
+                index += 1;
+            } else {
+                self.pending.remove(index);
+            }
+            if next != record {
+                self.ledger.record_ramp(payment_id, &next)?;
+                updates.push(RampUpdate {
+                    payment_id,
+                    record: next,
+                });
+            }
+        }
+        Ok(updates)
+    }
+
+    // What the record should say given the anchor's view of the ramp
+    fn advance(&self, payment_id: u64, record: &RampRecord) -> Result<RampRecord, AnchorError> {
+        let transaction = match record.kind {
+            RampKind::Deposit | RampKind::Withdrawal => {
+                self.sep24()?.transaction(&record.anchor_id)?
+            }
+            RampKind::Send => self
+                .anchor
+                .sep31
+                .as_ref()
+                .ok_or(AnchorError::Unsupported("SEP-31"))?
+                .transaction(&record.anchor_id)?,
+        };
+        let mut next = RampRecord {
+            status: transaction.ramp_status(),
+            ..record.clone()
+        };
+
+        match record.kind {
+            RampKind::Deposit => {
+                if let Some(hash) = &transaction.stellar_transaction_id {
+                    next.stellar_transaction_id = hash.clone();
+                }
+                if next.status == RampStatus::Completed {
+                    match self.ledger.execute_payment(payment_id, &self.asset.token) {
+                        Ok(()) => {}
+                        // The deposit arrived but cannot pay the request, e.g.
+                        // because it was paid some other way meanwhile
+                        Err(ClientError::Contract(_)) => next.status = RampStatus::Failed,
+                        Err(error) => return Err(error.into()),
+                    }
+                }
+            }
+            RampKind::Withdrawal => {
+                if transaction.awaits_payment() && record.stellar_transaction_id.is_empty() {
+                    let destination =
+                        transaction
+                            .withdraw_anchor_account
+                            .as_deref()
+                            .ok_or_else(|| {
+                                AnchorError::Protocol(
+                                    "withdrawal without an anchor account".to_string(),
+                                )
+                            })?;
+                    let memo = Memo::parse(
+                        transaction.withdraw_memo.as_deref(),
+                        transaction.withdraw_memo_type.as_deref(),
+                    )?;
+                    let amount = match &transaction.amount_in {
+                        Some(amount) => {
+                            amount::parse(amount, self.asset.decimals).ok_or_else(|| {
+                                AnchorError::Protocol(format!("invalid amount_in {amount:?}"))
+                            })?
+                        }
+                        None => record.amount,
+                    };
+                    next.stellar_transaction_id =
+                        self.ledger.pay(destination, &self.asset, amount, &memo)?;
+                }
+            }
+            RampKind::Send => {}
+        }
+        Ok(next)
+    }
+
+    fn request(
+        &self,
+        payment_id: u64,
+        statuses: &[PaymentStatus],
+    ) -> Result<PaymentRequest, AnchorError> {
+        let request = self.ledger.payment_request(payment_id)?;
+        if !statuses.contains(&request.status) {
+            return Err(AnchorError::RequestState {
+                payment_id,
+                status: request.status,
+            });
+        }
+        Ok(request)
+    }
+
+    // Withdrawals and sends pay out a completed request's proceeds, and
+    // together never more than the request's amount
+    fn check_payout(&self, payment_id: u64, amount: i128) -> Result<(), AnchorError> {
+        let request = self.request(payment_id, &[PaymentStatus::Completed])?;
+        let paid_out: i128 = self
+            .ledger
+            .ramps(payment_id)?
+            .iter()
+            .filter(|ramp| ramp.kind != RampKind::Deposit && ramp.status != RampStatus::Failed)
+            .map(|ramp| ramp.amount)
+            .sum();
+        if amount <= 0 || amount > request.amount - paid_out {
+            return Err(AnchorError::InvalidAmount(amount));
+        }
+        Ok(())
+    }
+
+    fn sep24(&self) -> Result<&Sep24, AnchorError> {
+        self.anchor
+            .sep24
+            .as_ref()
+            .ok_or(AnchorError::Unsupported("SEP-24"))
+    }
+
+    // Record a ramp the anchor accepted and follow it
+    fn start(
+        &mut self,
+        payment_id: u64,
+        kind: RampKind,
+        anchor_id: &str,
+        amount: i128,
+        stellar_transaction_id: String,
+    ) -> Result<(), AnchorError> {
+        let record = RampRecord {
+            kind,
+            anchor: self.anchor.home_domain.clone(),
+            anchor_id: anchor_id.to_string(),
+            token_address: self.asset.token,
+            amount,
+            status: RampStatus::Pending,
+            stellar_transaction_id,
+            recorded_by: self.ledger.account(),
+            updated_at: 0,
+        };
+        self.ledger.record_ramp(payment_id, &record)?;
+        self.pending.push((payment_id, record));
+        Ok(())
+    }
+}
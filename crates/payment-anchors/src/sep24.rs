@@ -0,0 +1,81 @@
+//! SEP-24 hosted deposits and withdrawals.
+
+use serde::Deserialize;
+
+use crate::{
+    anchor::{AnchorTransaction, Http},
+    AnchorError,
+};
+
+/// Interactive flow the user completes in the anchor's web app
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Interactive {
+    pub id: String,
+    pub url: String,
+}
+
+/// Client for an anchor's SEP-24 `TRANSFER_SERVER_SEP0024`
+#[derive(Clone)]
+pub struct Sep24 {
+    http: Http,
+}
+
+impl Sep24 {
+    /// `auth_token` is the SEP-10 JWT of the account ramping
+    pub fn new(transfer_server: &str, auth_token: &str) -> Sep24 {
+        Sep24 {
+            http: Http::new(transfer_server, auth_token),
+        }
+    }
+
+    /// Start depositing `amount` of `asset_code` into `account`
+    pub fn deposit(
+        &self,
+        asset_code: &str,
+        account: &str,
+        amount: &str,
+    ) -> Result<Interactive, AnchorError> {
+        self.interactive(
+            "/transactions/deposit/interactive",
+            asset_code,
+            account,
+            amount,
+        )
+    }
+
+    /// Start withdrawing `amount` of `asset_code` from `account`
+    pub fn withdraw(
+        &self,
+        asset_code: &str,
+        account: &str,
+        amount: &str,
+    ) -> Result<Interactive, AnchorError> {
+        self.interactive(
+            "/transactions/withdraw/interactive",
+            asset_code,
+            account,
+            amount,
+        )
+    }
+
+    pub fn transaction(&self, id: &str) -> Result<AnchorTransaction, AnchorError> {
+        self.http.transaction("/transaction", &[("id", id)])
+    }
+
+    fn interactive(
+        &self,
+        path: &str,
+        asset_code: &str,
+        account: &str,
+        amount: &str,
+    ) -> Result<Interactive, AnchorError> {
+        self.http.post_form(
+            path,
+            &[
+                ("asset_code", asset_code),
+                ("account", account),
+                ("amount", amount),
+            ],
+        )
+    }
+}
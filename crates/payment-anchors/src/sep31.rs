@@ -0,0 +1,63 @@
+//! SEP-31 cross-border sends.
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    anchor::{AnchorTransaction, Http},
+    AnchorError,
+};
+
+/// Customers registered with the receiving anchor through SEP-12
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parties {
+    pub sender_id: String,
+    pub receiver_id: String,
+}
+
+/// Where the sending anchor pays the receiving anchor
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SendInstructions {
+    pub id: String,
+    pub stellar_account_id: String,
+    pub stellar_memo_type: Option<String>,
+    pub stellar_memo: Option<String>,
+}
+
+/// Client for a receiving anchor's SEP-31 `DIRECT_PAYMENT_SERVER`
+#[derive(Clone)]
+pub struct Sep31 {
+    http: Http,
+}
+
+impl Sep31 {
+    /// `auth_token` is the SEP-10 JWT of the sending account
+    pub fn new(direct_payment_server: &str, auth_token: &str) -> Sep31 {
+        Sep31 {
+            http: Http::new(direct_payment_server, auth_token),
+        }
+    }
+
+    pub fn send(
+        &self,
+        amount: &str,
+        asset_code: &str,
+        asset_issuer: Option<&str>,
+        parties: &Parties,
+    ) -> Result<SendInstructions, AnchorError> {
+        let mut body = json!({
+            "amount": amount,
+            "asset_code": asset_code,
+            "sender_id": parties.sender_id,
+            "receiver_id": parties.receiver_id,
+        });
+        if let Some(issuer) = asset_issuer {
+            body["asset_issuer"] = json!(issuer);
+        }
+        self.http.post_json("/transactions", &body)
+    }
+
+    pub fn transaction(&self, id: &str) -> Result<AnchorTransaction, AnchorError> {
+        self.http.transaction(&format!("/transactions/{id}"), &[])
+    }
+}
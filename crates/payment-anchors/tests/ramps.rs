@@ -0,0 +1,587 @@
+//! Runs deposits, withdrawals and sends against a mock anchor, settling them
+//! on the contract in a sandbox environment.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use payment_anchors::{
+    Anchor, AnchorError, Ledger, Memo, Parties, RampAsset, RampService, RampUpdate, Sep24, Sep31,
+};
+use payment_requests::{PaymentContract, PaymentContractClient};
+use payments_client::{
+    types::{Address as ClientAddress, PaymentRequest, PaymentStatus, RampRecord, RampStatus},
+    ClientError, ScType,
+};
+use serde_json::{json, Value};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger as _},
+    token::StellarAssetClient,
+    xdr::ScVal,
+    Address, Env, IntoVal, String, TryFromVal, Val,
+};
+
+const TOKEN: &str = "test-jwt";
+const HOME_DOMAIN: &str = "anchor.example";
+const ANCHOR_ACCOUNT: &str = "GCALNQQBXAPZ2WIRSDDBMSTAKCUH5SG6U76YBFLQLIXJTF7FE5AX7AOO";
+// base64 of 32 bytes of 7
+const HASH_MEMO: &str = "BwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwcHBwc=";
+
+#[derive(Default)]
+struct AnchorState {
+    transactions: HashMap<std::string::String, Value>,
+    /// Method, path and body of every request
+    received: Vec<(std::string::String, std::string::String, Value)>,
+}
+
+/// SEP-24 and SEP-31 server whose transactions the test moves along
+struct MockAnchor {
+    server: Arc<tiny_http::Server>,
+    url: std::string::String,
+    state: Arc<Mutex<AnchorState>>,
+}
+
+impl MockAnchor {
+    fn start() -> MockAnchor {
+        let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").unwrap());
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let state = Arc::new(Mutex::new(AnchorState::default()));
+
+        let (incoming, shared) = (server.clone(), state.clone());
+        thread::spawn(move || {
+            for mut request in incoming.incoming_requests() {
+                let mut body = std::string::String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                let authorized = request.headers().iter().any(|header| {
+                    header.field.equiv("Authorization")
+                        && header.value.as_str() == format!("Bearer {TOKEN}")
+                });
+                let (status, response) = if authorized {
+                    respond(
+                        &mut shared.lock().unwrap(),
+                        request.method().as_str(),
+                        request.url(),
+                        &body,
+                    )
+                } else {
+                    (403, json!({ "error": "invalid token" }))
+                };
+                let _ = request.respond(
+                    tiny_http::Response::from_string(response.to_string()).with_status_code(status),
+                );
+            }
+        });
+
+        MockAnchor { server, url, state }
+    }
+
+    /// Merge `fields` into a transaction
+    fn update(&self, id: &str, fields: Value) {
+        let mut state = self.state.lock().unwrap();
+        let transaction = state.transactions.get_mut(id).unwrap();
+        for (name, value) in fields.as_object().unwrap() {
+            transaction[name] = value.clone();
+        }
+    }
+
+    fn received(&self) -> Vec<(std::string::String, std::string::String, Value)> {
+        self.state.lock().unwrap().received.clone()
+    }
+
+    fn anchor(&self, token: &str) -> Anchor {
+        Anchor {
+            home_domain: HOME_DOMAIN.to_string(),
+            sep24: Some(Sep24::new(&format!("{}/sep24", self.url), token)),
+            sep31: Some(Sep31::new(&format!("{}/sep31/", self.url), token)),
+        }
+    }
+}
+
+impl Drop for MockAnchor {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+fn respond(state: &mut AnchorState, method: &str, url: &str, body: &str) -> (u16, Value) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let body: Value = if body.starts_with('{') {
+        serde_json::from_str(body).unwrap()
+    } else {
+        let form = body
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| (name.to_string(), json!(value.replace("%3A", ":"))));
+        Value::Object(form.collect())
+    };
+    state
+        .received
+        .push((method.to_string(), path.to_string(), body.clone()));
+    let id = format!("tx-{}", state.transactions.len() + 1);
+
+    let found = |state: &AnchorState, id: &str| match state.transactions.get(id) {
+        Some(transaction) => (200, json!({ "transaction": transaction })),
+        None => (404, json!({ "error": "transaction not found" })),
+    };
+    match (method, path) {
+        ("POST", "/sep24/transactions/deposit/interactive")
+        | ("POST", "/sep24/transactions/withdraw/interactive") => {
+            state.transactions.insert(
+                id.clone(),
+                json!({ "id": id, "status": "incomplete", "amount_in": body["amount"] }),
+            );
+            (
+                200,
+                json!({
+                    "type": "interactive_customer_info_needed",
+                    "url": format!("https://{HOME_DOMAIN}/flow/{id}"),
+                    "id": id,
+                }),
+            )
+        }
+        ("GET", "/sep24/transaction") => found(state, query.trim_start_matches("id=")),
+        ("POST", "/sep31/transactions") => {
+            state.transactions.insert(
+                id.clone(),
+                json!({ "id": id, "status": "pending_sender", "amount_in": body["amount"] }),
+            );
+            (
+                201,
+                json!({
+                    "id": id,
+                    "stellar_account_id": ANCHOR_ACCOUNT,
+                    "stellar_memo_type": "hash",
+                    "stellar_memo": HASH_MEMO,
+                }),
+            )
+        }
+        ("GET", path) if path.starts_with("/sep31/transactions/") => {
+            found(state, path.trim_start_matches("/sep31/transactions/"))
+        }
+        _ => (404, json!({ "error": "not found" })),
+    }
+}
+
+/// The contract in a sandbox, acting as one account that records its
+/// classic payments instead of sending them
+struct SandboxLedger {
+    env: Env,
+    contract_id: Address,
+    account: Address,
+    payments: RefCell<Vec<(std::string::String, i128, Memo)>>,
+}
+
+impl SandboxLedger {
+    fn client(&self) -> PaymentContractClient<'_> {
+        PaymentContractClient::new(&self.env, &self.contract_id)
+    }
+
+    fn to_client<T: IntoVal<Env, Val>, C: ScType>(&self, value: T) -> C {
+        let value: Val = value.into_val(&self.env);
+        C::from_scval(&ScVal::try_from_val(&self.env, &value).unwrap()).unwrap()
+    }
+
+    fn to_sdk<C: ScType, T: TryFromVal<Env, Val>>(&self, value: &C) -> T {
+        let value = Val::try_from_val(&self.env, &value.to_scval()).unwrap();
+        T::try_from_val(&self.env, &value).ok().unwrap()
+    }
+}
+
+fn client_error(error: Result<payment_requests::Error, soroban_sdk::InvokeError>) -> ClientError {
+    match error {
+        Ok(error) => ClientError::Contract(error),
+        Err(error) => ClientError::Host(format!("{error:?}")),
+    }
+}
+
+impl Ledger for SandboxLedger {
+    fn account(&self) -> ClientAddress {
+        self.to_client(self.account.clone())
+    }
+
+    fn payment_request(&self, payment_id: u64) -> Result<PaymentRequest, ClientError> {
+        let request = self
+            .client()
+            .try_get_payment_request(&payment_id)
+            .map_err(client_error)?
+            .unwrap();
+        Ok(self.to_client(request))
+    }
+
+    fn ramps(&self, payment_id: u64) -> Result<Vec<RampRecord>, ClientError> {
+        let ramps = self.client().get_ramps(&payment_id);
+        Ok(ramps.iter().map(|record| self.to_client(record)).collect())
+    }
+
+    fn record_ramp(&self, payment_id: u64, record: &RampRecord) -> Result<(), ClientError> {
+        let record: payment_requests::RampRecord = self.to_sdk(record);
+        self.client()
+            .try_record_ramp(&payment_id, &self.account, &record)
+            .map_err(client_error)?
+            .unwrap();
+        Ok(())
+    }
+
+    fn execute_payment(&self, payment_id: u64, token: &ClientAddress) -> Result<(), ClientError> {
+        let token: Address = self.to_sdk(token);
+        self.client()
+            .try_execute_payment(&payment_id, &self.account, &token)
+            .map_err(client_error)?
+            .unwrap();
+        Ok(())
+    }
+
+    fn pay(
+        &self,
+        destination: &str,
+        _asset: &RampAsset,
+        amount: i128,
+        memo: &Memo,
+    ) -> Result<std::string::String, ClientError> {
+        let mut payments = self.payments.borrow_mut();
+        payments.push((destination.to_string(), amount, memo.clone()));
+        Ok(format!("{:064x}", payments.len()))
+    }
+}
+
+struct Setup {
+    anchor: MockAnchor,
+    payer: RampService<SandboxLedger>,
+    requester: RampService<SandboxLedger>,
+    asset: RampAsset,
+    token: Address,
+    payment_id: u64,
+}
+
+impl Setup {
+    fn new() -> Setup {
+        let env = Env::default();
+        env.mock_all_auths();
+        env.ledger().set_timestamp(10_000);
+
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+        let owner = Address::generate(&env);
+        client.initialize(&owner, &250u32, &Address::generate(&env));
+        let store = String::from_str(&env, "Test Store");
+        client.register_business(&store, &owner, &owner, &100u32);
+
+        let token = env
+            .register_stellar_asset_contract_v2(owner.clone())
+            .address();
+        let payer = Address::generate(&env);
+        let requester = Address::generate(&env);
+        let payment_id = client.create_payment_request(
+            &1_000,
+            &store,
+            &String::from_str(&env, "Order"),
+            &String::from_str(&env, "USDC"),
+            &soroban_sdk::vec![&env, payer.clone()],
+            &requester,
+            &None,
+        );
+
+        let anchor = MockAnchor::start();
+        let ledger = |account: &Address| SandboxLedger {
+            env: env.clone(),
+            contract_id: contract_id.clone(),
+            account: account.clone(),
+            payments: RefCell::new(Vec::new()),
+        };
+        let payer_ledger = ledger(&payer);
+        let asset = RampAsset {
+            code: "USDC".to_string(),
+            issuer: Some(ANCHOR_ACCOUNT.to_string()),
+            token: payer_ledger.to_client(token.clone()),
+            decimals: 7,
+        };
+        Setup {
+            payer: RampService::new(payer_ledger, anchor.anchor(TOKEN), asset.clone()),
+            requester: RampService::new(ledger(&requester), anchor.anchor(TOKEN), asset.clone()),
+            anchor,
+            asset,
+            token,
+            payment_id,
+        }
+    }
+
+    /// Another service for the payer, e.g. after a restart
+    fn service(&self, anchor: Anchor) -> RampService<SandboxLedger> {
+        let ledger = self.payer.ledger();
+        let ledger = SandboxLedger {
+            env: ledger.env.clone(),
+            contract_id: ledger.contract_id.clone(),
+            account: ledger.account.clone(),
+            payments: RefCell::new(Vec::new()),
+        };
+        RampService::new(ledger, anchor, self.asset.clone())
+    }
+
+    fn env(&self) -> &Env {
+        &self.payer.ledger().env
+    }
+
+    fn request(&self) -> PaymentRequest {
+        self.payer
+            .ledger()
+            .payment_request(self.payment_id)
+            .unwrap()
+    }
+
+    fn ramps(&self) -> Vec<RampRecord> {
+        self.payer.ledger().ramps(self.payment_id).unwrap()
+    }
+
+    // Pays the request directly, as a customer paying on chain would
+    fn pay_request(&self) {
+        let ledger = self.payer.ledger();
+        StellarAssetClient::new(self.env(), &self.token).mint(&ledger.account, &1_000);
+        ledger
+            .execute_payment(self.payment_id, &ledger.to_client(self.token.clone()))
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_deposit_pays_request() {
+    let mut setup = Setup::new();
+    let interactive = setup.payer.start_deposit(setup.payment_id).unwrap();
+    assert_eq!(interactive.url, format!("https://{HOME_DOMAIN}/flow/tx-1"));
+
+    let (method, path, form) = &setup.anchor.received()[0];
+    assert_eq!(
+        (method.as_str(), path.as_str()),
+        ("POST", "/sep24/transactions/deposit/interactive")
+    );
+    assert_eq!(form["asset_code"], "USDC");
+    assert_eq!(form["amount"], "0.0001");
+    assert_eq!(
+        form["account"].as_str().unwrap(),
+        setup.payer.ledger().account().to_string()
+    );
+
+    let ramps = setup.ramps();
+    assert_eq!(ramps.len(), 1);
+    assert_eq!(ramps[0].status, RampStatus::Pending);
+    assert_eq!(ramps[0].anchor, HOME_DOMAIN);
+    assert_eq!(ramps[0].recorded_by, setup.payer.ledger().account());
+    assert_eq!(ramps[0].updated_at, 10_000);
+
+    setup
+        .anchor
+        .update("tx-1", json!({ "status": "pending_anchor" }));
+    assert_eq!(setup.payer.poll().unwrap(), Vec::new());
+
+    // A restarted service picks the deposit up from the contract
+    let mut restarted = setup.service(setup.anchor.anchor(TOKEN));
+    assert_eq!(restarted.resume(setup.payment_id).unwrap(), 1);
+    assert_eq!(restarted.resume(setup.payment_id).unwrap(), 0);
+    // Only the payer's account may update the payer's ramp
+    assert_eq!(setup.requester.resume(setup.payment_id).unwrap(), 0);
+
+    // The anchor sends the funds, then reports the deposit as completed
+    StellarAssetClient::new(setup.env(), &setup.token).mint(&setup.payer.ledger().account, &1_000);
+    setup.anchor.update(
+        "tx-1",
+        json!({ "status": "completed", "stellar_transaction_id": "abc123" }),
+    );
+    let updates = restarted.poll().unwrap();
+    assert_eq!(updates.len(), 1);
+    let RampUpdate { payment_id, record } = &updates[0];
+    assert_eq!(*payment_id, setup.payment_id);
+    assert_eq!(record.status, RampStatus::Completed);
+    assert_eq!(record.stellar_transaction_id, "abc123");
+    assert!(restarted.pending().is_empty());
+
+    assert_eq!(setup.request().status, PaymentStatus::Completed);
+    assert_eq!(setup.ramps()[0].status, RampStatus::Completed);
+
+    // The ramp is final, so the first service cannot settle it again
+    assert!(matches!(
+        setup.payer.poll(),
+        Err(AnchorError::Ledger(ClientError::Contract(_)))
+    ));
+    assert!(setup.payer.pending().is_empty());
+}
+
+#[test]
+fn test_deposit_fails_when_request_paid_meanwhile() {
+    let mut setup = Setup::new();
+    setup.payer.start_deposit(setup.payment_id).unwrap();
+    setup.pay_request();
+
+    StellarAssetClient::new(setup.env(), &setup.token).mint(&setup.payer.ledger().account, &1_000);
+    setup
+        .anchor
+        .update("tx-1", json!({ "status": "completed" }));
+    let updates = setup.payer.poll().unwrap();
+    assert_eq!(updates[0].record.status, RampStatus::Failed);
+    assert_eq!(setup.ramps()[0].status, RampStatus::Failed);
+}
+
+#[test]
+fn test_withdrawal_pays_anchor_account() {
+    let mut setup = Setup::new();
+    assert!(matches!(
+        setup.requester.start_withdrawal(setup.payment_id, 975),
+        Err(AnchorError::RequestState {
+            status: PaymentStatus::Pending,
+            ..
+        })
+    ));
+    setup.pay_request();
+    assert!(matches!(
+        setup.requester.start_withdrawal(setup.payment_id, 1_001),
+        Err(AnchorError::InvalidAmount(1_001))
+    ));
+
+    let interactive = setup
+        .requester
+        .start_withdrawal(setup.payment_id, 975)
+        .unwrap();
+    let (_, path, form) = setup.anchor.received().pop().unwrap();
+    assert_eq!(path, "/sep24/transactions/withdraw/interactive");
+    assert_eq!(form["amount"], "0.0000975");
+
+    setup.anchor.update(
+        &interactive.id,
+        json!({
+            "status": "pending_user_transfer_start",
+            "withdraw_anchor_account": ANCHOR_ACCOUNT,
+            "withdraw_memo_type": "id",
+            "withdraw_memo": "42",
+        }),
+    );
+    let updates = setup.requester.poll().unwrap();
+    assert_eq!(updates[0].record.status, RampStatus::Pending);
+    assert_eq!(
+        updates[0].record.stellar_transaction_id,
+        format!("{:064x}", 1)
+    );
+    // Polling again does not pay twice
+    assert_eq!(setup.requester.poll().unwrap(), Vec::new());
+    assert_eq!(
+        *setup.requester.ledger().payments.borrow(),
+        vec![(ANCHOR_ACCOUNT.to_string(), 975, Memo::Id(42))]
+    );
+
+    setup
+        .anchor
+        .update(&interactive.id, json!({ "status": "completed" }));
+    let updates = setup.requester.poll().unwrap();
+    assert_eq!(updates[0].record.status, RampStatus::Completed);
+    let ramps = setup.ramps();
+    assert_eq!(ramps[0].status, RampStatus::Completed);
+    assert_eq!(ramps[0].stellar_transaction_id, format!("{:064x}", 1));
+}
+
+#[test]
+fn test_send_pays_receiving_anchor() {
+    let mut setup = Setup::new();
+    setup.pay_request();
+
+    let parties = Parties {
+        sender_id: "sender-1".to_string(),
+        receiver_id: "receiver-1".to_string(),
+    };
+    let instructions = setup
+        .requester
+        .start_send(setup.payment_id, 500, &parties)
+        .unwrap();
+    let (_, path, body) = setup.anchor.received().pop().unwrap();
+    assert_eq!(path, "/sep31/transactions");
+    assert_eq!(
+        body,
+        json!({
+            "amount": "0.00005",
+            "asset_code": "USDC",
+            "asset_issuer": ANCHOR_ACCOUNT,
+            "sender_id": "sender-1",
+            "receiver_id": "receiver-1",
+        })
+    );
+    assert_eq!(
+        *setup.requester.ledger().payments.borrow(),
+        vec![(ANCHOR_ACCOUNT.to_string(), 500, Memo::Hash([7; 32]))]
+    );
+    let ramps = setup.ramps();
+    assert_eq!(ramps[0].anchor_id, instructions.id);
+    assert_eq!(ramps[0].stellar_transaction_id, format!("{:064x}", 1));
+
+    // Pending sends count against the proceeds left to send
+    assert!(matches!(
+        setup.requester.start_send(setup.payment_id, 501, &parties),
+        Err(AnchorError::InvalidAmount(501))
+    ));
+
+    setup
+        .anchor
+        .update(&instructions.id, json!({ "status": "pending_receiver" }));
+    assert_eq!(setup.requester.poll().unwrap(), Vec::new());
+    setup.anchor.update(
+        &instructions.id,
+        json!({ "status": "error", "message": "receiver bank closed" }),
+    );
+    let updates = setup.requester.poll().unwrap();
+    assert_eq!(updates[0].record.status, RampStatus::Failed);
+    assert_eq!(setup.ramps()[0].status, RampStatus::Failed);
+    assert!(setup.requester.pending().is_empty());
+
+    // A failed send no longer does
+    setup
+        .requester
+        .start_send(setup.payment_id, 1_000, &parties)
+        .unwrap();
+}
+
+#[test]
+fn test_anchor_errors() {
+    let mut setup = Setup::new();
+
+    let mut unauthorized = setup.service(setup.anchor.anchor("expired-jwt"));
+    match unauthorized.start_deposit(setup.payment_id) {
+        Err(AnchorError::Anchor { status, message }) => {
+            assert_eq!((status, message.as_str()), (403, "invalid token"))
+        }
+        other => panic!("expected an anchor error, got {other:?}"),
+    }
+    // Nothing is recorded for a ramp the anchor refused
+    assert!(setup.ramps().is_empty());
+
+    setup.payer.start_deposit(setup.payment_id).unwrap();
+    setup
+        .anchor
+        .update("tx-1", json!({ "id": "tx-1", "status": "pending_anchor" }));
+    setup.anchor.state.lock().unwrap().transactions.clear();
+    assert!(matches!(
+        setup.payer.poll(),
+        Err(AnchorError::Anchor { status: 404, .. })
+    ));
+
+    let offline = Anchor {
+        home_domain: HOME_DOMAIN.to_string(),
+        sep24: Some(Sep24::new("http://127.0.0.1:1", TOKEN)),
+        sep31: None,
+    };
+    let mut offline = setup.service(offline);
+    assert!(matches!(
+        offline.start_deposit(setup.payment_id),
+        Err(AnchorError::Http(_))
+    ));
+
+    setup.pay_request();
+    assert!(matches!(
+        offline.start_send(
+            setup.payment_id,
+            500,
+            &Parties {
+                sender_id: "sender-1".to_string(),
+                receiver_id: "receiver-1".to_string(),
+            }
+        ),
+        Err(AnchorError::Unsupported("SEP-31"))
+    ));
+}
@@ -0,0 +1,122 @@
+//! The slice of the Solidity ABI the relayer speaks: 32-byte words and
+//! dynamic strings.
+
+use sha3::{Digest, Keccak256};
+
+pub type Word = [u8; 32];
+
+pub fn keccak256(data: &[u8]) -> Word {
+    Keccak256::digest(data).into()
+}
+
+/// First four bytes of the hash of a function signature
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Word(Word),
+    String(String),
+}
+
+pub fn uint(value: u128) -> Word {
+    let mut word = [0; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+pub fn address(address: &[u8; 20]) -> Word {
+    let mut word = [0; 32];
+    word[12..].copy_from_slice(address);
+    word
+}
+
+/// The value of a `uint` word, if it fits in 128 bits
+pub fn to_uint(word: &Word) -> Option<u128> {
+    if word[..16].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    Some(u128::from_be_bytes(word[16..].try_into().ok()?))
+}
+
+pub fn to_address(word: &Word) -> Option<[u8; 20]> {
+    if word[..12].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    word[12..].try_into().ok()
+}
+
+/// `abi.encode` of `tokens`: static words in place, strings as offsets into
+/// the tail
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let mut head = Vec::with_capacity(32 * tokens.len());
+    let mut tail = Vec::new();
+    for token in tokens {
+        match token {
+            Token::Word(word) => head.extend_from_slice(word),
+            Token::String(string) => {
+                head.extend_from_slice(&uint((32 * tokens.len() + tail.len()) as u128));
+                tail.extend_from_slice(&uint(string.len() as u128));
+                tail.extend_from_slice(string.as_bytes());
+                tail.resize(tail.len().div_ceil(32) * 32, 0);
+            }
+        }
+    }
+    head.extend(tail);
+    head
+}
+
+/// Calldata calling `signature` with `tokens`
+pub fn call(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    let mut data = selector(signature).to_vec();
+    data.extend(encode(tokens));
+    data
+}
+
+/// The `index`th head word of ABI encoded `data`
+pub fn word(data: &[u8], index: usize) -> Option<Word> {
+    data.get(32 * index..32 * (index + 1))?.try_into().ok()
+}
+
+/// The string whose offset is the `index`th head word of `data`
+pub fn string(data: &[u8], index: usize) -> Option<String> {
+    let offset = usize::try_from(to_uint(&word(data, index)?)?).ok()?;
+    let length = usize::try_from(to_uint(data.get(offset..offset + 32)?.try_into().ok()?)?).ok()?;
+    let start = offset + 32;
+    let bytes = data.get(start..start.checked_add(length)?)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(
+            hex::encode(selector("transfer(address,uint256)")),
+            "a9059cbb"
+        );
+        assert_eq!(
+            hex::encode(keccak256(b"Transfer(address,address,uint256)")),
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+        );
+    }
+
+    #[test]
+    fn test_strings_round_trip() {
+        let long = "a".repeat(40);
+        let data = encode(&[
+            Token::Word(uint(7)),
+            Token::String("ORDER-1".to_string()),
+            Token::String(long.clone()),
+        ]);
+        assert_eq!(data.len(), 32 * 3 + 64 + 96);
+        assert_eq!(to_uint(&word(&data, 0).unwrap()), Some(7));
+        assert_eq!(string(&data, 1).as_deref(), Some("ORDER-1"));
+        assert_eq!(string(&data, 2), Some(long));
+        assert_eq!(string(&data[..100], 2), None);
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt;
+
+use payments_client::ClientError;
+
+#[derive(Debug)]
+pub enum BridgeError {
+    Sqlite(rusqlite::Error),
+    /// The EVM node could not be reached
+    Http(String),
+    /// JSON-RPC error object returned by the EVM node, e.g. a revert
+    Rpc {
+        code: i64,
+        message: String,
+    },
+    /// The EVM node answered with something the relayer cannot use
+    Protocol(String),
+    /// A source event that does not decode as a payment
+    Decode(String),
+    Soroban(ClientError),
+    /// The registry contract returned one of its error codes
+    Registry(u32),
+}
+
+impl fmt::Display for BridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BridgeError::Sqlite(error) => write!(f, "database error: {error}"),
+            BridgeError::Http(message) => write!(f, "http error: {message}"),
+            BridgeError::Rpc { code, message } => write!(f, "evm rpc error {code}: {message}"),
+            BridgeError::Protocol(message) => write!(f, "unexpected evm rpc response: {message}"),
+            BridgeError::Decode(message) => write!(f, "undecodable payment: {message}"),
+            BridgeError::Soroban(error) => write!(f, "soroban error: {error}"),
+            BridgeError::Registry(code) => write!(f, "registry error #{code}"),
+        }
+    }
+}
+
+impl std::error::Error for BridgeError {}
+
+impl From<rusqlite::Error> for BridgeError {
+    fn from(error: rusqlite::Error) -> BridgeError {
+        BridgeError::Sqlite(error)
+    }
+}
+
+impl From<ClientError> for BridgeError {
+    fn from(error: ClientError) -> BridgeError {
+        BridgeError::Soroban(error)
+    }
+}
+
+impl From<stellar_xdr::curr::Error> for BridgeError {
+    fn from(error: stellar_xdr::curr::Error) -> BridgeError {
+        BridgeError::Soroban(ClientError::Xdr(error))
+    }
+}
@@ -0,0 +1,175 @@
+//! Minimal blocking EVM JSON-RPC client covering the methods the relayer
+//! needs.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde_json::{json, Value};
+
+use crate::{abi::Word, BridgeError};
+
+/// A log as returned by `eth_getLogs`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Log {
+    pub address: [u8; 20],
+    pub topics: Vec<Word>,
+    pub data: Vec<u8>,
+    pub block_number: u64,
+    pub transaction_hash: Word,
+    pub log_index: u64,
+    /// Dropped by a reorg
+    pub removed: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Receipt {
+    /// Whether the transaction succeeded rather than reverted
+    pub success: bool,
+    pub block_number: u64,
+}
+
+pub struct EvmRpc {
+    url: String,
+    agent: ureq::Agent,
+    next_id: AtomicU64,
+}
+
+impl EvmRpc {
+    pub fn new(url: &str) -> EvmRpc {
+        EvmRpc {
+            url: url.to_string(),
+            agent: ureq::Agent::new(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn request(&self, method: &str, params: Value) -> Result<Value, BridgeError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let body = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let mut response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(body)
+            .map_err(|e| BridgeError::Http(e.to_string()))?
+            .into_json()
+            .map_err(|e| BridgeError::Http(e.to_string()))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(BridgeError::Rpc {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        match response.get_mut("result") {
+            Some(result) => Ok(result.take()),
+            None => Err(BridgeError::Protocol(format!(
+                "{method} returned no result"
+            ))),
+        }
+    }
+
+    pub fn chain_id(&self) -> Result<u64, BridgeError> {
+        quantity(&self.request("eth_chainId", json!([]))?)
+    }
+
+    pub fn block_number(&self) -> Result<u64, BridgeError> {
+        quantity(&self.request("eth_blockNumber", json!([]))?)
+    }
+
+    /// Logs of `address` with `topic` as their first topic, in blocks
+    /// `from..=to`
+    pub fn logs(
+        &self,
+        address: &[u8; 20],
+        topic: &Word,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<Log>, BridgeError> {
+        let filter = json!({
+            "address": hex_data(address),
+            "topics": [hex_data(topic)],
+            "fromBlock": format!("{from:#x}"),
+            "toBlock": format!("{to:#x}"),
+        });
+        let logs = self.request("eth_getLogs", json!([filter]))?;
+        let logs = logs
+            .as_array()
+            .ok_or_else(|| BridgeError::Protocol("eth_getLogs returned no array".to_string()))?;
+        logs.iter()
+            .map(|log| {
+                Ok(Log {
+                    address: fixed(&log["address"])?,
+                    topics: log["topics"]
+                        .as_array()
+                        .map(|topics| topics.iter().map(fixed).collect())
+                        .transpose()?
+                        .unwrap_or_default(),
+                    data: data(&log["data"])?,
+                    block_number: quantity(&log["blockNumber"])?,
+                    transaction_hash: fixed(&log["transactionHash"])?,
+                    log_index: quantity(&log["logIndex"])?,
+                    removed: log["removed"].as_bool().unwrap_or(false),
+                })
+            })
+            .collect()
+    }
+
+    /// Run a read-only call against the latest block
+    pub fn call(&self, to: &[u8; 20], calldata: &[u8]) -> Result<Vec<u8>, BridgeError> {
+        let call = json!({ "to": hex_data(to), "data": hex_data(calldata) });
+        data(&self.request("eth_call", json!([call, "latest"]))?)
+    }
+
+    /// Send a transaction from an account the node signs for, returning its
+    /// hash
+    pub fn send_transaction(
+        &self,
+        from: &[u8; 20],
+        to: &[u8; 20],
+        calldata: &[u8],
+    ) -> Result<Word, BridgeError> {
+        let transaction = json!({
+            "from": hex_data(from),
+            "to": hex_data(to),
+            "data": hex_data(calldata),
+        });
+        fixed(&self.request("eth_sendTransaction", json!([transaction]))?)
+    }
+
+    /// The receipt of a mined transaction, or `None` while it is pending
+    pub fn receipt(&self, hash: &Word) -> Result<Option<Receipt>, BridgeError> {
+        let receipt = self.request("eth_getTransactionReceipt", json!([hex_data(hash)]))?;
+        if receipt.is_null() {
+            return Ok(None);
+        }
+        Ok(Some(Receipt {
+            success: quantity(&receipt["status"])? == 1,
+            block_number: quantity(&receipt["blockNumber"])?,
+        }))
+    }
+}
+
+pub fn hex_data(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn quantity(value: &Value) -> Result<u64, BridgeError> {
+    value
+        .as_str()
+        .and_then(|quantity| quantity.strip_prefix("0x"))
+        .and_then(|digits| u64::from_str_radix(digits, 16).ok())
+        .ok_or_else(|| BridgeError::Protocol(format!("invalid quantity {value}")))
+}
+
+fn data(value: &Value) -> Result<Vec<u8>, BridgeError> {
+    value
+        .as_str()
+        .and_then(|data| data.strip_prefix("0x"))
+        .and_then(|digits| hex::decode(digits).ok())
+        .ok_or_else(|| BridgeError::Protocol(format!("invalid data {value}")))
+}
+
+fn fixed<const N: usize>(value: &Value) -> Result<[u8; N], BridgeError> {
+    data(value)?
+        .try_into()
+        .map_err(|_| BridgeError::Protocol(format!("expected {N} bytes, got {value}")))
+}
@@ -0,0 +1,31 @@
+//! Unified payment IDs shared by both chains.
+//!
+//! A payment's ID is `keccak256(abi.encode(domain, contract, paymentId))`,
+//! where the domain is the EVM chain ID or, for Stellar, the network ID (the
+//! SHA-256 of the network passphrase). The registries on both chains derive
+//! the same ID and refuse to record it twice.
+
+use payments_client::transaction::network_id;
+
+use crate::abi::{self, Token, Word};
+
+pub fn evm_payment_id(chain_id: u64, contract: &[u8; 20], payment_id: &Word) -> Word {
+    unified_id(
+        abi::uint(chain_id.into()),
+        abi::address(contract),
+        *payment_id,
+    )
+}
+
+pub fn stellar_payment_id(network_passphrase: &str, contract: &[u8; 32], payment_id: u64) -> Word {
+    let network = network_id(network_passphrase).0;
+    unified_id(network, *contract, abi::uint(payment_id.into()))
+}
+
+fn unified_id(domain: Word, contract: Word, payment_id: Word) -> Word {
+    abi::keccak256(&abi::encode(&[
+        Token::Word(domain),
+        Token::Word(contract),
+        Token::Word(payment_id),
+    ]))
+}
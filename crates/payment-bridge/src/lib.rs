@@ -0,0 +1,380 @@
+//! Relayer mirroring payments between the EVM USDC payment processor and the
+//! Soroban payment processor.
+//!
+//! `PaymentProcessed` logs of the EVM processor are recorded in the Soroban
+//! `payment-registry` contract, and payments of the Soroban processor are
+//! recorded in the EVM `PaymentRegistry`. Both sides key payments by the same
+//! unified ID (see [`id`]), derived from where the payment was made.
+//!
+//! Delivery is exactly-once in effect. Source payments are queued in an
+//! SQLite outbox keyed by their unified ID, in the same transaction that
+//! advances the source cursor, so replayed logs and events are queued once.
+//! Before sending, the relayer asks the destination registry whether the
+//! payment is already there, which covers a crash between sending and
+//! marking the delivery. Both registries also refuse to record an ID twice.
+//!
+//! EVM logs are only relayed once they are `confirmations` blocks deep.
+//! Soroban events are fed in by the caller, like the indexer's.
+//!
+//! ```no_run
+//! # use ed25519_dalek::SigningKey;
+//! # use payment_bridge::{Bridge, BridgeConfig, EvmRpc, RpcRegistry};
+//! # use payments_client::PaymentsClient;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let events = Vec::new();
+//! # let relayer_key = SigningKey::from_bytes(&[7; 32]);
+//! let passphrase = "Test SDF Network ; September 2015";
+//! let config = BridgeConfig {
+//!     chain_id: 43_113,
+//!     evm_processor: [0x11; 20],
+//!     evm_registry: [0x22; 20],
+//!     evm_relayer: [0x33; 20],
+//!     confirmations: 12,
+//!     start_block: 0,
+//!     network_passphrase: passphrase.to_string(),
+//!     stellar_processor: "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse()?,
+//! };
+//! let registry = RpcRegistry::new(
+//!     PaymentsClient::new(
+//!         "https://soroban-testnet.stellar.org",
+//!         "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse()?,
+//!         passphrase,
+//!     ),
+//!     relayer_key,
+//! );
+//!
+//! let conn = rusqlite::Connection::open("bridge.db")?;
+//! let evm = EvmRpc::new("http://localhost:8545");
+//! let mut bridge = Bridge::new(conn, evm, registry, config)?;
+//!
+//! bridge.poll_evm()?;
+//! bridge.ingest_stellar(&events)?;
+//! bridge.deliver()?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod abi;
+mod error;
+pub mod evm;
+pub mod id;
+mod payment;
+mod registry;
+mod store;
+
+use payment_indexer::{ContractEvent, Cursor};
+use payments_client::types::Address;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use abi::{Token, Word};
+
+pub use error::BridgeError;
+pub use evm::EvmRpc;
+pub use payment::{EvmPayment, StellarPayment, IS_MIRRORED, PAYMENT_PROCESSED, RECORD_PAYMENT};
+pub use registry::{Registry, RpcRegistry, ALREADY_MIRRORED};
+pub use store::SCHEMA;
+
+/// Revert reason of the EVM registry for a payment it already recorded
+pub const ALREADY_MIRRORED_REASON: &str = "Already mirrored";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BridgeConfig {
+    pub chain_id: u64,
+    /// `USDCPaymentProcessor` whose payments are mirrored onto Stellar
+    pub evm_processor: [u8; 20],
+    /// `PaymentRegistry` that Stellar payments are mirrored into
+    pub evm_registry: [u8; 20],
+    /// The registry's relayer; the EVM node (or a signer in front of it)
+    /// signs its transactions
+    pub evm_relayer: [u8; 20],
+    /// Blocks a log must be buried under before it is relayed
+    pub confirmations: u64,
+    /// First block scanned when there is no cursor yet
+    pub start_block: u64,
+    pub network_passphrase: String,
+    /// Soroban payment processor whose payments are mirrored onto the EVM chain
+    pub stellar_processor: Address,
+}
+
+/// A payment queued for the other chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mirror {
+    ToStellar(EvmPayment),
+    ToEvm(StellarPayment),
+}
+
+impl Mirror {
+    pub fn id(&self) -> Word {
+        match self {
+            Mirror::ToStellar(payment) => payment.id,
+            Mirror::ToEvm(payment) => payment.id,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryState {
+    Pending,
+    Delivered,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delivery {
+    pub id: Word,
+    pub mirror: Mirror,
+    pub state: DeliveryState,
+    pub attempts: u32,
+    /// Hash of the transaction that recorded the payment, or of the EVM
+    /// transaction still waiting to be mined
+    pub destination_tx: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub delivered: usize,
+    /// EVM transactions sent but not mined yet
+    pub in_flight: usize,
+    /// Failed attempts, retried on the next run
+    pub retrying: usize,
+}
+
+enum Outcome {
+    Delivered(Option<String>),
+    InFlight(String),
+}
+
+pub struct Bridge<R> {
+    conn: Connection,
+    evm: EvmRpc,
+    registry: R,
+    config: BridgeConfig,
+}
+
+impl<R: Registry> Bridge<R> {
+    /// Relay with state kept in `conn`, creating the bridge tables if needed.
+    pub fn new(
+        conn: Connection,
+        evm: EvmRpc,
+        registry: R,
+        config: BridgeConfig,
+    ) -> Result<Bridge<R>, BridgeError> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Bridge {
+            conn,
+            evm,
+            registry,
+            config,
+        })
+    }
+
+    pub fn registry(&self) -> &R {
+        &self.registry
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Stop relaying, handing back the database
+    pub fn into_connection(self) -> Connection {
+        self.conn
+    }
+
+    /// Queue the processor's confirmed `PaymentProcessed` logs since the
+    /// last poll. Returns the number of payments queued.
+    pub fn poll_evm(&mut self) -> Result<usize, BridgeError> {
+        let confirmed = self
+            .evm
+            .block_number()?
+            .checked_sub(self.config.confirmations);
+        let from = match store::load_cursor(&self.conn, store::EVM)? {
+            Some(block) => {
+                block
+                    .parse::<u64>()
+                    .map_err(|_| BridgeError::Decode(format!("stored block {block}")))?
+                    + 1
+            }
+            None => self.config.start_block,
+        };
+        let Some(to) = confirmed.filter(|to| *to >= from) else {
+            return Ok(0);
+        };
+
+        let topic = abi::keccak256(PAYMENT_PROCESSED.as_bytes());
+        let logs = self
+            .evm
+            .logs(&self.config.evm_processor, &topic, from, to)?;
+
+        let tx = self.conn.transaction()?;
+        let mut queued = 0;
+        for log in logs.iter().filter(|log| !log.removed) {
+            let payment = EvmPayment::from_log(self.config.chain_id, log)?;
+            queued += usize::from(store::enqueue(&tx, &Mirror::ToStellar(payment))?);
+        }
+        store::save_cursor(&tx, store::EVM, &to.to_string())?;
+        tx.commit()?;
+        Ok(queued)
+    }
+
+    /// Queue the Soroban processor's payments among `events`. Events at or
+    /// before the last one ingested are skipped. Returns the number of
+    /// payments queued.
+    pub fn ingest_stellar(&mut self, events: &[ContractEvent]) -> Result<usize, BridgeError> {
+        let resume_after = store::load_cursor(&self.conn, store::STELLAR)?
+            .map(|cursor| parse_cursor(&cursor))
+            .transpose()?;
+
+        let tx = self.conn.transaction()?;
+        let mut last = resume_after;
+        let mut queued = 0;
+        for event in events {
+            if last.is_some_and(|cursor| event.cursor <= cursor) {
+                continue;
+            }
+            last = Some(event.cursor);
+            if event.contract != self.config.stellar_processor {
+                continue;
+            }
+            if let Some(payment) =
+                StellarPayment::from_event(&self.config.network_passphrase, event)?
+            {
+                queued += usize::from(store::enqueue(&tx, &Mirror::ToEvm(payment))?);
+            }
+        }
+        if let Some(cursor) = last {
+            store::save_cursor(&tx, store::STELLAR, &cursor.to_string())?;
+        }
+        tx.commit()?;
+        Ok(queued)
+    }
+
+    /// Attempt every pending delivery, oldest first.
+    pub fn deliver(&mut self) -> Result<DeliveryReport, BridgeError> {
+        let mut report = DeliveryReport::default();
+        for row in store::deliveries(&self.conn, true)? {
+            let (state, destination_tx, error) =
+                match self.deliver_one(&row.mirror, row.destination_tx.as_deref()) {
+                    Ok(Outcome::Delivered(hash)) => {
+                        report.delivered += 1;
+                        ("delivered", hash, None)
+                    }
+                    Ok(Outcome::InFlight(hash)) => {
+                        report.in_flight += 1;
+                        ("pending", Some(hash), None)
+                    }
+                    // Only the database failing stops the run
+                    Err(BridgeError::Sqlite(error)) => return Err(error.into()),
+                    Err(error) => {
+                        report.retrying += 1;
+                        ("pending", None, Some(error.to_string()))
+                    }
+                };
+            store::record_attempt(
+                &self.conn,
+                &row.id,
+                state,
+                destination_tx.as_deref(),
+                error.as_deref(),
+            )?;
+        }
+        Ok(report)
+    }
+
+    /// Every delivery in the order it was queued
+    pub fn deliveries(&self) -> Result<Vec<Delivery>, BridgeError> {
+        Ok(store::deliveries(&self.conn, false)?
+            .into_iter()
+            .map(|row| Delivery {
+                id: row.id,
+                mirror: row.mirror,
+                state: if row.state == "delivered" {
+                    DeliveryState::Delivered
+                } else {
+                    DeliveryState::Pending
+                },
+                attempts: row.attempts,
+                destination_tx: row.destination_tx,
+                last_error: row.last_error,
+            })
+            .collect())
+    }
+
+    fn deliver_one(&self, mirror: &Mirror, sent: Option<&str>) -> Result<Outcome, BridgeError> {
+        match mirror {
+            Mirror::ToStellar(payment) => {
+                if self.registry.is_mirrored(&payment.id)? {
+                    return Ok(Outcome::Delivered(None));
+                }
+                match self.registry.record_payment(payment) {
+                    Ok(hash) => Ok(Outcome::Delivered(Some(hash))),
+                    Err(BridgeError::Registry(ALREADY_MIRRORED)) => Ok(Outcome::Delivered(None)),
+                    Err(error) => Err(error),
+                }
+            }
+            Mirror::ToEvm(payment) => {
+                let registry = &self.config.evm_registry;
+                let mirrored = self.evm.call(
+                    registry,
+                    &abi::call(IS_MIRRORED, &[Token::Word(payment.id)]),
+                )?;
+                if abi::word(&mirrored, 0).and_then(|word| abi::to_uint(&word)) == Some(1) {
+                    return Ok(Outcome::Delivered(sent.map(str::to_string)));
+                }
+
+                // A transaction sent earlier is waited for rather than sent again
+                if let Some(sent) = sent {
+                    let hash = parse_hash(sent)?;
+                    match self.evm.receipt(&hash)? {
+                        None => return Ok(Outcome::InFlight(sent.to_string())),
+                        Some(receipt) if receipt.success => {
+                            return Ok(Outcome::Delivered(Some(sent.to_string())))
+                        }
+                        Some(_) => {}
+                    }
+                }
+
+                let calldata = payment.record_payment_call()?;
+                let hash =
+                    match self
+                        .evm
+                        .send_transaction(&self.config.evm_relayer, registry, &calldata)
+                    {
+                        Ok(hash) => hash,
+                        Err(BridgeError::Rpc { message, .. })
+                            if message.contains(ALREADY_MIRRORED_REASON) =>
+                        {
+                            return Ok(Outcome::Delivered(None))
+                        }
+                        Err(error) => return Err(error),
+                    };
+                let hash_hex = evm::hex_data(&hash);
+                match self.evm.receipt(&hash)? {
+                    None => Ok(Outcome::InFlight(hash_hex)),
+                    Some(receipt) if receipt.success => Ok(Outcome::Delivered(Some(hash_hex))),
+                    Some(_) => Err(BridgeError::Protocol(format!("{hash_hex} reverted"))),
+                }
+            }
+        }
+    }
+}
+
+fn parse_cursor(cursor: &str) -> Result<Cursor, BridgeError> {
+    cursor
+        .split_once('-')
+        .and_then(|(ledger, index)| {
+            Some(Cursor {
+                ledger: ledger.parse().ok()?,
+                index: index.parse().ok()?,
+            })
+        })
+        .ok_or_else(|| BridgeError::Decode(format!("stored cursor {cursor}")))
+}
+
+fn parse_hash(hash: &str) -> Result<Word, BridgeError> {
+    hash.strip_prefix("0x")
+        .and_then(|digits| hex::decode(digits).ok())
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| BridgeError::Decode(format!("stored transaction hash {hash}")))
+}
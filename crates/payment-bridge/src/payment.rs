@@ -0,0 +1,165 @@
+//! Payments as they are read on one chain and mirrored onto the other.
+
+use payment_indexer::{Asset, ContractEvent, PaymentEvent, Source};
+use payments_client::{types::Address, ScType};
+use serde::{Deserialize, Serialize};
+use stellar_xdr::curr::{ScMap, ScMapEntry, ScSymbol, ScVal, UInt256Parts};
+
+use crate::{
+    abi::{self, Token, Word},
+    evm::Log,
+    id, BridgeError,
+};
+
+/// `PaymentProcessed(uint256 indexed paymentId, address indexed sender,
+/// address indexed recipient, uint256 amount, string orderId)`
+pub const PAYMENT_PROCESSED: &str = "PaymentProcessed(uint256,address,address,uint256,string)";
+
+/// `recordPayment` of the EVM `PaymentRegistry`
+pub const RECORD_PAYMENT: &str = "recordPayment(bytes32,bytes32,uint64,string,string,uint256,bool)";
+
+pub const IS_MIRRORED: &str = "isMirrored(bytes32)";
+
+/// A payment of the EVM USDC processor
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvmPayment {
+    pub id: Word,
+    pub chain_id: u64,
+    pub contract: [u8; 20],
+    pub payment_id: Word,
+    pub sender: [u8; 20],
+    pub recipient: [u8; 20],
+    pub amount: i128,
+    pub order_id: String,
+    pub transaction_hash: Word,
+    pub block_number: u64,
+}
+
+impl EvmPayment {
+    pub fn from_log(chain_id: u64, log: &Log) -> Result<EvmPayment, BridgeError> {
+        let invalid = |what: &str| {
+            BridgeError::Decode(format!(
+                "PaymentProcessed in {} has an invalid {what}",
+                hex::encode(log.transaction_hash)
+            ))
+        };
+        let [_, payment_id, sender, recipient] = log.topics[..] else {
+            return Err(invalid("topic count"));
+        };
+        let amount = abi::word(&log.data, 0)
+            .and_then(|word| abi::to_uint(&word))
+            .and_then(|amount| i128::try_from(amount).ok())
+            .ok_or_else(|| invalid("amount"))?;
+
+        Ok(EvmPayment {
+            id: id::evm_payment_id(chain_id, &log.address, &payment_id),
+            chain_id,
+            contract: log.address,
+            payment_id,
+            sender: abi::to_address(&sender).ok_or_else(|| invalid("sender"))?,
+            recipient: abi::to_address(&recipient).ok_or_else(|| invalid("recipient"))?,
+            amount,
+            order_id: abi::string(&log.data, 1).ok_or_else(|| invalid("order ID"))?,
+            transaction_hash: log.transaction_hash,
+            block_number: log.block_number,
+        })
+    }
+
+    /// The registry contract's `EvmPayment`, with `recorded_at` left for the
+    /// contract to set
+    pub fn to_scval(&self) -> ScVal {
+        let word = |bytes: &[u8]| u64::from_be_bytes(bytes.try_into().expect("8 bytes"));
+        let payment_id = ScVal::U256(UInt256Parts {
+            hi_hi: word(&self.payment_id[..8]),
+            hi_lo: word(&self.payment_id[8..16]),
+            lo_hi: word(&self.payment_id[16..24]),
+            lo_lo: word(&self.payment_id[24..]),
+        });
+        // Struct fields encode as a map sorted by name
+        let fields = [
+            ("amount", self.amount.to_scval()),
+            ("chain_id", self.chain_id.to_scval()),
+            ("contract", self.contract.to_scval()),
+            ("id", self.id.to_scval()),
+            ("order_id", self.order_id.to_scval()),
+            ("payment_id", payment_id),
+            ("recipient", self.recipient.to_scval()),
+            ("recorded_at", 0u64.to_scval()),
+            ("sender", self.sender.to_scval()),
+            ("transaction_hash", self.transaction_hash.to_scval()),
+        ];
+        let entries: Vec<ScMapEntry> = fields
+            .into_iter()
+            .map(|(name, val)| ScMapEntry {
+                key: ScVal::Symbol(ScSymbol(name.try_into().expect("field names are symbols"))),
+                val,
+            })
+            .collect();
+        ScVal::Map(Some(ScMap(entries.try_into().expect("ten fields fit"))))
+    }
+}
+
+/// A payment of the Soroban payment processor
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StellarPayment {
+    pub id: Word,
+    pub contract: [u8; 32],
+    pub payment_id: u64,
+    /// Strkeys of the paying and receiving addresses
+    pub sender: String,
+    pub recipient: String,
+    pub amount: i128,
+    /// Paid in XLM rather than a token
+    pub native: bool,
+}
+
+impl StellarPayment {
+    /// The processor payment carried by `event`, if it is one
+    pub fn from_event(
+        network_passphrase: &str,
+        event: &ContractEvent,
+    ) -> Result<Option<StellarPayment>, BridgeError> {
+        let Address::Contract(contract) = event.contract else {
+            return Ok(None);
+        };
+        let decoded = PaymentEvent::decode(Source::Processor, &event.topics, &event.data)
+            .map_err(|error| BridgeError::Decode(format!("event {}: {error}", event.cursor)))?;
+        let Some(PaymentEvent::ProcessorPayment {
+            asset,
+            payment_id,
+            sender,
+            recipient,
+            amount,
+        }) = decoded
+        else {
+            return Ok(None);
+        };
+        Ok(Some(StellarPayment {
+            id: id::stellar_payment_id(network_passphrase, &contract, payment_id),
+            contract,
+            payment_id,
+            sender: sender.to_string(),
+            recipient: recipient.to_string(),
+            amount,
+            native: asset == Asset::Xlm,
+        }))
+    }
+
+    /// Calldata of the EVM registry's `recordPayment`
+    pub fn record_payment_call(&self) -> Result<Vec<u8>, BridgeError> {
+        let amount = u128::try_from(self.amount)
+            .map_err(|_| BridgeError::Decode(format!("negative amount {}", self.amount)))?;
+        Ok(abi::call(
+            RECORD_PAYMENT,
+            &[
+                Token::Word(self.id),
+                Token::Word(self.contract),
+                Token::Word(abi::uint(self.payment_id.into())),
+                Token::String(self.sender.clone()),
+                Token::String(self.recipient.clone()),
+                Token::Word(abi::uint(amount)),
+                Token::Word(abi::uint(self.native.into())),
+            ],
+        ))
+    }
+}
@@ -0,0 +1,80 @@
+//! The Soroban registry contract the relayer records EVM payments in.
+
+use ed25519_dalek::SigningKey;
+use payments_client::{contract_error_code, transaction, ClientError, PaymentsClient, ScType};
+use stellar_xdr::curr::{
+    HostFunction, InvokeContractArgs, Limits, ReadXdr, ScSymbol, ScVal, Transaction,
+};
+
+use crate::{abi::Word, BridgeError, EvmPayment};
+
+/// Code of the registry's `AlreadyMirrored` error
+pub const ALREADY_MIRRORED: u32 = 4;
+
+pub trait Registry {
+    fn is_mirrored(&self, id: &Word) -> Result<bool, BridgeError>;
+
+    /// Record a payment and return the transaction hash. Fails with
+    /// [`BridgeError::Registry`] and [`ALREADY_MIRRORED`] when the payment is
+    /// already recorded.
+    fn record_payment(&self, payment: &EvmPayment) -> Result<String, BridgeError>;
+}
+
+/// The registry through Soroban RPC, signing as the relayer account. The
+/// client's contract is the registry's address.
+pub struct RpcRegistry {
+    client: PaymentsClient,
+    key: SigningKey,
+}
+
+impl RpcRegistry {
+    pub fn new(client: PaymentsClient, key: SigningKey) -> RpcRegistry {
+        RpcRegistry { client, key }
+    }
+
+    /// The simulated transaction calling `function` and its return value
+    fn simulate(
+        &self,
+        function: &str,
+        args: Vec<ScVal>,
+    ) -> Result<(Transaction, ScVal), BridgeError> {
+        let rpc = self.client.rpc();
+        let source = self.key.verifying_key().to_bytes();
+        let function = HostFunction::InvokeContract(InvokeContractArgs {
+            contract_address: self.client.contract().to_sc_address(),
+            function_name: ScSymbol(function.try_into()?),
+            args: args.try_into()?,
+        });
+        let sequence = rpc.get_account_sequence(&source)? + 1;
+        let transaction = transaction::invoke_transaction(&source, sequence, function);
+        let simulation = rpc.simulate_transaction(&transaction::unsigned(transaction.clone()))?;
+
+        if let Some(message) = &simulation.error {
+            return Err(match contract_error_code(message) {
+                Some(code) => BridgeError::Registry(code),
+                None => BridgeError::Soroban(ClientError::Host(message.clone())),
+            });
+        }
+        let value = simulation.results.first().ok_or_else(|| {
+            BridgeError::Soroban(ClientError::Protocol(
+                "simulation returned no result".to_string(),
+            ))
+        })?;
+        let value = ScVal::from_xdr_base64(&value.xdr, Limits::none())?;
+        Ok((transaction::assemble(transaction, &simulation)?, value))
+    }
+}
+
+impl Registry for RpcRegistry {
+    fn is_mirrored(&self, id: &Word) -> Result<bool, BridgeError> {
+        let (_, value) = self.simulate("is_mirrored", vec![id.to_scval()])?;
+        Ok(bool::from_scval(&value).map_err(ClientError::from)?)
+    }
+
+    fn record_payment(&self, payment: &EvmPayment) -> Result<String, BridgeError> {
+        let (transaction, _) = self.simulate("record_payment", vec![payment.to_scval()])?;
+        let envelope = transaction::sign(transaction, self.client.network_passphrase(), &self.key)?;
+        let (hash, _) = self.client.send(&envelope)?;
+        Ok(hash)
+    }
+}
@@ -0,0 +1,123 @@
+//! Cursors and the delivery outbox, kept in SQLite.
+
+use rusqlite::{named_params, Connection, OptionalExtension};
+
+use crate::{abi::Word, BridgeError, Mirror};
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS bridge_cursors (
+    source TEXT PRIMARY KEY,
+    position TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS bridge_deliveries (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    id TEXT NOT NULL UNIQUE,
+    payment TEXT NOT NULL,
+    state TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    destination_tx TEXT,
+    last_error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS bridge_deliveries_state ON bridge_deliveries (state, seq);
+";
+
+pub const EVM: &str = "evm";
+pub const STELLAR: &str = "stellar";
+
+pub fn load_cursor(conn: &Connection, source: &str) -> rusqlite::Result<Option<String>> {
+    conn.query_row(
+        "SELECT position FROM bridge_cursors WHERE source = :source",
+        named_params! { ":source": source },
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+pub fn save_cursor(conn: &Connection, source: &str, position: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO bridge_cursors (source, position) VALUES (:source, :position)
+         ON CONFLICT (source) DO UPDATE SET position = excluded.position",
+        named_params! { ":source": source, ":position": position },
+    )?;
+    Ok(())
+}
+
+/// Queue a payment unless it was queued before. Returns whether it is new.
+pub fn enqueue(conn: &Connection, mirror: &Mirror) -> Result<bool, BridgeError> {
+    let payment =
+        serde_json::to_string(mirror).map_err(|error| BridgeError::Decode(error.to_string()))?;
+    let inserted = conn.execute(
+        "INSERT INTO bridge_deliveries (id, payment, state, attempts)
+         VALUES (:id, :payment, 'pending', 0)
+         ON CONFLICT (id) DO NOTHING",
+        named_params! { ":id": hex::encode(mirror.id()), ":payment": payment },
+    )?;
+    Ok(inserted == 1)
+}
+
+pub struct Row {
+    pub id: Word,
+    pub mirror: Mirror,
+    pub state: String,
+    pub attempts: u32,
+    pub destination_tx: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Deliveries in the order they were queued, only pending ones if asked
+pub fn deliveries(conn: &Connection, pending_only: bool) -> Result<Vec<Row>, BridgeError> {
+    let mut statement = conn.prepare(
+        "SELECT id, payment, state, attempts, destination_tx, last_error
+         FROM bridge_deliveries WHERE state = 'pending' OR NOT :pending_only ORDER BY seq",
+    )?;
+    let rows = statement.query_map(named_params! { ":pending_only": pending_only }, |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, u32>(3)?,
+            row.get::<_, Option<String>>(4)?,
+            row.get::<_, Option<String>>(5)?,
+        ))
+    })?;
+    rows.map(|row| {
+        let (id, payment, state, attempts, destination_tx, last_error) = row?;
+        let corrupt = |what: &str| BridgeError::Decode(format!("stored delivery {id}: {what}"));
+        Ok(Row {
+            id: hex::decode(&id)
+                .ok()
+                .and_then(|id| id.try_into().ok())
+                .ok_or_else(|| corrupt("invalid id"))?,
+            mirror: serde_json::from_str(&payment).map_err(|error| corrupt(&error.to_string()))?,
+            state,
+            attempts,
+            destination_tx,
+            last_error,
+        })
+    })
+    .collect()
+}
+
+pub fn record_attempt(
+    conn: &Connection,
+    id: &Word,
+    state: &str,
+    destination_tx: Option<&str>,
+    error: Option<&str>,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "UPDATE bridge_deliveries
+         SET state = :state, attempts = attempts + 1,
+             destination_tx = COALESCE(:destination_tx, destination_tx), last_error = :error
+         WHERE id = :id",
+        named_params! {
+            ":id": hex::encode(id),
+            ":state": state,
+            ":destination_tx": destination_tx,
+            ":error": error,
+        },
+    )?;
+    Ok(())
+}
@@ -0,0 +1,555 @@
+//! Relays payments between a stand-in EVM node and the Soroban contracts
+//! running in a sandbox environment.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use payment_bridge::{
+    abi::{self, Token, Word},
+    id, Bridge, BridgeConfig, BridgeError, DeliveryReport, DeliveryState, EvmPayment, EvmRpc,
+    Mirror, Registry, IS_MIRRORED, PAYMENT_PROCESSED, RECORD_PAYMENT,
+};
+use payment_indexer::{
+    testutils::{client_address, Recorder},
+    ContractEvent,
+};
+use payment_processor::{
+    PaymentContract as ProcessorContract, PaymentContractClient as ProcessorClient,
+};
+use payment_registry::{PaymentRegistry, PaymentRegistryClient};
+use payments_client::ScType;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+use soroban_sdk::{testutils::Address as _, Address, BytesN, Env, String, TryFromVal, Val, U256};
+
+const CHAIN_ID: u64 = 43_114;
+const PROCESSOR: [u8; 20] = [0xaa; 20];
+const REGISTRY: [u8; 20] = [0xbb; 20];
+const RELAYER: [u8; 20] = [0xcc; 20];
+const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+
+#[derive(Default)]
+struct NodeState {
+    block: u64,
+    logs: Vec<Value>,
+    /// IDs recorded in the registry
+    mirrored: HashSet<Word>,
+    /// Sent transactions by hash, with their receipt status once mined
+    transactions: HashMap<std::string::String, (Word, Option<u64>)>,
+    /// Leave sent transactions pending until `mine` is called
+    hold: bool,
+    /// Revert the next `recordPayment`
+    revert_next: bool,
+    sent: usize,
+}
+
+/// JSON-RPC node holding the EVM processor's logs and the registry's state
+struct EvmNode {
+    server: Arc<tiny_http::Server>,
+    url: std::string::String,
+    state: Arc<Mutex<NodeState>>,
+}
+
+impl EvmNode {
+    fn start() -> EvmNode {
+        let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").unwrap());
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let state = Arc::new(Mutex::new(NodeState::default()));
+
+        let (incoming, shared) = (server.clone(), state.clone());
+        thread::spawn(move || {
+            for mut request in incoming.incoming_requests() {
+                let body: Value = serde_json::from_reader(request.as_reader()).unwrap();
+                let reply = match respond(
+                    &mut shared.lock().unwrap(),
+                    body["method"].as_str().unwrap(),
+                    &body["params"],
+                ) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": body["id"], "result": result }),
+                    Err(message) => json!({
+                        "jsonrpc": "2.0",
+                        "id": body["id"],
+                        "error": { "code": 3, "message": message },
+                    }),
+                };
+                let _ = request.respond(tiny_http::Response::from_string(reply.to_string()));
+            }
+        });
+
+        EvmNode { server, url, state }
+    }
+
+    fn rpc(&self) -> EvmRpc {
+        EvmRpc::new(&self.url)
+    }
+
+    /// Mine a block with a `PaymentProcessed` log of the processor
+    fn emit(&self, payment_id: u128, amount: u128, order_id: &str) {
+        let mut state = self.state.lock().unwrap();
+        state.block += 1;
+        let topics = [
+            abi::keccak256(PAYMENT_PROCESSED.as_bytes()),
+            abi::uint(payment_id),
+            abi::address(&[1; 20]),
+            abi::address(&[2; 20]),
+        ];
+        let data = abi::encode(&[
+            Token::Word(abi::uint(amount)),
+            Token::String(order_id.to_string()),
+        ]);
+        let log = json!({
+            "address": hex(&PROCESSOR),
+            "topics": topics.iter().map(|topic| hex(topic)).collect::<Vec<_>>(),
+            "data": hex(&data),
+            "blockNumber": format!("{:#x}", state.block),
+            "transactionHash": hex(&abi::keccak256(&payment_id.to_be_bytes())),
+            "logIndex": "0x0",
+            "removed": false,
+        });
+        state.logs.push(log);
+    }
+
+    fn mine_blocks(&self, blocks: u64) {
+        self.state.lock().unwrap().block += blocks;
+    }
+
+    /// Mine the transactions left pending
+    fn mine(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.block += 1;
+        let pending: Vec<Word> = state
+            .transactions
+            .values_mut()
+            .filter(|(_, status)| status.is_none())
+            .map(|(id, status)| {
+                *status = Some(1);
+                *id
+            })
+            .collect();
+        state.mirrored.extend(pending);
+    }
+
+    fn set_hold(&self, hold: bool) {
+        self.state.lock().unwrap().hold = hold;
+    }
+
+    fn revert_next(&self) {
+        self.state.lock().unwrap().revert_next = true;
+    }
+
+    fn mirrored(&self) -> HashSet<Word> {
+        self.state.lock().unwrap().mirrored.clone()
+    }
+
+    fn sent(&self) -> usize {
+        self.state.lock().unwrap().sent
+    }
+}
+
+impl Drop for EvmNode {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+fn hex(bytes: &[u8]) -> std::string::String {
+    format!("0x{}", ::hex::encode(bytes))
+}
+
+fn unhex(value: &Value) -> Vec<u8> {
+    ::hex::decode(value.as_str().unwrap().trim_start_matches("0x")).unwrap()
+}
+
+fn quantity(value: &Value) -> u64 {
+    u64::from_str_radix(value.as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+}
+
+fn respond(
+    state: &mut NodeState,
+    method: &str,
+    params: &Value,
+) -> Result<Value, std::string::String> {
+    match method {
+        "eth_chainId" => Ok(json!(format!("{CHAIN_ID:#x}"))),
+        "eth_blockNumber" => Ok(json!(format!("{:#x}", state.block))),
+        "eth_getLogs" => {
+            let filter = &params[0];
+            let (from, to) = (quantity(&filter["fromBlock"]), quantity(&filter["toBlock"]));
+            let logs: Vec<&Value> = state
+                .logs
+                .iter()
+                .filter(|log| (from..=to).contains(&quantity(&log["blockNumber"])))
+                .filter(|log| log["address"] == filter["address"])
+                .collect();
+            Ok(json!(logs))
+        }
+        "eth_call" => {
+            let data = unhex(&params[0]["data"]);
+            assert_eq!(data[..4], abi::selector(IS_MIRRORED));
+            let id: Word = data[4..36].try_into().unwrap();
+            let mirrored = state.mirrored.contains(&id);
+            Ok(json!(hex(&abi::uint(mirrored.into()))))
+        }
+        "eth_sendTransaction" => {
+            let transaction = &params[0];
+            assert_eq!(unhex(&transaction["from"]), RELAYER);
+            assert_eq!(unhex(&transaction["to"]), REGISTRY);
+            let data = unhex(&transaction["data"]);
+            assert_eq!(data[..4], abi::selector(RECORD_PAYMENT));
+            let id: Word = data[4..36].try_into().unwrap();
+
+            let pending = state
+                .transactions
+                .values()
+                .any(|(sent, status)| *sent == id && status.is_none());
+            if state.mirrored.contains(&id) || pending {
+                return Err("execution reverted: Already mirrored".to_string());
+            }
+            state.sent += 1;
+            let hash = hex(&abi::keccak256(
+                &[&data[..], &state.sent.to_be_bytes()].concat(),
+            ));
+            let status = if state.hold {
+                None
+            } else if std::mem::take(&mut state.revert_next) {
+                state.block += 1;
+                Some(0)
+            } else {
+                state.block += 1;
+                state.mirrored.insert(id);
+                Some(1)
+            };
+            state.transactions.insert(hash.clone(), (id, status));
+            Ok(json!(hash))
+        }
+        "eth_getTransactionReceipt" => {
+            let hash = params[0].as_str().unwrap();
+            Ok(match state.transactions.get(hash) {
+                Some((_, Some(status))) => json!({
+                    "status": format!("{status:#x}"),
+                    "blockNumber": format!("{:#x}", state.block),
+                }),
+                _ => Value::Null,
+            })
+        }
+        _ => Err(format!("unsupported method {method}")),
+    }
+}
+
+/// The registry contract in a sandbox, counting the payments recorded
+struct SandboxRegistry {
+    env: Env,
+    client: PaymentRegistryClient<'static>,
+    recorded: RefCell<Vec<Word>>,
+}
+
+impl SandboxRegistry {
+    fn new() -> SandboxRegistry {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, PaymentRegistry);
+        let client = PaymentRegistryClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env), &Address::generate(&env));
+        SandboxRegistry {
+            env,
+            client,
+            recorded: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn to_sdk<C: ScType, T: TryFromVal<Env, Val>>(&self, value: &C) -> T {
+        let value = Val::try_from_val(&self.env, &value.to_scval()).unwrap();
+        T::try_from_val(&self.env, &value).ok().unwrap()
+    }
+
+    fn to_sdk_payment(&self, payment: &EvmPayment) -> payment_registry::EvmPayment {
+        let value = Val::try_from_val(&self.env, &payment.to_scval()).unwrap();
+        payment_registry::EvmPayment::try_from_val(&self.env, &value).unwrap()
+    }
+}
+
+impl Registry for SandboxRegistry {
+    fn is_mirrored(&self, id: &Word) -> Result<bool, BridgeError> {
+        Ok(self.client.is_mirrored(&self.to_sdk(id)))
+    }
+
+    fn record_payment(&self, payment: &EvmPayment) -> Result<std::string::String, BridgeError> {
+        match self
+            .client
+            .try_record_payment(&self.to_sdk_payment(payment))
+        {
+            Ok(_) => {
+                self.recorded.borrow_mut().push(payment.id);
+                Ok(format!("sandbox-{}", self.recorded.borrow().len()))
+            }
+            Err(Ok(error)) => Err(BridgeError::Registry(error as u32)),
+            Err(Err(error)) => Err(BridgeError::Protocol(format!("{error:?}"))),
+        }
+    }
+}
+
+fn config(stellar_processor: payments_client::types::Address) -> BridgeConfig {
+    BridgeConfig {
+        chain_id: CHAIN_ID,
+        evm_processor: PROCESSOR,
+        evm_registry: REGISTRY,
+        evm_relayer: RELAYER,
+        confirmations: 2,
+        start_block: 0,
+        network_passphrase: PASSPHRASE.to_string(),
+        stellar_processor,
+    }
+}
+
+fn bridge(node: &EvmNode, conn: Connection) -> Bridge<SandboxRegistry> {
+    let processor = payments_client::types::Address::Contract([7; 32]);
+    Bridge::new(conn, node.rpc(), SandboxRegistry::new(), config(processor)).unwrap()
+}
+
+/// Payments made through the Soroban processor and the events they published
+struct StellarHistory {
+    processor: payments_client::types::Address,
+    events: Vec<ContractEvent>,
+}
+
+fn stellar_payments(amounts: &[i128]) -> StellarHistory {
+    let env = Env::default();
+    env.mock_all_auths();
+    let mut recorder = Recorder::new(&env);
+
+    let processor_id = env.register_contract(None, ProcessorContract);
+    let processor = ProcessorClient::new(&env, &processor_id);
+    let (owner, merchant, payer) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    processor.initialize(&owner, &soroban_sdk::vec![&env, merchant.clone()]);
+    processor.configure_business(&merchant, &0, &1, &1_000_000);
+    recorder.capture();
+
+    for (index, amount) in amounts.iter().enumerate() {
+        processor.process_xlm_payment(
+            &payer,
+            &merchant,
+            amount,
+            &String::from_str(&env, "Test Store"),
+            &String::from_str(&env, "Customer"),
+            &String::from_str(&env, &format!("order-{index}")),
+        );
+        recorder.capture();
+    }
+
+    StellarHistory {
+        processor: client_address(&env, &processor_id),
+        events: recorder.into_events(),
+    }
+}
+
+fn states(bridge: &Bridge<SandboxRegistry>) -> Vec<(DeliveryState, u32)> {
+    bridge
+        .deliveries()
+        .unwrap()
+        .iter()
+        .map(|delivery| (delivery.state, delivery.attempts))
+        .collect()
+}
+
+#[test]
+fn test_evm_payments_are_recorded_once_confirmed() {
+    let node = EvmNode::start();
+    let mut bridge = bridge(&node, Connection::open_in_memory().unwrap());
+
+    node.emit(1, 25_000_000, "ORDER-1");
+    node.emit(2, 5_000_000, "ORDER-2");
+    // Block 2 is not buried deep enough yet
+    assert_eq!(bridge.poll_evm().unwrap(), 0);
+    node.mine_blocks(1);
+    assert_eq!(bridge.poll_evm().unwrap(), 1);
+    node.mine_blocks(1);
+    assert_eq!(bridge.poll_evm().unwrap(), 1);
+    assert_eq!(bridge.poll_evm().unwrap(), 0);
+
+    assert_eq!(
+        bridge.deliver().unwrap(),
+        DeliveryReport {
+            delivered: 2,
+            ..Default::default()
+        }
+    );
+    assert_eq!(bridge.deliver().unwrap(), DeliveryReport::default());
+
+    let deliveries = bridge.deliveries().unwrap();
+    let Mirror::ToStellar(first) = &deliveries[0].mirror else {
+        panic!("EVM payments are mirrored onto Stellar");
+    };
+    assert_eq!(first.amount, 25_000_000);
+    assert_eq!(first.order_id, "ORDER-1");
+    assert_eq!(first.sender, [1; 20]);
+    assert_eq!(deliveries[0].destination_tx.as_deref(), Some("sandbox-1"));
+
+    let registry = bridge.registry();
+    let recorded = registry.client.get_payment(&registry.to_sdk(&first.id));
+    assert_eq!(recorded.amount, 25_000_000);
+    assert_eq!(recorded.payment_id, U256::from_u32(&registry.env, 1));
+    assert_eq!(
+        registry.recorded.borrow().clone(),
+        vec![deliveries[0].id, deliveries[1].id]
+    );
+}
+
+#[test]
+fn test_replayed_logs_are_queued_once() {
+    let node = EvmNode::start();
+    let conn = Connection::open_in_memory().unwrap();
+    let mut bridge = bridge(&node, conn);
+
+    node.emit(1, 1_000, "ORDER-1");
+    node.mine_blocks(2);
+    assert_eq!(bridge.poll_evm().unwrap(), 1);
+
+    // Rescanning from the start, e.g. after losing the cursor
+    bridge
+        .connection()
+        .execute("DELETE FROM bridge_cursors", [])
+        .unwrap();
+    assert_eq!(bridge.poll_evm().unwrap(), 0);
+    bridge.deliver().unwrap();
+    assert_eq!(states(&bridge), [(DeliveryState::Delivered, 1)]);
+    assert_eq!(bridge.registry().recorded.borrow().len(), 1);
+}
+
+#[test]
+fn test_payment_recorded_before_a_crash_is_not_recorded_again() {
+    let node = EvmNode::start();
+    let mut bridge = bridge(&node, Connection::open_in_memory().unwrap());
+    node.emit(1, 1_000, "ORDER-1");
+    node.mine_blocks(2);
+    bridge.poll_evm().unwrap();
+
+    // Recorded, but the relayer stopped before marking the delivery
+    let Mirror::ToStellar(payment) = bridge.deliveries().unwrap()[0].mirror.clone() else {
+        panic!("EVM payments are mirrored onto Stellar");
+    };
+    let registry = bridge.registry();
+    registry
+        .client
+        .record_payment(&registry.to_sdk_payment(&payment));
+
+    assert_eq!(bridge.deliver().unwrap().delivered, 1);
+    assert!(bridge.registry().recorded.borrow().is_empty());
+    let delivery = &bridge.deliveries().unwrap()[0];
+    assert_eq!(delivery.state, DeliveryState::Delivered);
+    assert_eq!(delivery.destination_tx, None);
+}
+
+#[test]
+fn test_stellar_payments_are_mirrored_across_restarts() {
+    let node = EvmNode::start();
+    let history = stellar_payments(&[750, 1_250]);
+    let processor = history.processor;
+    let new_bridge =
+        |conn| Bridge::new(conn, node.rpc(), SandboxRegistry::new(), config(processor)).unwrap();
+    let mut bridge = new_bridge(Connection::open_in_memory().unwrap());
+
+    // Only the first payment's events have arrived so far
+    let split = history.events.len() - 1;
+    assert_eq!(bridge.ingest_stellar(&history.events[..split]).unwrap(), 1);
+    node.set_hold(true);
+    assert_eq!(
+        bridge.deliver().unwrap(),
+        DeliveryReport {
+            in_flight: 1,
+            ..Default::default()
+        }
+    );
+
+    // Restarted while the transaction is pending, then fed every event again
+    let mut bridge = new_bridge(bridge.into_connection());
+    assert_eq!(bridge.ingest_stellar(&history.events).unwrap(), 1);
+    // The pending transaction is waited for, not sent again
+    assert_eq!(bridge.deliver().unwrap().in_flight, 2);
+    assert_eq!(node.sent(), 2);
+
+    node.mine();
+    node.set_hold(false);
+    assert_eq!(bridge.deliver().unwrap().delivered, 2);
+    assert_eq!(node.sent(), 2);
+
+    let deliveries = bridge.deliveries().unwrap();
+    let Mirror::ToEvm(first) = &deliveries[0].mirror else {
+        panic!("Stellar payments are mirrored onto the EVM chain");
+    };
+    assert_eq!(
+        (first.payment_id, first.amount, first.native),
+        (1, 750, true)
+    );
+    assert_eq!(
+        node.mirrored(),
+        deliveries.iter().map(|delivery| delivery.id).collect()
+    );
+    assert!(deliveries
+        .iter()
+        .all(|delivery| delivery.destination_tx.is_some()));
+}
+
+#[test]
+fn test_reverted_transaction_is_retried() {
+    let node = EvmNode::start();
+    let history = stellar_payments(&[750]);
+    let mut bridge = Bridge::new(
+        Connection::open_in_memory().unwrap(),
+        node.rpc(),
+        SandboxRegistry::new(),
+        config(history.processor),
+    )
+    .unwrap();
+    bridge.ingest_stellar(&history.events).unwrap();
+
+    node.revert_next();
+    assert_eq!(bridge.deliver().unwrap().retrying, 1);
+    let delivery = &bridge.deliveries().unwrap()[0];
+    assert_eq!(delivery.state, DeliveryState::Pending);
+    assert!(delivery.last_error.as_deref().unwrap().contains("reverted"));
+
+    assert_eq!(bridge.deliver().unwrap().delivered, 1);
+    assert_eq!(states(&bridge), [(DeliveryState::Delivered, 2)]);
+    assert_eq!(node.sent(), 2);
+    assert_eq!(bridge.deliveries().unwrap()[0].last_error, None);
+}
+
+#[test]
+fn test_unified_ids_match_the_contracts() {
+    let env = Env::default();
+    let payment_id = abi::uint(42);
+    let expected = payment_registry::evm_payment_id(
+        &env,
+        CHAIN_ID,
+        &BytesN::from_array(&env, &PROCESSOR),
+        &U256::from_u32(&env, 42),
+    );
+    assert_eq!(
+        id::evm_payment_id(CHAIN_ID, &PROCESSOR, &payment_id),
+        expected.to_array()
+    );
+
+    // Each chain's payments live in their own ID domain
+    let stellar = id::stellar_payment_id(PASSPHRASE, &[0xaa; 32], 42);
+    assert_ne!(stellar, expected.to_array());
+    assert_ne!(
+        stellar,
+        id::stellar_payment_id(
+            "Public Global Stellar Network ; September 2015",
+            &[0xaa; 32],
+            42
+        )
+    );
+
+    // The EVM registry reads the ID back as the first argument
+    let call = abi::call(RECORD_PAYMENT, &[Token::Word(stellar)]);
+    assert_eq!(abi::word(&call[4..], 0), Some(stellar));
+}
@@ -0,0 +1,192 @@
+use soroban_sdk::{contractclient, contracttype, symbol_short, Address, Env, IntoVal, Val, Vec};
+
+use crate::{error::Error, storage::extend_persistent};
+
+/// Interface an external screening service must expose to be consulted
+/// before funds move.
+#[contractclient(name = "ComplianceClient")]
+pub trait Compliance {
+    /// Whether `address` may send or receive funds.
+    fn is_allowed(env: Env, address: Address) -> bool;
+}
+
+/// Where compliance decisions come from
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ComplianceProvider {
+    Disabled,
+    OnContract,         // Denylist and allowlist kept by the contract admin
+    External(Address),  // A contract implementing `Compliance`
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ComplianceConfig {
+    pub provider: ComplianceProvider,
+    pub require_allowlist: bool, // On-contract only: addresses must be allowlisted (KYC'd)
+}
+
+/// A screening result, reused for the rest of the ledger it was made in
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ComplianceCacheEntry {
+    pub ledger: u32,
+    pub allowed: bool,
+}
+
+/// A payment attempt that was refused because one of its parties failed
+/// screening. The attempt still succeeds as a call, so the record and its
+/// event are kept.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct BlockedPayment {
+    pub payer: Address,
+    pub blocked_address: Address,
+    pub ledger: u32,
+    pub timestamp: u64,
+}
+
+/// Storage keys for screening state. Each contract keeps them next to its
+/// own keys; the variant names match what the contracts used before.
+#[derive(Clone)]
+#[contracttype]
+pub enum ComplianceKey {
+    ComplianceConfig,
+    ComplianceEpoch,
+    Denylisted(Address),
+    Allowlisted(Address),
+    ComplianceCache(u32, Address),
+}
+
+/// Decision for an address on the on-contract lists
+pub fn is_allowed_by_lists(config: &ComplianceConfig, denylisted: bool, allowlisted: bool) -> bool {
+    !denylisted && (allowlisted || !config.require_allowlist)
+}
+
+/// The configured screening, if any
+pub fn compliance_config(env: &Env) -> Option<ComplianceConfig> {
+    env.storage().instance().get(&ComplianceKey::ComplianceConfig)
+}
+
+/// Replace the screening config. Decisions cached under the old config are
+/// no longer looked up.
+pub fn set_compliance_config(env: &Env, config: &ComplianceConfig) {
+    env.storage().instance().set(&ComplianceKey::ComplianceConfig, config);
+
+    let epoch: u32 = env.storage().instance().get(&ComplianceKey::ComplianceEpoch).unwrap_or(0);
+    env.storage().instance().set(&ComplianceKey::ComplianceEpoch, &(epoch + 1));
+}
+
+/// Add or remove an address on the on-contract denylist
+pub fn set_denylisted(env: &Env, address: &Address, listed: bool) {
+    set_listed(env, ComplianceKey::Denylisted(address.clone()), address, listed);
+}
+
+/// Add or remove an address on the on-contract allowlist
+pub fn set_allowlisted(env: &Env, address: &Address, listed: bool) {
+    set_listed(env, ComplianceKey::Allowlisted(address.clone()), address, listed);
+}
+
+fn set_listed(env: &Env, key: ComplianceKey, address: &Address, listed: bool) {
+    if listed {
+        env.storage().persistent().set(&key, &true);
+        extend_persistent(env, &key);
+    } else {
+        env.storage().persistent().remove(&key);
+    }
+
+    // List changes apply immediately, not from the next ledger
+    env.storage().temporary().remove(&cache_key(env, address));
+}
+
+fn cache_key(env: &Env, address: &Address) -> ComplianceKey {
+    let epoch: u32 = env.storage().instance().get(&ComplianceKey::ComplianceEpoch).unwrap_or(0);
+    ComplianceKey::ComplianceCache(epoch, address.clone())
+}
+
+/// Consult the configured provider, reusing a decision made earlier in the
+/// same ledger. Everything passes when screening is not configured.
+pub fn screen_address(env: &Env, address: &Address) -> bool {
+    let Some(config) = compliance_config(env) else {
+        return true;
+    };
+
+    let ledger = env.ledger().sequence();
+    let cache_key = cache_key(env, address);
+    if let Some(entry) = env.storage().temporary().get::<_, ComplianceCacheEntry>(&cache_key) {
+        if entry.ledger == ledger {
+            return entry.allowed;
+        }
+    }
+
+    let allowed = match &config.provider {
+        ComplianceProvider::Disabled => return true,
+        ComplianceProvider::OnContract => is_allowed_by_lists(
+            &config,
+            env.storage().persistent().has(&ComplianceKey::Denylisted(address.clone())),
+            env.storage().persistent().has(&ComplianceKey::Allowlisted(address.clone())),
+        ),
+        ComplianceProvider::External(provider) => ComplianceClient::new(env, provider).is_allowed(address),
+    };
+
+    env.storage().temporary().set(&cache_key, &ComplianceCacheEntry { ledger, allowed });
+    allowed
+}
+
+/// Reject a fund movement involving an address that fails screening
+pub fn ensure_compliant(env: &Env, address: &Address) -> Result<(), Error> {
+    if screen_address(env, address) {
+        Ok(())
+    } else {
+        Err(Error::AddressBlocked)
+    }
+}
+
+/// The first of `parties` that fails screening
+pub fn find_blocked(env: &Env, parties: &Vec<Address>) -> Option<Address> {
+    parties.iter().find(|party| !screen_address(env, party))
+}
+
+/// Store a blocked payment under `key` and announce it as
+/// `("payment", "blocked") -> (payment, payer, blocked_address)`. Callers
+/// return normally afterwards, since the host discards the record and
+/// event along with a failed call.
+pub fn record_blocked_payment<K, P>(env: &Env, key: &K, payment: P, payer: &Address, blocked_address: &Address)
+where
+    K: IntoVal<Env, Val>,
+    P: IntoVal<Env, Val>,
+{
+    let blocked = BlockedPayment {
+        payer: payer.clone(),
+        blocked_address: blocked_address.clone(),
+        ledger: env.ledger().sequence(),
+        timestamp: env.ledger().timestamp(),
+    };
+    env.storage().persistent().set(key, &blocked);
+    extend_persistent(env, key);
+
+    let payment: Val = payment.into_val(env);
+    env.events().publish(
+        (symbol_short!("payment"), symbol_short!("blocked")),
+        (payment, payer.clone(), blocked_address.clone()),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_allowed_by_lists() {
+        let mut config = ComplianceConfig {
+            provider: ComplianceProvider::OnContract,
+            require_allowlist: false,
+        };
+        assert!(is_allowed_by_lists(&config, false, false));
+        assert!(!is_allowed_by_lists(&config, true, true));
+
+        config.require_allowlist = true;
+        assert!(!is_allowed_by_lists(&config, false, false));
+        assert!(is_allowed_by_lists(&config, false, true));
+    }
+}
@@ -0,0 +1,86 @@
+use soroban_sdk::{contracttype, Address, BytesN};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum DisputeStatus {
+    Open,
+    Responded,
+    Resolved(DisputeOutcome),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum DisputeOutcome {
+    Refund,
+    PartialRefund(i128),
+    Rejected,
+}
+
+/// Arbiter and deadlines applied to every dispute.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DisputeConfig {
+    pub arbiter: Address,
+    pub response_period: u64, // Seconds the merchant has to respond
+    pub ruling_period: u64,   // Seconds the arbiter has to rule after a response
+    pub hold_period: u64,     // Seconds proceeds stay in the contract after a payment
+}
+
+/// A payer's challenge of a completed payment.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Dispute {
+    pub payment_id: u64,
+    pub payer: Address,
+    pub disputed_amount: i128,
+    pub payer_evidence: BytesN<32>,
+    pub merchant_evidence: BytesN<32>, // Zeroed until the merchant responds
+    pub opened_at: u64,
+    pub response_deadline: u64,
+    pub ruling_deadline: u64, // Set once the merchant responds
+    pub status: DisputeStatus,
+    pub refund_amount: i128,
+    pub refund_paid: i128,
+    pub escrowed_amount: i128, // Held proceeds set aside for the payer while unresolved
+}
+
+impl Dispute {
+    /// Amount the payer is owed under `outcome`, capped at the disputed amount.
+    pub fn refund_for(&self, outcome: &DisputeOutcome) -> i128 {
+        match outcome {
+            DisputeOutcome::Refund => self.disputed_amount,
+            DisputeOutcome::PartialRefund(amount) => (*amount).clamp(0, self.disputed_amount),
+            DisputeOutcome::Rejected => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, Env};
+
+    #[test]
+    fn test_refund_for() {
+        let env = Env::default();
+        let dispute = Dispute {
+            payment_id: 1,
+            payer: Address::generate(&env),
+            disputed_amount: 500,
+            payer_evidence: BytesN::from_array(&env, &[1; 32]),
+            merchant_evidence: BytesN::from_array(&env, &[0; 32]),
+            opened_at: 0,
+            response_deadline: 100,
+            ruling_deadline: 0,
+            status: DisputeStatus::Open,
+            refund_amount: 0,
+            refund_paid: 0,
+            escrowed_amount: 0,
+        };
+
+        assert_eq!(dispute.refund_for(&DisputeOutcome::Refund), 500);
+        assert_eq!(dispute.refund_for(&DisputeOutcome::PartialRefund(200)), 200);
+        assert_eq!(dispute.refund_for(&DisputeOutcome::PartialRefund(900)), 500);
+        assert_eq!(dispute.refund_for(&DisputeOutcome::Rejected), 0);
+    }
+}
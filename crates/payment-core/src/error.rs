@@ -0,0 +1,61 @@
+use soroban_sdk::contracterror;
+
+/// Errors returned by the payment contracts.
+///
+/// Codes are part of the public contract interface and must never be
+/// renumbered; new variants are appended with the next free code.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    NotAuthorized = 1,
+    PaymentNotFound = 2,
+    InvalidAmount = 3,
+    InvalidFeePercentage = 4,
+    PaymentAlreadyCompleted = 5,
+    BusinessNotActive = 6,
+    InsufficientBalance = 7,
+    InvalidAddress = 8,
+    PaymentExpired = 9,
+    ContractNotInitialized = 10,
+    BusinessNotFound = 11,
+    AmountOutOfRange = 12,
+    InvalidInvoice = 13,
+    DuplicateMerchantReference = 14,
+    DuplicateOrder = 15,
+    Overpayment = 16,
+    TokenMismatch = 17,
+    InvalidPaymentStatus = 18,
+    InvalidRequestKind = 19,
+    InvalidSplit = 20,
+    StreamNotFound = 21,
+    InvalidStream = 22,
+    StreamNotActive = 23,
+    DeadlineExpired = 24,
+    SlippageExceeded = 25,
+    SwapAdapterNotSet = 26,
+    OracleNotSet = 27,
+    PriceUnavailable = 28,
+    StalePrice = 29,
+    PriceDeviation = 30,
+    SettlementNotConfigured = 31,
+    SettlementNotDue = 32,
+    SettlementNotFound = 33,
+    RefundExceedsPayment = 34,
+    DisputeNotConfigured = 35,
+    DisputeNotFound = 36,
+    DisputeAlreadyExists = 37,
+    InvalidDisputeStatus = 38,
+    DisputeDeadlineNotReached = 39,
+    PaymentLimitExceeded = 40,
+    PayerDailyLimitExceeded = 41,
+    PayerWeeklyLimitExceeded = 42,
+    BusinessDailyLimitExceeded = 43,
+    BusinessWeeklyLimitExceeded = 44,
+    AddressBlocked = 45,
+    IntentKeyNotSet = 46,
+    InvalidNonce = 47,
+    InsufficientAllowance = 48,
+    RampAlreadyFinal = 49,
+    InvalidOracleConfig = 50,
+}
@@ -0,0 +1,36 @@
+/// Basis points that make up 100% (100 = 1%).
+pub const BASIS_POINTS_DENOMINATOR: i128 = 10_000;
+
+/// Returns true when `basis_points` is within 0%..=100%.
+pub fn is_valid_basis_points(basis_points: i128) -> bool {
+    (0..=BASIS_POINTS_DENOMINATOR).contains(&basis_points)
+}
+
+/// Splits `amount` into `(fee, net)` for a fee expressed in basis points.
+///
+/// The fee is rounded down, so any remainder stays with the net amount.
+pub fn split_fee(amount: i128, basis_points: i128) -> (i128, i128) {
+    let fee = (amount * basis_points) / BASIS_POINTS_DENOMINATOR;
+    (fee, amount - fee)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_fee_rounds_down() {
+        assert_eq!(split_fee(1_000_000, 250), (25_000, 975_000));
+        assert_eq!(split_fee(999, 100), (9, 990));
+        assert_eq!(split_fee(1_000, 0), (0, 1_000));
+        assert_eq!(split_fee(1_000, 10_000), (1_000, 0));
+    }
+
+    #[test]
+    fn test_basis_points_bounds() {
+        assert!(is_valid_basis_points(0));
+        assert!(is_valid_basis_points(10_000));
+        assert!(!is_valid_basis_points(10_001));
+        assert!(!is_valid_basis_points(-1));
+    }
+}
@@ -0,0 +1,20 @@
+use soroban_sdk::{contracttype, xdr::ToXdr, Address, Bytes, Env};
+
+/// A payer's off-chain authorization to pay `amount` of `token_address`
+/// towards one payment request. Intents are used in nonce order and can be
+/// submitted by anyone until `expiry`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PaymentIntent {
+    pub payment_id: u64,
+    pub payer: Address,
+    pub token_address: Address,
+    pub amount: i128,
+    pub nonce: u64,
+    pub expiry: u64,
+}
+
+/// The bytes a payer signs: the intent bound to the contract that executes it
+pub fn intent_message(env: &Env, contract: &Address, intent: &PaymentIntent) -> Bytes {
+    (contract.clone(), intent.clone()).to_xdr(env)
+}
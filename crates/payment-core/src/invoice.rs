@@ -0,0 +1,133 @@
+use soroban_sdk::{contracttype, String, Vec};
+
+use crate::error::Error;
+use crate::fees::{is_valid_basis_points, BASIS_POINTS_DENOMINATOR};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct LineItem {
+    pub sku: String,
+    pub quantity: u32,
+    pub unit_price: i128,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Invoice {
+    pub line_items: Vec<LineItem>,
+    pub tax_basis_points: u32, // Applied after the discount
+    pub discount: i128,
+    pub due_date: u64,
+    pub merchant_reference: String,
+}
+
+/// Amounts derived from an invoice.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct InvoiceTotals {
+    pub subtotal: i128,
+    pub discount: i128,
+    pub tax: i128,
+    pub total: i128,
+}
+
+/// Computes the payable amount of an invoice.
+///
+/// Tax is charged on the discounted subtotal and rounded down. Any
+/// malformed input (no line items, zero quantities, negative prices, a
+/// discount larger than the subtotal or an out-of-range tax rate) yields
+/// `Error::InvalidInvoice`, as does arithmetic overflow.
+pub fn invoice_totals(invoice: &Invoice) -> Result<InvoiceTotals, Error> {
+    if invoice.line_items.is_empty() || !is_valid_basis_points(invoice.tax_basis_points as i128) {
+        return Err(Error::InvalidInvoice);
+    }
+
+    let mut subtotal: i128 = 0;
+    for item in invoice.line_items.iter() {
+        if item.quantity == 0 || item.unit_price < 0 {
+            return Err(Error::InvalidInvoice);
+        }
+        subtotal = item
+            .unit_price
+            .checked_mul(item.quantity as i128)
+            .and_then(|line_total| subtotal.checked_add(line_total))
+            .ok_or(Error::InvalidInvoice)?;
+    }
+
+    if invoice.discount < 0 || invoice.discount > subtotal {
+        return Err(Error::InvalidInvoice);
+    }
+
+    let taxable = subtotal - invoice.discount;
+    let tax = taxable
+        .checked_mul(invoice.tax_basis_points as i128)
+        .ok_or(Error::InvalidInvoice)?
+        / BASIS_POINTS_DENOMINATOR;
+
+    Ok(InvoiceTotals {
+        subtotal,
+        discount: invoice.discount,
+        tax,
+        total: taxable + tax,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{vec, Env};
+
+    fn invoice(env: &Env, line_items: Vec<LineItem>, tax_basis_points: u32, discount: i128) -> Invoice {
+        Invoice {
+            line_items,
+            tax_basis_points,
+            discount,
+            due_date: 0,
+            merchant_reference: String::from_str(env, "INV-1"),
+        }
+    }
+
+    fn item(env: &Env, sku: &str, quantity: u32, unit_price: i128) -> LineItem {
+        LineItem {
+            sku: String::from_str(env, sku),
+            quantity,
+            unit_price,
+        }
+    }
+
+    #[test]
+    fn test_invoice_totals() {
+        let env = Env::default();
+        let items = vec![&env, item(&env, "WIDGET", 3, 1_000), item(&env, "GADGET", 1, 500)];
+
+        let totals = invoice_totals(&invoice(&env, items, 1_000, 500)).unwrap();
+        assert_eq!(totals.subtotal, 3_500);
+        assert_eq!(totals.discount, 500);
+        assert_eq!(totals.tax, 300);
+        assert_eq!(totals.total, 3_300);
+    }
+
+    #[test]
+    fn test_invalid_invoices() {
+        let env = Env::default();
+        let items = vec![&env, item(&env, "WIDGET", 1, 1_000)];
+
+        let empty = invoice(&env, Vec::new(&env), 0, 0);
+        assert_eq!(invoice_totals(&empty), Err(Error::InvalidInvoice));
+
+        let zero_quantity = invoice(&env, vec![&env, item(&env, "WIDGET", 0, 1_000)], 0, 0);
+        assert_eq!(invoice_totals(&zero_quantity), Err(Error::InvalidInvoice));
+
+        let negative_price = invoice(&env, vec![&env, item(&env, "WIDGET", 1, -1)], 0, 0);
+        assert_eq!(invoice_totals(&negative_price), Err(Error::InvalidInvoice));
+
+        let excessive_discount = invoice(&env, items.clone(), 0, 1_001);
+        assert_eq!(invoice_totals(&excessive_discount), Err(Error::InvalidInvoice));
+
+        let excessive_tax = invoice(&env, items, 10_001, 0);
+        assert_eq!(invoice_totals(&excessive_tax), Err(Error::InvalidInvoice));
+
+        let overflow = invoice(&env, vec![&env, item(&env, "WIDGET", 2, i128::MAX)], 0, 0);
+        assert_eq!(invoice_totals(&overflow), Err(Error::InvalidInvoice));
+    }
+}
@@ -0,0 +1,49 @@
+#![no_std]
+
+pub mod compliance;
+pub mod dispute;
+pub mod error;
+pub mod fees;
+pub mod intent;
+pub mod invoice;
+pub mod limits;
+pub mod mirror;
+pub mod oracle;
+pub mod ramp;
+pub mod settlement;
+pub mod split;
+pub mod storage;
+pub mod stream;
+pub mod swap;
+pub mod types;
+
+pub use compliance::{
+    compliance_config, ensure_compliant, find_blocked, is_allowed_by_lists, record_blocked_payment,
+    screen_address, set_allowlisted, set_compliance_config, set_denylisted, BlockedPayment,
+    Compliance, ComplianceCacheEntry, ComplianceClient, ComplianceConfig, ComplianceKey,
+    ComplianceProvider,
+};
+pub use dispute::{Dispute, DisputeConfig, DisputeOutcome, DisputeStatus};
+pub use error::Error;
+pub use fees::{is_valid_basis_points, split_fee, BASIS_POINTS_DENOMINATOR};
+pub use intent::{intent_message, PaymentIntent};
+pub use invoice::{invoice_totals, Invoice, InvoiceTotals, LineItem};
+pub use limits::{
+    check_spending_limits, enforce_spending_limits, record_spend, window_total, SpendBucket,
+    SpendingLimits, DAY, WEEK,
+};
+pub use mirror::{evm_payment_id, EvmPayment};
+pub use oracle::{
+    fiat_to_token_amount, price_deviation_bps, Asset, FiatQuote, OracleConfig, PriceData,
+    PriceOracle, PriceOracleClient,
+};
+pub use ramp::{RampKind, RampRecord, RampStatus};
+pub use settlement::{is_settlement_due, SettlementConfig, SettlementRecord};
+pub use split::{allocate_splits, validate_splits, SplitShare, MAX_SPLIT_RECIPIENTS};
+pub use storage::{extend_persistent, DAY_IN_LEDGERS, PERSISTENT_TTL, PERSISTENT_TTL_THRESHOLD};
+pub use stream::{is_valid_schedule, vested_amount, Stream, StreamStatus};
+pub use swap::{SwapAdapter, SwapAdapterClient};
+pub use types::{
+    AmountBounds, FeeBasis, PaymentDetails, PaymentHistory, PaymentRecord, PaymentRequest,
+    PaymentStatus, RequestKind,
+};
@@ -0,0 +1,181 @@
+use soroban_sdk::{contracttype, Env, IntoVal, Val, Vec};
+
+use crate::{error::Error, storage::extend_persistent};
+
+pub const DAY: u64 = 86_400;
+pub const WEEK: u64 = 7 * DAY;
+/// Spend is tracked in hourly buckets, so rolling windows are exact to the hour.
+pub const SPEND_BUCKET: u64 = 3_600;
+
+/// Caps on payment size and rolling volume. `None` leaves a dimension unlimited.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct SpendingLimits {
+    pub max_payment_amount: Option<i128>,
+    pub payer_daily_limit: Option<i128>,
+    pub payer_weekly_limit: Option<i128>,
+    pub business_daily_limit: Option<i128>,
+    pub business_weekly_limit: Option<i128>,
+}
+
+/// Volume spent during one bucket of ledger time
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SpendBucket {
+    pub start: u64,
+    pub amount: i128,
+}
+
+/// Volume recorded in the `window` seconds up to `now`
+pub fn window_total(buckets: &Vec<SpendBucket>, now: u64, window: u64) -> i128 {
+    buckets
+        .iter()
+        .filter(|bucket| bucket.start + window > now)
+        .map(|bucket| bucket.amount)
+        .sum()
+}
+
+/// Add `amount` at `now`, dropping buckets that fell out of the weekly window
+pub fn record_spend(env: &Env, buckets: &Vec<SpendBucket>, now: u64, amount: i128) -> Vec<SpendBucket> {
+    let start = now - now % SPEND_BUCKET;
+    let mut updated = Vec::new(env);
+    let mut recorded = false;
+
+    for mut bucket in buckets.iter() {
+        if bucket.start + WEEK <= now {
+            continue;
+        }
+        if bucket.start == start {
+            bucket.amount += amount;
+            recorded = true;
+        }
+        updated.push_back(bucket);
+    }
+
+    if !recorded {
+        updated.push_back(SpendBucket { start, amount });
+    }
+    updated
+}
+
+/// Check a payment of `amount` against the limits. Exempt payers skip the
+/// per-payment and per-payer caps; business caps always apply.
+pub fn check_spending_limits(
+    limits: &SpendingLimits,
+    amount: i128,
+    now: u64,
+    payer_spend: &Vec<SpendBucket>,
+    business_spend: &Vec<SpendBucket>,
+    payer_exempt: bool,
+) -> Result<(), Error> {
+    let exceeds = |limit: Option<i128>, spend: &Vec<SpendBucket>, window: u64| {
+        limit.is_some_and(|limit| window_total(spend, now, window) + amount > limit)
+    };
+
+    if !payer_exempt {
+        if limits.max_payment_amount.is_some_and(|max| amount > max) {
+            return Err(Error::PaymentLimitExceeded);
+        }
+        if exceeds(limits.payer_daily_limit, payer_spend, DAY) {
+            return Err(Error::PayerDailyLimitExceeded);
+        }
+        if exceeds(limits.payer_weekly_limit, payer_spend, WEEK) {
+            return Err(Error::PayerWeeklyLimitExceeded);
+        }
+    }
+
+    if exceeds(limits.business_daily_limit, business_spend, DAY) {
+        return Err(Error::BusinessDailyLimitExceeded);
+    }
+    if exceeds(limits.business_weekly_limit, business_spend, WEEK) {
+        return Err(Error::BusinessWeeklyLimitExceeded);
+    }
+    Ok(())
+}
+
+/// Reject a payment that would break a limit, otherwise record its volume
+/// in the payer and business spend kept under the given keys
+pub fn enforce_spending_limits<K: IntoVal<Env, Val>>(
+    env: &Env,
+    limits: &SpendingLimits,
+    amount: i128,
+    payer_key: &K,
+    business_key: &K,
+    payer_exempt: bool,
+) -> Result<(), Error> {
+    let payer_spend: Vec<SpendBucket> = env.storage().persistent().get(payer_key).unwrap_or(Vec::new(env));
+    let business_spend: Vec<SpendBucket> = env.storage().persistent().get(business_key).unwrap_or(Vec::new(env));
+    let now = env.ledger().timestamp();
+
+    check_spending_limits(limits, amount, now, &payer_spend, &business_spend, payer_exempt)?;
+
+    env.storage().persistent().set(payer_key, &record_spend(env, &payer_spend, now, amount));
+    env.storage().persistent().set(business_key, &record_spend(env, &business_spend, now, amount));
+    extend_persistent(env, payer_key);
+    extend_persistent(env, business_key);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::vec;
+
+    #[test]
+    fn test_rolling_windows() {
+        let env = Env::default();
+        let spend = record_spend(&env, &Vec::new(&env), 10_000, 100);
+        let spend = record_spend(&env, &spend, 10_500, 50);
+        assert_eq!(spend, vec![&env, SpendBucket { start: 7_200, amount: 150 }]);
+
+        let spend = record_spend(&env, &spend, 10_000 + DAY, 30);
+        assert_eq!(window_total(&spend, 10_000 + DAY, DAY), 30);
+        assert_eq!(window_total(&spend, 10_000 + DAY, WEEK), 180);
+
+        // Buckets older than a week are dropped
+        let spend = record_spend(&env, &spend, 7_200 + WEEK, 1);
+        assert_eq!(spend.len(), 2);
+        assert_eq!(window_total(&spend, 7_200 + WEEK, WEEK), 31);
+    }
+
+    #[test]
+    fn test_check_spending_limits() {
+        let env = Env::default();
+        let limits = SpendingLimits {
+            max_payment_amount: Some(500),
+            payer_daily_limit: Some(800),
+            payer_weekly_limit: Some(1_000),
+            business_daily_limit: Some(2_000),
+            business_weekly_limit: None,
+        };
+        let payer_spend = vec![&env, SpendBucket { start: 0, amount: 600 }];
+        let business_spend = vec![&env, SpendBucket { start: 0, amount: 1_900 }];
+        let empty = Vec::new(&env);
+
+        assert_eq!(check_spending_limits(&limits, 500, 100, &empty, &empty, false), Ok(()));
+        assert_eq!(
+            check_spending_limits(&limits, 501, 100, &empty, &empty, false),
+            Err(Error::PaymentLimitExceeded)
+        );
+        assert_eq!(
+            check_spending_limits(&limits, 201, 100, &payer_spend, &empty, false),
+            Err(Error::PayerDailyLimitExceeded)
+        );
+        assert_eq!(
+            check_spending_limits(&limits, 201, DAY, &payer_spend, &empty, false),
+            Ok(())
+        );
+        assert_eq!(
+            check_spending_limits(&limits, 401, DAY, &payer_spend, &empty, false),
+            Err(Error::PayerWeeklyLimitExceeded)
+        );
+        assert_eq!(
+            check_spending_limits(&limits, 401, DAY, &payer_spend, &empty, true),
+            Ok(())
+        );
+        assert_eq!(
+            check_spending_limits(&limits, 101, 100, &empty, &business_spend, true),
+            Err(Error::BusinessDailyLimitExceeded)
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use soroban_sdk::{contracttype, Bytes, BytesN, Env, String, U256};
+
+/// A `PaymentProcessed` payment of an EVM payment processor, mirrored onto
+/// Stellar by the bridge relayer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct EvmPayment {
+    pub id: BytesN<32>, // Unified payment ID, see `evm_payment_id`
+    pub chain_id: u64,
+    pub contract: BytesN<20>, // Processor contract address
+    pub payment_id: U256,     // Payment ID on the processor
+    pub sender: BytesN<20>,
+    pub recipient: BytesN<20>,
+    pub amount: i128, // Token base units on the EVM chain
+    pub order_id: String,
+    pub transaction_hash: BytesN<32>,
+    pub recorded_at: u64,
+}
+
+/// Unified ID of a payment on an EVM chain:
+/// `keccak256(abi.encode(uint256 chainId, address contract, uint256 paymentId))`.
+/// Stellar payments use the network ID in place of the chain ID, so IDs from
+/// both sides share one space.
+pub fn evm_payment_id(env: &Env, chain_id: u64, contract: &BytesN<20>, payment_id: &U256) -> BytesN<32> {
+    let mut encoded = Bytes::from_array(env, &[0; 24]);
+    encoded.extend_from_array(&chain_id.to_be_bytes());
+    encoded.extend_from_array(&[0; 12]);
+    encoded.append(&Bytes::from(contract.clone()));
+    encoded.append(&payment_id.to_be_bytes());
+    env.crypto().keccak256(&encoded).into()
+}
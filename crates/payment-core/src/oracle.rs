@@ -0,0 +1,102 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env, String, Symbol, Vec};
+
+use crate::error::Error;
+use crate::fees::BASIS_POINTS_DENOMINATOR;
+
+/// Asset identifier used by SEP-40 price feeds.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// A single SEP-40 price record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// The subset of the SEP-40 price feed interface the payment contract uses.
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Number of decimals prices are scaled by.
+    fn decimals(env: Env) -> u32;
+
+    /// Most recent price of `asset`, if any.
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+
+    /// Up to `records` most recent prices of `asset`, newest first.
+    fn prices(env: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>>;
+}
+
+/// Oracle used to price fiat-denominated requests.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct OracleConfig {
+    pub oracle: Address,
+    pub denomination: String,   // Currency the feed quotes in, e.g. "USD"
+    pub max_age: u64,           // Seconds before a price is considered stale
+    pub max_deviation_bps: u32, // Largest move allowed between the last two prices
+}
+
+/// Conversion recorded when a fiat-denominated request is paid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FiatQuote {
+    pub fiat_amount: i128,
+    pub token_address: Address,
+    pub price: i128,
+    pub price_timestamp: u64,
+    pub token_amount: i128,
+}
+
+/// Relative move from `previous` to `latest`, in basis points.
+pub fn price_deviation_bps(previous: i128, latest: i128) -> i128 {
+    if previous <= 0 {
+        return i128::MAX;
+    }
+    (latest - previous).abs() * BASIS_POINTS_DENOMINATOR / previous
+}
+
+/// Converts a fiat amount into token units at `price` (fiat per token,
+/// scaled by `10^decimals`). Both amounts use the same fixed-point scale.
+///
+/// Rounds up so the merchant never receives less than the quoted value.
+pub fn fiat_to_token_amount(fiat_amount: i128, price: i128, decimals: u32) -> Result<i128, Error> {
+    if price <= 0 {
+        return Err(Error::PriceUnavailable);
+    }
+    let scaled = 10i128
+        .checked_pow(decimals)
+        .and_then(|scale| fiat_amount.checked_mul(scale))
+        .ok_or(Error::InvalidAmount)?;
+    Ok((scaled + price - 1) / price)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fiat_to_token_amount() {
+        // 25.00 USD at 0.125 USD per token (14 decimals) is 200 tokens
+        let price = 12_500_000_000_000;
+        assert_eq!(fiat_to_token_amount(250_000_000, price, 14), Ok(2_000_000_000));
+
+        // Rounds up in the merchant's favour
+        assert_eq!(fiat_to_token_amount(10, 3, 0), Ok(4));
+        assert_eq!(fiat_to_token_amount(10, 0, 0), Err(Error::PriceUnavailable));
+        assert_eq!(fiat_to_token_amount(i128::MAX, 1, 2), Err(Error::InvalidAmount));
+    }
+
+    #[test]
+    fn test_price_deviation() {
+        assert_eq!(price_deviation_bps(1_000, 1_000), 0);
+        assert_eq!(price_deviation_bps(1_000, 1_050), 500);
+        assert_eq!(price_deviation_bps(1_000, 900), 1_000);
+        assert_eq!(price_deviation_bps(0, 900), i128::MAX);
+    }
+}
@@ -0,0 +1,41 @@
+use soroban_sdk::{contracttype, Address, String};
+
+/// Anchor flow that moved a payment request's funds on or off Stellar
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RampKind {
+    Deposit,    // SEP-24 deposit funding the payer
+    Withdrawal, // SEP-24 withdrawal of the proceeds
+    Send,       // SEP-31 cross-border send of the proceeds
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RampStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// An anchor transaction tied to a payment request, as reported by a party
+/// to the request. Only the party that first recorded it may update it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RampRecord {
+    pub kind: RampKind,
+    pub anchor: String,    // Anchor home domain
+    pub anchor_id: String, // Transaction ID at the anchor
+    pub token_address: Address,
+    pub amount: i128,
+    pub status: RampStatus,
+    pub stellar_transaction_id: String, // Empty until the on-chain leg is known
+    pub recorded_by: Address,           // Set by the contract to the first recorder
+    pub updated_at: u64,
+}
+
+impl RampStatus {
+    /// Completed and failed transfers are never reopened.
+    pub fn is_final(&self) -> bool {
+        !matches!(self, RampStatus::Pending)
+    }
+}
@@ -0,0 +1,65 @@
+use soroban_sdk::{contracttype, Address, String, Vec};
+
+/// Batches a business's proceeds in the contract instead of paying the
+/// requester on every payment.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SettlementConfig {
+    pub enabled: bool,
+    pub payout_address: Address,
+    pub interval: u64,   // Seconds between scheduled payouts
+    pub threshold: i128, // Balance that triggers an early payout
+}
+
+/// One payout of accumulated proceeds, listing the payments it covers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SettlementRecord {
+    pub id: u64,
+    pub business_name: String,
+    pub token_address: Address,
+    pub payout_address: Address,
+    pub amount: i128,
+    pub payment_ids: Vec<u64>,
+    pub timestamp: u64,
+}
+
+/// Returns true when a balance may be paid out: a disabled configuration
+/// drains immediately, otherwise the schedule or threshold must be reached.
+pub fn is_settlement_due(
+    config: &SettlementConfig,
+    balance: i128,
+    last_settled_at: u64,
+    now: u64,
+) -> bool {
+    balance > 0
+        && (!config.enabled
+            || balance >= config.threshold
+            || now >= last_settled_at.saturating_add(config.interval))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, Env};
+
+    #[test]
+    fn test_settlement_due() {
+        let env = Env::default();
+        let mut config = SettlementConfig {
+            enabled: true,
+            payout_address: Address::generate(&env),
+            interval: 100,
+            threshold: 1_000,
+        };
+
+        assert!(!is_settlement_due(&config, 0, 0, 1_000));
+        assert!(!is_settlement_due(&config, 999, 50, 149));
+        assert!(is_settlement_due(&config, 999, 50, 150));
+        assert!(is_settlement_due(&config, 1_000, 50, 51));
+
+        config.enabled = false;
+        assert!(is_settlement_due(&config, 1, 50, 51));
+        assert!(!is_settlement_due(&config, 0, 50, 51));
+    }
+}
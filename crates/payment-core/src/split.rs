@@ -0,0 +1,107 @@
+use soroban_sdk::{contracttype, Address, Env, Vec};
+
+use crate::error::Error;
+use crate::fees::BASIS_POINTS_DENOMINATOR;
+
+/// Upper bound on payees per payment to keep execution costs predictable.
+pub const MAX_SPLIT_RECIPIENTS: u32 = 10;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SplitShare {
+    pub recipient: Address,
+    pub share_basis_points: u32,
+}
+
+/// Checks that every share is positive, recipients are unique and the
+/// shares add up to exactly 100%.
+pub fn validate_splits(splits: &Vec<SplitShare>) -> Result<(), Error> {
+    if splits.is_empty() || splits.len() > MAX_SPLIT_RECIPIENTS {
+        return Err(Error::InvalidSplit);
+    }
+
+    let mut total: i128 = 0;
+    for (index, split) in splits.iter().enumerate() {
+        if split.share_basis_points == 0 {
+            return Err(Error::InvalidSplit);
+        }
+        if splits.iter().skip(index + 1).any(|other| other.recipient == split.recipient) {
+            return Err(Error::InvalidSplit);
+        }
+        total += split.share_basis_points as i128;
+    }
+
+    if total != BASIS_POINTS_DENOMINATOR {
+        return Err(Error::InvalidSplit);
+    }
+    Ok(())
+}
+
+/// Divides `amount` between the payees of a validated split.
+///
+/// Each leg is rounded down and the rounding remainder goes to the last
+/// payee, so the legs always add up to `amount`.
+pub fn allocate_splits(env: &Env, amount: i128, splits: &Vec<SplitShare>) -> Vec<(Address, i128)> {
+    let mut legs = Vec::new(env);
+    let mut allocated: i128 = 0;
+    let last = splits.len().saturating_sub(1);
+
+    for (index, split) in splits.iter().enumerate() {
+        let leg_amount = if index as u32 == last {
+            amount - allocated
+        } else {
+            amount * split.share_basis_points as i128 / BASIS_POINTS_DENOMINATOR
+        };
+        allocated += leg_amount;
+        legs.push_back((split.recipient, leg_amount));
+    }
+    legs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, vec};
+
+    fn share(recipient: &Address, share_basis_points: u32) -> SplitShare {
+        SplitShare {
+            recipient: recipient.clone(),
+            share_basis_points,
+        }
+    }
+
+    #[test]
+    fn test_allocate_splits_assigns_remainder_to_last_leg() {
+        let env = Env::default();
+        let seller = Address::generate(&env);
+        let affiliate = Address::generate(&env);
+        let platform = Address::generate(&env);
+        let splits = vec![&env, share(&seller, 8_000), share(&affiliate, 1_500), share(&platform, 500)];
+
+        assert_eq!(validate_splits(&splits), Ok(()));
+
+        let legs = allocate_splits(&env, 999, &splits);
+        assert_eq!(legs, vec![&env, (seller, 799), (affiliate, 149), (platform, 51)]);
+    }
+
+    #[test]
+    fn test_invalid_splits() {
+        let env = Env::default();
+        let seller = Address::generate(&env);
+        let platform = Address::generate(&env);
+
+        assert_eq!(validate_splits(&Vec::new(&env)), Err(Error::InvalidSplit));
+        assert_eq!(
+            validate_splits(&vec![&env, share(&seller, 9_000), share(&platform, 500)]),
+            Err(Error::InvalidSplit)
+        );
+        assert_eq!(
+            validate_splits(&vec![&env, share(&seller, 10_000), share(&platform, 0)]),
+            Err(Error::InvalidSplit)
+        );
+        assert_eq!(
+            validate_splits(&vec![&env, share(&seller, 5_000), share(&seller, 5_000)]),
+            Err(Error::InvalidSplit)
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use soroban_sdk::{Env, IntoVal, Val};
+
+/// Ledgers in a day at the network's 5 second close time
+pub const DAY_IN_LEDGERS: u32 = 17_280;
+
+/// How long a persistent entry stays live after it is written or read
+pub const PERSISTENT_TTL: u32 = 30 * DAY_IN_LEDGERS;
+
+/// Remaining TTL below which an entry is topped back up to `PERSISTENT_TTL`
+pub const PERSISTENT_TTL_THRESHOLD: u32 = 7 * DAY_IN_LEDGERS;
+
+/// Keep the persistent entry under `key` from being archived. The entry must exist.
+pub fn extend_persistent<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    env.storage()
+        .persistent()
+        .extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{contract, symbol_short, testutils::storage::Persistent as _};
+
+    #[contract]
+    struct TestContract;
+
+    #[test]
+    fn test_extend_persistent() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, TestContract);
+        let key = symbol_short!("key");
+
+        env.as_contract(&contract_id, || {
+            env.storage().persistent().set(&key, &1u32);
+            extend_persistent(&env, &key);
+            assert_eq!(env.storage().persistent().get_ttl(&key), PERSISTENT_TTL);
+        });
+    }
+}
@@ -0,0 +1,112 @@
+use soroban_sdk::{contracttype, Address};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum StreamStatus {
+    Active,
+    Cancelled,
+    Depleted,
+}
+
+/// A deposit that vests linearly from `start_time` to `end_time`, with
+/// nothing withdrawable before `cliff_time`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Stream {
+    pub id: u64,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token_address: Address,
+    pub deposit: i128,
+    pub withdrawn: i128,
+    pub start_time: u64,
+    pub cliff_time: u64,
+    pub end_time: u64,
+    pub status: StreamStatus,
+}
+
+/// Returns true when the schedule and deposit describe a valid stream.
+pub fn is_valid_schedule(deposit: i128, start_time: u64, cliff_time: u64, end_time: u64) -> bool {
+    deposit > 0 && start_time < end_time && (start_time..=end_time).contains(&cliff_time)
+}
+
+/// Amount of the deposit that has vested at `now`.
+pub fn vested_amount(stream: &Stream, now: u64) -> i128 {
+    if now < stream.cliff_time {
+        0
+    } else if now >= stream.end_time {
+        stream.deposit
+    } else {
+        // deposit * elapsed / duration, split so that no product overflows:
+        // the remainder is below duration, so its product fits in a u128
+        let elapsed = now - stream.start_time;
+        let duration = stream.end_time - stream.start_time;
+        let quotient = stream.deposit / duration as i128;
+        let remainder = (stream.deposit % duration as i128) as u128;
+        quotient * elapsed as i128 + (remainder * elapsed as u128 / duration as u128) as i128
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::{testutils::Address as _, Env};
+
+    #[test]
+    fn test_vested_amount() {
+        let env = Env::default();
+        let stream = Stream {
+            id: 1,
+            sender: Address::generate(&env),
+            recipient: Address::generate(&env),
+            token_address: Address::generate(&env),
+            deposit: 1_000,
+            withdrawn: 0,
+            start_time: 100,
+            cliff_time: 200,
+            end_time: 1_100,
+            status: StreamStatus::Active,
+        };
+
+        assert_eq!(vested_amount(&stream, 0), 0);
+        assert_eq!(vested_amount(&stream, 199), 0);
+        assert_eq!(vested_amount(&stream, 200), 100);
+        assert_eq!(vested_amount(&stream, 600), 500);
+        assert_eq!(vested_amount(&stream, 1_100), 1_000);
+        assert_eq!(vested_amount(&stream, u64::MAX), 1_000);
+    }
+
+    #[test]
+    fn test_vested_amount_does_not_overflow() {
+        let env = Env::default();
+        let stream = Stream {
+            id: 1,
+            sender: Address::generate(&env),
+            recipient: Address::generate(&env),
+            token_address: Address::generate(&env),
+            deposit: u64::MAX as i128 * 1_000_000_000_000,
+            withdrawn: 0,
+            start_time: 0,
+            cliff_time: 0,
+            end_time: u64::MAX,
+            status: StreamStatus::Active,
+        };
+
+        // deposit * elapsed would not fit in an i128
+        let elapsed = u64::MAX / 3;
+        assert_eq!(vested_amount(&stream, elapsed), elapsed as i128 * 1_000_000_000_000);
+
+        let stream = Stream { deposit: i128::MAX, ..stream };
+        assert_eq!(vested_amount(&stream, u64::MAX - 1), i128::MAX - i128::MAX / u64::MAX as i128 - 1);
+    }
+
+    #[test]
+    fn test_schedule_validation() {
+        assert!(is_valid_schedule(1, 0, 0, 1));
+        assert!(is_valid_schedule(1, 0, 1, 1));
+        assert!(!is_valid_schedule(0, 0, 0, 1));
+        assert!(!is_valid_schedule(1, 1, 1, 1));
+        assert!(!is_valid_schedule(1, 5, 4, 10));
+        assert!(!is_valid_schedule(1, 5, 11, 10));
+    }
+}
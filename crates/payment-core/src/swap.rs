@@ -0,0 +1,23 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Interface a liquidity source (AMM pool, router, ...) must expose to be
+/// used for cross-currency payments.
+///
+/// Swaps follow the transfer-then-swap pattern: the caller first moves
+/// `amount_in` of `token_in` to the adapter, then calls `swap`.
+#[contractclient(name = "SwapAdapterClient")]
+pub trait SwapAdapter {
+    /// Amount of `token_in` needed to receive `amount_out` of `token_out`.
+    fn quote_in(env: Env, token_in: Address, token_out: Address, amount_out: i128) -> i128;
+
+    /// Swaps the `amount_in` already held by the adapter and sends at least
+    /// `min_amount_out` of `token_out` to `to`. Returns the amount sent.
+    fn swap(
+        env: Env,
+        token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_amount_out: i128,
+        to: Address,
+    ) -> i128;
+}
@@ -0,0 +1,92 @@
+use soroban_sdk::{contracttype, Address, String, Symbol, Vec};
+
+use crate::split::SplitShare;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PaymentRequest {
+    pub id: u64,
+    pub amount: i128,
+    pub business_name: String,
+    pub description: String,
+    pub denomination: String,
+    pub authorized_addresses: Vec<Address>,
+    pub requester: Address,
+    pub timestamp: u64,
+    pub status: PaymentStatus,
+    pub fee_percentage: u32, // Basis points (100 = 1%)
+    pub amount_paid: i128,
+    pub kind: RequestKind,
+    pub tip_amount: i128,
+    pub splits: Vec<SplitShare>, // Empty pays the requester
+}
+
+/// How the payable amount of a request is determined.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RequestKind {
+    /// `amount` is fixed by the requester.
+    Fixed,
+    /// The payer chooses the amount within optional bounds (donations).
+    OpenAmount(AmountBounds),
+    /// `amount` is fixed and the payer may add a tip on top.
+    Tippable,
+    /// `amount` is in fiat and converted to tokens through the oracle when paid.
+    FiatPriced,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AmountBounds {
+    pub min_amount: Option<i128>,
+    pub max_amount: Option<i128>,
+}
+
+/// Which part of a tipped payment the business fee is charged on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum FeeBasis {
+    BaseOnly,
+    Total,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum PaymentStatus {
+    Pending,
+    Authorized,
+    Completed,
+    Failed,
+    Cancelled,
+    PartiallyPaid,
+    Refunded,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PaymentHistory {
+    pub total_payments: u64,
+    pub total_amount: i128,
+    pub last_payment_id: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PaymentDetails {
+    pub amount: i128,
+    pub sender: Address,
+    pub recipient: Address,
+    pub token_address: Address,
+    pub business_name: String,
+    pub customer_name: String,
+    pub order_id: String,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PaymentRecord {
+    pub payment_id: u64,
+    pub details: PaymentDetails,
+    pub timestamp: u64,
+    pub status: Symbol,
+}
@@ -0,0 +1,318 @@
+//! Resource costs of contract entrypoints, checked against a baseline.
+//!
+//! [`measure`] runs one contract call in a test `Env` and reports the CPU
+//! instructions and memory bytes the host metered for it, along with the
+//! ledger entries it read and wrote. Costs are recorded per entrypoint and
+//! data size in [`Costs`] and compared with a baseline checked in next to the
+//! tests. A CPU or memory cost more than `threshold_percent` above the
+//! baseline fails the check, and so does any extra ledger read or write.
+//!
+//! Contracts registered natively run their own code outside the budget, so
+//! CPU and memory cover the host work a call does: storage, auth, token
+//! calls, cryptography and value conversions. With the `wasm` feature the
+//! tests register the contracts' release WASM instead, which meters their
+//! code as the network would, and check it against `baselines/wasm`:
+//!
+//! ```text
+//! cargo build --target wasm32-unknown-unknown --release
+//! UPDATE_COST_BASELINE=1 cargo test -p payment-costs --features wasm
+//! ```
+//!
+//! ```no_run
+//! # use payment_costs::{check, Costs};
+//! # use soroban_sdk::Env;
+//! # let env = Env::default();
+//! let mut costs = Costs::default();
+//! costs.record(&env, "get_payment_counter", 1, || {
+//!     // client.get_payment_counter()
+//! });
+//! check("baselines/payment-processor.json", &costs);
+//! ```
+//!
+//! Set `UPDATE_COST_BASELINE` to rewrite the baseline with the measured costs
+//! instead of checking them.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use soroban_env_host::storage::{AccessType, Footprint};
+use soroban_sdk::Env;
+
+/// Variable that makes [`check`] rewrite the baseline
+pub const UPDATE_VAR: &str = "UPDATE_COST_BASELINE";
+
+/// Percentage CPU and memory may grow by before [`check`] fails
+pub const DEFAULT_THRESHOLD_PERCENT: u64 = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cost {
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+    /// Ledger entries only read
+    pub ledger_reads: u32,
+    /// Ledger entries written, whether or not they were read first
+    pub ledger_writes: u32,
+}
+
+/// Run `call` and return its result along with what it cost.
+///
+/// The budget is left unlimited afterwards so unmeasured setup between calls
+/// never runs out of it.
+pub fn measure<T>(env: &Env, call: impl FnOnce() -> T) -> (T, Cost) {
+    env.host()
+        .with_mut_storage(|storage| {
+            storage.footprint = Footprint::default();
+            Ok(())
+        })
+        .expect("storage is borrowed");
+    env.budget().reset_default();
+
+    let result = call();
+
+    let cpu_instructions = env.budget().cpu_instruction_cost();
+    let memory_bytes = env.budget().memory_bytes_cost();
+    env.budget().reset_unlimited();
+
+    let (ledger_reads, ledger_writes) = env
+        .host()
+        .with_mut_storage(|storage| {
+            let budget = env.host().budget_cloned();
+            let mut counts = (0, 0);
+            for (_, access) in storage.footprint.0.iter(&budget)? {
+                match access {
+                    AccessType::ReadOnly => counts.0 += 1,
+                    AccessType::ReadWrite => counts.1 += 1,
+                }
+            }
+            Ok(counts)
+        })
+        .expect("storage is borrowed");
+    let cost = Cost {
+        cpu_instructions,
+        memory_bytes,
+        ledger_reads,
+        ledger_writes,
+    };
+
+    (result, cost)
+}
+
+/// Costs measured in one run, keyed by entrypoint and data size
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Costs(BTreeMap<String, Cost>);
+
+impl Costs {
+    /// Measure `call` and record it as `entrypoint` at `size`.
+    pub fn record<T>(
+        &mut self,
+        env: &Env,
+        entrypoint: &str,
+        size: u32,
+        call: impl FnOnce() -> T,
+    ) -> T {
+        let (result, cost) = measure(env, call);
+        self.insert(entrypoint, size, cost);
+        result
+    }
+
+    pub fn insert(&mut self, entrypoint: &str, size: u32, cost: Cost) {
+        self.0.insert(key(entrypoint, size), cost);
+    }
+
+    pub fn get(&self, entrypoint: &str, size: u32) -> Option<&Cost> {
+        self.0.get(&key(entrypoint, size))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Cost)> {
+        self.0.iter().map(|(key, cost)| (key.as_str(), cost))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+fn key(entrypoint: &str, size: u32) -> String {
+    format!("{entrypoint}/{size}")
+}
+
+/// Checked-in costs that new measurements are compared with
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub threshold_percent: u64,
+    pub costs: BTreeMap<String, Cost>,
+}
+
+impl Baseline {
+    pub fn new(costs: &Costs) -> Baseline {
+        Baseline {
+            threshold_percent: DEFAULT_THRESHOLD_PERCENT,
+            costs: costs.0.clone(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Baseline> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        if let Some(dir) = path.as_ref().parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, json)
+    }
+
+    /// Everything about `costs` that should fail a check
+    pub fn compare(&self, costs: &Costs) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for (key, measured) in &costs.0 {
+            let Some(baseline) = self.costs.get(key) else {
+                findings.push(Finding::NotInBaseline(key.clone()));
+                continue;
+            };
+            let resources = [
+                (
+                    Resource::CpuInstructions,
+                    baseline.cpu_instructions,
+                    measured.cpu_instructions,
+                ),
+                (
+                    Resource::MemoryBytes,
+                    baseline.memory_bytes,
+                    measured.memory_bytes,
+                ),
+                (
+                    Resource::LedgerReads,
+                    baseline.ledger_reads.into(),
+                    measured.ledger_reads.into(),
+                ),
+                (
+                    Resource::LedgerWrites,
+                    baseline.ledger_writes.into(),
+                    measured.ledger_writes.into(),
+                ),
+            ];
+            for (resource, baseline, measured) in resources {
+                let allowed = match resource {
+                    Resource::CpuInstructions | Resource::MemoryBytes => {
+                        baseline + baseline * self.threshold_percent / 100
+                    }
+                    Resource::LedgerReads | Resource::LedgerWrites => baseline,
+                };
+                if measured > allowed {
+                    findings.push(Finding::Regression {
+                        key: key.clone(),
+                        resource,
+                        baseline,
+                        measured,
+                    });
+                }
+            }
+        }
+        for key in self.costs.keys() {
+            if !costs.0.contains_key(key) {
+                findings.push(Finding::NotMeasured(key.clone()));
+            }
+        }
+        findings
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resource {
+    CpuInstructions,
+    MemoryBytes,
+    LedgerReads,
+    LedgerWrites,
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Resource::CpuInstructions => "CPU instructions",
+            Resource::MemoryBytes => "memory bytes",
+            Resource::LedgerReads => "ledger reads",
+            Resource::LedgerWrites => "ledger writes",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Finding {
+    Regression {
+        key: String,
+        resource: Resource,
+        baseline: u64,
+        measured: u64,
+    },
+    /// Measured but missing from the baseline
+    NotInBaseline(String),
+    /// In the baseline but no longer measured
+    NotMeasured(String),
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Finding::Regression {
+                key,
+                resource,
+                baseline,
+                measured,
+            } => {
+                let change = (*measured as f64 / (*baseline).max(1) as f64 - 1.0) * 100.0;
+                write!(
+                    f,
+                    "{key}: {resource} went from {baseline} to {measured} (+{change:.1}%)"
+                )
+            }
+            Finding::NotInBaseline(key) => write!(f, "{key}: not in the baseline"),
+            Finding::NotMeasured(key) => write!(f, "{key}: in the baseline but not measured"),
+        }
+    }
+}
+
+/// Compare `costs` with the baseline at `path`, panicking with every finding.
+/// With `UPDATE_COST_BASELINE` set, the baseline is rewritten instead, keeping
+/// its threshold.
+pub fn check(path: impl AsRef<Path>, costs: &Costs) {
+    let path = path.as_ref();
+    let existing = Baseline::load(path);
+
+    if std::env::var_os(UPDATE_VAR).is_some() {
+        let mut baseline = Baseline::new(costs);
+        if let Ok(existing) = existing {
+            baseline.threshold_percent = existing.threshold_percent;
+        }
+        baseline
+            .save(path)
+            .unwrap_or_else(|error| panic!("writing {}: {error}", path.display()));
+        return;
+    }
+
+    let baseline = existing.unwrap_or_else(|error| {
+        panic!(
+            "reading {}: {error}; run with {UPDATE_VAR}=1 to create it",
+            path.display()
+        )
+    });
+    let findings = baseline.compare(costs);
+    if !findings.is_empty() {
+        let findings: Vec<String> = findings.iter().map(Finding::to_string).collect();
+        panic!(
+            "costs differ from {}:\n  {}\nrun with {UPDATE_VAR}=1 to accept them",
+            path.display(),
+            findings.join("\n  ")
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use payment_costs::{measure, Baseline, Cost, Costs, Finding, Resource};
+use soroban_sdk::{testutils::Address as _, token::StellarAssetClient, Address, Env};
+
+const COST: Cost = Cost {
+    cpu_instructions: 100_000,
+    memory_bytes: 20_000,
+    ledger_reads: 2,
+    ledger_writes: 1,
+};
+
+fn costs(cost: Cost) -> Costs {
+    let mut costs = Costs::default();
+    costs.insert("execute_payment", 10, cost);
+    costs
+}
+
+#[test]
+fn test_growth_within_threshold_passes() {
+    let baseline = Baseline::new(&costs(COST));
+    assert_eq!(baseline.threshold_percent, 10);
+
+    let measured = Cost {
+        cpu_instructions: 110_000,
+        memory_bytes: 18_000,
+        ledger_reads: 1,
+        ..COST
+    };
+    assert_eq!(baseline.compare(&costs(measured)), vec![]);
+}
+
+#[test]
+fn test_regressions_reported() {
+    let baseline = Baseline::new(&costs(COST));
+
+    let measured = Cost {
+        cpu_instructions: 110_001,
+        ledger_writes: 2,
+        ..COST
+    };
+    assert_eq!(
+        baseline.compare(&costs(measured)),
+        vec![
+            Finding::Regression {
+                key: "execute_payment/10".to_string(),
+                resource: Resource::CpuInstructions,
+                baseline: 100_000,
+                measured: 110_001,
+            },
+            Finding::Regression {
+                key: "execute_payment/10".to_string(),
+                resource: Resource::LedgerWrites,
+                baseline: 1,
+                measured: 2,
+            },
+        ]
+    );
+    assert_eq!(
+        baseline.compare(&costs(measured))[0].to_string(),
+        "execute_payment/10: CPU instructions went from 100000 to 110001 (+10.0%)"
+    );
+}
+
+#[test]
+fn test_baseline_keys_must_match() {
+    let baseline = Baseline::new(&costs(COST));
+
+    let mut measured = Costs::default();
+    measured.insert("execute_payment", 50, COST);
+    assert_eq!(
+        baseline.compare(&measured),
+        vec![
+            Finding::NotInBaseline("execute_payment/50".to_string()),
+            Finding::NotMeasured("execute_payment/10".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_measure_counts_ledger_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let token = StellarAssetClient::new(
+        &env,
+        &env.register_stellar_asset_contract_v2(admin).address(),
+    );
+    let holder = Address::generate(&env);
+
+    // Reads the instance and the admin's account, writes the admin's auth
+    // nonce and the new balance
+    let ((), cost) = measure(&env, || token.mint(&holder, &1_000));
+    assert_eq!((cost.ledger_reads, cost.ledger_writes), (2, 2));
+    assert!(cost.cpu_instructions > 0 && cost.memory_bytes > 0);
+
+    // Earlier calls are not counted again
+    let (_, cost) = measure(&env, || token.admin());
+    assert_eq!((cost.ledger_reads, cost.ledger_writes), (1, 0));
+}
@@ -0,0 +1,237 @@
+//! Costs of the payment processor contract's entrypoints, checked against
+//! `baselines/payment-processor.json`.
+//!
+//! The size is both the number of authorized addresses (with the merchant
+//! last) and the number of payments already recorded. Payments are kept in
+//! persistent entries of their own, so recorded payments should not make
+//! calls dearer.
+
+use payment_costs::{check, Costs};
+use payment_processor::{
+    ComplianceConfig, ComplianceProvider, PaymentContractClient, SpendingLimits,
+};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    Address, Env, String, Vec,
+};
+
+// Built by `cargo build --target wasm32-unknown-unknown --release` beforehand
+#[cfg(feature = "wasm")]
+mod wasm {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/payment_processor.wasm"
+    );
+}
+
+/// The contract as deployed with the `wasm` feature, natively otherwise
+fn register(env: &Env) -> Address {
+    #[cfg(feature = "wasm")]
+    return env.register_contract_wasm(None, wasm::WASM);
+    #[cfg(not(feature = "wasm"))]
+    env.register_contract(None, payment_processor::PaymentContract)
+}
+
+/// WASM costs include the contract's own code, so they have baselines of their own
+fn baseline(file: &str) -> std::string::String {
+    let dir = if cfg!(feature = "wasm") {
+        "baselines/wasm"
+    } else {
+        "baselines"
+    };
+    format!("{}/{dir}/{file}", env!("CARGO_MANIFEST_DIR"))
+}
+
+const SIZES: [u32; 3] = [1, 10, 50];
+
+struct Setup<'a> {
+    env: Env,
+    client: PaymentContractClient<'a>,
+    token: TokenClient<'a>,
+    admin: Address,
+    merchant: Address,
+    payer: Address,
+    authorized: Vec<Address>,
+}
+
+impl Setup<'_> {
+    fn pay_xlm(&self, order_id: &str) -> u64 {
+        self.client.process_xlm_payment(
+            &self.payer,
+            &self.merchant,
+            &750,
+            &String::from_str(&self.env, "Test Store"),
+            &String::from_str(&self.env, "Customer"),
+            &String::from_str(&self.env, order_id),
+        )
+    }
+}
+
+fn setup<'a>(size: u32) -> Setup<'a> {
+    let env = Env::default();
+    let contract_id = register(&env);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+
+    let admin = Address::generate(&env);
+    let merchant = Address::generate(&env);
+    let mut authorized = Vec::new(&env);
+    for _ in 1..size {
+        authorized.push_back(Address::generate(&env));
+    }
+    authorized.push_back(merchant.clone());
+
+    let token = TokenClient::new(
+        &env,
+        &env.register_stellar_asset_contract_v2(admin.clone())
+            .address(),
+    );
+    let payer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token.address).mint(&payer, &1_000_000_000);
+
+    Setup {
+        env,
+        client,
+        token,
+        admin,
+        merchant,
+        payer,
+        authorized,
+    }
+}
+
+/// A setup with the merchant configured and `size - 1` payments recorded
+fn with_history<'a>(size: u32) -> Setup<'a> {
+    let s = setup(size);
+    s.client.initialize(&s.admin, &s.authorized);
+    s.client
+        .configure_business(&s.merchant, &100, &1, &1_000_000_000);
+    for index in 1..size {
+        s.pay_xlm(&format!("order-{index}"));
+    }
+    s
+}
+
+fn payments(size: u32, costs: &mut Costs) {
+    let s = &setup(size);
+    let Setup {
+        env,
+        client,
+        token,
+        admin,
+        merchant,
+        payer,
+        authorized,
+    } = s;
+
+    costs.record(env, "initialize", size, || {
+        client.initialize(admin, authorized)
+    });
+    costs.record(env, "configure_business", size, || {
+        client.configure_business(merchant, &100, &1, &1_000_000_000)
+    });
+    for index in 1..size {
+        s.pay_xlm(&format!("order-{index}"));
+    }
+
+    let payment_id = costs.record(env, "process_xlm_payment", size, || {
+        s.pay_xlm(&format!("order-{size}"))
+    });
+    costs.record(env, "process_token_payment", size, || {
+        client.process_token_payment(
+            payer,
+            merchant,
+            &token.address,
+            &10_000,
+            &String::from_str(env, "Test Store"),
+            &String::from_str(env, "Customer"),
+            &String::from_str(env, "token-order"),
+        )
+    });
+
+    costs.record(env, "get_payment", size, || client.get_payment(&payment_id));
+    costs.record(env, "get_payment_by_order_id", size, || {
+        client.get_payment_by_order_id(merchant, &String::from_str(env, "token-order"))
+    });
+    costs.record(env, "get_business_config", size, || {
+        client.get_business_config(merchant)
+    });
+    costs.record(env, "get_authorized_addresses", size, || {
+        client.get_authorized_addresses()
+    });
+    costs.record(env, "get_payment_counter", size, || {
+        client.get_payment_counter()
+    });
+    costs.record(env, "update_business_status", size, || {
+        client.update_business_status(merchant, &true)
+    });
+}
+
+fn limits(size: u32, costs: &mut Costs) {
+    let s = &with_history(size);
+    let Setup {
+        env,
+        client,
+        merchant,
+        payer,
+        ..
+    } = s;
+
+    let limits = SpendingLimits {
+        max_payment_amount: Some(1_000_000),
+        payer_daily_limit: Some(10_000_000),
+        payer_weekly_limit: Some(50_000_000),
+        business_daily_limit: Some(100_000_000),
+        business_weekly_limit: Some(500_000_000),
+    };
+    costs.record(env, "set_spending_limits", size, || {
+        client.set_spending_limits(&limits)
+    });
+    costs.record(env, "set_business_limits", size, || {
+        client.set_business_limits(merchant, &limits)
+    });
+    costs.record(env, "set_limit_exemption", size, || {
+        client.set_limit_exemption(payer, &true)
+    });
+    costs.record(env, "get_spending_limits", size, || {
+        client.get_spending_limits(merchant)
+    });
+}
+
+fn compliance(size: u32, costs: &mut Costs) {
+    let s = &with_history(size);
+    let Setup {
+        env, client, payer, ..
+    } = s;
+
+    let config = ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    };
+    costs.record(env, "set_compliance_config", size, || {
+        client.set_compliance_config(&config)
+    });
+    costs.record(env, "set_denylisted", size, || {
+        client.set_denylisted(&Address::generate(env), &true)
+    });
+    costs.record(env, "set_allowlisted", size, || {
+        client.set_allowlisted(payer, &true)
+    });
+    costs.record(env, "check_compliance", size, || {
+        client.check_compliance(payer)
+    });
+}
+
+#[test]
+fn test_processor_costs_within_baseline() {
+    let mut costs = Costs::default();
+    for size in SIZES {
+        payments(size, &mut costs);
+        limits(size, &mut costs);
+        compliance(size, &mut costs);
+    }
+
+    check(baseline("payment-processor.json"), &costs);
+}
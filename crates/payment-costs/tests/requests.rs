@@ -0,0 +1,825 @@
+//! Costs of the payment request contract's entrypoints, checked against
+//! `baselines/payment-requests.json`.
+//!
+//! The size is the length of the data a call walks: the authorized addresses
+//! of a request (with the payer last), invoice line items, split recipients
+//! (up to `MAX_SPLIT_RECIPIENTS`), contributors to one request, ramps,
+//! unsettled payments and spend buckets. Calls that walk none of these are
+//! measured at every size all the same, against the same state.
+
+use ed25519_dalek::{Signer, SigningKey};
+use payment_costs::{check, Costs};
+use payment_requests::{
+    intent_message, AmountBounds, Asset, ComplianceConfig, ComplianceProvider, DisputeConfig,
+    DisputeOutcome, FeeBasis, Invoice, LineItem, OracleConfig, PaymentContractClient,
+    PaymentIntent, PriceData, RampKind, RampRecord, RampStatus, SettlementConfig, SpendingLimits,
+    SplitShare,
+};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{Address as _, Ledger},
+    token::{Client as TokenClient, StellarAssetClient},
+    vec, Address, BytesN, Env, String, Vec,
+};
+
+// Built by `cargo build --target wasm32-unknown-unknown --release` beforehand
+#[cfg(feature = "wasm")]
+mod wasm {
+    soroban_sdk::contractimport!(
+        file = "../../target/wasm32-unknown-unknown/release/payment_requests.wasm"
+    );
+}
+
+/// The contract as deployed with the `wasm` feature, natively otherwise
+fn register(env: &Env) -> Address {
+    #[cfg(feature = "wasm")]
+    return env.register_contract_wasm(None, wasm::WASM);
+    #[cfg(not(feature = "wasm"))]
+    env.register_contract(None, payment_requests::PaymentContract)
+}
+
+/// WASM costs include the contract's own code, so they have baselines of their own
+fn baseline(file: &str) -> std::string::String {
+    let dir = if cfg!(feature = "wasm") {
+        "baselines/wasm"
+    } else {
+        "baselines"
+    };
+    format!("{}/{dir}/{file}", env!("CARGO_MANIFEST_DIR"))
+}
+
+const SIZES: [u32; 3] = [1, 10, 50];
+
+// Limit of `payment_core::validate_splits`
+const MAX_SPLIT_RECIPIENTS: u32 = 10;
+
+/// Stand-in for a SEP-40 price feed; prices are stored newest first.
+#[contract]
+pub struct TestOracle;
+
+#[contractimpl]
+impl TestOracle {
+    pub fn set_prices(env: Env, asset: Asset, prices: Vec<PriceData>) {
+        env.storage().instance().set(&asset, &prices);
+    }
+
+    pub fn decimals(_env: Env) -> u32 {
+        14
+    }
+
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        let prices: Vec<PriceData> = env.storage().instance().get(&asset)?;
+        prices.get(0)
+    }
+
+    pub fn prices(env: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+        let prices: Vec<PriceData> = env.storage().instance().get(&asset)?;
+        Some(prices.slice(0..records.min(prices.len())))
+    }
+}
+
+/// Constant-price pool standing in for an AMM: `rate` source tokens buy one
+/// unit of the destination token.
+#[contract]
+pub struct MockPool;
+
+#[contractimpl]
+impl MockPool {
+    pub fn set_rate(env: Env, rate: i128) {
+        env.storage().instance().set(&symbol_short!("rate"), &rate);
+    }
+
+    pub fn quote_in(env: Env, _token_in: Address, _token_out: Address, amount_out: i128) -> i128 {
+        let rate: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("rate"))
+            .unwrap();
+        amount_out * rate
+    }
+
+    pub fn swap(
+        env: Env,
+        _token_in: Address,
+        token_out: Address,
+        amount_in: i128,
+        min_amount_out: i128,
+        to: Address,
+    ) -> i128 {
+        let rate: i128 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("rate"))
+            .unwrap();
+        let amount_out = amount_in / rate;
+        assert!(amount_out >= min_amount_out, "slippage");
+        TokenClient::new(&env, &token_out).transfer(
+            &env.current_contract_address(),
+            &to,
+            &amount_out,
+        );
+        amount_out
+    }
+}
+
+struct Setup<'a> {
+    env: Env,
+    client: PaymentContractClient<'a>,
+    token: TokenClient<'a>,
+    xlm: TokenClient<'a>,
+    owner: Address,
+    business_owner: Address,
+    business_name: String,
+    payer: Address,
+    requester: Address,
+    size: u32,
+}
+
+impl Setup<'_> {
+    /// `size` addresses allowed to pay, ending with `payer`
+    fn authorized(&self, payer: &Address) -> Vec<Address> {
+        let mut addresses = Vec::new(&self.env);
+        for _ in 1..self.size {
+            addresses.push_back(Address::generate(&self.env));
+        }
+        addresses.push_back(payer.clone());
+        addresses
+    }
+
+    fn create_request(
+        &self,
+        business_name: &String,
+        amount: i128,
+        authorized: &Vec<Address>,
+    ) -> u64 {
+        self.client.create_payment_request(
+            &amount,
+            business_name,
+            &String::from_str(&self.env, "Order"),
+            &String::from_str(&self.env, "USDC"),
+            authorized,
+            &self.requester,
+            &None,
+        )
+    }
+
+    fn mint(&self, to: &Address, amount: i128) {
+        StellarAssetClient::new(&self.env, &self.token.address).mint(to, &amount);
+    }
+
+    fn advance(&self, seconds: u64) {
+        let now = self.env.ledger().timestamp();
+        self.env.ledger().set_timestamp(now + seconds);
+    }
+
+    fn text(&self, text: &str) -> String {
+        String::from_str(&self.env, text)
+    }
+}
+
+fn setup<'a>(size: u32) -> Setup<'a> {
+    let env = Env::default();
+    let contract_id = register(&env);
+    let client = PaymentContractClient::new(&env, &contract_id);
+
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+    env.ledger().set_timestamp(10_000);
+
+    let owner = Address::generate(&env);
+    let token = TokenClient::new(
+        &env,
+        &env.register_stellar_asset_contract_v2(owner.clone())
+            .address(),
+    );
+    let xlm = TokenClient::new(
+        &env,
+        &env.register_stellar_asset_contract_v2(owner.clone())
+            .address(),
+    );
+
+    let payer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token.address).mint(&payer, &1_000_000_000);
+    StellarAssetClient::new(&env, &xlm.address).mint(&payer, &1_000_000_000);
+
+    Setup {
+        client,
+        token,
+        xlm,
+        owner,
+        business_owner: Address::generate(&env),
+        business_name: String::from_str(&env, "Test Store"),
+        payer,
+        requester: Address::generate(&env),
+        size,
+        env,
+    }
+}
+
+/// A setup with the contract initialized and "Test Store" registered
+fn initialized<'a>(size: u32) -> Setup<'a> {
+    let s = setup(size);
+    s.client.initialize(&s.owner, &250u32, &s.xlm.address);
+    s.client.register_business(
+        &s.business_name,
+        &s.business_owner,
+        &Address::generate(&s.env),
+        &100u32,
+    );
+    s
+}
+
+fn core(size: u32, costs: &mut Costs) {
+    let s = &setup(size);
+    let Setup {
+        env,
+        client,
+        token,
+        payer,
+        requester,
+        business_name,
+        ..
+    } = s;
+    let authorized = s.authorized(payer);
+
+    costs.record(env, "initialize", size, || {
+        client.initialize(&s.owner, &250u32, &s.xlm.address)
+    });
+    costs.record(env, "register_business", size, || {
+        client.register_business(
+            business_name,
+            &s.business_owner,
+            &Address::generate(env),
+            &100u32,
+        )
+    });
+
+    let fixed_id = costs.record(env, "create_payment_request", size, || {
+        s.create_request(business_name, 1_000, &authorized)
+    });
+    let open_id = costs.record(env, "create_open_payment_request", size, || {
+        client.create_open_payment_request(
+            business_name,
+            &s.text("Donation"),
+            &s.text("USDC"),
+            &authorized,
+            requester,
+            &None,
+            &AmountBounds {
+                min_amount: Some(100),
+                max_amount: None,
+            },
+        )
+    });
+    let tip_id = costs.record(env, "create_tippable_request", size, || {
+        client.create_tippable_request(
+            &1_000i128,
+            business_name,
+            &s.text("Dinner"),
+            &s.text("USDC"),
+            &authorized,
+            requester,
+            &None,
+        )
+    });
+    let mut line_items = Vec::new(env);
+    for index in 0..size {
+        line_items.push_back(LineItem {
+            sku: String::from_str(env, &format!("SKU-{index}")),
+            quantity: 1,
+            unit_price: 100,
+        });
+    }
+    let invoice = Invoice {
+        line_items,
+        tax_basis_points: 800,
+        discount: 50,
+        due_date: env.ledger().timestamp() + 86_400,
+        merchant_reference: s.text("INV-1"),
+    };
+    let invoice_id = costs.record(env, "create_invoice_request", size, || {
+        client.create_invoice_request(
+            business_name,
+            &s.text("Invoice"),
+            &s.text("USDC"),
+            &authorized,
+            requester,
+            &None,
+            &invoice,
+        )
+    });
+
+    costs.record(env, "get_payment_request", size, || {
+        client.get_payment_request(&fixed_id)
+    });
+    costs.record(env, "get_invoice", size, || client.get_invoice(&invoice_id));
+    costs.record(env, "get_invoice_totals", size, || {
+        client.get_invoice_totals(&invoice_id)
+    });
+    costs.record(env, "get_payment_by_merchant_ref", size, || {
+        client.get_payment_by_merchant_ref(business_name, &s.text("INV-1"))
+    });
+    costs.record(env, "get_business_config", size, || {
+        client.get_business_config(business_name)
+    });
+
+    let recipients = size.min(MAX_SPLIT_RECIPIENTS);
+    let mut splits = Vec::new(env);
+    for index in 0..recipients {
+        let share = 10_000 / recipients + if index == 0 { 10_000 % recipients } else { 0 };
+        splits.push_back(SplitShare {
+            recipient: Address::generate(env),
+            share_basis_points: share,
+        });
+    }
+    costs.record(env, "set_payment_splits", size, || {
+        client.set_payment_splits(&fixed_id, &splits, requester)
+    });
+    costs.record(env, "execute_payment", size, || {
+        client.execute_payment(&fixed_id, payer, &token.address)
+    });
+
+    // Every contributor but the last pays up front
+    let contributors: std::vec::Vec<Address> = (0..size).map(|_| Address::generate(env)).collect();
+    let mut contributor_list = Vec::new(env);
+    for contributor in &contributors {
+        s.mint(contributor, 1_000);
+        contributor_list.push_back(contributor.clone());
+    }
+    let shared_id = s.create_request(business_name, 100 * size as i128, &contributor_list);
+    let (last, first) = contributors.split_last().unwrap();
+    for contributor in first {
+        client.execute_partial_payment(&shared_id, contributor, &token.address, &100);
+    }
+    costs.record(env, "execute_partial_payment", size, || {
+        client.execute_partial_payment(&shared_id, last, &token.address, &100)
+    });
+    costs.record(env, "get_payment_contributions", size, || {
+        client.get_payment_contributions(&shared_id)
+    });
+
+    costs.record(env, "execute_open_payment", size, || {
+        client.execute_open_payment(&open_id, payer, &token.address, &500)
+    });
+    costs.record(env, "execute_payment_with_tip", size, || {
+        client.execute_payment_with_tip(&tip_id, payer, &token.address, &50)
+    });
+    let xlm_id = s.create_request(business_name, 1_000, &authorized);
+    costs.record(env, "execute_xlm_payment", size, || {
+        client.execute_xlm_payment(&xlm_id, payer)
+    });
+
+    costs.record(env, "refund_payment", size, || {
+        client.refund_payment(&shared_id, last, &50)
+    });
+    costs.record(env, "get_payment_refunds", size, || {
+        client.get_payment_refunds(&shared_id)
+    });
+    costs.record(env, "get_payment_history", size, || {
+        client.get_payment_history(payer)
+    });
+
+    let cancelled_id = s.create_request(business_name, 1_000, &authorized);
+    costs.record(env, "cancel_payment_request", size, || {
+        client.cancel_payment_request(&cancelled_id, requester)
+    });
+    costs.record(env, "update_business_status", size, || {
+        client.update_business_status(business_name, &true, &s.business_owner)
+    });
+    costs.record(env, "set_fee_basis", size, || {
+        client.set_fee_basis(business_name, &FeeBasis::BaseOnly, &s.business_owner)
+    });
+}
+
+fn ramps(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        token,
+        payer,
+        requester,
+        business_name,
+        ..
+    } = s;
+    let payment_id = s.create_request(business_name, 1_000, &s.authorized(payer));
+
+    let ramp = |index: u32| RampRecord {
+        kind: RampKind::Deposit,
+        anchor: s.text("testanchor.stellar.org"),
+        anchor_id: String::from_str(env, &format!("ramp-{index}")),
+        token_address: token.address.clone(),
+        amount: 1_000,
+        status: RampStatus::Pending,
+        stellar_transaction_id: s.text(""),
+        recorded_by: requester.clone(),
+        updated_at: 0,
+    };
+    for index in 1..size {
+        client.record_ramp(&payment_id, requester, &ramp(index));
+    }
+    costs.record(env, "record_ramp", size, || {
+        client.record_ramp(&payment_id, requester, &ramp(0))
+    });
+    costs.record(env, "get_ramps", size, || client.get_ramps(&payment_id));
+}
+
+fn settlements(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        token,
+        payer,
+        ..
+    } = s;
+
+    let business_name = s.text("Batch Store");
+    client.register_business(
+        &business_name,
+        &s.business_owner,
+        &Address::generate(env),
+        &100u32,
+    );
+    let config = SettlementConfig {
+        enabled: true,
+        payout_address: Address::generate(env),
+        interval: 3_600,
+        threshold: 1_000_000_000,
+    };
+    costs.record(env, "set_settlement_config", size, || {
+        client.set_settlement_config(&business_name, &config, &s.business_owner)
+    });
+    costs.record(env, "get_settlement_config", size, || {
+        client.get_settlement_config(&business_name)
+    });
+
+    let authorized = s.authorized(payer);
+    for _ in 0..size {
+        let payment_id = s.create_request(&business_name, 1_000, &authorized);
+        client.execute_payment(&payment_id, payer, &token.address);
+    }
+    costs.record(env, "get_merchant_balance", size, || {
+        client.get_merchant_balance(&business_name, &token.address)
+    });
+    costs.record(env, "get_unsettled_payments", size, || {
+        client.get_unsettled_payments(&business_name, &token.address)
+    });
+
+    s.advance(3_601);
+    let settlement_id = costs.record(env, "settle", size, || {
+        client.settle(&business_name, &token.address)
+    });
+    costs.record(env, "get_settlement", size, || {
+        client.get_settlement(&settlement_id)
+    });
+}
+
+fn disputes(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        owner,
+        token,
+        payer,
+        requester,
+        business_name,
+        ..
+    } = s;
+
+    let mut config = DisputeConfig {
+        arbiter: Address::generate(env),
+        response_period: 600,
+        ruling_period: 600,
+        hold_period: 0,
+    };
+    costs.record(env, "set_dispute_config", size, || {
+        client.set_dispute_config(owner, &config)
+    });
+    costs.record(env, "get_dispute_config", size, || {
+        client.get_dispute_config()
+    });
+
+    let authorized = s.authorized(payer);
+    let disputed_id = s.create_request(business_name, 1_000, &authorized);
+    client.execute_payment(&disputed_id, payer, &token.address);
+    let abandoned_id = s.create_request(business_name, 1_000, &authorized);
+    client.execute_payment(&abandoned_id, payer, &token.address);
+
+    let evidence = BytesN::from_array(env, &[1; 32]);
+    costs.record(env, "open_dispute", size, || {
+        client.open_dispute(&disputed_id, payer, &evidence)
+    });
+    costs.record(env, "respond_to_dispute", size, || {
+        client.respond_to_dispute(&disputed_id, payer, &evidence)
+    });
+    costs.record(env, "resolve_dispute", size, || {
+        client.resolve_dispute(&disputed_id, payer, &DisputeOutcome::PartialRefund(500))
+    });
+    costs.record(env, "pay_dispute_refund", size, || {
+        client.pay_dispute_refund(&disputed_id, payer, requester)
+    });
+    costs.record(env, "get_dispute", size, || {
+        client.get_dispute(&disputed_id, payer)
+    });
+
+    // The merchant never answers this one
+    client.open_dispute(&abandoned_id, payer, &evidence);
+    s.advance(601);
+    costs.record(env, "finalize_dispute", size, || {
+        client.finalize_dispute(&abandoned_id, payer)
+    });
+
+    // Proceeds kept for the dispute window
+    config.hold_period = 600;
+    client.set_dispute_config(owner, &config);
+    let held_id = s.create_request(business_name, 1_000, &authorized);
+    client.execute_payment(&held_id, payer, &token.address);
+    costs.record(env, "get_held_proceeds", size, || {
+        client.get_held_proceeds(&held_id)
+    });
+    s.advance(601);
+    costs.record(env, "release_proceeds", size, || {
+        client.release_proceeds(&held_id)
+    });
+}
+
+fn intents(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        token,
+        payer,
+        business_name,
+        ..
+    } = s;
+
+    let signing_key = SigningKey::from_bytes(&[7; 32]);
+    let public_key = BytesN::from_array(env, &signing_key.verifying_key().to_bytes());
+    costs.record(env, "set_intent_key", size, || {
+        client.set_intent_key(payer, &public_key)
+    });
+    costs.record(env, "get_intent_key", size, || client.get_intent_key(payer));
+    let nonce = costs.record(env, "get_intent_nonce", size, || {
+        client.get_intent_nonce(payer)
+    });
+
+    token.approve(
+        payer,
+        &client.address,
+        &1_000_000,
+        &(env.ledger().sequence() + 1_000),
+    );
+    let payment_id = s.create_request(business_name, 1_000, &s.authorized(payer));
+    let intent = PaymentIntent {
+        payment_id,
+        payer: payer.clone(),
+        token_address: token.address.clone(),
+        amount: 1_000,
+        nonce,
+        expiry: env.ledger().timestamp() + 1_000,
+    };
+    let message: std::vec::Vec<u8> = intent_message(env, &client.address, &intent)
+        .iter()
+        .collect();
+    let signature = BytesN::from_array(env, &signing_key.sign(&message).to_bytes());
+    costs.record(env, "execute_payment_with_signature", size, || {
+        client.execute_payment_with_signature(&intent, &signature)
+    });
+}
+
+fn oracles(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        owner,
+        token,
+        payer,
+        requester,
+        business_name,
+        ..
+    } = s;
+
+    let oracle = TestOracleClient::new(env, &env.register_contract(None, TestOracle));
+    let config = OracleConfig {
+        oracle: oracle.address.clone(),
+        denomination: s.text("USD"),
+        max_age: 300,
+        max_deviation_bps: 1_000,
+    };
+    costs.record(env, "set_oracle_config", size, || {
+        client.set_oracle_config(owner, &config)
+    });
+    costs.record(env, "get_oracle_config", size, || {
+        client.get_oracle_config()
+    });
+
+    let authorized = s.authorized(payer);
+    let payment_id = costs.record(env, "create_fiat_payment_request", size, || {
+        client.create_fiat_payment_request(
+            &25_000_000i128,
+            business_name,
+            &s.text("Subscription"),
+            &s.text("USD"),
+            &authorized,
+            requester,
+            &None,
+        )
+    });
+
+    // 0.125 USD per token
+    let now = env.ledger().timestamp();
+    oracle.set_prices(
+        &Asset::Stellar(token.address.clone()),
+        &vec![
+            env,
+            PriceData {
+                price: 12_500_000_000_000,
+                timestamp: now,
+            },
+        ],
+    );
+    client.execute_payment(&payment_id, payer, &token.address);
+    costs.record(env, "get_fiat_quote", size, || {
+        client.get_fiat_quote(&payment_id)
+    });
+}
+
+fn swaps(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        owner,
+        token,
+        xlm,
+        payer,
+        business_name,
+        ..
+    } = s;
+
+    let pool = MockPoolClient::new(env, &env.register_contract(None, MockPool));
+    pool.set_rate(&10);
+    s.mint(&pool.address, 1_000_000);
+
+    costs.record(env, "set_swap_adapter", size, || {
+        client.set_swap_adapter(owner, &pool.address)
+    });
+    costs.record(env, "get_swap_adapter", size, || client.get_swap_adapter());
+
+    let payment_id = s.create_request(business_name, 1_000, &s.authorized(payer));
+    let deadline = env.ledger().timestamp() + 100;
+    costs.record(env, "execute_swap_payment", size, || {
+        client.execute_swap_payment(
+            &payment_id,
+            payer,
+            &xlm.address,
+            &token.address,
+            &10_500,
+            &deadline,
+        )
+    });
+}
+
+fn streams(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        token,
+        payer,
+        requester,
+        ..
+    } = s;
+
+    let now = env.ledger().timestamp();
+    let stream_id = costs.record(env, "create_stream", size, || {
+        client.create_stream(
+            payer,
+            requester,
+            &token.address,
+            &1_000,
+            &now,
+            &now,
+            &(now + 1_000),
+        )
+    });
+    s.advance(500);
+    costs.record(env, "withdraw_from_stream", size, || {
+        client.withdraw_from_stream(&stream_id)
+    });
+    costs.record(env, "get_stream", size, || client.get_stream(&stream_id));
+    costs.record(env, "get_stream_balance", size, || {
+        client.get_stream_balance(&stream_id)
+    });
+    costs.record(env, "cancel_stream", size, || {
+        client.cancel_stream(&stream_id)
+    });
+}
+
+fn limits(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        owner,
+        token,
+        payer,
+        business_name,
+        ..
+    } = s;
+
+    let limits = SpendingLimits {
+        max_payment_amount: Some(1_000_000_000),
+        payer_daily_limit: Some(10_000_000_000),
+        payer_weekly_limit: Some(50_000_000_000),
+        business_daily_limit: Some(100_000_000_000),
+        business_weekly_limit: Some(500_000_000_000),
+    };
+    costs.record(env, "set_spending_limits", size, || {
+        client.set_spending_limits(owner, &limits)
+    });
+    costs.record(env, "set_business_limits", size, || {
+        client.set_business_limits(business_name, &limits, owner)
+    });
+    costs.record(env, "get_spending_limits", size, || {
+        client.get_spending_limits(business_name)
+    });
+
+    // One payment per hourly bucket
+    let spender = Address::generate(env);
+    s.mint(&spender, 1_000_000);
+    let authorized = s.authorized(&spender);
+    for _ in 0..size {
+        s.advance(3_600);
+        let payment_id = s.create_request(business_name, 1_000, &authorized);
+        client.execute_payment(&payment_id, &spender, &token.address);
+    }
+    costs.record(env, "get_payer_volume", size, || {
+        client.get_payer_volume(&spender, &604_800)
+    });
+    costs.record(env, "get_business_volume", size, || {
+        client.get_business_volume(business_name, &604_800)
+    });
+    costs.record(env, "set_limit_exemption", size, || {
+        client.set_limit_exemption(owner, payer, &true)
+    });
+}
+
+fn compliance(size: u32, costs: &mut Costs) {
+    let s = &initialized(size);
+    let Setup {
+        env,
+        client,
+        owner,
+        payer,
+        ..
+    } = s;
+
+    let config = ComplianceConfig {
+        provider: ComplianceProvider::OnContract,
+        require_allowlist: false,
+    };
+    costs.record(env, "set_compliance_config", size, || {
+        client.set_compliance_config(owner, &config)
+    });
+    costs.record(env, "get_compliance_config", size, || {
+        client.get_compliance_config()
+    });
+
+    let blocked = Address::generate(env);
+    costs.record(env, "set_denylisted", size, || {
+        client.set_denylisted(owner, &blocked, &true)
+    });
+    costs.record(env, "set_allowlisted", size, || {
+        client.set_allowlisted(owner, payer, &true)
+    });
+    costs.record(env, "check_compliance", size, || {
+        client.check_compliance(payer)
+    });
+    costs.record(env, "get_blocked_payment", size, || {
+        client.get_blocked_payment(&1)
+    });
+}
+
+#[test]
+fn test_request_costs_within_baseline() {
+    let mut costs = Costs::default();
+    for size in SIZES {
+        core(size, &mut costs);
+        ramps(size, &mut costs);
+        settlements(size, &mut costs);
+        disputes(size, &mut costs);
+        intents(size, &mut costs);
+        oracles(size, &mut costs);
+        swaps(size, &mut costs);
+        streams(size, &mut costs);
+        limits(size, &mut costs);
+        compliance(size, &mut costs);
+    }
+
+    check(baseline("payment-requests.json"), &costs);
+}
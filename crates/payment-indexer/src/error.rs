@@ -0,0 +1,41 @@
+use std::fmt;
+
+use payments_client::DecodeError;
+
+use crate::event::Cursor;
+
+#[derive(Debug)]
+pub enum IndexerError {
+    Sqlite(rusqlite::Error),
+    /// A tracked event carried a payload the indexer could not read
+    Decode {
+        cursor: Cursor,
+        error: DecodeError,
+    },
+}
+
+impl fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexerError::Sqlite(error) => write!(f, "database error: {error}"),
+            IndexerError::Decode { cursor, error } => {
+                write!(f, "cannot decode event {cursor}: {error}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for IndexerError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IndexerError::Sqlite(error) => Some(error),
+            IndexerError::Decode { error, .. } => Some(error),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for IndexerError {
+    fn from(error: rusqlite::Error) -> IndexerError {
+        IndexerError::Sqlite(error)
+    }
+}
@@ -0,0 +1,245 @@
+//! Raw contract events and the payment events decoded from them.
+
+use std::fmt;
+
+use payments_client::{types::Address, types::PaymentStatus, DecodeError, ScType};
+use stellar_xdr::curr::{self as xdr, ContractEventBody, ContractEventType, ScVal};
+
+/// Position of an event in the ledger history: the ledger it closed in and
+/// its index among the events of that ledger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cursor {
+    pub ledger: u32,
+    pub index: u32,
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.ledger, self.index)
+    }
+}
+
+/// A contract event as emitted on the ledger.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContractEvent {
+    pub cursor: Cursor,
+    pub contract: Address,
+    pub topics: Vec<ScVal>,
+    pub data: ScVal,
+}
+
+impl ContractEvent {
+    /// Take a `ContractEvent` from transaction meta. System and diagnostic
+    /// events are skipped.
+    pub fn from_xdr(cursor: Cursor, event: &xdr::ContractEvent) -> Option<ContractEvent> {
+        if event.type_ != ContractEventType::Contract {
+            return None;
+        }
+        let contract = Address::Contract(event.contract_id.as_ref()?.0);
+        let ContractEventBody::V0(body) = &event.body;
+        Some(ContractEvent {
+            cursor,
+            contract,
+            topics: body.topics.to_vec(),
+            data: body.data.clone(),
+        })
+    }
+}
+
+/// Whether a processor payment was made in XLM or another token
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Asset {
+    Xlm,
+    Token,
+}
+
+/// The events the indexer materializes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaymentEvent {
+    BusinessRegistered {
+        name: String,
+        owner: Address,
+        fee_recipient: Address,
+        fee_percentage: u32,
+    },
+    BusinessStatus {
+        name: String,
+        is_active: bool,
+    },
+    RequestCreated {
+        payment_id: u64,
+        business_name: String,
+        requester: Address,
+        amount: i128,
+        fee_percentage: u32,
+    },
+    RequestPaid {
+        payment_id: u64,
+        payer: Address,
+        token_address: Address,
+        amount: i128,
+        tip: i128,
+        status: PaymentStatus,
+    },
+    FeeCharged {
+        payment_id: u64,
+        fee_recipient: Address,
+        token_address: Address,
+        amount: i128,
+    },
+    RequestCancelled {
+        payment_id: u64,
+    },
+    Refunded {
+        payment_id: u64,
+        payer: Address,
+        amount: i128,
+    },
+    ProcessorPayment {
+        asset: Asset,
+        payment_id: u64,
+        sender: Address,
+        recipient: Address,
+        amount: i128,
+    },
+}
+
+/// Which of the two contracts emitted an event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Requests,
+    Processor,
+}
+
+impl PaymentEvent {
+    /// Decode an event by its topics. Events the indexer does not track give
+    /// `Ok(None)`; a tracked topic with a malformed payload is an error.
+    pub fn decode(
+        source: Source,
+        topics: &[ScVal],
+        data: &ScVal,
+    ) -> Result<Option<PaymentEvent>, DecodeError> {
+        let [first, second] = topics else {
+            return Ok(None);
+        };
+        let (Some(first), Some(second)) = (symbol(first), symbol(second)) else {
+            return Ok(None);
+        };
+
+        let event = match (source, first, second) {
+            (Source::Requests, "business", "register") => {
+                let [name, owner, fee_recipient, fee_percentage] = fields(data)?;
+                PaymentEvent::BusinessRegistered {
+                    name: String::from_scval(name)?,
+                    owner: Address::from_scval(owner)?,
+                    fee_recipient: Address::from_scval(fee_recipient)?,
+                    fee_percentage: u32::from_scval(fee_percentage)?,
+                }
+            }
+            (Source::Requests, "business", "status") => {
+                let [name, is_active] = fields(data)?;
+                PaymentEvent::BusinessStatus {
+                    name: String::from_scval(name)?,
+                    is_active: bool::from_scval(is_active)?,
+                }
+            }
+            (Source::Requests, "payment", "create") => {
+                let [payment_id, business_name, requester, amount, fee_percentage] = fields(data)?;
+                PaymentEvent::RequestCreated {
+                    payment_id: u64::from_scval(payment_id)?,
+                    business_name: String::from_scval(business_name)?,
+                    requester: Address::from_scval(requester)?,
+                    amount: i128::from_scval(amount)?,
+                    fee_percentage: u32::from_scval(fee_percentage)?,
+                }
+            }
+            (Source::Requests, "payment", "paid") => {
+                let [payment_id, payer, token_address, amount, tip, status] = fields(data)?;
+                PaymentEvent::RequestPaid {
+                    payment_id: u64::from_scval(payment_id)?,
+                    payer: Address::from_scval(payer)?,
+                    token_address: Address::from_scval(token_address)?,
+                    amount: i128::from_scval(amount)?,
+                    tip: i128::from_scval(tip)?,
+                    status: PaymentStatus::from_scval(status)?,
+                }
+            }
+            // The processor keeps its fees, the request contract pays them out
+            (_, "payment", "fee") => {
+                let [payment_id, fee_recipient, token_address, amount] = fields(data)?;
+                PaymentEvent::FeeCharged {
+                    payment_id: u64::from_scval(payment_id)?,
+                    fee_recipient: Address::from_scval(fee_recipient)?,
+                    token_address: Address::from_scval(token_address)?,
+                    amount: i128::from_scval(amount)?,
+                }
+            }
+            (Source::Requests, "payment", "cancel") => PaymentEvent::RequestCancelled {
+                payment_id: u64::from_scval(data)?,
+            },
+            (Source::Requests, "payment", "refund") => {
+                let [payment_id, payer, amount] = fields(data)?;
+                PaymentEvent::Refunded {
+                    payment_id: u64::from_scval(payment_id)?,
+                    payer: Address::from_scval(payer)?,
+                    amount: i128::from_scval(amount)?,
+                }
+            }
+            (Source::Processor, "payment", asset @ ("xlm" | "token")) => {
+                let [payment_id, sender, recipient, amount] = fields(data)?;
+                PaymentEvent::ProcessorPayment {
+                    asset: if asset == "xlm" {
+                        Asset::Xlm
+                    } else {
+                        Asset::Token
+                    },
+                    payment_id: u64::from_scval(payment_id)?,
+                    sender: Address::from_scval(sender)?,
+                    recipient: Address::from_scval(recipient)?,
+                    amount: i128::from_scval(amount)?,
+                }
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(event))
+    }
+}
+
+fn symbol(value: &ScVal) -> Option<&str> {
+    match value {
+        ScVal::Symbol(symbol) => std::str::from_utf8(symbol.as_slice()).ok(),
+        _ => None,
+    }
+}
+
+// Events publish their payload as a tuple, which is encoded as a vector
+fn fields<const N: usize>(data: &ScVal) -> Result<&[ScVal; N], DecodeError> {
+    let error = || DecodeError {
+        expected: "event payload tuple",
+        found: format!("{data:?}"),
+    };
+    match data {
+        ScVal::Vec(Some(values)) => values.as_slice().try_into().map_err(|_| error()),
+        _ => Err(error()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_malformed_payload_is_an_error() {
+        let topics = [
+            ScVal::Symbol("payment".try_into().unwrap()),
+            ScVal::Symbol("cancel".try_into().unwrap()),
+        ];
+        assert!(PaymentEvent::decode(Source::Requests, &topics, &ScVal::Bool(true)).is_err());
+
+        // The processor has no cancel event, so the payload is not inspected
+        assert_eq!(
+            PaymentEvent::decode(Source::Processor, &topics, &ScVal::Bool(true)),
+            Ok(None)
+        );
+    }
+}
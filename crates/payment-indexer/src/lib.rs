@@ -0,0 +1,155 @@
+//! Indexer that materializes the payment contracts' events into SQLite.
+//!
+//! Events from the payment request contract and the payment processor are
+//! decoded into `businesses`, `payments` and `fees` tables. The position of
+//! the last event applied is kept in the `cursor` table, in the same
+//! transaction as the rows it produced, so an indexer can be stopped at any
+//! point and fed the same events again without counting them twice.
+//! Amounts are kept as decimal text, so any `i128` the contracts accept can
+//! be indexed.
+//!
+//! Indexing is expected to start from the contracts' deployment; events about
+//! payments the indexer has not seen created are ignored.
+//!
+//! With the `testutils` feature, `testutils::Recorder` captures the events
+//! of contracts running in a test `Env` as they would be fetched from RPC.
+//!
+//! ```no_run
+//! # use payment_indexer::{Contracts, Indexer};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let (requests, processor) = (
+//! #     "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse()?,
+//! #     "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse()?,
+//! # );
+//! # let events = Vec::new();
+//! let mut indexer = Indexer::open("payments.db", Contracts { requests, processor })?;
+//! let resume_after = indexer.cursor()?;
+//! // ... fetch the events after `resume_after` ...
+//! indexer.apply(&events)?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod error;
+mod event;
+mod store;
+#[cfg(feature = "testutils")]
+pub mod testutils;
+
+use std::path::Path;
+
+use payments_client::types::Address;
+use rusqlite::{Connection, Transaction};
+
+pub use error::IndexerError;
+pub use event::{Asset, ContractEvent, Cursor, PaymentEvent, Source};
+pub use store::SCHEMA;
+
+/// Addresses of the deployed contracts whose events are indexed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Contracts {
+    pub requests: Address,
+    pub processor: Address,
+}
+
+impl Contracts {
+    fn source(&self, contract: &Address) -> Option<Source> {
+        if *contract == self.requests {
+            Some(Source::Requests)
+        } else if *contract == self.processor {
+            Some(Source::Processor)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct Indexer {
+    conn: Connection,
+    contracts: Contracts,
+}
+
+impl Indexer {
+    /// Open or create the database at `path`.
+    pub fn open(path: impl AsRef<Path>, contracts: Contracts) -> Result<Indexer, IndexerError> {
+        Indexer::new(Connection::open(path)?, contracts)
+    }
+
+    /// Index into an existing connection, creating the tables if needed.
+    pub fn new(conn: Connection, contracts: Contracts) -> Result<Indexer, IndexerError> {
+        conn.execute_batch(store::SCHEMA)?;
+        Ok(Indexer { conn, contracts })
+    }
+
+    /// The last event applied, after which indexing resumes
+    pub fn cursor(&self) -> Result<Option<Cursor>, IndexerError> {
+        Ok(store::load_cursor(&self.conn)?)
+    }
+
+    /// Apply events in ledger order and advance the cursor past them.
+    ///
+    /// Events at or before the stored cursor are skipped, as are events of
+    /// other contracts and topics that are not indexed. Returns the number of
+    /// events materialized. Nothing is written if any event fails.
+    pub fn apply(&mut self, events: &[ContractEvent]) -> Result<usize, IndexerError> {
+        self.apply_with(events, |_, _, _| Ok(()))
+    }
+
+    /// Like [`Indexer::apply`], calling `on_applied` after each event is
+    /// materialized. The callback runs inside the indexing transaction, so
+    /// whatever it writes is committed or rolled back with the event.
+    pub fn apply_with<F>(
+        &mut self,
+        events: &[ContractEvent],
+        mut on_applied: F,
+    ) -> Result<usize, IndexerError>
+    where
+        F: FnMut(&Transaction, &ContractEvent, &PaymentEvent) -> Result<(), IndexerError>,
+    {
+        let resume_after = self.cursor()?;
+        let tx = self.conn.transaction()?;
+
+        let mut last = resume_after;
+        let mut applied = 0;
+        for event in events {
+            if last.is_some_and(|cursor| event.cursor <= cursor) {
+                continue;
+            }
+            last = Some(event.cursor);
+
+            let Some(source) = self.contracts.source(&event.contract) else {
+                continue;
+            };
+            let decoded =
+                PaymentEvent::decode(source, &event.topics, &event.data).map_err(|error| {
+                    IndexerError::Decode {
+                        cursor: event.cursor,
+                        error,
+                    }
+                })?;
+            if let Some(decoded) = decoded {
+                store::apply(&tx, &event.contract.to_string(), event.cursor, &decoded)?;
+                on_applied(&tx, event, &decoded)?;
+                applied += 1;
+            }
+        }
+
+        if let Some(cursor) = last {
+            if last != resume_after {
+                store::save_cursor(&tx, cursor)?;
+            }
+        }
+        tx.commit()?;
+        Ok(applied)
+    }
+
+    /// Addresses of the indexed contracts
+    pub fn contracts(&self) -> &Contracts {
+        &self.contracts
+    }
+
+    /// Connection for querying the materialized tables
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+}
@@ -0,0 +1,308 @@
+//! Tables the events are materialized into.
+
+use rusqlite::{named_params, types::Type, Connection, OptionalExtension, Row};
+
+use crate::{
+    error::IndexerError,
+    event::{Asset, Cursor, PaymentEvent},
+};
+
+/// Amounts are i128 on chain, wider than a SQLite integer, so they are stored
+/// as decimal text and added up in Rust
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS businesses (
+    contract TEXT NOT NULL,
+    name TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    fee_recipient TEXT NOT NULL,
+    fee_percentage INTEGER NOT NULL,
+    is_active INTEGER NOT NULL,
+    registered_ledger INTEGER NOT NULL,
+    PRIMARY KEY (contract, name)
+);
+
+CREATE TABLE IF NOT EXISTS payments (
+    contract TEXT NOT NULL,
+    payment_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    business_name TEXT,
+    requester TEXT NOT NULL,
+    payer TEXT,
+    token_address TEXT,
+    amount TEXT NOT NULL,
+    amount_paid TEXT NOT NULL,
+    tip TEXT NOT NULL,
+    refunded TEXT NOT NULL,
+    fee_percentage INTEGER,
+    status TEXT NOT NULL,
+    created_ledger INTEGER NOT NULL,
+    updated_ledger INTEGER NOT NULL,
+    PRIMARY KEY (contract, payment_id)
+);
+
+CREATE INDEX IF NOT EXISTS payments_by_business ON payments (contract, business_name);
+
+CREATE TABLE IF NOT EXISTS fees (
+    ledger INTEGER NOT NULL,
+    event_index INTEGER NOT NULL,
+    contract TEXT NOT NULL,
+    payment_id INTEGER NOT NULL,
+    fee_recipient TEXT NOT NULL,
+    token_address TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    PRIMARY KEY (ledger, event_index)
+);
+
+CREATE TABLE IF NOT EXISTS cursor (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    ledger INTEGER NOT NULL,
+    event_index INTEGER NOT NULL
+);
+";
+
+pub fn load_cursor(conn: &Connection) -> rusqlite::Result<Option<Cursor>> {
+    conn.query_row(
+        "SELECT ledger, event_index FROM cursor WHERE id = 0",
+        [],
+        |row| {
+            Ok(Cursor {
+                ledger: row.get(0)?,
+                index: row.get(1)?,
+            })
+        },
+    )
+    .optional()
+}
+
+pub fn save_cursor(conn: &Connection, cursor: Cursor) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO cursor (id, ledger, event_index) VALUES (0, :ledger, :index)
+         ON CONFLICT (id) DO UPDATE SET ledger = excluded.ledger, event_index = excluded.event_index",
+        named_params! { ":ledger": cursor.ledger, ":index": cursor.index },
+    )?;
+    Ok(())
+}
+
+/// Apply one decoded event emitted by `contract` at `cursor`.
+pub fn apply(
+    conn: &Connection,
+    contract: &str,
+    cursor: Cursor,
+    event: &PaymentEvent,
+) -> Result<(), IndexerError> {
+    let ledger = cursor.ledger;
+
+    match event {
+        PaymentEvent::BusinessRegistered {
+            name,
+            owner,
+            fee_recipient,
+            fee_percentage,
+        } => {
+            // Registering a name again replaces its configuration
+            conn.execute(
+                "INSERT INTO businesses (contract, name, owner, fee_recipient, fee_percentage, is_active, registered_ledger)
+                 VALUES (:contract, :name, :owner, :fee_recipient, :fee_percentage, 1, :ledger)
+                 ON CONFLICT (contract, name) DO UPDATE SET
+                     owner = excluded.owner,
+                     fee_recipient = excluded.fee_recipient,
+                     fee_percentage = excluded.fee_percentage,
+                     is_active = 1",
+                named_params! {
+                    ":contract": contract,
+                    ":name": name,
+                    ":owner": owner.to_string(),
+                    ":fee_recipient": fee_recipient.to_string(),
+                    ":fee_percentage": fee_percentage,
+                    ":ledger": ledger,
+                },
+            )?;
+        }
+        PaymentEvent::BusinessStatus { name, is_active } => {
+            conn.execute(
+                "UPDATE businesses SET is_active = :is_active WHERE contract = :contract AND name = :name",
+                named_params! { ":contract": contract, ":name": name, ":is_active": is_active },
+            )?;
+        }
+        PaymentEvent::RequestCreated {
+            payment_id,
+            business_name,
+            requester,
+            amount,
+            fee_percentage,
+        } => {
+            conn.execute(
+                "INSERT INTO payments (contract, payment_id, kind, business_name, requester, amount,
+                     amount_paid, tip, refunded, fee_percentage, status, created_ledger, updated_ledger)
+                 VALUES (:contract, :payment_id, 'request', :business_name, :requester, :amount,
+                     '0', '0', '0', :fee_percentage, 'Pending', :ledger, :ledger)",
+                named_params! {
+                    ":contract": contract,
+                    ":payment_id": payment_id,
+                    ":business_name": business_name,
+                    ":requester": requester.to_string(),
+                    ":amount": amount.to_string(),
+                    ":fee_percentage": fee_percentage,
+                    ":ledger": ledger,
+                },
+            )?;
+        }
+        PaymentEvent::RequestPaid {
+            payment_id,
+            payer,
+            token_address,
+            amount,
+            tip,
+            status,
+        } => {
+            let Some(totals) = load_totals(conn, contract, *payment_id)? else {
+                return Ok(());
+            };
+            // Open-amount and fiat-priced requests only learn their amount
+            // when paid, so a completed request is due what was paid
+            let amount_paid = totals.amount_paid.saturating_add(*amount);
+            let status = format!("{status:?}");
+            let due = if status == "Completed" {
+                amount_paid
+            } else {
+                totals.amount
+            };
+            conn.execute(
+                "UPDATE payments SET
+                     payer = COALESCE(payer, :payer),
+                     token_address = :token_address,
+                     amount = :due,
+                     amount_paid = :amount_paid,
+                     tip = :tip,
+                     status = :status,
+                     updated_ledger = :ledger
+                 WHERE contract = :contract AND payment_id = :payment_id",
+                named_params! {
+                    ":contract": contract,
+                    ":payment_id": payment_id,
+                    ":payer": payer.to_string(),
+                    ":token_address": token_address.to_string(),
+                    ":due": due.to_string(),
+                    ":amount_paid": amount_paid.to_string(),
+                    ":tip": totals.tip.saturating_add(*tip).to_string(),
+                    ":status": status,
+                    ":ledger": ledger,
+                },
+            )?;
+        }
+        PaymentEvent::FeeCharged {
+            payment_id,
+            fee_recipient,
+            token_address,
+            amount,
+        } => {
+            conn.execute(
+                "INSERT INTO fees (ledger, event_index, contract, payment_id, fee_recipient, token_address, amount)
+                 VALUES (:ledger, :index, :contract, :payment_id, :fee_recipient, :token_address, :amount)",
+                named_params! {
+                    ":ledger": ledger,
+                    ":index": cursor.index,
+                    ":contract": contract,
+                    ":payment_id": payment_id,
+                    ":fee_recipient": fee_recipient.to_string(),
+                    ":token_address": token_address.to_string(),
+                    ":amount": amount.to_string(),
+                },
+            )?;
+        }
+        PaymentEvent::RequestCancelled { payment_id } => {
+            conn.execute(
+                "UPDATE payments SET status = 'Cancelled', updated_ledger = :ledger
+                 WHERE contract = :contract AND payment_id = :payment_id",
+                named_params! { ":contract": contract, ":payment_id": payment_id, ":ledger": ledger },
+            )?;
+        }
+        PaymentEvent::Refunded {
+            payment_id, amount, ..
+        } => {
+            let Some(totals) = load_totals(conn, contract, *payment_id)? else {
+                return Ok(());
+            };
+            // Mirrors the contract: refunding the whole paid amount refunds the request
+            let refunded = totals.refunded.saturating_add(*amount);
+            conn.execute(
+                "UPDATE payments SET
+                     refunded = :refunded,
+                     status = CASE WHEN :fully_refunded THEN 'Refunded' ELSE status END,
+                     updated_ledger = :ledger
+                 WHERE contract = :contract AND payment_id = :payment_id",
+                named_params! {
+                    ":contract": contract,
+                    ":payment_id": payment_id,
+                    ":refunded": refunded.to_string(),
+                    ":fully_refunded": refunded == totals.amount_paid,
+                    ":ledger": ledger,
+                },
+            )?;
+        }
+        PaymentEvent::ProcessorPayment {
+            asset,
+            payment_id,
+            sender,
+            recipient,
+            amount,
+        } => {
+            let kind = match asset {
+                Asset::Xlm => "xlm",
+                Asset::Token => "token",
+            };
+            conn.execute(
+                "INSERT INTO payments (contract, payment_id, kind, requester, payer, amount,
+                     amount_paid, tip, refunded, status, created_ledger, updated_ledger)
+                 VALUES (:contract, :payment_id, :kind, :recipient, :sender, :amount,
+                     :amount, '0', '0', 'Completed', :ledger, :ledger)",
+                named_params! {
+                    ":contract": contract,
+                    ":payment_id": payment_id,
+                    ":kind": kind,
+                    ":recipient": recipient.to_string(),
+                    ":sender": sender.to_string(),
+                    ":amount": amount.to_string(),
+                    ":ledger": ledger,
+                },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Running amounts of an indexed payment
+struct Totals {
+    amount: i128,
+    amount_paid: i128,
+    tip: i128,
+    refunded: i128,
+}
+
+fn load_totals(
+    conn: &Connection,
+    contract: &str,
+    payment_id: u64,
+) -> rusqlite::Result<Option<Totals>> {
+    conn.query_row(
+        "SELECT amount, amount_paid, tip, refunded FROM payments
+         WHERE contract = :contract AND payment_id = :payment_id",
+        named_params! { ":contract": contract, ":payment_id": payment_id },
+        |row| {
+            Ok(Totals {
+                amount: amount_at(row, 0)?,
+                amount_paid: amount_at(row, 1)?,
+                tip: amount_at(row, 2)?,
+                refunded: amount_at(row, 3)?,
+            })
+        },
+    )
+    .optional()
+}
+
+fn amount_at(row: &Row, index: usize) -> rusqlite::Result<i128> {
+    let text: String = row.get(index)?;
+    text.parse().map_err(|error| {
+        rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(error))
+    })
+}
@@ -0,0 +1,79 @@
+//! Capturing events from contracts running in a testutils `Env`.
+
+use payments_client::types::Address as ClientAddress;
+use soroban_sdk::{
+    testutils::{Events, Ledger},
+    xdr::{self, ScVal},
+    Address, Env, TryFromVal,
+};
+
+use crate::{ContractEvent, Cursor};
+
+/// Collects the events published since the last capture. Each capture closes
+/// a ledger, so invocations captured one by one land in ledgers of their own.
+pub struct Recorder {
+    env: Env,
+    events: Vec<ContractEvent>,
+    // The test environment keeps every event published so far
+    seen: u32,
+}
+
+impl Recorder {
+    pub fn new(env: &Env) -> Recorder {
+        Recorder {
+            env: env.clone(),
+            events: Vec::new(),
+            seen: 0,
+        }
+    }
+
+    pub fn capture(&mut self) {
+        let env = &self.env;
+        let ledger = env.ledger().sequence();
+        let all = env.events().all();
+        for (index, (contract, topics, data)) in all.iter().skip(self.seen as usize).enumerate() {
+            let ScVal::Address(xdr::ScAddress::Contract(contract_id)) =
+                ScVal::try_from_val(env, &contract.to_val()).unwrap()
+            else {
+                panic!("events are emitted by contracts");
+            };
+            let topics: Vec<ScVal> = topics
+                .iter()
+                .map(|topic| ScVal::try_from_val(env, &topic).unwrap())
+                .collect();
+            let event = xdr::ContractEvent {
+                ext: xdr::ExtensionPoint::V0,
+                contract_id: Some(contract_id),
+                type_: xdr::ContractEventType::Contract,
+                body: xdr::ContractEventBody::V0(xdr::ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data: ScVal::try_from_val(env, &data).unwrap(),
+                }),
+            };
+            let cursor = Cursor {
+                ledger,
+                index: index as u32,
+            };
+            self.events
+                .push(ContractEvent::from_xdr(cursor, &event).unwrap());
+        }
+        self.seen = all.len();
+        env.ledger().with_mut(|info| info.sequence_number += 1);
+    }
+
+    pub fn events(&self) -> &[ContractEvent] {
+        &self.events
+    }
+
+    pub fn into_events(self) -> Vec<ContractEvent> {
+        self.events
+    }
+}
+
+/// The client-side form of an SDK address
+pub fn client_address(env: &Env, address: &Address) -> ClientAddress {
+    let ScVal::Address(address) = ScVal::try_from_val(env, &address.to_val()).unwrap() else {
+        panic!("addresses encode as ScVal::Address");
+    };
+    ClientAddress::from_sc_address(&address)
+}
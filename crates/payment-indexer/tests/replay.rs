@@ -0,0 +1,396 @@
+//! Replays events captured from the contracts running in a testutils `Env`.
+
+use payment_indexer::{
+    testutils::{client_address, Recorder},
+    Contracts, Indexer, IndexerError,
+};
+use payment_processor::{
+    PaymentContract as ProcessorContract, PaymentContractClient as ProcessorClient,
+};
+use payment_requests::{AmountBounds, PaymentContract, PaymentContractClient};
+use payments_client::types::Address as ClientAddress;
+use rusqlite::types::Value;
+use soroban_sdk::{
+    testutils::Address as _, token::StellarAssetClient, xdr::ScVal, Address, Env, String,
+};
+
+struct History {
+    contracts: Contracts,
+    events: Vec<payment_indexer::ContractEvent>,
+    business_owner: ClientAddress,
+    fee_recipient: ClientAddress,
+    payer: ClientAddress,
+    token: ClientAddress,
+}
+
+fn run_history() -> History {
+    let env = Env::default();
+    env.mock_all_auths();
+    let mut recorder = Recorder::new(&env);
+
+    let requests_id = env.register_contract(None, PaymentContract);
+    let requests = PaymentContractClient::new(&env, &requests_id);
+    let processor_id = env.register_contract(None, ProcessorContract);
+    let processor = ProcessorClient::new(&env, &processor_id);
+
+    let owner = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(owner.clone())
+        .address();
+    let payer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&payer, &100_000);
+    requests.initialize(&owner, &250u32, &Address::generate(&env));
+    recorder.capture();
+
+    let business_name = String::from_str(&env, "Test Store");
+    let business_owner = Address::generate(&env);
+    let fee_recipient = Address::generate(&env);
+    requests.register_business(&business_name, &business_owner, &fee_recipient, &100u32);
+    recorder.capture();
+
+    let requester = Address::generate(&env);
+    let text = String::from_str(&env, "Order");
+    let denomination = String::from_str(&env, "USDC");
+    let payers = soroban_sdk::vec![&env, payer.clone()];
+
+    // Paid in full, then partly refunded
+    let paid = requests.create_payment_request(
+        &1_000,
+        &business_name,
+        &text,
+        &denomination,
+        &payers,
+        &requester,
+        &None,
+    );
+    recorder.capture();
+    requests.execute_payment(&paid, &payer, &token);
+    recorder.capture();
+    requests.refund_payment(&paid, &payer, &400);
+    recorder.capture();
+
+    // Cancelled before anyone paid
+    let cancelled = requests.create_payment_request(
+        &500,
+        &business_name,
+        &text,
+        &denomination,
+        &payers,
+        &requester,
+        &Some(0u32),
+    );
+    recorder.capture();
+    requests.cancel_payment_request(&cancelled, &requester);
+    recorder.capture();
+
+    // The payer picks the amount
+    let open = requests.create_open_payment_request(
+        &business_name,
+        &text,
+        &denomination,
+        &payers,
+        &requester,
+        &None,
+        &AmountBounds {
+            min_amount: Some(100),
+            max_amount: None,
+        },
+    );
+    recorder.capture();
+    requests.execute_open_payment(&open, &payer, &token, &2_000);
+    recorder.capture();
+
+    requests.update_business_status(&business_name, &false, &business_owner);
+    recorder.capture();
+
+    // A direct payment through the processor
+    let merchant = Address::generate(&env);
+    processor.initialize(&owner, &soroban_sdk::vec![&env, merchant.clone()]);
+    processor.configure_business(&merchant, &100, &1, &1_000_000);
+    recorder.capture();
+    processor.process_xlm_payment(
+        &payer,
+        &merchant,
+        &750,
+        &business_name,
+        &String::from_str(&env, "Customer"),
+        &String::from_str(&env, "order-1"),
+    );
+    recorder.capture();
+    processor.process_token_payment(
+        &payer,
+        &merchant,
+        &token,
+        &1_000,
+        &business_name,
+        &String::from_str(&env, "Customer"),
+        &String::from_str(&env, "order-2"),
+    );
+    recorder.capture();
+
+    History {
+        contracts: Contracts {
+            requests: client_address(&env, &requests_id),
+            processor: client_address(&env, &processor_id),
+        },
+        events: recorder.into_events(),
+        business_owner: client_address(&env, &business_owner),
+        fee_recipient: client_address(&env, &fee_recipient),
+        payer: client_address(&env, &payer),
+        token: client_address(&env, &token),
+    }
+}
+
+fn rows(indexer: &Indexer, query: &str) -> Vec<Vec<Value>> {
+    let mut statement = indexer.connection().prepare(query).unwrap();
+    let columns = statement.column_count();
+    statement
+        .query_map([], |row| {
+            (0..columns).map(|i| row.get::<_, Value>(i)).collect()
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+fn dump(indexer: &Indexer) -> Vec<Vec<Vec<Value>>> {
+    ["businesses", "payments", "fees", "cursor"]
+        .iter()
+        .map(|table| rows(indexer, &format!("SELECT * FROM {table} ORDER BY 1, 2")))
+        .collect()
+}
+
+fn text(value: &str) -> Value {
+    Value::Text(value.to_string())
+}
+
+#[test]
+fn test_replay_materializes_tables() {
+    let history = run_history();
+    let mut indexer = Indexer::new(
+        rusqlite::Connection::open_in_memory().unwrap(),
+        history.contracts,
+    )
+    .unwrap();
+
+    // Registration, three requests, two payments and their fees, a refund, a
+    // cancellation, a status change and two processor payments, one with a fee
+    assert_eq!(indexer.apply(&history.events).unwrap(), 14);
+    assert_eq!(
+        indexer.cursor().unwrap(),
+        history.events.last().map(|event| event.cursor)
+    );
+
+    let owner = history.business_owner.to_string();
+    let requests = history.contracts.requests.to_string();
+    let processor = history.contracts.processor.to_string();
+    assert_eq!(
+        rows(
+            &indexer,
+            "SELECT contract, name, owner, fee_percentage, is_active FROM businesses"
+        ),
+        vec![vec![
+            text(&requests),
+            text("Test Store"),
+            text(&owner),
+            Value::Integer(100),
+            Value::Integer(0)
+        ]]
+    );
+
+    let payer = history.payer.to_string();
+    let fee_recipient = history.fee_recipient.to_string();
+    let token = history.token.to_string();
+    assert_eq!(
+        rows(
+            &indexer,
+            "SELECT contract, payment_id, kind, payer, token_address, amount, amount_paid, refunded, status
+             FROM payments ORDER BY kind, payment_id",
+        ),
+        vec![
+            vec![
+                text(&requests), Value::Integer(1), text("request"), text(&payer), text(&token),
+                text("1000"), text("1000"), text("400"), text("Completed"),
+            ],
+            vec![
+                text(&requests), Value::Integer(2), text("request"), Value::Null, Value::Null,
+                text("500"), text("0"), text("0"), text("Cancelled"),
+            ],
+            vec![
+                text(&requests), Value::Integer(3), text("request"), text(&payer), text(&token),
+                text("2000"), text("2000"), text("0"), text("Completed"),
+            ],
+            vec![
+                text(&processor), Value::Integer(2), text("token"), text(&payer), Value::Null,
+                text("1000"), text("1000"), text("0"), text("Completed"),
+            ],
+            vec![
+                text(&processor), Value::Integer(1), text("xlm"), text(&payer), Value::Null,
+                text("750"), text("750"), text("0"), text("Completed"),
+            ],
+        ]
+    );
+
+    // 1% of each token payment; the cancelled request paid nothing and the
+    // processor keeps its fee
+    assert_eq!(
+        rows(
+            &indexer,
+            "SELECT contract, payment_id, fee_recipient, token_address, amount FROM fees
+             ORDER BY ledger, event_index"
+        ),
+        vec![
+            vec![
+                text(&requests),
+                Value::Integer(1),
+                text(&fee_recipient),
+                text(&token),
+                text("10")
+            ],
+            vec![
+                text(&requests),
+                Value::Integer(3),
+                text(&fee_recipient),
+                text(&token),
+                text("20")
+            ],
+            vec![
+                text(&processor),
+                Value::Integer(2),
+                text(&processor),
+                text(&token),
+                text("10")
+            ],
+        ]
+    );
+}
+
+#[test]
+fn test_resumes_from_cursor() {
+    let history = run_history();
+    let path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+
+    let mut once = Indexer::new(
+        rusqlite::Connection::open_in_memory().unwrap(),
+        history.contracts,
+    )
+    .unwrap();
+    once.apply(&history.events).unwrap();
+
+    // Stop part way through, then restart and replay an overlapping range
+    let (first, _) = history.events.split_at(history.events.len() / 2);
+    let mut indexer = Indexer::open(&path, history.contracts).unwrap();
+    let applied = indexer.apply(first).unwrap();
+    assert_eq!(
+        indexer.cursor().unwrap(),
+        first.last().map(|event| event.cursor)
+    );
+    drop(indexer);
+
+    let mut indexer = Indexer::open(&path, history.contracts).unwrap();
+    let resumed = indexer.apply(&history.events).unwrap();
+    assert_eq!(applied + resumed, 14);
+    assert_eq!(dump(&indexer), dump(&once));
+
+    // Everything has been seen
+    assert_eq!(indexer.apply(&history.events).unwrap(), 0);
+    assert_eq!(dump(&indexer), dump(&once));
+}
+
+#[test]
+fn test_failed_batch_is_not_applied() {
+    let history = run_history();
+    let mut indexer = Indexer::new(
+        rusqlite::Connection::open_in_memory().unwrap(),
+        history.contracts,
+    )
+    .unwrap();
+
+    let (first, rest) = history.events.split_at(4);
+    indexer.apply(first).unwrap();
+    let before = dump(&indexer);
+
+    // A cancellation whose payload is not a payment ID
+    let mut batch = rest.to_vec();
+    let position = batch
+        .iter()
+        .position(|event| event.contract == history.contracts.requests)
+        .unwrap();
+    batch[position].topics = vec![
+        ScVal::Symbol("payment".try_into().unwrap()),
+        ScVal::Symbol("cancel".try_into().unwrap()),
+    ];
+    batch[position].data = ScVal::Bool(true);
+
+    let result = indexer.apply(&batch);
+    assert!(matches!(
+        result,
+        Err(IndexerError::Decode { cursor, .. }) if cursor == batch[position].cursor
+    ));
+    assert_eq!(dump(&indexer), before);
+}
+
+#[test]
+fn test_amounts_beyond_sqlite_integers_are_indexed() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let mut recorder = Recorder::new(&env);
+
+    let requests_id = env.register_contract(None, PaymentContract);
+    let requests = PaymentContractClient::new(&env, &requests_id);
+    let owner = Address::generate(&env);
+    requests.initialize(&owner, &250u32, &Address::generate(&env));
+    let business_name = String::from_str(&env, "Test Store");
+    requests.register_business(
+        &business_name,
+        &Address::generate(&env),
+        &Address::generate(&env),
+        &100u32,
+    );
+
+    let requester = Address::generate(&env);
+    let order = String::from_str(&env, "Order");
+    let payers = soroban_sdk::vec![&env, Address::generate(&env)];
+    let create = |amount: i128| {
+        requests.create_payment_request(
+            &amount,
+            &business_name,
+            &order,
+            &order,
+            &payers,
+            &requester,
+            &None,
+        )
+    };
+    create(i128::MAX);
+    recorder.capture();
+    create(1_000);
+    recorder.capture();
+
+    let events = recorder.into_events();
+    let mut indexer = Indexer::new(
+        rusqlite::Connection::open_in_memory().unwrap(),
+        Contracts {
+            requests: client_address(&env, &requests_id),
+            processor: client_address(&env, &Address::generate(&env)),
+        },
+    )
+    .unwrap();
+
+    // Registration and both requests
+    assert_eq!(indexer.apply(&events).unwrap(), 3);
+    assert_eq!(
+        indexer.cursor().unwrap(),
+        events.last().map(|event| event.cursor)
+    );
+    assert_eq!(
+        rows(
+            &indexer,
+            "SELECT payment_id, amount FROM payments ORDER BY payment_id"
+        ),
+        vec![
+            vec![Value::Integer(1), text(&i128::MAX.to_string())],
+            vec![Value::Integer(2), text("1000")],
+        ]
+    );
+}
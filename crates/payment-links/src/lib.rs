@@ -0,0 +1,188 @@
+//! SEP-7 payment URIs and QR codes for payment requests.
+//!
+//! [`PaymentLink::uri`] turns a `PaymentRequest` into a `web+stellar:tx` URI
+//! whose transaction calls the contract's `execute_payment` with the
+//! request's ID and the token to pay in. Without a known payer, the source
+//! account and the `payer` argument are left for the wallet to fill in
+//! through SEP-7 `replace`. The same URI can be rendered as an SVG or PNG QR
+//! code with [`qr`]. [`Sep7Uri`] also parses `tx` and `pay` URIs built
+//! elsewhere.
+//!
+//! ```
+//! # use payment_links::{qr, PaymentLink};
+//! # use payments_client::types::{Address, PaymentRequest, PaymentStatus, RequestKind};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let request = PaymentRequest {
+//! #     id: 1,
+//! #     amount: 1_000_000_000,
+//! #     business_name: "Test Store".to_string(),
+//! #     description: "Order #1".to_string(),
+//! #     denomination: "USDC".to_string(),
+//! #     authorized_addresses: Vec::new(),
+//! #     requester: Address::Account([1; 32]),
+//! #     timestamp: 0,
+//! #     status: PaymentStatus::Pending,
+//! #     fee_percentage: 250,
+//! #     amount_paid: 0,
+//! #     kind: RequestKind::Fixed,
+//! #     tip_amount: 0,
+//! #     splits: Vec::new(),
+//! # };
+//! let link = PaymentLink {
+//!     contract: "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse()?,
+//!     token: "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC".parse()?,
+//!     payer: None,
+//!     network_passphrase: "Test SDF Network ; September 2015".to_string(),
+//!     callback: None,
+//! };
+//! let uri = link.uri(&request)?.to_string();
+//! let svg = qr::svg(&uri, 4)?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod qr;
+mod uri;
+
+use std::fmt;
+
+use payments_client::{
+    transaction,
+    types::{Address, PaymentRequest, PaymentStatus, RequestKind},
+    ScType,
+};
+use stellar_xdr::curr::{HostFunction, InvokeContractArgs, Limits, ScSymbol, WriteXdr};
+
+pub use uri::{
+    Common, PayRequest, Replacement, Sep7Uri, TxRequest, UriError, MAX_MESSAGE_LENGTH, SCHEME,
+};
+
+/// Source account of links without a payer, replaced by the wallet
+pub const PLACEHOLDER_ACCOUNT: Address = Address::Account([0; 32]);
+
+/// Fields of the `execute_payment` transaction a wallet fills in with the
+/// paying account, as SEP-11 txrep paths
+pub const PAYER_PATHS: [&str; 2] = [
+    "sourceAccount",
+    "operations[0].body.invokeHostFunctionOp.hostFunction.invokeContract.args[1].address",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkError {
+    /// Only pending and partially paid requests can be paid
+    NotPayable(PaymentStatus),
+    /// Open amount requests are paid with `execute_open_payment`, which
+    /// needs an amount the link cannot know
+    OpenAmount,
+    /// The payer is not one of the request's authorized addresses
+    NotAuthorized(Address),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::NotPayable(status) => write!(f, "request is {status:?}"),
+            LinkError::OpenAmount => f.write_str("open amount requests need an amount"),
+            LinkError::NotAuthorized(payer) => write!(f, "{payer} may not pay this request"),
+        }
+    }
+}
+
+impl std::error::Error for LinkError {}
+
+/// Where and how a payment request is paid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentLink {
+    /// Payment request contract
+    pub contract: Address,
+    pub token: Address,
+    /// Account to pre-fill as the payer; the wallet asks for one otherwise
+    pub payer: Option<Address>,
+    pub network_passphrase: String,
+    /// `url:` prefixed address to post the signed transaction to
+    pub callback: Option<String>,
+}
+
+impl PaymentLink {
+    /// `web+stellar:tx` URI paying `request` in full. The transaction has
+    /// sequence number 0 and no resources attached, so the wallet sets the
+    /// sequence and simulates it before signing.
+    pub fn uri(&self, request: &PaymentRequest) -> Result<Sep7Uri, LinkError> {
+        if !matches!(
+            request.status,
+            PaymentStatus::Pending | PaymentStatus::PartiallyPaid
+        ) {
+            return Err(LinkError::NotPayable(request.status.clone()));
+        }
+        if matches!(request.kind, RequestKind::OpenAmount(_)) {
+            return Err(LinkError::OpenAmount);
+        }
+        if let Some(payer) = &self.payer {
+            if !request.authorized_addresses.contains(payer) {
+                return Err(LinkError::NotAuthorized(*payer));
+            }
+        }
+
+        // Contract accounts cannot source transactions, so the wallet picks
+        // the account that submits a contract's payment
+        let payer = self.payer.unwrap_or(PLACEHOLDER_ACCOUNT);
+        let (source, replaced, hint) = match self.payer {
+            Some(Address::Account(key)) => (key, &PAYER_PATHS[..0], ""),
+            Some(Address::Contract(_)) => {
+                ([0; 32], &PAYER_PATHS[..1], "account submitting the payment")
+            }
+            None => ([0; 32], &PAYER_PATHS[..], "account paying the request"),
+        };
+        let function = HostFunction::InvokeContract(InvokeContractArgs {
+            contract_address: self.contract.to_sc_address(),
+            function_name: ScSymbol(
+                "execute_payment"
+                    .try_into()
+                    .expect("function names are symbols"),
+            ),
+            args: vec![
+                request.id.to_scval(),
+                payer.to_scval(),
+                self.token.to_scval(),
+            ]
+            .try_into()
+            .expect("three arguments fit"),
+        });
+        let envelope = transaction::unsigned(transaction::invoke_transaction(&source, 0, function));
+        let replace = replaced
+            .iter()
+            .map(|path| Replacement {
+                path: path.to_string(),
+                id: "X".to_string(),
+                hint: hint.to_string(),
+            })
+            .collect();
+
+        Ok(Sep7Uri::Tx(TxRequest {
+            xdr: envelope
+                .to_xdr_base64(Limits::none())
+                .expect("envelope encodes"),
+            replace,
+            pubkey: match self.payer {
+                Some(payer @ Address::Account(_)) => Some(payer.to_string()),
+                _ => None,
+            },
+            common: Common {
+                callback: self.callback.clone(),
+                msg: Some(message(request)),
+                network_passphrase: Some(self.network_passphrase.clone()),
+                origin_domain: None,
+                signature: None,
+            },
+        }))
+    }
+}
+
+fn message(request: &PaymentRequest) -> String {
+    let message = if request.description.is_empty() {
+        request.business_name.clone()
+    } else {
+        format!("{}: {}", request.business_name, request.description)
+    };
+    message.chars().take(MAX_MESSAGE_LENGTH).collect()
+}
@@ -0,0 +1,55 @@
+//! QR codes for payment URIs.
+
+use qrcode::{render::svg, Color, EcLevel, QrCode};
+
+pub use qrcode::types::QrError;
+
+/// Blank modules around the code, as the QR specification asks for
+const QUIET_ZONE: usize = 4;
+
+// Medium error correction keeps transaction URIs within a scannable size
+fn encode(data: &str) -> Result<QrCode, QrError> {
+    QrCode::with_error_correction_level(data, EcLevel::M)
+}
+
+/// SVG document with `module_size` pixels per module
+pub fn svg(data: &str, module_size: u32) -> Result<String, QrError> {
+    Ok(encode(data)?
+        .render::<svg::Color<'_>>()
+        .module_dimensions(module_size, module_size)
+        .quiet_zone(true)
+        .build())
+}
+
+/// 8-bit grayscale PNG with `module_size` pixels per module
+pub fn png(data: &str, module_size: u32) -> Result<Vec<u8>, QrError> {
+    let code = encode(data)?;
+    let colors = code.to_colors();
+    let modules = code.width() + 2 * QUIET_ZONE;
+    let scale = module_size.max(1) as usize;
+    let side = modules * scale;
+
+    let mut pixels = vec![u8::MAX; side * side];
+    for (index, color) in colors.iter().enumerate() {
+        if *color == Color::Light {
+            continue;
+        }
+        let (x, y) = (
+            index % code.width() + QUIET_ZONE,
+            index / code.width() + QUIET_ZONE,
+        );
+        for row in y * scale..(y + 1) * scale {
+            pixels[row * side + x * scale..row * side + (x + 1) * scale].fill(0);
+        }
+    }
+
+    let side = u32::try_from(side).expect("image side fits in u32");
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, side, side);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().expect("writing to memory");
+    writer.write_image_data(&pixels).expect("writing to memory");
+    writer.finish().expect("writing to memory");
+    Ok(bytes)
+}
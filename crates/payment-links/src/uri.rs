@@ -0,0 +1,292 @@
+//! SEP-7 `web+stellar:` URIs.
+
+use std::{fmt, str::FromStr};
+
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use stellar_xdr::curr::{Limits, ReadXdr, TransactionEnvelope};
+
+pub const SCHEME: &str = "web+stellar:";
+
+/// Longest `msg` a wallet has to accept
+pub const MAX_MESSAGE_LENGTH: usize = 300;
+
+// Everything but RFC 3986 unreserved characters is escaped, so base64
+// `+`, `/` and `=` survive query parsing
+const QUERY_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UriError {
+    Scheme,
+    /// Operation other than `tx` or `pay`
+    Operation(String),
+    MissingParameter(&'static str),
+    DuplicateParameter(String),
+    /// Parameter value that is not valid percent-encoded UTF-8
+    Encoding(String),
+    InvalidReplace(String),
+    MessageTooLong(usize),
+    Xdr(String),
+}
+
+impl fmt::Display for UriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UriError::Scheme => write!(f, "URI does not start with {SCHEME}"),
+            UriError::Operation(operation) => write!(f, "unsupported operation {operation:?}"),
+            UriError::MissingParameter(name) => write!(f, "missing parameter {name}"),
+            UriError::DuplicateParameter(name) => write!(f, "parameter {name} given twice"),
+            UriError::Encoding(name) => write!(f, "parameter {name} is not valid UTF-8"),
+            UriError::InvalidReplace(replace) => write!(f, "invalid replace value {replace:?}"),
+            UriError::MessageTooLong(length) => write!(
+                f,
+                "msg is {length} characters, at most {MAX_MESSAGE_LENGTH} are allowed"
+            ),
+            UriError::Xdr(message) => write!(f, "invalid transaction envelope: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for UriError {}
+
+/// A transaction field the wallet fills in before signing, named by its
+/// SEP-11 txrep path (e.g. `sourceAccount`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replacement {
+    pub path: String,
+    /// Reference shared by fields that take the same value
+    pub id: String,
+    /// What the wallet should ask the user for
+    pub hint: String,
+}
+
+/// Parameters every operation accepts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Common {
+    /// `url:` prefixed address to post the signed transaction to instead of
+    /// submitting it
+    pub callback: Option<String>,
+    /// Message shown to the user, at most [`MAX_MESSAGE_LENGTH`] characters
+    pub msg: Option<String>,
+    /// Network to sign for; wallets assume the public network without it
+    pub network_passphrase: Option<String>,
+    pub origin_domain: Option<String>,
+    pub signature: Option<String>,
+}
+
+/// `web+stellar:tx`: sign a prepared transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxRequest {
+    /// Base64 `TransactionEnvelope`
+    pub xdr: String,
+    pub replace: Vec<Replacement>,
+    /// Account the transaction should be signed by
+    pub pubkey: Option<String>,
+    pub common: Common,
+}
+
+impl TxRequest {
+    pub fn envelope(&self) -> Result<TransactionEnvelope, UriError> {
+        TransactionEnvelope::from_xdr_base64(&self.xdr, Limits::none())
+            .map_err(|error| UriError::Xdr(error.to_string()))
+    }
+}
+
+/// `web+stellar:pay`: pay `destination` with a transaction the wallet builds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PayRequest {
+    pub destination: String,
+    /// Decimal amount; the wallet asks for one when absent
+    pub amount: Option<String>,
+    /// Asset code, or native lumens when absent
+    pub asset_code: Option<String>,
+    pub asset_issuer: Option<String>,
+    pub memo: Option<String>,
+    /// `MEMO_TEXT`, `MEMO_ID`, `MEMO_HASH` or `MEMO_RETURN`
+    pub memo_type: Option<String>,
+    pub common: Common,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sep7Uri {
+    Tx(TxRequest),
+    Pay(PayRequest),
+}
+
+impl Sep7Uri {
+    pub fn common(&self) -> &Common {
+        match self {
+            Sep7Uri::Tx(request) => &request.common,
+            Sep7Uri::Pay(request) => &request.common,
+        }
+    }
+}
+
+impl fmt::Display for Sep7Uri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (operation, mut params) = match self {
+            Sep7Uri::Tx(request) => {
+                let mut params = vec![("xdr", Some(request.xdr.clone()))];
+                if !request.replace.is_empty() {
+                    params.push(("replace", Some(format_replace(&request.replace))));
+                }
+                params.push(("pubkey", request.pubkey.clone()));
+                ("tx", params)
+            }
+            Sep7Uri::Pay(request) => (
+                "pay",
+                vec![
+                    ("destination", Some(request.destination.clone())),
+                    ("amount", request.amount.clone()),
+                    ("asset_code", request.asset_code.clone()),
+                    ("asset_issuer", request.asset_issuer.clone()),
+                    ("memo", request.memo.clone()),
+                    ("memo_type", request.memo_type.clone()),
+                ],
+            ),
+        };
+        let common = self.common();
+        params.extend([
+            ("callback", common.callback.clone()),
+            ("msg", common.msg.clone()),
+            ("network_passphrase", common.network_passphrase.clone()),
+            ("origin_domain", common.origin_domain.clone()),
+            // The signature covers everything before it
+            ("signature", common.signature.clone()),
+        ]);
+
+        write!(f, "{SCHEME}{operation}")?;
+        let mut separator = '?';
+        for (name, value) in params {
+            if let Some(value) = value {
+                write!(
+                    f,
+                    "{separator}{name}={}",
+                    utf8_percent_encode(&value, QUERY_VALUE)
+                )?;
+                separator = '&';
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Sep7Uri {
+    type Err = UriError;
+
+    fn from_str(uri: &str) -> Result<Sep7Uri, UriError> {
+        let rest = uri.strip_prefix(SCHEME).ok_or(UriError::Scheme)?;
+        let (operation, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let mut params = Params::parse(query)?;
+
+        let common = Common {
+            callback: params.take("callback"),
+            msg: params.take("msg"),
+            network_passphrase: params.take("network_passphrase"),
+            origin_domain: params.take("origin_domain"),
+            signature: params.take("signature"),
+        };
+        if let Some(msg) = &common.msg {
+            let length = msg.chars().count();
+            if length > MAX_MESSAGE_LENGTH {
+                return Err(UriError::MessageTooLong(length));
+            }
+        }
+
+        match operation {
+            "tx" => Ok(Sep7Uri::Tx(TxRequest {
+                xdr: params.require("xdr")?,
+                replace: params
+                    .take("replace")
+                    .map(|replace| parse_replace(&replace))
+                    .transpose()?
+                    .unwrap_or_default(),
+                pubkey: params.take("pubkey"),
+                common,
+            })),
+            "pay" => Ok(Sep7Uri::Pay(PayRequest {
+                destination: params.require("destination")?,
+                amount: params.take("amount"),
+                asset_code: params.take("asset_code"),
+                asset_issuer: params.take("asset_issuer"),
+                memo: params.take("memo"),
+                memo_type: params.take("memo_type"),
+                common,
+            })),
+            other => Err(UriError::Operation(other.to_string())),
+        }
+    }
+}
+
+// Decoded query parameters; ones a wallet does not know are ignored
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    fn parse(query: &str) -> Result<Params, UriError> {
+        let mut params: Vec<(String, String)> = Vec::new();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            if params.iter().any(|(seen, _)| seen == name) {
+                return Err(UriError::DuplicateParameter(name.to_string()));
+            }
+            let value = percent_decode_str(value)
+                .decode_utf8()
+                .map_err(|_| UriError::Encoding(name.to_string()))?;
+            params.push((name.to_string(), value.into_owned()));
+        }
+        Ok(Params(params))
+    }
+
+    fn take(&mut self, name: &str) -> Option<String> {
+        let index = self.0.iter().position(|(seen, _)| seen == name)?;
+        Some(self.0.remove(index).1)
+    }
+
+    fn require(&mut self, name: &'static str) -> Result<String, UriError> {
+        self.take(name).ok_or(UriError::MissingParameter(name))
+    }
+}
+
+// `path:id,path:id;id:hint,id:hint`
+fn format_replace(replace: &[Replacement]) -> String {
+    let fields: Vec<String> = replace
+        .iter()
+        .map(|field| format!("{}:{}", field.path, field.id))
+        .collect();
+    let mut hints: Vec<String> = Vec::new();
+    for field in replace {
+        let hint = format!("{}:{}", field.id, field.hint);
+        if !hints.contains(&hint) {
+            hints.push(hint);
+        }
+    }
+    format!("{};{}", fields.join(","), hints.join(","))
+}
+
+fn parse_replace(replace: &str) -> Result<Vec<Replacement>, UriError> {
+    let invalid = || UriError::InvalidReplace(replace.to_string());
+    let (fields, hints) = replace.split_once(';').ok_or_else(invalid)?;
+    let hints = hints
+        .split(',')
+        .map(|hint| hint.split_once(':').ok_or_else(invalid))
+        .collect::<Result<Vec<_>, _>>()?;
+    fields
+        .split(',')
+        .map(|field| {
+            let (path, id) = field.split_once(':').ok_or_else(invalid)?;
+            let hint = hints
+                .iter()
+                .find(|(hint_id, _)| *hint_id == id)
+                .ok_or_else(invalid)?
+                .1;
+            Ok(Replacement {
+                path: path.to_string(),
+                id: id.to_string(),
+                hint: hint.to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,286 @@
+//! Builds payment links for requests on the contract, parses them back and
+//! submits the transaction they carry.
+
+use payment_links::{
+    qr, LinkError, PayRequest, PaymentLink, Sep7Uri, UriError, PAYER_PATHS, PLACEHOLDER_ACCOUNT,
+};
+use payment_requests::{PaymentContract, PaymentContractClient};
+use payments_client::{
+    types::{Address as ClientAddress, PaymentRequest, PaymentStatus, RequestKind},
+    ScType,
+};
+use soroban_sdk::{
+    testutils::Address as _, token::StellarAssetClient, xdr::ScVal, Address, Env, IntoVal, String,
+    Symbol, TryFromVal, Val,
+};
+use stellar_xdr::curr::{HostFunction, OperationBody, TransactionEnvelope};
+
+const TESTNET: &str = "Test SDF Network ; September 2015";
+
+struct Setup {
+    env: Env,
+    contract_id: Address,
+    token: Address,
+    payer: Address,
+    payment_id: u64,
+}
+
+impl Setup {
+    fn new() -> Setup {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract_id);
+        let owner = Address::generate(&env);
+        let token = env
+            .register_stellar_asset_contract_v2(owner.clone())
+            .address();
+        let payer = Address::generate(&env);
+        StellarAssetClient::new(&env, &token).mint(&payer, &1_000);
+        client.initialize(&owner, &250u32, &Address::generate(&env));
+
+        let store = String::from_str(&env, "Test Store");
+        client.register_business(&store, &owner, &owner, &250u32);
+        let payment_id = client.create_payment_request(
+            &1_000,
+            &store,
+            &String::from_str(&env, "Order #1 & 2"),
+            &String::from_str(&env, "USDC"),
+            &soroban_sdk::vec![&env, payer.clone()],
+            &owner,
+            &None,
+        );
+
+        Setup {
+            env,
+            contract_id,
+            token,
+            payer,
+            payment_id,
+        }
+    }
+
+    fn client(&self) -> PaymentContractClient<'_> {
+        PaymentContractClient::new(&self.env, &self.contract_id)
+    }
+
+    fn address(&self, address: &Address) -> ClientAddress {
+        ClientAddress::from_scval(&ScVal::try_from_val(&self.env, &address.to_val()).unwrap())
+            .unwrap()
+    }
+
+    fn request(&self) -> PaymentRequest {
+        let request = self.client().get_payment_request(&self.payment_id);
+        let value: Val = request.into_val(&self.env);
+        PaymentRequest::from_scval(&ScVal::try_from_val(&self.env, &value).unwrap()).unwrap()
+    }
+
+    fn link(&self, payer: Option<&Address>) -> PaymentLink {
+        PaymentLink {
+            contract: self.address(&self.contract_id),
+            token: self.address(&self.token),
+            payer: payer.map(|payer| self.address(payer)),
+            network_passphrase: TESTNET.to_string(),
+            callback: None,
+        }
+    }
+}
+
+fn tx(uri: &Sep7Uri) -> &payment_links::TxRequest {
+    match uri {
+        Sep7Uri::Tx(request) => request,
+        other => panic!("expected a tx URI, got {other:?}"),
+    }
+}
+
+fn invocation(envelope: &TransactionEnvelope) -> &stellar_xdr::curr::InvokeContractArgs {
+    let TransactionEnvelope::Tx(envelope) = envelope else {
+        panic!("expected a v1 envelope");
+    };
+    let OperationBody::InvokeHostFunction(operation) = &envelope.tx.operations[0].body else {
+        panic!("expected an InvokeHostFunction operation");
+    };
+    let HostFunction::InvokeContract(args) = &operation.host_function else {
+        panic!("expected a contract invocation");
+    };
+    args
+}
+
+#[test]
+fn test_tx_uri_round_trips() {
+    let setup = Setup::new();
+    let uri = setup.link(None).uri(&setup.request()).unwrap();
+    let text = uri.to_string();
+
+    assert!(text.starts_with("web+stellar:tx?xdr="));
+    assert!(text.contains("&msg=Test%20Store%3A%20Order%20%231%20%26%202&"));
+    assert!(text.ends_with("&network_passphrase=Test%20SDF%20Network%20%3B%20September%202015"));
+    assert_eq!(text.parse::<Sep7Uri>().unwrap(), uri);
+
+    let request = tx(&uri);
+    let paths: Vec<&str> = request
+        .replace
+        .iter()
+        .map(|field| field.path.as_str())
+        .collect();
+    assert_eq!(paths, PAYER_PATHS);
+    assert_eq!(request.pubkey, None);
+
+    let envelope = request.envelope().unwrap();
+    let args = invocation(&envelope);
+    assert_eq!(args.function_name.0.to_string(), "execute_payment");
+    assert_eq!(
+        args.contract_address,
+        setup.address(&setup.contract_id).to_sc_address()
+    );
+    assert_eq!(args.args[0], setup.payment_id.to_scval());
+    assert_eq!(args.args[1], PLACEHOLDER_ACCOUNT.to_scval());
+    assert_eq!(args.args[2], setup.address(&setup.token).to_scval());
+}
+
+#[test]
+fn test_link_invokes_execute_payment() {
+    let setup = Setup::new();
+    let uri = setup
+        .link(Some(&setup.payer))
+        .uri(&setup.request())
+        .unwrap();
+    let uri: Sep7Uri = uri.to_string().parse().unwrap();
+
+    // Generated addresses are contracts, so only the source is left open
+    let request = tx(&uri);
+    let paths: Vec<&str> = request
+        .replace
+        .iter()
+        .map(|field| field.path.as_str())
+        .collect();
+    assert_eq!(paths, PAYER_PATHS[..1]);
+
+    let envelope = request.envelope().unwrap();
+    let args = invocation(&envelope);
+    let env = &setup.env;
+    let mut call = soroban_sdk::Vec::<Val>::new(env);
+    for arg in args.args.iter() {
+        call.push_back(Val::try_from_val(env, arg).unwrap());
+    }
+    env.invoke_contract::<()>(
+        &setup.contract_id,
+        &Symbol::new(env, &args.function_name.0.to_string()),
+        call,
+    );
+
+    assert_eq!(setup.request().status, PaymentStatus::Completed);
+    assert_eq!(
+        setup.link(Some(&setup.payer)).uri(&setup.request()),
+        Err(LinkError::NotPayable(PaymentStatus::Completed))
+    );
+}
+
+#[test]
+fn test_link_requires_payable_request() {
+    let setup = Setup::new();
+    let mut request = setup.request();
+
+    let stranger = Address::generate(&setup.env);
+    assert_eq!(
+        setup.link(Some(&stranger)).uri(&request),
+        Err(LinkError::NotAuthorized(setup.address(&stranger)))
+    );
+
+    request.status = PaymentStatus::Cancelled;
+    assert_eq!(
+        setup.link(None).uri(&request),
+        Err(LinkError::NotPayable(PaymentStatus::Cancelled))
+    );
+
+    request.status = PaymentStatus::Pending;
+    request.kind = RequestKind::OpenAmount(payments_client::types::AmountBounds {
+        min_amount: None,
+        max_amount: None,
+    });
+    assert_eq!(setup.link(None).uri(&request), Err(LinkError::OpenAmount));
+}
+
+#[test]
+fn test_pay_uri_round_trips() {
+    // Example from SEP-7
+    let text = "web+stellar:pay?destination=GCALNQQBXAPZ2WIRSDDBMSTAKCUH5SG6U76YBFLQLIXJTF7FE5AX7AOO&amount=120.1234567&memo=skdjfasf&memo_type=MEMO_TEXT&msg=pay%20me%20with%20lumens";
+    let uri: Sep7Uri = text.parse().unwrap();
+    let Sep7Uri::Pay(PayRequest {
+        destination,
+        amount,
+        asset_code,
+        memo,
+        memo_type,
+        common,
+        ..
+    }) = &uri
+    else {
+        panic!("expected a pay URI");
+    };
+    assert_eq!(
+        destination,
+        "GCALNQQBXAPZ2WIRSDDBMSTAKCUH5SG6U76YBFLQLIXJTF7FE5AX7AOO"
+    );
+    assert_eq!(amount.as_deref(), Some("120.1234567"));
+    assert_eq!(asset_code, &None);
+    assert_eq!(memo.as_deref(), Some("skdjfasf"));
+    assert_eq!(memo_type.as_deref(), Some("MEMO_TEXT"));
+    assert_eq!(common.msg.as_deref(), Some("pay me with lumens"));
+    assert_eq!(uri.to_string(), text);
+}
+
+#[test]
+fn test_rejects_malformed_uris() {
+    let cases = [
+        ("https://example.com/pay", UriError::Scheme),
+        (
+            "web+stellar:sign?xdr=AAAA",
+            UriError::Operation("sign".to_string()),
+        ),
+        ("web+stellar:tx?msg=hi", UriError::MissingParameter("xdr")),
+        (
+            "web+stellar:pay?destination=G1&destination=G2",
+            UriError::DuplicateParameter("destination".to_string()),
+        ),
+        (
+            "web+stellar:pay?destination=%FF",
+            UriError::Encoding("destination".to_string()),
+        ),
+        (
+            "web+stellar:tx?xdr=AAAA&replace=sourceAccount",
+            UriError::InvalidReplace("sourceAccount".to_string()),
+        ),
+    ];
+    for (text, error) in cases {
+        assert_eq!(text.parse::<Sep7Uri>(), Err(error), "{text}");
+    }
+
+    let long = format!("web+stellar:pay?destination=G1&msg={}", "a".repeat(301));
+    assert_eq!(long.parse::<Sep7Uri>(), Err(UriError::MessageTooLong(301)));
+}
+
+#[test]
+fn test_qr_codes_render() {
+    let setup = Setup::new();
+    let uri = setup.link(None).uri(&setup.request()).unwrap().to_string();
+
+    let svg = qr::svg(&uri, 4).unwrap();
+    assert!(svg.starts_with("<?xml"));
+    assert!(svg.contains("<svg"));
+
+    let png = qr::png(&uri, 3).unwrap();
+    let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(info.width, info.height);
+    assert_eq!(info.width % 3, 0);
+    let modules = (info.width / 3) as usize;
+    assert!(modules > 2 * 4 + 21, "{modules} modules");
+
+    // Quiet zone, then the top-left finder pattern
+    let side = info.width as usize;
+    assert_eq!(pixels[0], u8::MAX);
+    assert_eq!(pixels[4 * 3 * side + 4 * 3], 0);
+}
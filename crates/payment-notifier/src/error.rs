@@ -0,0 +1,39 @@
+use std::fmt;
+
+use payment_indexer::IndexerError;
+
+#[derive(Debug)]
+pub enum NotifierError {
+    Indexer(IndexerError),
+    Sqlite(rusqlite::Error),
+}
+
+impl fmt::Display for NotifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NotifierError::Indexer(error) => write!(f, "indexing failed: {error}"),
+            NotifierError::Sqlite(error) => write!(f, "database error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for NotifierError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NotifierError::Indexer(error) => Some(error),
+            NotifierError::Sqlite(error) => Some(error),
+        }
+    }
+}
+
+impl From<IndexerError> for NotifierError {
+    fn from(error: IndexerError) -> NotifierError {
+        NotifierError::Indexer(error)
+    }
+}
+
+impl From<rusqlite::Error> for NotifierError {
+    fn from(error: rusqlite::Error) -> NotifierError {
+        NotifierError::Sqlite(error)
+    }
+}
@@ -0,0 +1,274 @@
+//! Merchant webhooks for payment requests that become `Completed`,
+//! `Cancelled` or `Refunded`.
+//!
+//! The notifier feeds the contract event stream through a
+//! [`payment_indexer::Indexer`] and, in the same transaction, queues a
+//! delivery for every payment request whose business has a webhook. Each
+//! delivery is a JSON `POST` signed with the business's secret (see
+//! [`signature`]) and is retried with exponential backoff until the merchant
+//! answers with a 2xx status or the attempts run out. Deliveries and every
+//! attempt are kept in the database alongside the indexed tables. Amounts in
+//! the payload are decimal strings, as the indexer stores them.
+//!
+//! Times are UNIX seconds supplied by the caller, matching ledger timestamps.
+//!
+//! ```no_run
+//! # use payment_indexer::{Contracts, Indexer};
+//! # use payment_notifier::{Notifier, RetryPolicy};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let (requests, processor) = (
+//! #     "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse()?,
+//! #     "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse()?,
+//! # );
+//! # let (events, now) = (Vec::new(), 1_709_251_200);
+//! let indexer = Indexer::open("payments.db", Contracts { requests, processor })?;
+//! let mut notifier = Notifier::new(indexer, RetryPolicy::default())?;
+//! notifier.set_webhook("Test Store", "https://merchant.example/hooks", b"secret")?;
+//!
+//! notifier.ingest(&events, now)?;
+//! notifier.deliver_due(now)?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod error;
+mod outbox;
+mod signature;
+
+use std::time::Duration;
+
+use payment_indexer::{ContractEvent, Indexer};
+use rusqlite::named_params;
+
+pub use error::NotifierError;
+pub use outbox::SCHEMA;
+pub use signature::{signature, DELIVERY_HEADER, SIGNATURE_HEADER};
+
+/// How failed deliveries are retried
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Seconds before the first retry; each further retry waits twice as long
+    pub initial_delay: u64,
+    /// Upper bound on the wait between attempts, in seconds
+    pub max_delay: u64,
+    /// Attempts made before a delivery is given up on
+    pub max_attempts: u32,
+    /// How long to wait for the merchant's response
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            initial_delay: 30,
+            max_delay: 6 * 60 * 60,
+            max_attempts: 10,
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait after the given failed attempt, counting from 1
+    pub fn delay(&self, attempt: u32) -> u64 {
+        let factor = 1u64
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u64::MAX);
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeliveryState {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl DeliveryState {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryState::Pending => "pending",
+            DeliveryState::Delivered => "delivered",
+            DeliveryState::Failed => "failed",
+        }
+    }
+
+    fn parse(state: &str) -> DeliveryState {
+        match state {
+            "delivered" => DeliveryState::Delivered,
+            "failed" => DeliveryState::Failed,
+            _ => DeliveryState::Pending,
+        }
+    }
+}
+
+/// An entry of the delivery log
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Delivery {
+    pub id: i64,
+    pub contract: String,
+    pub payment_id: u64,
+    pub status: String,
+    pub business_name: String,
+    pub payload: String,
+    pub state: DeliveryState,
+    pub attempts: u32,
+    pub next_attempt_at: u64,
+}
+
+/// Outcome of one [`Notifier::deliver_due`] round
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub delivered: usize,
+    pub retrying: usize,
+    pub failed: usize,
+}
+
+pub struct Notifier {
+    indexer: Indexer,
+    policy: RetryPolicy,
+    agent: ureq::Agent,
+}
+
+impl Notifier {
+    /// Notify from the indexer's database, creating the webhook tables if needed.
+    pub fn new(indexer: Indexer, policy: RetryPolicy) -> Result<Notifier, NotifierError> {
+        indexer.connection().execute_batch(outbox::SCHEMA)?;
+        let agent = ureq::AgentBuilder::new().timeout(policy.timeout).build();
+        Ok(Notifier {
+            indexer,
+            policy,
+            agent,
+        })
+    }
+
+    /// Send a business's notifications to `url`, signed with `secret`.
+    /// Pending deliveries follow the new configuration.
+    pub fn set_webhook(
+        &self,
+        business_name: &str,
+        url: &str,
+        secret: &[u8],
+    ) -> Result<(), NotifierError> {
+        self.indexer.connection().execute(
+            "INSERT INTO webhooks (business_name, url, secret) VALUES (:business_name, :url, :secret)
+             ON CONFLICT (business_name) DO UPDATE SET url = excluded.url, secret = excluded.secret",
+            named_params! { ":business_name": business_name, ":url": url, ":secret": secret },
+        )?;
+        Ok(())
+    }
+
+    /// Stop notifying a business. Its pending deliveries fail on their next attempt.
+    pub fn remove_webhook(&self, business_name: &str) -> Result<(), NotifierError> {
+        self.indexer.connection().execute(
+            "DELETE FROM webhooks WHERE business_name = :business_name",
+            named_params! { ":business_name": business_name },
+        )?;
+        Ok(())
+    }
+
+    /// Index `events` and queue the notifications they trigger. Returns the
+    /// number of events materialized; events already indexed are skipped.
+    pub fn ingest(&mut self, events: &[ContractEvent], now: u64) -> Result<usize, NotifierError> {
+        let applied = self.indexer.apply_with(events, |tx, event, decoded| {
+            outbox::enqueue(tx, event, decoded, now)
+        })?;
+        Ok(applied)
+    }
+
+    /// Attempt every delivery that is due at `now`.
+    pub fn deliver_due(&mut self, now: u64) -> Result<DeliveryReport, NotifierError> {
+        let mut report = DeliveryReport::default();
+        for due in outbox::due(self.indexer.connection(), now)? {
+            let attempt = due.attempts + 1;
+            let (response_status, error) = match &due.webhook {
+                Some((url, secret)) => self.post(due.id, url, secret, &due.payload),
+                None => (None, Some("no webhook configured".to_string())),
+            };
+
+            let (state, next_attempt_at) = if error.is_none() {
+                report.delivered += 1;
+                (DeliveryState::Delivered, now)
+            } else if attempt >= self.policy.max_attempts || due.webhook.is_none() {
+                report.failed += 1;
+                (DeliveryState::Failed, now)
+            } else {
+                report.retrying += 1;
+                (
+                    DeliveryState::Pending,
+                    now.saturating_add(self.policy.delay(attempt)),
+                )
+            };
+
+            let tx = self.indexer.connection().unchecked_transaction()?;
+            outbox::record_attempt(
+                &tx,
+                due.id,
+                attempt,
+                now,
+                response_status,
+                error.as_deref(),
+                state.as_str(),
+                next_attempt_at,
+            )?;
+            tx.commit()?;
+        }
+        Ok(report)
+    }
+
+    // Returns the response status and, unless it is a 2xx, why the attempt failed
+    fn post(
+        &self,
+        id: i64,
+        url: &str,
+        secret: &[u8],
+        payload: &str,
+    ) -> (Option<u16>, Option<String>) {
+        let result = self
+            .agent
+            .post(url)
+            .set("Content-Type", "application/json")
+            .set(DELIVERY_HEADER, &id.to_string())
+            .set(SIGNATURE_HEADER, &signature(secret, payload.as_bytes()))
+            .send_string(payload);
+        match result {
+            Ok(response) if (200..300).contains(&response.status()) => {
+                (Some(response.status()), None)
+            }
+            Ok(response) | Err(ureq::Error::Status(_, response)) => {
+                let status = response.status();
+                (Some(status), Some(format!("merchant answered {status}")))
+            }
+            Err(error) => (None, Some(error.to_string())),
+        }
+    }
+
+    /// The delivery log, oldest first
+    pub fn deliveries(&self) -> Result<Vec<Delivery>, NotifierError> {
+        let mut statement = self.indexer.connection().prepare(
+            "SELECT id, contract, payment_id, status, business_name, payload, state, attempts, next_attempt_at
+             FROM deliveries ORDER BY id",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok(Delivery {
+                id: row.get(0)?,
+                contract: row.get(1)?,
+                payment_id: row.get(2)?,
+                status: row.get(3)?,
+                business_name: row.get(4)?,
+                payload: row.get(5)?,
+                state: DeliveryState::parse(&row.get::<_, String>(6)?),
+                attempts: row.get(7)?,
+                next_attempt_at: row.get(8)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn indexer(&self) -> &Indexer {
+        &self.indexer
+    }
+}
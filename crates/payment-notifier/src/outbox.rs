@@ -0,0 +1,172 @@
+//! Webhook configuration and the persisted delivery log.
+
+use payment_indexer::{ContractEvent, IndexerError, PaymentEvent};
+use rusqlite::{named_params, Connection, OptionalExtension};
+use serde_json::json;
+
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS webhooks (
+    business_name TEXT PRIMARY KEY,
+    url TEXT NOT NULL,
+    secret BLOB NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS deliveries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    contract TEXT NOT NULL,
+    payment_id INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    business_name TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    state TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    next_attempt_at INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE (contract, payment_id, status)
+);
+
+CREATE INDEX IF NOT EXISTS deliveries_due ON deliveries (state, next_attempt_at);
+
+CREATE TABLE IF NOT EXISTS delivery_attempts (
+    delivery_id INTEGER NOT NULL REFERENCES deliveries (id),
+    attempt INTEGER NOT NULL,
+    attempted_at INTEGER NOT NULL,
+    response_status INTEGER,
+    error TEXT,
+    PRIMARY KEY (delivery_id, attempt)
+);
+";
+
+/// Queue a webhook if `decoded` moved a payment request into a status
+/// merchants are notified about. Each payment is notified at most once per
+/// status, however often its events are replayed.
+pub fn enqueue(
+    conn: &Connection,
+    event: &ContractEvent,
+    decoded: &PaymentEvent,
+    now: u64,
+) -> Result<(), IndexerError> {
+    let payment_id = match decoded {
+        PaymentEvent::RequestPaid { payment_id, .. }
+        | PaymentEvent::RequestCancelled { payment_id }
+        | PaymentEvent::Refunded { payment_id, .. } => *payment_id,
+        _ => return Ok(()),
+    };
+    let contract = event.contract.to_string();
+
+    let payment = conn
+        .query_row(
+            "SELECT p.business_name, p.status, p.amount, p.amount_paid, p.tip, p.refunded,
+                    p.token_address, p.payer, p.requester
+             FROM payments p JOIN webhooks w ON w.business_name = p.business_name
+             WHERE p.contract = :contract AND p.payment_id = :payment_id
+               AND p.status IN ('Completed', 'Cancelled', 'Refunded')",
+            named_params! { ":contract": contract, ":payment_id": payment_id },
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    json!({
+                        "amount": row.get::<_, String>(2)?,
+                        "amount_paid": row.get::<_, String>(3)?,
+                        "tip": row.get::<_, String>(4)?,
+                        "refunded": row.get::<_, String>(5)?,
+                        "token_address": row.get::<_, Option<String>>(6)?,
+                        "payer": row.get::<_, Option<String>>(7)?,
+                        "requester": row.get::<_, String>(8)?,
+                    }),
+                ))
+            },
+        )
+        .optional()?;
+    let Some((business_name, status, mut payload)) = payment else {
+        return Ok(());
+    };
+
+    payload["type"] = json!(format!("payment.{}", status.to_lowercase()));
+    payload["contract"] = json!(contract);
+    payload["payment_id"] = json!(payment_id);
+    payload["business_name"] = json!(business_name);
+    payload["status"] = json!(status);
+    payload["ledger"] = json!(event.cursor.ledger);
+
+    conn.execute(
+        "INSERT INTO deliveries (contract, payment_id, status, business_name, payload, state,
+             attempts, next_attempt_at, created_at)
+         VALUES (:contract, :payment_id, :status, :business_name, :payload, 'pending', 0, :now, :now)
+         ON CONFLICT (contract, payment_id, status) DO NOTHING",
+        named_params! {
+            ":contract": contract,
+            ":payment_id": payment_id,
+            ":status": status,
+            ":business_name": business_name,
+            ":payload": payload.to_string(),
+            ":now": now,
+        },
+    )?;
+    Ok(())
+}
+
+/// A delivery whose next attempt is due, with its current destination
+pub struct Due {
+    pub id: i64,
+    pub payload: String,
+    pub attempts: u32,
+    /// URL and secret, unless the business removed its webhook
+    pub webhook: Option<(String, Vec<u8>)>,
+}
+
+pub fn due(conn: &Connection, now: u64) -> rusqlite::Result<Vec<Due>> {
+    let mut statement = conn.prepare(
+        "SELECT d.id, d.payload, d.attempts, w.url, w.secret
+         FROM deliveries d LEFT JOIN webhooks w ON w.business_name = d.business_name
+         WHERE d.state = 'pending' AND d.next_attempt_at <= :now
+         ORDER BY d.id",
+    )?;
+    let rows = statement.query_map(named_params! { ":now": now }, |row| {
+        let url: Option<String> = row.get(3)?;
+        let secret: Option<Vec<u8>> = row.get(4)?;
+        Ok(Due {
+            id: row.get(0)?,
+            payload: row.get(1)?,
+            attempts: row.get(2)?,
+            webhook: url.zip(secret),
+        })
+    })?;
+    rows.collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn record_attempt(
+    conn: &Connection,
+    id: i64,
+    attempt: u32,
+    now: u64,
+    response_status: Option<u16>,
+    error: Option<&str>,
+    state: &str,
+    next_attempt_at: u64,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO delivery_attempts (delivery_id, attempt, attempted_at, response_status, error)
+         VALUES (:id, :attempt, :now, :response_status, :error)",
+        named_params! {
+            ":id": id,
+            ":attempt": attempt,
+            ":now": now,
+            ":response_status": response_status,
+            ":error": error,
+        },
+    )?;
+    conn.execute(
+        "UPDATE deliveries SET state = :state, attempts = :attempt, next_attempt_at = :next_attempt_at
+         WHERE id = :id",
+        named_params! {
+            ":id": id,
+            ":attempt": attempt,
+            ":state": state,
+            ":next_attempt_at": next_attempt_at,
+        },
+    )?;
+    Ok(())
+}
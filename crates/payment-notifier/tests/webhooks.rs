@@ -0,0 +1,341 @@
+//! Delivers webhooks for events captured from the contract to a local stub.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use payment_indexer::{
+    testutils::{client_address, Recorder},
+    ContractEvent, Contracts, Indexer,
+};
+use payment_notifier::{
+    signature, DeliveryReport, DeliveryState, Notifier, RetryPolicy, DELIVERY_HEADER,
+    SIGNATURE_HEADER,
+};
+use payment_requests::{PaymentContract, PaymentContractClient};
+use soroban_sdk::{testutils::Address as _, token::StellarAssetClient, Address, Env, String};
+
+const SECRET: &[u8] = b"whsec_test_store";
+
+struct Received {
+    headers: HashMap<std::string::String, std::string::String>,
+    body: serde_json::Value,
+    raw: std::string::String,
+}
+
+/// Merchant endpoint answering with scripted statuses, then 200
+struct Stub {
+    server: Arc<tiny_http::Server>,
+    url: std::string::String,
+    received: mpsc::Receiver<Received>,
+}
+
+impl Stub {
+    fn start(statuses: &[u16]) -> Stub {
+        let server = Arc::new(tiny_http::Server::http("127.0.0.1:0").unwrap());
+        let url = format!("http://{}/hooks", server.server_addr().to_ip().unwrap());
+        let statuses = Arc::new(Mutex::new(
+            statuses.iter().copied().collect::<VecDeque<_>>(),
+        ));
+        let (sender, received) = mpsc::channel();
+
+        let incoming = server.clone();
+        thread::spawn(move || {
+            for mut request in incoming.incoming_requests() {
+                let mut raw = std::string::String::new();
+                request.as_reader().read_to_string(&mut raw).unwrap();
+                let headers = request
+                    .headers()
+                    .iter()
+                    .map(|header| (header.field.to_string(), header.value.to_string()))
+                    .collect();
+                let status = statuses.lock().unwrap().pop_front().unwrap_or(200);
+                let _ = sender.send(Received {
+                    headers,
+                    body: serde_json::from_str(&raw).unwrap(),
+                    raw,
+                });
+                let _ = request.respond(tiny_http::Response::empty(status));
+            }
+        });
+
+        Stub {
+            server,
+            url,
+            received,
+        }
+    }
+
+    fn received(&self) -> Vec<Received> {
+        self.received.try_iter().collect()
+    }
+}
+
+impl Drop for Stub {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+struct History {
+    contracts: Contracts,
+    events: Vec<ContractEvent>,
+}
+
+/// Request 1 is paid then refunded, 2 is cancelled, 3 is only partly paid
+/// and 4 is paid to a business without a webhook
+fn run_history() -> History {
+    let env = Env::default();
+    env.mock_all_auths();
+    let mut recorder = Recorder::new(&env);
+
+    let contract_id = env.register_contract(None, PaymentContract);
+    let client = PaymentContractClient::new(&env, &contract_id);
+    let owner = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(owner.clone())
+        .address();
+    let payer = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&payer, &100_000);
+    client.initialize(&owner, &250u32, &Address::generate(&env));
+
+    let store = String::from_str(&env, "Test Store");
+    let other = String::from_str(&env, "Other Store");
+    for name in [&store, &other] {
+        client.register_business(
+            name,
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &100u32,
+        );
+    }
+    recorder.capture();
+
+    // The requester refunds out of pocket the fee it never received
+    let requester = Address::generate(&env);
+    StellarAssetClient::new(&env, &token).mint(&requester, &100);
+    let text = String::from_str(&env, "Order");
+    let payers = soroban_sdk::vec![&env, payer.clone()];
+    let create = |business: &String, amount: i128| {
+        client.create_payment_request(&amount, business, &text, &text, &payers, &requester, &None)
+    };
+    let refunded = create(&store, 1_000);
+    let cancelled = create(&store, 500);
+    let partial = create(&store, 800);
+    let elsewhere = create(&other, 300);
+    recorder.capture();
+
+    client.execute_payment(&refunded, &payer, &token);
+    recorder.capture();
+    client.cancel_payment_request(&cancelled, &requester);
+    recorder.capture();
+    client.execute_partial_payment(&partial, &payer, &token, &200);
+    recorder.capture();
+    client.execute_payment(&elsewhere, &payer, &token);
+    recorder.capture();
+    client.refund_payment(&refunded, &payer, &1_000);
+    recorder.capture();
+
+    History {
+        contracts: Contracts {
+            requests: client_address(&env, &contract_id),
+            processor: client_address(&env, &Address::generate(&env)),
+        },
+        events: recorder.into_events(),
+    }
+}
+
+// Events up to the first payment, in the third ledger
+fn completed_only(history: &History) -> Vec<ContractEvent> {
+    history
+        .events
+        .iter()
+        .filter(|event| event.cursor.ledger <= 2)
+        .cloned()
+        .collect()
+}
+
+fn notifier(history: &History, policy: RetryPolicy) -> Notifier {
+    let conn = rusqlite::Connection::open_in_memory().unwrap();
+    Notifier::new(Indexer::new(conn, history.contracts).unwrap(), policy).unwrap()
+}
+
+fn attempt_statuses(notifier: &Notifier, delivery_id: i64) -> Vec<Option<u16>> {
+    let conn = notifier.indexer().connection();
+    let mut statement = conn
+        .prepare(
+            "SELECT response_status FROM delivery_attempts WHERE delivery_id = ?1 ORDER BY attempt",
+        )
+        .unwrap();
+    let rows = statement
+        .query_map([delivery_id], |row| row.get(0))
+        .unwrap();
+    rows.collect::<Result<_, _>>().unwrap()
+}
+
+#[test]
+fn test_notifies_lifecycle_transitions() {
+    let stub = Stub::start(&[]);
+    let history = run_history();
+    let mut notifier = notifier(&history, RetryPolicy::default());
+    notifier
+        .set_webhook("Test Store", &stub.url, SECRET)
+        .unwrap();
+
+    notifier.ingest(&history.events, 1_000).unwrap();
+    assert_eq!(
+        notifier.deliver_due(1_000).unwrap(),
+        DeliveryReport {
+            delivered: 3,
+            retrying: 0,
+            failed: 0
+        }
+    );
+
+    let received = stub.received();
+    let summary: Vec<_> = received
+        .iter()
+        .map(|request| {
+            (
+                request.body["type"].as_str().unwrap(),
+                request.body["payment_id"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("payment.completed", 1),
+            ("payment.cancelled", 2),
+            ("payment.refunded", 1)
+        ]
+    );
+
+    for (request, delivery) in received.iter().zip(notifier.deliveries().unwrap()) {
+        assert_eq!(
+            request.headers[SIGNATURE_HEADER],
+            signature(SECRET, request.raw.as_bytes())
+        );
+        assert_eq!(request.headers[DELIVERY_HEADER], delivery.id.to_string());
+        assert_eq!(request.body["business_name"], "Test Store");
+        assert_eq!(
+            request.body["contract"],
+            history.contracts.requests.to_string()
+        );
+        assert_eq!(delivery.state, DeliveryState::Delivered);
+    }
+    assert_eq!(received[0].body["amount_paid"], "1000");
+    assert_eq!(received[2].body["refunded"], "1000");
+
+    // Replaying the stream neither queues nor sends anything again
+    assert_eq!(notifier.ingest(&history.events, 2_000).unwrap(), 0);
+    assert_eq!(
+        notifier.deliver_due(2_000).unwrap(),
+        DeliveryReport::default()
+    );
+    assert!(stub.received().is_empty());
+    assert_eq!(notifier.deliveries().unwrap().len(), 3);
+}
+
+#[test]
+fn test_retries_with_backoff() {
+    let stub = Stub::start(&[500, 503]);
+    let history = run_history();
+    let policy = RetryPolicy {
+        initial_delay: 10,
+        max_delay: 15,
+        max_attempts: 5,
+        timeout: Duration::from_secs(5),
+    };
+    let mut notifier = notifier(&history, policy);
+    notifier
+        .set_webhook("Test Store", &stub.url, SECRET)
+        .unwrap();
+
+    // Only the completed request is queued so far
+    let first = completed_only(&history);
+    notifier.ingest(&first, 100).unwrap();
+
+    let retrying = DeliveryReport {
+        delivered: 0,
+        retrying: 1,
+        failed: 0,
+    };
+    assert_eq!(notifier.deliver_due(100).unwrap(), retrying);
+    assert_eq!(notifier.deliveries().unwrap()[0].next_attempt_at, 110);
+    assert_eq!(
+        notifier.deliver_due(109).unwrap(),
+        DeliveryReport::default()
+    );
+
+    // The doubled delay is capped
+    assert_eq!(notifier.deliver_due(110).unwrap(), retrying);
+    assert_eq!(notifier.deliveries().unwrap()[0].next_attempt_at, 125);
+
+    assert_eq!(
+        notifier.deliver_due(125).unwrap(),
+        DeliveryReport {
+            delivered: 1,
+            retrying: 0,
+            failed: 0
+        }
+    );
+
+    let delivery = &notifier.deliveries().unwrap()[0];
+    assert_eq!(
+        (delivery.state, delivery.attempts),
+        (DeliveryState::Delivered, 3)
+    );
+    assert_eq!(
+        attempt_statuses(&notifier, delivery.id),
+        [Some(500), Some(503), Some(200)]
+    );
+
+    // Every attempt carried the same delivery
+    let received = stub.received();
+    assert_eq!(received.len(), 3);
+    assert!(received
+        .iter()
+        .all(|request| request.raw == delivery.payload));
+}
+
+#[test]
+fn test_gives_up_after_max_attempts() {
+    let stub = Stub::start(&[500, 500, 500]);
+    let history = run_history();
+    let policy = RetryPolicy {
+        initial_delay: 1,
+        max_attempts: 2,
+        ..RetryPolicy::default()
+    };
+    let mut notifier = notifier(&history, policy);
+    notifier
+        .set_webhook("Test Store", &stub.url, SECRET)
+        .unwrap();
+
+    let first = completed_only(&history);
+    notifier.ingest(&first, 0).unwrap();
+    notifier.deliver_due(0).unwrap();
+    assert_eq!(
+        notifier.deliver_due(1).unwrap(),
+        DeliveryReport {
+            delivered: 0,
+            retrying: 0,
+            failed: 1
+        }
+    );
+    assert_eq!(
+        notifier.deliver_due(100).unwrap(),
+        DeliveryReport::default()
+    );
+
+    let delivery = &notifier.deliveries().unwrap()[0];
+    assert_eq!(
+        (delivery.state, delivery.attempts),
+        (DeliveryState::Failed, 2)
+    );
+    assert_eq!(stub.received().len(), 2);
+}
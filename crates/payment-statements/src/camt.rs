@@ -0,0 +1,190 @@
+use std::fmt::Write;
+
+use crate::{
+    format::{account_id, amount, escape_xml, truncate, DateTime},
+    Asset, ExportError, ExportOptions, Kind, Statement,
+};
+
+const NAMESPACE: &str = "urn:iso:std:iso:20022:tech:xsd:camt.053.001.02";
+
+/// ISO 20022 camt.053.001.02 bank-to-customer statement with one `Stmt` per
+/// token. Entries are booked at their net amount with the fee as a charge and
+/// the gross amount in the transaction details; refunds are debits with the
+/// refunded payer as creditor. Balances cover the statement only: it opens
+/// at zero and closes at the net amount received.
+pub fn camt053(statement: &Statement, options: &ExportOptions) -> Result<String, ExportError> {
+    let created = DateTime::from_timestamp(options.generated_at).iso8601();
+    let (start, end) = statement.time_bounds(options.generated_at);
+    let (start, end) = (
+        DateTime::from_timestamp(start).iso8601(),
+        DateTime::from_timestamp(end).iso8601(),
+    );
+    let business = escape_xml(&statement.business_name);
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(out, "<Document xmlns=\"{NAMESPACE}\">");
+    out.push_str("  <BkToCstmrStmt>\n");
+    out.push_str("    <GrpHdr>\n");
+    let _ = writeln!(out, "      <MsgId>STMT-{}</MsgId>", options.generated_at);
+    let _ = writeln!(out, "      <CreDtTm>{created}</CreDtTm>");
+    out.push_str("    </GrpHdr>\n");
+
+    for (index, token) in statement.tokens().into_iter().enumerate() {
+        let asset = options.asset(token);
+        let totals = statement.totals(token);
+        let money = |value: i128| money(&asset, token, value);
+        let ccy = escape_xml(&asset.code);
+
+        out.push_str("    <Stmt>\n");
+        let _ = writeln!(
+            out,
+            "      <Id>STMT-{}-{}</Id>",
+            options.generated_at,
+            index + 1
+        );
+        let _ = writeln!(out, "      <CreDtTm>{created}</CreDtTm>");
+        out.push_str("      <FrToDt>\n");
+        let _ = writeln!(out, "        <FrDtTm>{start}</FrDtTm>");
+        let _ = writeln!(out, "        <ToDtTm>{end}</ToDtTm>");
+        out.push_str("      </FrToDt>\n");
+        out.push_str("      <Acct>\n");
+        let _ = writeln!(
+            out,
+            "        <Id><Othr><Id>{}</Id></Othr></Id>",
+            escape_xml(&account_id(&asset.code, token))
+        );
+        let _ = writeln!(out, "        <Ccy>{ccy}</Ccy>");
+        let _ = writeln!(out, "        <Ownr><Nm>{business}</Nm></Ownr>");
+        out.push_str("      </Acct>\n");
+        push_balance(&mut out, "OPBD", &ccy, &money(0)?, indicator(0), &start);
+        push_balance(
+            &mut out,
+            "CLBD",
+            &ccy,
+            &money(totals.net)?,
+            indicator(totals.net),
+            &end,
+        );
+        out.push_str("      <TxsSummry>\n");
+        out.push_str("        <TtlNtries>\n");
+        let _ = writeln!(out, "          <NbOfNtries>{}</NbOfNtries>", totals.count);
+        let _ = writeln!(
+            out,
+            "          <TtlNetNtryAmt>{}</TtlNetNtryAmt>",
+            money(totals.net)?
+        );
+        let _ = writeln!(
+            out,
+            "          <CdtDbtInd>{}</CdtDbtInd>",
+            indicator(totals.net)
+        );
+        out.push_str("        </TtlNtries>\n");
+        out.push_str("      </TxsSummry>\n");
+
+        for entry in statement.entries_in(token) {
+            let booked = DateTime::from_timestamp(entry.timestamp).iso8601();
+            let reference = escape_xml(truncate(&entry.reference, 140));
+            let (code, party) = match entry.kind {
+                Kind::Payment(_) => ("PAYMENT", "Dbtr"),
+                Kind::Refund(_) => ("REFUND", "Cdtr"),
+            };
+
+            out.push_str("      <Ntry>\n");
+            let _ = writeln!(out, "        <NtryRef>{}</NtryRef>", entry.id());
+            let _ = writeln!(
+                out,
+                "        <Amt Ccy=\"{ccy}\">{}</Amt>",
+                money(entry.net())?
+            );
+            let _ = writeln!(
+                out,
+                "        <CdtDbtInd>{}</CdtDbtInd>",
+                indicator(entry.net())
+            );
+            out.push_str("        <Sts>BOOK</Sts>\n");
+            let _ = writeln!(out, "        <BookgDt><DtTm>{booked}</DtTm></BookgDt>");
+            let _ = writeln!(out, "        <ValDt><DtTm>{booked}</DtTm></ValDt>");
+            let _ = writeln!(out, "        <AcctSvcrRef>{}</AcctSvcrRef>", entry.ledger);
+            let _ = writeln!(
+                out,
+                "        <BkTxCd><Prtry><Cd>{code}</Cd><Issr>SOROBAN</Issr></Prtry></BkTxCd>"
+            );
+            if entry.fee != 0 {
+                let _ = writeln!(
+                    out,
+                    "        <Chrgs><Amt Ccy=\"{ccy}\">{}</Amt></Chrgs>",
+                    money(entry.fee)?
+                );
+            }
+            out.push_str("        <NtryDtls>\n");
+            out.push_str("          <TxDtls>\n");
+            let _ = writeln!(
+                out,
+                "            <Refs><EndToEndId>{}</EndToEndId></Refs>",
+                entry.id()
+            );
+            let _ = writeln!(
+                out,
+                "            <AmtDtls><TxAmt><Amt Ccy=\"{ccy}\">{}</Amt></TxAmt></AmtDtls>",
+                money(entry.gross)?
+            );
+            let _ = writeln!(
+                out,
+                "            <RltdPties><{party}><Nm>{}</Nm></{party}></RltdPties>",
+                escape_xml(&entry.counterparty)
+            );
+            let _ = writeln!(
+                out,
+                "            <RmtInf><Ustrd>{reference}</Ustrd></RmtInf>"
+            );
+            out.push_str("          </TxDtls>\n");
+            out.push_str("        </NtryDtls>\n");
+            out.push_str("      </Ntry>\n");
+        }
+
+        out.push_str("    </Stmt>\n");
+    }
+
+    out.push_str("  </BkToCstmrStmt>\n");
+    out.push_str("</Document>\n");
+    Ok(out)
+}
+
+// Amounts are unsigned with at most 5 decimal places; trailing zeros beyond
+// the second are dropped to fit
+fn money(asset: &Asset, token: &str, value: i128) -> Result<String, ExportError> {
+    let formatted = amount(value.abs(), asset.decimals);
+    let Some((units, fraction)) = formatted.split_once('.') else {
+        return Ok(formatted);
+    };
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > 5 {
+        return Err(ExportError::Precision {
+            token: token.to_string(),
+            amount: value,
+        });
+    }
+    Ok(format!("{units}.{fraction:0<2}"))
+}
+
+// Amounts carry their sign in a separate credit or debit indicator
+fn indicator(value: i128) -> &'static str {
+    if value < 0 {
+        "DBIT"
+    } else {
+        "CRDT"
+    }
+}
+
+fn push_balance(out: &mut String, code: &str, ccy: &str, amount: &str, indicator: &str, at: &str) {
+    out.push_str("      <Bal>\n");
+    let _ = writeln!(
+        out,
+        "        <Tp><CdOrPrtry><Cd>{code}</Cd></CdOrPrtry></Tp>"
+    );
+    let _ = writeln!(out, "        <Amt Ccy=\"{ccy}\">{amount}</Amt>");
+    let _ = writeln!(out, "        <CdtDbtInd>{indicator}</CdtDbtInd>");
+    let _ = writeln!(out, "        <Dt><DtTm>{at}</DtTm></Dt>");
+    out.push_str("      </Bal>\n");
+}
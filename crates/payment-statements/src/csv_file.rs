@@ -0,0 +1,58 @@
+use crate::{
+    format::{amount, DateTime},
+    ExportOptions, Kind, Source, Statement,
+};
+
+const HEADER: [&str; 13] = [
+    "business_name",
+    "source",
+    "kind",
+    "payment_id",
+    "ledger",
+    "booked_at",
+    "token",
+    "currency",
+    "counterparty",
+    "reference",
+    "gross",
+    "fee",
+    "net",
+];
+
+/// One row per payment or refund, amounts in whole token units
+pub fn csv(statement: &Statement, options: &ExportOptions) -> String {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(HEADER).expect("writing to memory");
+
+    for entry in &statement.entries {
+        let asset = options.asset(&entry.token);
+        let source = match entry.source {
+            Source::Request => "request",
+            Source::Processor => "processor",
+        };
+        let kind = match entry.kind {
+            Kind::Payment(_) => "payment",
+            Kind::Refund(_) => "refund",
+        };
+        writer
+            .write_record([
+                entry.business_name.as_str(),
+                source,
+                kind,
+                &entry.payment_id.to_string(),
+                &entry.ledger.to_string(),
+                &DateTime::from_timestamp(entry.timestamp).iso8601(),
+                &entry.token,
+                &asset.code,
+                &entry.counterparty,
+                &entry.reference,
+                &amount(entry.gross, asset.decimals),
+                &amount(entry.fee, asset.decimals),
+                &amount(entry.net(), asset.decimals),
+            ])
+            .expect("writing to memory");
+    }
+
+    let bytes = writer.into_inner().expect("writing to memory");
+    String::from_utf8(bytes).expect("records are UTF-8")
+}
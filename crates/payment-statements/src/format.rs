@@ -0,0 +1,130 @@
+//! Amount, date and XML formatting shared by the exporters.
+
+/// `amount` in whole units of a token with `decimals` decimal places
+pub fn amount(amount: i128, decimals: u32) -> String {
+    let sign = if amount < 0 { "-" } else { "" };
+    let magnitude = amount.unsigned_abs();
+    if decimals == 0 {
+        return format!("{sign}{magnitude}");
+    }
+    let scale = 10u128.pow(decimals);
+    format!(
+        "{sign}{}.{:0width$}",
+        magnitude / scale,
+        magnitude % scale,
+        width = decimals as usize
+    )
+}
+
+/// Calendar date and time of a UNIX timestamp, in UTC
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    pub fn from_timestamp(timestamp: u64) -> DateTime {
+        let days = (timestamp / 86_400) as i64;
+        let seconds = (timestamp % 86_400) as u32;
+
+        // Days to civil date, after Howard Hinnant's `civil_from_days`
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        DateTime {
+            year,
+            month,
+            day,
+            hour: seconds / 3_600,
+            minute: seconds % 3_600 / 60,
+            second: seconds % 60,
+        }
+    }
+
+    /// ISO 8601 in UTC, e.g. `2024-03-01T12:00:00Z`
+    pub fn iso8601(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+
+    /// OFX datetime, e.g. `20240301120000[0:GMT]`
+    pub fn ofx(&self) -> String {
+        format!(
+            "{:04}{:02}{:02}{:02}{:02}{:02}[0:GMT]",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// At most `max` characters of `text`, for fields with a length limit
+pub fn truncate(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// Account identifier of a token: the currency code and the start of the
+/// token address, short enough for OFX and camt.053 account IDs
+pub fn account_id(code: &str, token: &str) -> String {
+    format!("{code}-{}", truncate(token, 12))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_amounts_keep_every_decimal() {
+        assert_eq!(amount(9_900_000, 7), "0.9900000");
+        assert_eq!(amount(-25_000_000, 7), "-2.5000000");
+        assert_eq!(amount(1, 2), "0.01");
+        assert_eq!(amount(42, 0), "42");
+    }
+
+    #[test]
+    fn test_dates_are_utc() {
+        assert_eq!(
+            DateTime::from_timestamp(0).iso8601(),
+            "1970-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            DateTime::from_timestamp(951_782_400).iso8601(),
+            "2000-02-29T00:00:00Z"
+        );
+        assert_eq!(
+            DateTime::from_timestamp(1_709_294_645).ofx(),
+            "20240301120405[0:GMT]"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Per-business accounting statements of the payments recorded by the
+//! payment request and payment processor contracts.
+//!
+//! Each [`Entry`] is built from one `("payment", "paid")` event of a
+//! `PaymentRequest`, so installments are entries of their own, or from a
+//! processor `PaymentRecord`. It carries the gross amount, the fee, the
+//! token and the paying counterparty. Refunds of payment requests are
+//! entries of their own with a negative gross amount. A [`Statement`]
+//! selects one business's entries over a ledger or time range, and can be exported as CSV, as an OFX 2.2 bank
+//! statement or as an ISO 20022 camt.053 bank-to-customer statement. The OFX
+//! and camt.053 exports hold one account statement per token.
+//!
+//! ```
+//! # use std::collections::BTreeMap;
+//! # use payment_statements::{camt053, csv, ofx, ExportOptions, Period, Statement};
+//! # fn main() -> Result<(), payment_statements::ExportError> {
+//! # let entries = Vec::new();
+//! let statement = Statement::new("Test Store", Period::Ledgers { first: 100, last: 200 }, entries);
+//! let options = ExportOptions { generated_at: 1_709_251_200, assets: BTreeMap::new() };
+//! let csv = csv(&statement, &options);
+//! let ofx = ofx(&statement, &options);
+//! let camt = camt053(&statement, &options)?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod camt;
+mod csv_file;
+mod format;
+mod ofx;
+mod statement;
+
+use std::{collections::BTreeMap, fmt};
+
+pub use camt::camt053;
+pub use csv_file::csv;
+pub use ofx::ofx;
+pub use statement::{Entry, Kind, PaidEvent, Period, Source, Statement, Totals, NATIVE};
+
+/// How amounts of a token are presented
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Asset {
+    /// ISO 4217 currency code the token tracks, or `XXX` for none
+    pub code: String,
+    /// Decimal places of the token's amounts
+    pub decimals: u32,
+}
+
+impl Default for Asset {
+    /// Stellar assets have 7 decimal places
+    fn default() -> Asset {
+        Asset {
+            code: "XXX".to_string(),
+            decimals: 7,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExportOptions {
+    /// Creation time written into the export, in UNIX seconds
+    pub generated_at: u64,
+    /// Presentation of each token, keyed like [`Entry::token`]; other
+    /// tokens use [`Asset::default`]
+    pub assets: BTreeMap<String, Asset>,
+}
+
+impl ExportOptions {
+    fn asset(&self, token: &str) -> Asset {
+        self.assets.get(token).cloned().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportError {
+    /// camt.053 amounts have at most 5 decimal places
+    Precision { token: String, amount: i128 },
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::Precision { token, amount } => write!(
+                f,
+                "amount {amount} of {token} needs more than 5 decimal places"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
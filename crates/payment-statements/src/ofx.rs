@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+use crate::{
+    format::{account_id, amount, escape_xml, truncate, DateTime},
+    ExportOptions, Kind, Statement,
+};
+
+/// OFX 2.2 bank statement response. Each token is an account whose ID is
+/// the currency code and the start of the token address; payments are
+/// credits, their fees separate `FEE` debits and refunds `DEBIT`s, so the
+/// ledger balance is the net amount received over the statement.
+pub fn ofx(statement: &Statement, options: &ExportOptions) -> String {
+    let generated = DateTime::from_timestamp(options.generated_at).ofx();
+    let (start, end) = statement.time_bounds(options.generated_at);
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+    out.push_str(
+        "<?OFX OFXHEADER=\"200\" VERSION=\"220\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" NEWFILEUID=\"NONE\"?>\n",
+    );
+    out.push_str("<OFX>\n");
+    out.push_str("  <SIGNONMSGSRSV1>\n    <SONRS>\n");
+    push_status(&mut out, 6);
+    let _ = writeln!(out, "      <DTSERVER>{generated}</DTSERVER>");
+    out.push_str("      <LANGUAGE>ENG</LANGUAGE>\n");
+    out.push_str("    </SONRS>\n  </SIGNONMSGSRSV1>\n");
+    out.push_str("  <BANKMSGSRSV1>\n");
+
+    for (index, token) in statement.tokens().into_iter().enumerate() {
+        let asset = options.asset(token);
+        let totals = statement.totals(token);
+
+        out.push_str("    <STMTTRNRS>\n");
+        let _ = writeln!(out, "      <TRNUID>{}</TRNUID>", index + 1);
+        push_status(&mut out, 6);
+        out.push_str("      <STMTRS>\n");
+        let _ = writeln!(out, "        <CURDEF>{}</CURDEF>", escape_xml(&asset.code));
+        out.push_str("        <BANKACCTFROM>\n");
+        out.push_str("          <BANKID>STELLAR</BANKID>\n");
+        let _ = writeln!(
+            out,
+            "          <ACCTID>{}</ACCTID>",
+            escape_xml(&account_id(&asset.code, token))
+        );
+        out.push_str("          <ACCTTYPE>CHECKING</ACCTTYPE>\n");
+        out.push_str("        </BANKACCTFROM>\n");
+        out.push_str("        <BANKTRANLIST>\n");
+        let _ = writeln!(
+            out,
+            "          <DTSTART>{}</DTSTART>",
+            DateTime::from_timestamp(start).ofx()
+        );
+        let _ = writeln!(
+            out,
+            "          <DTEND>{}</DTEND>",
+            DateTime::from_timestamp(end).ofx()
+        );
+
+        for entry in statement.entries_in(token) {
+            let posted = DateTime::from_timestamp(entry.timestamp).ofx();
+            let name = escape_xml(truncate(&entry.reference, 32));
+            let memo = escape_xml(&entry.counterparty);
+
+            let trntype = match entry.kind {
+                Kind::Payment(_) => "CREDIT",
+                Kind::Refund(_) => "DEBIT",
+            };
+
+            out.push_str("          <STMTTRN>\n");
+            let _ = writeln!(out, "            <TRNTYPE>{trntype}</TRNTYPE>");
+            let _ = writeln!(out, "            <DTPOSTED>{posted}</DTPOSTED>");
+            let _ = writeln!(
+                out,
+                "            <TRNAMT>{}</TRNAMT>",
+                amount(entry.gross, asset.decimals)
+            );
+            let _ = writeln!(out, "            <FITID>{}</FITID>", entry.id());
+            let _ = writeln!(out, "            <NAME>{name}</NAME>");
+            let _ = writeln!(out, "            <MEMO>{memo}</MEMO>");
+            out.push_str("          </STMTTRN>\n");
+
+            if entry.fee != 0 {
+                out.push_str("          <STMTTRN>\n");
+                out.push_str("            <TRNTYPE>FEE</TRNTYPE>\n");
+                let _ = writeln!(out, "            <DTPOSTED>{posted}</DTPOSTED>");
+                let _ = writeln!(
+                    out,
+                    "            <TRNAMT>{}</TRNAMT>",
+                    amount(-entry.fee, asset.decimals)
+                );
+                let _ = writeln!(out, "            <FITID>{}-fee</FITID>", entry.id());
+                let _ = writeln!(out, "            <NAME>{name}</NAME>");
+                out.push_str("            <MEMO>Payment fee</MEMO>\n");
+                out.push_str("          </STMTTRN>\n");
+            }
+        }
+
+        out.push_str("        </BANKTRANLIST>\n");
+        out.push_str("        <LEDGERBAL>\n");
+        let _ = writeln!(
+            out,
+            "          <BALAMT>{}</BALAMT>",
+            amount(totals.net, asset.decimals)
+        );
+        let _ = writeln!(
+            out,
+            "          <DTASOF>{}</DTASOF>",
+            DateTime::from_timestamp(end).ofx()
+        );
+        out.push_str("        </LEDGERBAL>\n");
+        out.push_str("      </STMTRS>\n");
+        out.push_str("    </STMTTRNRS>\n");
+    }
+
+    out.push_str("  </BANKMSGSRSV1>\n");
+    out.push_str("</OFX>\n");
+    out
+}
+
+fn push_status(out: &mut String, indent: usize) {
+    let pad = " ".repeat(indent);
+    let _ = writeln!(out, "{pad}<STATUS>");
+    let _ = writeln!(out, "{pad}  <CODE>0</CODE>");
+    let _ = writeln!(out, "{pad}  <SEVERITY>INFO</SEVERITY>");
+    let _ = writeln!(out, "{pad}</STATUS>");
+}
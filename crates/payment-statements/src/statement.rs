@@ -0,0 +1,285 @@
+//! Statement entries and the period a statement covers.
+
+use payment_core::split_fee;
+use payments_client::types::{Address, FeeBasis, PaymentRecord, PaymentRequest};
+
+/// Token identifier used for processor payments in the native asset
+pub const NATIVE: &str = "native";
+
+/// Which contract recorded a payment
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Source {
+    Request,
+    Processor,
+}
+
+/// What an entry records
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kind {
+    /// The `n`th payment towards a payment, counting from 1
+    Payment(u32),
+    /// The `n`th refund of a payment request, counting from 1
+    Refund(u32),
+}
+
+/// The data of one `("payment", "paid")` event
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaidEvent {
+    pub payer: Address,
+    pub token: Address,
+    /// Amount paid towards the request, tip excluded
+    pub amount: i128,
+    pub tip: i128,
+    /// Ledger the event was emitted in
+    pub ledger: u32,
+    /// Close time of that ledger, in UNIX seconds
+    pub timestamp: u64,
+}
+
+/// One payment received by a business, or one refund it paid back
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub source: Source,
+    pub kind: Kind,
+    pub payment_id: u64,
+    pub business_name: String,
+    /// Ledger the payment was made in
+    pub ledger: u32,
+    /// Close time of that ledger, in UNIX seconds
+    pub timestamp: u64,
+    /// Token contract address, or [`NATIVE`]
+    pub token: String,
+    /// Who paid, or who was refunded
+    pub counterparty: String,
+    /// Request description or processor order ID
+    pub reference: String,
+    /// Amount paid, tips included; negative for refunds
+    pub gross: i128,
+    pub fee: i128,
+}
+
+impl Entry {
+    /// The payments made towards a payment request, one entry per
+    /// `("payment", "paid")` event in the order they were emitted. The events
+    /// carry the payer, token, amount and tip of each payment; the ledger and
+    /// close time come from the ledger they were emitted in. Fees follow the
+    /// contract: each installment is charged the request's fee on the amount
+    /// paid so far less what earlier installments were charged and, with
+    /// [`FeeBasis::Total`], on its tip as well. Refunds are separate entries
+    /// built with [`Entry::refund`].
+    pub fn payments(
+        request: &PaymentRequest,
+        fee_basis: &FeeBasis,
+        events: &[PaidEvent],
+    ) -> Vec<Entry> {
+        let fee_percentage = i128::from(request.fee_percentage);
+        let mut paid = 0;
+        (1..)
+            .zip(events)
+            .map(|(sequence, event)| {
+                let mut fee = split_fee(paid + event.amount, fee_percentage).0
+                    - split_fee(paid, fee_percentage).0;
+                if *fee_basis == FeeBasis::Total {
+                    fee += split_fee(event.tip, fee_percentage).0;
+                }
+                paid += event.amount;
+                Entry {
+                    source: Source::Request,
+                    kind: Kind::Payment(sequence),
+                    payment_id: request.id,
+                    business_name: request.business_name.clone(),
+                    ledger: event.ledger,
+                    timestamp: event.timestamp,
+                    token: event.token.to_string(),
+                    counterparty: event.payer.to_string(),
+                    reference: request.description.clone(),
+                    gross: event.amount + event.tip,
+                    fee,
+                }
+            })
+            .collect()
+    }
+
+    /// The `sequence`th refund of `amount` to `payer` on a payment request,
+    /// from its `("payment", "refund")` event. Refunds come out of the
+    /// business's proceeds and the fee already charged is not returned, so
+    /// the whole amount is deducted from gross and net.
+    pub fn refund(
+        request: &PaymentRequest,
+        sequence: u32,
+        payer: &Address,
+        token: &Address,
+        amount: i128,
+        ledger: u32,
+        timestamp: u64,
+    ) -> Entry {
+        Entry {
+            source: Source::Request,
+            kind: Kind::Refund(sequence),
+            payment_id: request.id,
+            business_name: request.business_name.clone(),
+            ledger,
+            timestamp,
+            token: token.to_string(),
+            counterparty: payer.to_string(),
+            reference: request.description.clone(),
+            gross: -amount,
+            fee: 0,
+        }
+    }
+
+    /// A payment processed by the processor contract at `processor`, whose
+    /// business charged `fee_rate` basis points. XLM payments are recorded
+    /// against the processor's own address and carry no fee.
+    pub fn from_record(
+        record: &PaymentRecord,
+        processor: &Address,
+        fee_rate: i128,
+        ledger: u32,
+    ) -> Entry {
+        let details = &record.details;
+        let (token, fee) = if details.token_address == *processor {
+            (NATIVE.to_string(), 0)
+        } else {
+            (
+                details.token_address.to_string(),
+                split_fee(details.amount, fee_rate).0,
+            )
+        };
+        Entry {
+            source: Source::Processor,
+            kind: Kind::Payment(1),
+            payment_id: record.payment_id,
+            business_name: details.business_name.clone(),
+            ledger,
+            timestamp: record.timestamp,
+            token,
+            counterparty: details.sender.to_string(),
+            reference: details.order_id.clone(),
+            gross: details.amount,
+            fee,
+        }
+    }
+
+    pub fn net(&self) -> i128 {
+        self.gross - self.fee
+    }
+
+    /// Identifier that is unique across both contracts
+    pub fn id(&self) -> String {
+        let source = match self.source {
+            Source::Request => "request",
+            Source::Processor => "processor",
+        };
+        match self.kind {
+            Kind::Payment(1) => format!("{source}-{}", self.payment_id),
+            Kind::Payment(sequence) => format!("{source}-{}-payment-{sequence}", self.payment_id),
+            Kind::Refund(sequence) => format!("{source}-{}-refund-{sequence}", self.payment_id),
+        }
+    }
+}
+
+/// Range a statement covers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    /// Ledgers `first..=last`
+    Ledgers { first: u32, last: u32 },
+    /// UNIX seconds `start..end`
+    Time { start: u64, end: u64 },
+}
+
+impl Period {
+    pub fn contains(&self, entry: &Entry) -> bool {
+        match *self {
+            Period::Ledgers { first, last } => (first..=last).contains(&entry.ledger),
+            Period::Time { start, end } => (start..end).contains(&entry.timestamp),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Totals {
+    pub count: usize,
+    pub gross: i128,
+    pub fee: i128,
+    pub net: i128,
+}
+
+/// The payments one business received over a period, in ledger order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Statement {
+    pub business_name: String,
+    pub period: Period,
+    pub entries: Vec<Entry>,
+}
+
+impl Statement {
+    /// Keep the entries of `business_name` that fall within `period`.
+    pub fn new(
+        business_name: &str,
+        period: Period,
+        entries: impl IntoIterator<Item = Entry>,
+    ) -> Statement {
+        let mut entries: Vec<Entry> = entries
+            .into_iter()
+            .filter(|entry| entry.business_name == business_name && period.contains(entry))
+            .collect();
+        entries.sort_by_key(|entry| {
+            (
+                entry.ledger,
+                entry.timestamp,
+                entry.source,
+                entry.payment_id,
+                entry.kind,
+            )
+        });
+        Statement {
+            business_name: business_name.to_string(),
+            period,
+            entries,
+        }
+    }
+
+    /// Tokens the business was paid in, sorted
+    pub fn tokens(&self) -> Vec<&str> {
+        let mut tokens: Vec<&str> = self
+            .entries
+            .iter()
+            .map(|entry| entry.token.as_str())
+            .collect();
+        tokens.sort_unstable();
+        tokens.dedup();
+        tokens
+    }
+
+    pub fn entries_in<'a>(&'a self, token: &'a str) -> impl Iterator<Item = &'a Entry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.token == token)
+    }
+
+    pub fn totals(&self, token: &str) -> Totals {
+        self.entries_in(token)
+            .fold(Totals::default(), |totals, entry| Totals {
+                count: totals.count + 1,
+                gross: totals.gross + entry.gross,
+                fee: totals.fee + entry.fee,
+                net: totals.net + entry.net(),
+            })
+    }
+
+    /// Start and end of the statement in UNIX seconds. Ledger periods span
+    /// their entries, or `fallback` when there are none.
+    pub fn time_bounds(&self, fallback: u64) -> (u64, u64) {
+        match self.period {
+            Period::Time { start, end } => (start, end),
+            Period::Ledgers { .. } => {
+                let times = self.entries.iter().map(|entry| entry.timestamp);
+                (
+                    times.clone().min().unwrap_or(fallback),
+                    times.max().unwrap_or(fallback),
+                )
+            }
+        }
+    }
+}
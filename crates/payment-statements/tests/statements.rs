@@ -0,0 +1,346 @@
+//! Exports a fixed set of payments and compares them with the files in
+//! `tests/golden`. Run with `UPDATE_GOLDEN=1` to rewrite those files.
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use payment_statements::{
+    camt053, csv, ofx, Asset, Entry, ExportError, ExportOptions, Kind, PaidEvent, Period,
+    Statement, Totals, NATIVE,
+};
+use payments_client::{
+    types::{
+        Address, FeeBasis, PaymentDetails, PaymentRecord, PaymentRequest, PaymentStatus,
+        RequestKind,
+    },
+    Symbol,
+};
+
+const BUSINESS: &str = "Test Store";
+const USDC: Address = Address::Contract([7; 32]);
+const PROCESSOR: Address = Address::Contract([9; 32]);
+const OWNER: Address = Address::Account([1; 32]);
+const ALICE: Address = Address::Account([2; 32]);
+const BOB: Address = Address::Account([3; 32]);
+
+// 2024-03-01T00:00:00Z
+const MARCH_1: u64 = 1_709_251_200;
+
+fn request(
+    id: u64,
+    business_name: &str,
+    kind: RequestKind,
+    paid: i128,
+    tip: i128,
+) -> PaymentRequest {
+    PaymentRequest {
+        id,
+        amount: paid,
+        business_name: business_name.to_string(),
+        description: format!("Order #{id} & co"),
+        denomination: String::new(),
+        authorized_addresses: Vec::new(),
+        requester: OWNER,
+        timestamp: MARCH_1,
+        status: PaymentStatus::Completed,
+        fee_percentage: 250,
+        amount_paid: paid,
+        kind,
+        tip_amount: tip,
+        splits: Vec::new(),
+    }
+}
+
+fn record(payment_id: u64, token_address: Address, amount: i128, timestamp: u64) -> PaymentRecord {
+    PaymentRecord {
+        payment_id,
+        details: PaymentDetails {
+            amount,
+            sender: BOB,
+            recipient: OWNER,
+            token_address,
+            business_name: BUSINESS.to_string(),
+            customer_name: "Bob".to_string(),
+            order_id: format!("ORD-{payment_id}"),
+        },
+        timestamp,
+        status: Symbol("completed".to_string()),
+    }
+}
+
+fn paid(payer: Address, amount: i128, tip: i128, ledger: u32, timestamp: u64) -> PaidEvent {
+    PaidEvent {
+        payer,
+        token: USDC,
+        amount,
+        tip,
+        ledger,
+        timestamp,
+    }
+}
+
+fn entries() -> Vec<Entry> {
+    let mut entries = Vec::new();
+    entries.extend(Entry::payments(
+        &request(1, BUSINESS, RequestKind::Fixed, 1_000_000_000, 0),
+        &FeeBasis::BaseOnly,
+        &[paid(ALICE, 1_000_000_000, 0, 120, MARCH_1 + 600)],
+    ));
+    entries.extend(Entry::payments(
+        &request(2, BUSINESS, RequestKind::Tippable, 400_000_000, 100_000_000),
+        &FeeBasis::BaseOnly,
+        &[paid(ALICE, 400_000_000, 100_000_000, 150, MARCH_1 + 750)],
+    ));
+    entries.push(Entry::from_record(
+        &record(1, USDC, 2_000_000_000, MARCH_1 + 900),
+        &PROCESSOR,
+        250,
+        180,
+    ));
+    entries.push(Entry::refund(
+        &request(2, BUSINESS, RequestKind::Tippable, 400_000_000, 100_000_000),
+        1,
+        &ALICE,
+        &USDC,
+        100_000_000,
+        190,
+        MARCH_1 + 950,
+    ));
+    entries.push(Entry::from_record(
+        &record(2, PROCESSOR, 55_000_000, MARCH_1 + 1_000),
+        &PROCESSOR,
+        250,
+        200,
+    ));
+    entries.extend(Entry::payments(
+        &request(3, "Other Shop", RequestKind::Fixed, 1_000_000_000, 0),
+        &FeeBasis::BaseOnly,
+        &[paid(ALICE, 1_000_000_000, 0, 160, MARCH_1 + 800)],
+    ));
+    entries.extend(Entry::payments(
+        &request(4, BUSINESS, RequestKind::Fixed, 1_000_000_000, 0),
+        &FeeBasis::BaseOnly,
+        &[paid(ALICE, 1_000_000_000, 0, 300, MARCH_1 + 1_500)],
+    ));
+    entries
+}
+
+fn options() -> ExportOptions {
+    let mut assets = BTreeMap::new();
+    assets.insert(
+        USDC.to_string(),
+        Asset {
+            code: "USD".to_string(),
+            decimals: 7,
+        },
+    );
+    ExportOptions {
+        generated_at: MARCH_1 + 86_400,
+        assets,
+    }
+}
+
+fn statement() -> Statement {
+    Statement::new(
+        BUSINESS,
+        Period::Ledgers {
+            first: 100,
+            last: 200,
+        },
+        entries(),
+    )
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap();
+    assert_eq!(actual, expected, "{} differs", path.display());
+}
+
+#[test]
+fn test_statement_selects_business_and_period() {
+    let statement = statement();
+    let ids: Vec<String> = statement.entries.iter().map(Entry::id).collect();
+    assert_eq!(
+        ids,
+        [
+            "request-1",
+            "request-2",
+            "processor-1",
+            "request-2-refund-1",
+            "processor-2"
+        ]
+    );
+
+    // Tips are not charged with FeeBasis::BaseOnly
+    assert_eq!(statement.entries[1].gross, 500_000_000);
+    assert_eq!(statement.entries[1].fee, 10_000_000);
+    assert_eq!(statement.entries[4].token, NATIVE);
+    assert_eq!(statement.entries[4].fee, 0);
+
+    assert_eq!(statement.tokens(), [USDC.to_string().as_str(), NATIVE]);
+    assert_eq!(
+        statement.totals(&USDC.to_string()),
+        Totals {
+            count: 4,
+            gross: 3_400_000_000,
+            fee: 85_000_000,
+            net: 3_315_000_000,
+        }
+    );
+}
+
+#[test]
+fn test_statement_over_time_range() {
+    let statement = Statement::new(
+        BUSINESS,
+        Period::Time {
+            start: MARCH_1 + 750,
+            end: MARCH_1 + 1_500,
+        },
+        entries(),
+    );
+    let ids: Vec<String> = statement.entries.iter().map(Entry::id).collect();
+    assert_eq!(
+        ids,
+        [
+            "request-2",
+            "processor-1",
+            "request-2-refund-1",
+            "processor-2"
+        ]
+    );
+    assert_eq!(statement.time_bounds(0), (MARCH_1 + 750, MARCH_1 + 1_500));
+}
+
+#[test]
+fn test_total_fee_basis_charges_tips() {
+    let entry = Entry::payments(
+        &request(2, BUSINESS, RequestKind::Tippable, 400_000_000, 100_000_000),
+        &FeeBasis::Total,
+        &[paid(ALICE, 400_000_000, 100_000_000, 150, MARCH_1)],
+    )
+    .remove(0);
+    assert_eq!(entry.fee, 12_500_000);
+    assert_eq!(entry.net(), 487_500_000);
+}
+
+#[test]
+fn test_installments_are_entries_of_their_own() {
+    let mut invoice = request(6, BUSINESS, RequestKind::Fixed, 1_000, 0);
+    invoice.fee_percentage = 333;
+    let entries = Entry::payments(
+        &invoice,
+        &FeeBasis::BaseOnly,
+        &[
+            paid(ALICE, 500, 0, 120, MARCH_1),
+            paid(BOB, 500, 0, 140, MARCH_1 + 60),
+        ],
+    );
+    let ids: Vec<String> = entries.iter().map(Entry::id).collect();
+    assert_eq!(ids, ["request-6", "request-6-payment-2"]);
+    assert_eq!(entries[1].kind, Kind::Payment(2));
+    assert_eq!(entries[0].counterparty, ALICE.to_string());
+    assert_eq!(entries[1].counterparty, BOB.to_string());
+    assert_eq!(
+        (entries[1].ledger, entries[1].timestamp),
+        (140, MARCH_1 + 60)
+    );
+
+    // Each installment is charged what the contract charged it, which adds
+    // up to the fee on the whole amount
+    assert_eq!((entries[0].fee, entries[1].fee), (16, 17));
+    assert_eq!(entries[0].fee + entries[1].fee, 33);
+}
+
+#[test]
+fn test_refunds_are_netted_out() {
+    let mut refunded = request(5, BUSINESS, RequestKind::Fixed, 1_000_000_000, 0);
+    refunded.status = PaymentStatus::Refunded;
+    let payment = Entry::payments(
+        &refunded,
+        &FeeBasis::BaseOnly,
+        &[paid(ALICE, 1_000_000_000, 0, 120, MARCH_1)],
+    )
+    .remove(0);
+    let first = Entry::refund(&refunded, 1, &ALICE, &USDC, 400_000_000, 130, MARCH_1 + 60);
+    let second = Entry::refund(&refunded, 2, &ALICE, &USDC, 600_000_000, 130, MARCH_1 + 60);
+    assert_eq!(first.kind, Kind::Refund(1));
+    assert_eq!(
+        (first.gross, first.fee, first.net()),
+        (-400_000_000, 0, -400_000_000)
+    );
+
+    // Refunds in the same ledger stay distinct and follow the payment
+    let statement = Statement::new(
+        BUSINESS,
+        Period::Ledgers {
+            first: 100,
+            last: 200,
+        },
+        vec![second, first, payment],
+    );
+    let ids: Vec<String> = statement.entries.iter().map(Entry::id).collect();
+    assert_eq!(
+        ids,
+        ["request-5", "request-5-refund-1", "request-5-refund-2"]
+    );
+
+    // The whole payment went back but the fee charged on it was not returned
+    assert_eq!(
+        statement.totals(&USDC.to_string()),
+        Totals {
+            count: 3,
+            gross: 0,
+            fee: 25_000_000,
+            net: -25_000_000,
+        }
+    );
+    let camt = camt053(&statement, &options()).unwrap();
+    assert!(camt.contains("<Amt Ccy=\"USD\">2.50</Amt>\n        <CdtDbtInd>DBIT</CdtDbtInd>"));
+    assert!(camt.contains("<RltdPties><Cdtr>"));
+}
+
+#[test]
+fn test_csv_matches_golden() {
+    assert_golden("statement.csv", &csv(&statement(), &options()));
+}
+
+#[test]
+fn test_ofx_matches_golden() {
+    assert_golden("statement.ofx", &ofx(&statement(), &options()));
+}
+
+#[test]
+fn test_camt053_matches_golden() {
+    assert_golden(
+        "statement.camt053.xml",
+        &camt053(&statement(), &options()).unwrap(),
+    );
+}
+
+#[test]
+fn test_camt053_rejects_excess_precision() {
+    let mut entries = entries();
+    entries[0].gross += 1;
+    let statement = Statement::new(
+        BUSINESS,
+        Period::Ledgers {
+            first: 100,
+            last: 200,
+        },
+        entries,
+    );
+    assert_eq!(
+        camt053(&statement, &options()),
+        Err(ExportError::Precision {
+            token: USDC.to_string(),
+            amount: 3_315_000_001,
+        })
+    );
+}
@@ -0,0 +1,60 @@
+use ed25519_dalek::SigningKey;
+use payments_client::{ClientError, Invocation, PaymentsClient, ScType};
+
+use crate::{sandbox::Sandbox, CliError};
+
+/// Where commands run: the deployed contract, or the sandbox for dry runs.
+pub trait Ledger {
+    /// Builds the contract invocations commands make
+    fn contract(&self) -> &PaymentsClient;
+
+    /// Call an entrypoint for its result without changing the ledger
+    fn read<T: ScType>(&self, invocation: Invocation<'_, T>) -> Result<T, CliError>;
+
+    /// Call an entrypoint that changes the ledger
+    fn write<T: ScType>(&self, invocation: Invocation<'_, T>) -> Result<T, CliError>;
+
+    /// The sandbox, for commands that only make sense on a local ledger
+    fn sandbox(&self) -> Result<&Sandbox, CliError>;
+}
+
+/// The deployed contract through Soroban RPC. Transactions are signed by a
+/// single account, which must also be the address authorizing each call.
+pub struct Network {
+    client: PaymentsClient,
+    key: SigningKey,
+}
+
+impl Network {
+    pub fn new(client: PaymentsClient, key: SigningKey) -> Network {
+        Network { client, key }
+    }
+}
+
+impl Ledger for Network {
+    fn contract(&self) -> &PaymentsClient {
+        &self.client
+    }
+
+    fn read<T: ScType>(&self, invocation: Invocation<'_, T>) -> Result<T, CliError> {
+        let source = self.key.verifying_key().to_bytes();
+        Ok(invocation.simulate(&source)?.result)
+    }
+
+    fn write<T: ScType>(&self, invocation: Invocation<'_, T>) -> Result<T, CliError> {
+        Ok(invocation.submit(&self.key)?)
+    }
+
+    fn sandbox(&self) -> Result<&Sandbox, CliError> {
+        Err(CliError::Usage("this command only runs with --dry-run"))
+    }
+}
+
+impl From<ClientError> for CliError {
+    fn from(error: ClientError) -> CliError {
+        match error {
+            ClientError::Contract(error) => CliError::Contract(error),
+            other => CliError::Network(other),
+        }
+    }
+}
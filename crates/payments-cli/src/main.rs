@@ -0,0 +1,602 @@
+//! Operate the payment request contract from the command line.
+//!
+//! Commands call the deployed contract through Soroban RPC: reads are
+//! simulated, writes are simulated, signed with `--secret-key` and
+//! submitted. With `--dry-run` they run against a local sandbox ledger
+//! stored in a snapshot file instead, so operators can rehearse a sequence
+//! of calls (with all authorizations mocked) before submitting the real
+//! transactions.
+
+mod ledger;
+mod output;
+mod sandbox;
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ed25519_dalek::SigningKey;
+use payment_links::{qr, PaymentLink};
+use payment_requests::Error;
+use payments_client::{
+    types::{Address as ClientAddress, AmountBounds, RequestKind},
+    ClientError, PaymentsClient,
+};
+use serde_json::{json, Value};
+use soroban_sdk::{
+    testutils::Address as _,
+    token::{Client as TokenClient, StellarAssetClient},
+    xdr::ScErrorType,
+    Address,
+};
+
+use ledger::{Ledger, Network};
+use output::Format;
+use sandbox::Sandbox;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "payments-cli",
+    version,
+    about = "Operate the payment request contract"
+)]
+struct Cli {
+    #[command(flatten)]
+    network: NetworkArgs,
+
+    /// Run against the local sandbox ledger instead of the network
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Snapshot file holding the sandbox ledger
+    #[arg(long, global = true, default_value = "payments-sandbox.json")]
+    state: PathBuf,
+
+    /// With `--dry-run`, leave the saved sandbox ledger unchanged
+    #[arg(long, global = true, requires = "dry_run")]
+    no_save: bool,
+
+    /// With `--dry-run`, the ledger timestamp to run the command at
+    #[arg(long, global = true, requires = "dry_run")]
+    timestamp: Option<u64>,
+
+    /// Output format
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    output: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Args)]
+struct NetworkArgs {
+    /// Soroban RPC endpoint
+    #[arg(long, global = true, env = "PAYMENTS_RPC_URL")]
+    rpc_url: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        env = "PAYMENTS_NETWORK_PASSPHRASE",
+        default_value = "Test SDF Network ; September 2015"
+    )]
+    network_passphrase: String,
+
+    /// Payment contract to call; with `--dry-run` the sandbox stands in
+    /// for it
+    #[arg(long, global = true, env = "PAYMENTS_CONTRACT", value_parser = parse_address)]
+    contract: Option<String>,
+
+    /// `S...` secret key that signs and pays for transactions. Its account
+    /// is also the address that authorizes calls, so it must be the payer,
+    /// owner or caller the command names.
+    #[arg(
+        long,
+        global = true,
+        env = "PAYMENTS_SECRET_KEY",
+        hide_env_values = true
+    )]
+    secret_key: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+enum Kind {
+    Fixed,
+    Open,
+    Tippable,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Initialize the contract
+    Initialize {
+        #[arg(long, value_parser = parse_address)]
+        owner: String,
+        #[arg(long, value_parser = parse_basis_points)]
+        fee_bps: u32,
+        #[arg(long, value_parser = parse_address)]
+        native_token: String,
+    },
+    /// Register a business
+    RegisterBusiness {
+        #[arg(long)]
+        name: String,
+        #[arg(long, value_parser = parse_address)]
+        owner: String,
+        #[arg(long, value_parser = parse_address)]
+        fee_recipient: String,
+        #[arg(long, value_parser = parse_basis_points)]
+        fee_bps: u32,
+    },
+    /// Create a payment request
+    CreateRequest {
+        #[arg(long)]
+        business: String,
+        #[arg(long, value_enum, default_value_t = Kind::Fixed)]
+        kind: Kind,
+        /// Amount in token base units (not used by open requests)
+        #[arg(long)]
+        amount: Option<i128>,
+        #[arg(long, default_value = "")]
+        description: String,
+        #[arg(long, default_value = "XLM")]
+        denomination: String,
+        /// Address allowed to pay; repeat for several payers
+        #[arg(long = "payer", value_parser = parse_address, required = true)]
+        payers: Vec<String>,
+        #[arg(long, value_parser = parse_address)]
+        requester: String,
+        /// Override the business's fee
+        #[arg(long, value_parser = parse_basis_points)]
+        fee_bps: Option<u32>,
+        /// Lower bound for open requests
+        #[arg(long)]
+        min_amount: Option<i128>,
+        /// Upper bound for open requests
+        #[arg(long)]
+        max_amount: Option<i128>,
+    },
+    /// Pay a request; `--amount` pays an installment or an open amount
+    Execute {
+        #[arg(long)]
+        payment_id: u64,
+        #[arg(long, value_parser = parse_address)]
+        payer: String,
+        #[arg(long, value_parser = parse_address)]
+        token: String,
+        #[arg(long, conflicts_with = "tip")]
+        amount: Option<i128>,
+        #[arg(long)]
+        tip: Option<i128>,
+    },
+    /// Cancel a pending request
+    Cancel {
+        #[arg(long)]
+        payment_id: u64,
+        #[arg(long, value_parser = parse_address)]
+        caller: String,
+    },
+    /// Refund part or all of a payer's contribution
+    Refund {
+        #[arg(long)]
+        payment_id: u64,
+        #[arg(long, value_parser = parse_address)]
+        payer: String,
+        #[arg(long)]
+        amount: i128,
+    },
+    /// Show a payment request
+    GetRequest {
+        #[arg(long)]
+        payment_id: u64,
+    },
+    /// Show a business configuration
+    GetBusiness {
+        #[arg(long)]
+        name: String,
+    },
+    /// Show an address's payment history
+    GetHistory {
+        #[arg(long, value_parser = parse_address)]
+        address: String,
+    },
+    /// Show how much each payer contributed to a request
+    GetContributions {
+        #[arg(long)]
+        payment_id: u64,
+    },
+    /// Print a SEP-7 URI that pays a request in full
+    PaymentLink {
+        #[arg(long)]
+        payment_id: u64,
+        #[arg(long, value_parser = parse_address)]
+        token: String,
+        /// Payer to pre-fill; wallets ask for one otherwise
+        #[arg(long, value_parser = parse_address)]
+        payer: Option<String>,
+        /// Also write the URI as a QR code to this `.svg` or `.png` file
+        #[arg(long)]
+        qr: Option<PathBuf>,
+    },
+    /// Sandbox only: deploy a test token administered by `admin`
+    DeployToken {
+        #[arg(long, value_parser = parse_address)]
+        admin: String,
+    },
+    /// Sandbox only: mint test tokens
+    Mint {
+        #[arg(long, value_parser = parse_address)]
+        token: String,
+        #[arg(long, value_parser = parse_address)]
+        to: String,
+        #[arg(long)]
+        amount: i128,
+    },
+    /// Sandbox only: generate a fresh account-less address
+    NewAddress,
+    /// Sandbox only: show a token balance
+    Balance {
+        #[arg(long, value_parser = parse_address)]
+        token: String,
+        #[arg(long, value_parser = parse_address)]
+        address: String,
+    },
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    Contract(Error),
+    Host(String),
+    Link(String),
+    Network(ClientError),
+    State(String),
+    Token(String),
+    Usage(&'static str),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Contract(error) => {
+                write!(f, "contract error: {error:?} (#{})", *error as u32)
+            }
+            CliError::Host(message) => write!(f, "invocation failed: {message}"),
+            CliError::Link(message) => write!(f, "payment link: {message}"),
+            CliError::Network(error) => write!(f, "network: {error}"),
+            CliError::State(message) => write!(f, "sandbox state: {message}"),
+            CliError::Token(message) => write!(f, "token: {message}"),
+            CliError::Usage(message) => f.write_str(message),
+        }
+    }
+}
+
+fn parse_address(value: &str) -> Result<String, String> {
+    match stellar_strkey::Strkey::from_string(value) {
+        Ok(stellar_strkey::Strkey::PublicKeyEd25519(_))
+        | Ok(stellar_strkey::Strkey::Contract(_)) => Ok(value.to_string()),
+        _ => Err("expected a G... account or C... contract address".to_string()),
+    }
+}
+
+fn parse_basis_points(value: &str) -> Result<u32, String> {
+    let bps: u32 = value
+        .parse()
+        .map_err(|_| "expected basis points (0-10000)".to_string())?;
+    if bps > 10_000 {
+        return Err("basis points cannot exceed 10000".to_string());
+    }
+    Ok(bps)
+}
+
+fn parse_secret_key(value: &str) -> Result<SigningKey, CliError> {
+    stellar_strkey::ed25519::PrivateKey::from_string(value)
+        .map(|key| SigningKey::from_bytes(&key.0))
+        .map_err(|_| CliError::Usage("--secret-key must be an S... secret key"))
+}
+
+// Flattens an SDK `try_` result into a CLI error
+fn invoke<T, C, H: fmt::Debug>(
+    result: Result<Result<T, C>, Result<Error, H>>,
+) -> Result<T, CliError> {
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(_)) => Err(CliError::Host("unexpected return value".to_string())),
+        Err(Ok(error)) => Err(CliError::Contract(error)),
+        Err(Err(error)) => Err(CliError::Host(format!("{error:?}"))),
+    }
+}
+
+fn client_address(strkey: &str) -> ClientAddress {
+    strkey.parse().expect("addresses are validated by clap")
+}
+
+// Token calls only exist in the sandbox, where any address can be passed
+fn token_call<T, C, H>(
+    token: &str,
+    result: Result<Result<T, C>, Result<soroban_sdk::Error, H>>,
+) -> Result<T, CliError> {
+    match result {
+        Ok(Ok(value)) => Ok(value),
+        Err(Ok(error)) if error.is_type(ScErrorType::Contract) => {
+            Err(CliError::Token(format!("{token} failed: {error:?}")))
+        }
+        _ => Err(CliError::Token(format!("{token} is not a token contract"))),
+    }
+}
+
+fn write_qr(path: &Path, uri: &str) -> Result<(), CliError> {
+    let bytes = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("svg") => qr::svg(uri, 8).map(std::string::String::into_bytes),
+        Some("png") => qr::png(uri, 8),
+        _ => return Err(CliError::Usage("--qr must name a .svg or .png file")),
+    }
+    .map_err(|error| CliError::Link(error.to_string()))?;
+    std::fs::write(path, bytes)
+        .map_err(|error| CliError::Link(format!("writing {}: {error}", path.display())))
+}
+
+fn run<L: Ledger>(ledger: &L, command: Command) -> Result<Value, CliError> {
+    let contract = ledger.contract();
+
+    let value = match command {
+        Command::Initialize {
+            owner,
+            fee_bps,
+            native_token,
+        } => {
+            ledger.write(contract.initialize(
+                &client_address(&owner),
+                &fee_bps,
+                &client_address(&native_token),
+            ))?;
+            json!({ "contract_id": contract.contract().to_string() })
+        }
+        Command::RegisterBusiness {
+            name,
+            owner,
+            fee_recipient,
+            fee_bps,
+        } => {
+            ledger.write(contract.register_business(
+                &name,
+                &client_address(&owner),
+                &client_address(&fee_recipient),
+                &fee_bps,
+            ))?;
+            output::business_config(&ledger.read(contract.get_business_config(&name))?)
+        }
+        Command::CreateRequest {
+            business,
+            kind,
+            amount,
+            description,
+            denomination,
+            payers,
+            requester,
+            fee_bps,
+            min_amount,
+            max_amount,
+        } => {
+            let authorized_addresses: Vec<ClientAddress> =
+                payers.iter().map(|payer| client_address(payer)).collect();
+            let requester = client_address(&requester);
+
+            let amount = match (kind, amount) {
+                (Kind::Open, _) => 0,
+                (_, Some(amount)) => amount,
+                (_, None) => {
+                    return Err(CliError::Usage(
+                        "--amount is required for fixed and tippable requests",
+                    ))
+                }
+            };
+
+            let payment_id = match kind {
+                Kind::Fixed => ledger.write(contract.create_payment_request(
+                    &amount,
+                    &business,
+                    &description,
+                    &denomination,
+                    &authorized_addresses,
+                    &requester,
+                    &fee_bps,
+                ))?,
+                Kind::Tippable => ledger.write(contract.create_tippable_request(
+                    &amount,
+                    &business,
+                    &description,
+                    &denomination,
+                    &authorized_addresses,
+                    &requester,
+                    &fee_bps,
+                ))?,
+                Kind::Open => ledger.write(contract.create_open_payment_request(
+                    &business,
+                    &description,
+                    &denomination,
+                    &authorized_addresses,
+                    &requester,
+                    &fee_bps,
+                    &AmountBounds {
+                        min_amount,
+                        max_amount,
+                    },
+                ))?,
+            };
+            output::payment_request(&ledger.read(contract.get_payment_request(&payment_id))?)
+        }
+        Command::Execute {
+            payment_id,
+            payer,
+            token,
+            amount,
+            tip,
+        } => {
+            let request = ledger.read(contract.get_payment_request(&payment_id))?;
+            let (payer, token) = (client_address(&payer), client_address(&token));
+            match (amount, tip) {
+                (_, Some(tip)) => ledger.write(contract.execute_payment_with_tip(
+                    &payment_id,
+                    &payer,
+                    &token,
+                    &tip,
+                ))?,
+                (Some(amount), _) if matches!(request.kind, RequestKind::OpenAmount(_)) => ledger
+                    .write(
+                    contract.execute_open_payment(&payment_id, &payer, &token, &amount),
+                )?,
+                (Some(amount), _) => ledger.write(contract.execute_partial_payment(
+                    &payment_id,
+                    &payer,
+                    &token,
+                    &amount,
+                ))?,
+                (None, None) => {
+                    ledger.write(contract.execute_payment(&payment_id, &payer, &token))?
+                }
+            }
+            output::payment_request(&ledger.read(contract.get_payment_request(&payment_id))?)
+        }
+        Command::Cancel { payment_id, caller } => {
+            ledger.write(contract.cancel_payment_request(&payment_id, &client_address(&caller)))?;
+            output::payment_request(&ledger.read(contract.get_payment_request(&payment_id))?)
+        }
+        Command::Refund {
+            payment_id,
+            payer,
+            amount,
+        } => {
+            ledger.write(contract.refund_payment(&payment_id, &client_address(&payer), &amount))?;
+            output::payment_request(&ledger.read(contract.get_payment_request(&payment_id))?)
+        }
+        Command::GetRequest { payment_id } => {
+            output::payment_request(&ledger.read(contract.get_payment_request(&payment_id))?)
+        }
+        Command::GetBusiness { name } => {
+            output::business_config(&ledger.read(contract.get_business_config(&name))?)
+        }
+        Command::GetHistory { address } => {
+            output::history(&ledger.read(contract.get_payment_history(&client_address(&address)))?)
+        }
+        Command::GetContributions { payment_id } => {
+            output::contributions(&ledger.read(contract.get_payment_contributions(&payment_id))?)
+        }
+        Command::PaymentLink {
+            payment_id,
+            token,
+            payer,
+            qr,
+        } => {
+            let request = ledger.read(contract.get_payment_request(&payment_id))?;
+            let link = PaymentLink {
+                contract: *contract.contract(),
+                token: client_address(&token),
+                payer: payer.as_deref().map(client_address),
+                network_passphrase: contract.network_passphrase().to_string(),
+                callback: None,
+            };
+            let uri = link
+                .uri(&request)
+                .map_err(|error| CliError::Link(error.to_string()))?
+                .to_string();
+
+            let mut value = json!({ "uri": uri });
+            if let Some(path) = qr {
+                write_qr(&path, &uri)?;
+                value["qr"] = json!(path.display().to_string());
+            }
+            value
+        }
+        Command::DeployToken { admin } => {
+            let sandbox = ledger.sandbox()?;
+            let token = sandbox
+                .env
+                .register_stellar_asset_contract_v2(sandbox.address(&admin));
+            json!({ "token": sandbox::strkey(&token.address()) })
+        }
+        Command::Mint { token, to, amount } => {
+            let sandbox = ledger.sandbox()?;
+            let (token_id, to) = (sandbox.address(&token), sandbox.address(&to));
+            token_call(
+                &token,
+                StellarAssetClient::new(&sandbox.env, &token_id).try_mint(&to, &amount),
+            )?;
+            let balance = token_call(
+                &token,
+                TokenClient::new(&sandbox.env, &token_id).try_balance(&to),
+            )?;
+            json!({ "balance": balance.to_string() })
+        }
+        Command::NewAddress => {
+            let sandbox = ledger.sandbox()?;
+            json!({ "address": sandbox::strkey(&Address::generate(&sandbox.env)) })
+        }
+        Command::Balance { token, address } => {
+            let sandbox = ledger.sandbox()?;
+            let balance = token_call(
+                &token,
+                TokenClient::new(&sandbox.env, &sandbox.address(&token))
+                    .try_balance(&sandbox.address(&address)),
+            )?;
+            json!({ "balance": balance.to_string() })
+        }
+    };
+    Ok(value)
+}
+
+fn network(args: NetworkArgs) -> Result<Network, CliError> {
+    let (Some(rpc_url), Some(contract), Some(secret_key)) =
+        (args.rpc_url, args.contract, args.secret_key)
+    else {
+        return Err(CliError::Usage(
+            "--rpc-url, --contract and --secret-key are required unless --dry-run is given",
+        ));
+    };
+    let client = PaymentsClient::new(
+        &rpc_url,
+        client_address(&contract),
+        &args.network_passphrase,
+    );
+    Ok(Network::new(client, parse_secret_key(&secret_key)?))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = if cli.dry_run {
+        // Contract logs are printed to stdout by the host; keep them on
+        // stderr so stdout only carries the command result
+        let redirect = gag::Redirect::stdout(std::io::stderr()).ok();
+        let result = Sandbox::open(
+            &cli.state,
+            cli.timestamp,
+            cli.network.contract.as_deref().map(client_address),
+            &cli.network.network_passphrase,
+        )
+        .and_then(|sandbox| {
+            let value = run(&sandbox, cli.command)?;
+            if !cli.no_save {
+                sandbox.save()?;
+            }
+            Ok(value)
+        });
+        drop(redirect);
+        result
+    } else {
+        network(cli.network).and_then(|network| run(&network, cli.command))
+    };
+
+    match result {
+        Ok(value) => {
+            println!("{}", output::render(&value, cli.output));
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use payments_client::types::{
+    Address, BusinessConfig, PaymentHistory, PaymentRequest, PaymentStatus, RequestKind,
+};
+use serde_json::{json, Map as JsonMap, Value};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Format {
+    Json,
+    Table,
+}
+
+/// Render a command result. Amounts are strings so i128 values survive
+/// JSON consumers that only handle 64-bit numbers.
+pub fn render(value: &Value, format: Format) -> String {
+    match format {
+        Format::Json => serde_json::to_string_pretty(value).expect("JSON values always serialize"),
+        Format::Table => table(value),
+    }
+}
+
+pub fn address(value: &Address) -> String {
+    value.to_string()
+}
+
+pub fn payment_request(request: &PaymentRequest) -> Value {
+    let kind = match &request.kind {
+        RequestKind::Fixed => json!({ "type": "fixed" }),
+        RequestKind::OpenAmount(bounds) => json!({
+            "type": "open",
+            "min_amount": bounds.min_amount.map(|v| v.to_string()),
+            "max_amount": bounds.max_amount.map(|v| v.to_string()),
+        }),
+        RequestKind::Tippable => json!({ "type": "tippable" }),
+        RequestKind::FiatPriced => json!({ "type": "fiat_priced" }),
+    };
+    json!({
+        "id": request.id,
+        "business_name": request.business_name,
+        "description": request.description,
+        "denomination": request.denomination,
+        "amount": request.amount.to_string(),
+        "amount_paid": request.amount_paid.to_string(),
+        "tip_amount": request.tip_amount.to_string(),
+        "fee_basis_points": request.fee_percentage,
+        "status": status(&request.status),
+        "kind": kind,
+        "requester": address(&request.requester),
+        "authorized_addresses": request.authorized_addresses.iter().map(address).collect::<Vec<_>>(),
+        "splits": request.splits.iter().map(|share| json!({
+            "recipient": address(&share.recipient),
+            "share_basis_points": share.share_basis_points,
+        })).collect::<Vec<_>>(),
+        "timestamp": request.timestamp,
+    })
+}
+
+pub fn status(status: &PaymentStatus) -> &'static str {
+    match status {
+        PaymentStatus::Pending => "pending",
+        PaymentStatus::Authorized => "authorized",
+        PaymentStatus::Completed => "completed",
+        PaymentStatus::Failed => "failed",
+        PaymentStatus::Cancelled => "cancelled",
+        PaymentStatus::PartiallyPaid => "partially_paid",
+        PaymentStatus::Refunded => "refunded",
+    }
+}
+
+pub fn business_config(config: &BusinessConfig) -> Value {
+    json!({
+        "name": config.name,
+        "owner": address(&config.owner),
+        "fee_recipient": address(&config.fee_recipient),
+        "default_fee_basis_points": config.default_fee_percentage,
+        "is_active": config.is_active,
+        "fee_basis": format!("{:?}", config.fee_basis),
+    })
+}
+
+pub fn history(history: &PaymentHistory) -> Value {
+    json!({
+        "total_payments": history.total_payments,
+        "total_amount": history.total_amount.to_string(),
+        "last_payment_id": history.last_payment_id,
+    })
+}
+
+pub fn contributions(contributions: &BTreeMap<Address, i128>) -> Value {
+    Value::Array(
+        contributions
+            .iter()
+            .map(|(payer, amount)| json!({ "payer": address(payer), "amount": amount.to_string() }))
+            .collect(),
+    )
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".into(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.is_empty() => "-".into(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(", "),
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| format!("{key}={}", cell(value)))
+            .collect::<Vec<_>>()
+            .join(" "),
+        other => other.to_string(),
+    }
+}
+
+fn grid(header: &[String], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.len()).collect();
+    for row in rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.len());
+        }
+    }
+
+    let line = |cells: &[String]| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = vec![line(header)];
+    out.push(line(
+        &widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>(),
+    ));
+    out.extend(rows.iter().map(|row| line(row)));
+    out.join("\n")
+}
+
+fn table(value: &Value) -> String {
+    match value {
+        Value::Object(fields) => {
+            let rows: Vec<Vec<String>> = fields
+                .iter()
+                .map(|(key, value)| vec![key.clone(), cell(value)])
+                .collect();
+            grid(&["field".into(), "value".into()], &rows)
+        }
+        Value::Array(items) if items.iter().all(Value::is_object) && !items.is_empty() => {
+            let empty = JsonMap::new();
+            let header: Vec<String> = items[0]
+                .as_object()
+                .unwrap_or(&empty)
+                .keys()
+                .cloned()
+                .collect();
+            let rows: Vec<Vec<String>> = items
+                .iter()
+                .map(|item| header.iter().map(|key| cell(&item[key.as_str()])).collect())
+                .collect();
+            grid(&header, &rows)
+        }
+        other => cell(other),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_table_layout() {
+        let value = json!([
+            { "payer": "GA", "amount": "1000" },
+            { "payer": "GBBB", "amount": "5" },
+        ]);
+        assert_eq!(
+            render(&value, Format::Table),
+            "payer  amount\n-----  ------\nGA     1000\nGBBB   5"
+        );
+        assert_eq!(
+            render(&json!({ "id": 7, "splits": [] }), Format::Table),
+            "field   value\n------  -----\nid      7\nsplits  -"
+        );
+    }
+}
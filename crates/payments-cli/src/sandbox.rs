@@ -0,0 +1,147 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use payment_requests::{Error, PaymentContract};
+use payments_client::{types::Address as ClientAddress, Invocation, PaymentsClient, ScType};
+use soroban_sdk::{
+    testutils::{Ledger as _, Snapshot},
+    xdr::{HostFunction, ScVal},
+    Address, Env, String, Symbol, TryFromVal, Val,
+};
+
+use crate::{invoke, ledger::Ledger, CliError};
+
+/// Fixed ID of the payment contract inside every sandbox ledger.
+const CONTRACT_ID: [u8; 32] = [0xca; 32];
+
+fn run_seed() -> [u8; 32] {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut seed = [0u8; 32];
+    seed[..16].copy_from_slice(&nanos.to_le_bytes());
+    seed[16..20].copy_from_slice(&std::process::id().to_le_bytes());
+    seed
+}
+
+/// A local ledger dry runs execute against. State is loaded from and saved
+/// to a snapshot file, so multi-step flows can be rehearsed across
+/// invocations without touching a network.
+pub struct Sandbox {
+    pub env: Env,
+    pub contract_id: Address,
+    // Only builds invocations; the RPC server is never contacted
+    client: PaymentsClient,
+    path: PathBuf,
+}
+
+impl Sandbox {
+    /// Open the sandbox stored at `path`, creating an empty ledger if the file
+    /// does not exist yet. All authorizations are mocked. Invocations built
+    /// for `contract` run against the sandbox's own payment contract.
+    pub fn open(
+        path: &Path,
+        timestamp: Option<u64>,
+        contract: Option<ClientAddress>,
+        network_passphrase: &str,
+    ) -> Result<Sandbox, CliError> {
+        let env = if path.exists() {
+            let snapshot = Snapshot::read_file(path)
+                .map_err(|e| CliError::State(format!("cannot read {}: {e}", path.display())))?;
+            Env::from_snapshot(snapshot)
+        } else {
+            Env::default()
+        };
+
+        // Mocked authorizations consume nonces drawn from the host PRNG, so
+        // every run needs its own seed to avoid colliding with earlier runs
+        env.host()
+            .set_base_prng_seed(run_seed())
+            .map_err(|e| CliError::State(format!("cannot prepare sandbox: {e:?}")))?;
+        env.mock_all_auths();
+
+        if let Some(timestamp) = timestamp {
+            env.ledger().set_timestamp(timestamp);
+        }
+
+        // Native contracts are not part of the ledger state, so the code is
+        // attached to the same ID on every run
+        let strkey = stellar_strkey::Contract(CONTRACT_ID).to_string();
+        let contract_id = Address::from_string(&String::from_str(&env, &strkey));
+        env.register_contract(Some(&contract_id), PaymentContract);
+
+        Ok(Sandbox {
+            env,
+            contract_id,
+            client: PaymentsClient::new(
+                "",
+                contract.unwrap_or(ClientAddress::Contract(CONTRACT_ID)),
+                network_passphrase,
+            ),
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Parse an address given on the command line (`G...` or `C...`)
+    pub fn address(&self, strkey: &str) -> Address {
+        Address::from_string(&String::from_str(&self.env, strkey))
+    }
+
+    /// Persist the ledger so the next invocation continues from it
+    pub fn save(&self) -> Result<(), CliError> {
+        self.env
+            .to_snapshot()
+            .write_file(&self.path)
+            .map_err(|e| CliError::State(format!("cannot write {}: {e}", self.path.display())))
+    }
+}
+
+/// Strkey of an address created in the sandbox
+pub fn strkey(address: &Address) -> std::string::String {
+    address.to_string().to_string()
+}
+
+impl Ledger for Sandbox {
+    fn contract(&self) -> &PaymentsClient {
+        &self.client
+    }
+
+    fn read<T: ScType>(&self, invocation: Invocation<'_, T>) -> Result<T, CliError> {
+        self.write(invocation)
+    }
+
+    // Runs the invocation in the sandbox `Env`, converting arguments and
+    // the result between the client's and the SDK's representations
+    fn write<T: ScType>(&self, invocation: Invocation<'_, T>) -> Result<T, CliError> {
+        let env = &self.env;
+        let conversion = |error| CliError::Host(format!("cannot convert value: {error:?}"));
+
+        let HostFunction::InvokeContract(call) = invocation.host_function() else {
+            unreachable!("invocations call a contract function");
+        };
+        let mut args = soroban_sdk::Vec::<Val>::new(env);
+        for arg in call.args.iter() {
+            args.push_back(Val::try_from_val(env, arg).map_err(conversion)?);
+        }
+        let function = Symbol::new(
+            env,
+            &call
+                .function_name
+                .0
+                .to_utf8_string()
+                .expect("function names are ASCII"),
+        );
+
+        let value =
+            invoke(env.try_invoke_contract::<Val, Error>(&self.contract_id, &function, args))?;
+        let value = ScVal::try_from_val(env, &value).map_err(conversion)?;
+        T::from_scval(&value).map_err(|error| CliError::Host(error.to_string()))
+    }
+
+    fn sandbox(&self) -> Result<&Sandbox, CliError> {
+        Ok(self)
+    }
+}
@@ -0,0 +1,207 @@
+use std::{path::Path, process::Command};
+
+use serde_json::Value;
+
+struct Cli<'a> {
+    state: &'a Path,
+}
+
+impl Cli<'_> {
+    fn command(&self, args: &[&str]) -> std::process::Output {
+        Command::new(env!("CARGO_BIN_EXE_payments-cli"))
+            .arg("--dry-run")
+            .arg("--state")
+            .arg(self.state)
+            .args(["--output", "json"])
+            .args(args)
+            .output()
+            .expect("failed to run payments-cli")
+    }
+
+    fn json(&self, args: &[&str]) -> Value {
+        let output = self.command(args);
+        assert!(
+            output.status.success(),
+            "{args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).expect("stdout is not JSON")
+    }
+
+    fn field(&self, args: &[&str], field: &str) -> String {
+        self.json(args)[field]
+            .as_str()
+            .expect("missing field")
+            .to_string()
+    }
+}
+
+#[test]
+fn test_payment_flow_across_invocations() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("sandbox.json");
+    let cli = Cli { state: &state };
+
+    let owner = cli.field(&["new-address"], "address");
+    let payer = cli.field(&["new-address"], "address");
+    let token = cli.field(&["deploy-token", "--admin", &owner], "token");
+
+    cli.json(&[
+        "initialize",
+        "--owner",
+        &owner,
+        "--fee-bps",
+        "100",
+        "--native-token",
+        &token,
+    ]);
+    let business = cli.json(&[
+        "register-business",
+        "--name",
+        "acme",
+        "--owner",
+        &owner,
+        "--fee-recipient",
+        &owner,
+        "--fee-bps",
+        "50",
+    ]);
+    assert_eq!(business["default_fee_basis_points"], 50);
+    cli.json(&[
+        "mint", "--token", &token, "--to", &payer, "--amount", "5000",
+    ]);
+
+    let request = cli.json(&[
+        "create-request",
+        "--business",
+        "acme",
+        "--amount",
+        "1000",
+        "--payer",
+        &payer,
+        "--requester",
+        &owner,
+    ]);
+    assert_eq!(request["id"], 1);
+    assert_eq!(request["status"], "pending");
+
+    let qr = dir.path().join("request-1.png");
+    let link = cli.json(&[
+        "payment-link",
+        "--payment-id",
+        "1",
+        "--token",
+        &token,
+        "--qr",
+        qr.to_str().unwrap(),
+    ]);
+    let uri = link["uri"].as_str().unwrap();
+    assert!(uri.starts_with("web+stellar:tx?xdr="), "{uri}");
+    assert!(uri.contains("&msg=acme&"), "{uri}");
+    assert!(std::fs::read(&qr).unwrap().starts_with(b"\x89PNG"));
+
+    // `--no-save` leaves the saved ledger untouched
+    let preview = cli.json(&[
+        "--no-save",
+        "execute",
+        "--payment-id",
+        "1",
+        "--payer",
+        &payer,
+        "--token",
+        &token,
+    ]);
+    assert_eq!(preview["status"], "completed");
+    assert_eq!(
+        cli.json(&["get-request", "--payment-id", "1"])["status"],
+        "pending"
+    );
+
+    let paid = cli.json(&[
+        "execute",
+        "--payment-id",
+        "1",
+        "--payer",
+        &payer,
+        "--token",
+        &token,
+    ]);
+    assert_eq!(paid["status"], "completed");
+    assert_eq!(paid["amount_paid"], "1000");
+    assert_eq!(
+        cli.field(
+            &["balance", "--token", &token, "--address", &payer],
+            "balance"
+        ),
+        "4000"
+    );
+
+    let contributions = cli.json(&["get-contributions", "--payment-id", "1"]);
+    assert_eq!(contributions[0]["payer"], payer.as_str());
+    assert_eq!(contributions[0]["amount"], "1000");
+
+    let refunded = cli.json(&[
+        "refund",
+        "--payment-id",
+        "1",
+        "--payer",
+        &payer,
+        "--amount",
+        "1000",
+    ]);
+    assert_eq!(refunded["status"], "refunded");
+    assert_eq!(
+        cli.field(
+            &["balance", "--token", &token, "--address", &payer],
+            "balance"
+        ),
+        "5000"
+    );
+}
+
+#[test]
+fn test_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("sandbox.json");
+    let cli = Cli { state: &state };
+
+    let output = cli.command(&["get-request", "--payment-id", "9"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("PaymentNotFound (#2)"));
+
+    let owner = cli.field(&["new-address"], "address");
+    let output = cli.command(&[
+        "create-request",
+        "--business",
+        "acme",
+        "--payer",
+        &owner,
+        "--requester",
+        &owner,
+    ]);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--amount is required"));
+
+    // Malformed addresses are rejected before touching the ledger
+    let output = cli.command(&["get-history", "--address", "not-an-address"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    // Token commands report addresses that are not token contracts
+    let output = cli.command(&["mint", "--token", &owner, "--to", &owner, "--amount", "5"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is not a token contract"), "{stderr}");
+    let output = cli.command(&["balance", "--token", &owner, "--address", &owner]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_network_mode_needs_an_endpoint() {
+    let output = Command::new(env!("CARGO_BIN_EXE_payments-cli"))
+        .args(["get-request", "--payment-id", "1"])
+        .env_remove("PAYMENTS_RPC_URL")
+        .output()
+        .expect("failed to run payments-cli");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--rpc-url"));
+}
@@ -0,0 +1,268 @@
+//! Run the CLI against a mock RPC server that executes invocations on the
+//! real contract.
+
+use std::{
+    process::Command,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use ed25519_dalek::{Signature, SigningKey, Verifier};
+use payment_requests::{PaymentContract, PaymentContractClient};
+use serde_json::{json, Value};
+use soroban_sdk::{
+    testutils::Address as _,
+    xdr::{
+        AccountEntry, AccountEntryExt, AccountId, ExtensionPoint, HostFunction, LedgerEntryChanges,
+        LedgerEntryData, LedgerFootprint, Limits, OperationBody, PublicKey, ReadXdr, ScVal,
+        SequenceNumber, SorobanResources, SorobanTransactionData, SorobanTransactionMeta,
+        SorobanTransactionMetaExt, Thresholds, TransactionEnvelope, TransactionMeta,
+        TransactionMetaV3, Uint256, WriteXdr,
+    },
+    Address, Env, String as SorobanString, Symbol, TryFromVal, Val,
+};
+
+const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+const CONTRACT_ID: [u8; 32] = [7; 32];
+
+fn signer() -> SigningKey {
+    SigningKey::from_bytes(&[1; 32])
+}
+
+fn contract_strkey() -> String {
+    stellar_strkey::Contract(CONTRACT_ID).to_string()
+}
+
+fn account_strkey(key: &SigningKey) -> String {
+    stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()).to_string()
+}
+
+fn contract_address(env: &Env) -> Address {
+    Address::from_string(&SorobanString::from_str(env, &contract_strkey()))
+}
+
+fn invoke(env: &Env, function: &HostFunction) -> Result<ScVal, String> {
+    let HostFunction::InvokeContract(call) = function else {
+        return Err("unsupported host function".to_string());
+    };
+    let mut args = soroban_sdk::Vec::<Val>::new(env);
+    for arg in call.args.iter() {
+        args.push_back(Val::try_from_val(env, arg).unwrap());
+    }
+    let contract = contract_address(env);
+    let function = Symbol::new(env, &call.function_name.0.to_utf8_string().unwrap());
+    match env.try_invoke_contract::<Val, soroban_sdk::Error>(&contract, &function, args) {
+        Ok(Ok(value)) => Ok(ScVal::try_from_val(env, &value).unwrap()),
+        other => Err(format!("HostError: {other:?}")),
+    }
+}
+
+fn host_function(params: &Value) -> (TransactionEnvelope, HostFunction) {
+    let envelope = TransactionEnvelope::from_xdr_base64(
+        params["transaction"].as_str().unwrap(),
+        Limits::none(),
+    )
+    .unwrap();
+    let TransactionEnvelope::Tx(tx) = &envelope else {
+        panic!("expected a v1 envelope");
+    };
+    let OperationBody::InvokeHostFunction(op) = &tx.tx.operations[0].body else {
+        panic!("expected a contract invocation");
+    };
+    let function = op.host_function.clone();
+    (envelope, function)
+}
+
+fn account_entry(key: [u8; 32]) -> String {
+    LedgerEntryData::Account(AccountEntry {
+        account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key))),
+        balance: 100_000_000,
+        seq_num: SequenceNumber(41),
+        num_sub_entries: 0,
+        inflation_dest: None,
+        flags: 0,
+        home_domain: Default::default(),
+        thresholds: Thresholds([1, 0, 0, 0]),
+        signers: Default::default(),
+        ext: AccountEntryExt::V0,
+    })
+    .to_xdr_base64(Limits::none())
+    .unwrap()
+}
+
+fn transaction_data() -> String {
+    SorobanTransactionData {
+        ext: ExtensionPoint::V0,
+        resources: SorobanResources {
+            footprint: LedgerFootprint {
+                read_only: Default::default(),
+                read_write: Default::default(),
+            },
+            instructions: 1_000_000,
+            read_bytes: 1_000,
+            write_bytes: 1_000,
+        },
+        resource_fee: 5_000,
+    }
+    .to_xdr_base64(Limits::none())
+    .unwrap()
+}
+
+fn result_meta(value: ScVal) -> String {
+    TransactionMeta::V3(TransactionMetaV3 {
+        ext: ExtensionPoint::V0,
+        tx_changes_before: LedgerEntryChanges(Default::default()),
+        operations: Default::default(),
+        tx_changes_after: LedgerEntryChanges(Default::default()),
+        soroban_meta: Some(SorobanTransactionMeta {
+            ext: SorobanTransactionMetaExt::V0,
+            events: Default::default(),
+            return_value: value,
+            diagnostic_events: Default::default(),
+        }),
+    })
+    .to_xdr_base64(Limits::none())
+    .unwrap()
+}
+
+/// Serves one account and the payment contract with an `acme` business.
+/// Simulations run on a copy of the ledger; submissions must be signed by
+/// the account and are applied to the ledger itself.
+fn spawn(account: [u8; 32], submitted: Arc<Mutex<Vec<String>>>) -> String {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+
+    thread::spawn(move || {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract = contract_address(&env);
+        env.register_contract(Some(&contract), PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract);
+        let owner = Address::generate(&env);
+        client.initialize(&owner, &100, &Address::generate(&env));
+        client.register_business(&SorobanString::from_str(&env, "acme"), &owner, &owner, &250);
+
+        let mut results = Vec::new();
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            let body: Value = serde_json::from_str(&body).unwrap();
+            let params = &body["params"];
+
+            let mut response = match body["method"].as_str().unwrap() {
+                "getLedgerEntries" => json!({ "result": {
+                    "entries": [{ "xdr": account_entry(account) }],
+                    "latestLedger": 100,
+                }}),
+                "simulateTransaction" => {
+                    let fork = Env::from_snapshot(env.to_snapshot());
+                    fork.mock_all_auths();
+                    fork.register_contract(Some(&contract_address(&fork)), PaymentContract);
+                    let result = match invoke(&fork, &host_function(params).1) {
+                        Ok(value) => json!({
+                            "transactionData": transaction_data(),
+                            "minResourceFee": "5000",
+                            "results": [{ "auth": [], "xdr": value.to_xdr_base64(Limits::none()).unwrap() }],
+                            "latestLedger": 100,
+                        }),
+                        Err(error) => json!({ "error": error, "latestLedger": 100 }),
+                    };
+                    json!({ "result": result })
+                }
+                "sendTransaction" => {
+                    let (envelope, function) = host_function(params);
+                    let TransactionEnvelope::Tx(signed) = &envelope else {
+                        unreachable!()
+                    };
+                    let hash = payments_client::transaction::hash(&signed.tx, PASSPHRASE).unwrap();
+                    let signature = Signature::from_slice(&signed.signatures[0].signature).unwrap();
+                    ed25519_dalek::VerifyingKey::from_bytes(&account)
+                        .unwrap()
+                        .verify(&hash, &signature)
+                        .expect("transactions are signed by the account");
+                    let HostFunction::InvokeContract(call) = &function else {
+                        unreachable!()
+                    };
+                    submitted
+                        .lock()
+                        .unwrap()
+                        .push(call.function_name.0.to_utf8_string().unwrap());
+                    results.push(invoke(&env, &function).unwrap());
+                    let hash = (results.len() - 1).to_string();
+                    json!({ "result": { "status": "PENDING", "hash": hash, "latestLedger": 100 } })
+                }
+                "getTransaction" => {
+                    let index: usize = params["hash"].as_str().unwrap().parse().unwrap();
+                    json!({ "result": {
+                        "status": "SUCCESS",
+                        "ledger": 101,
+                        "resultMetaXdr": result_meta(results[index].clone()),
+                    }})
+                }
+                other => panic!("unexpected method {other}"),
+            };
+            response["jsonrpc"] = json!("2.0");
+            response["id"] = body["id"].clone();
+            let header = tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap();
+            request
+                .respond(tiny_http::Response::from_string(response.to_string()).with_header(header))
+                .unwrap();
+        }
+    });
+    url
+}
+
+#[test]
+fn test_commands_simulate_and_submit() {
+    let key = signer();
+    let submitted = Arc::new(Mutex::new(Vec::new()));
+    let url = spawn(key.verifying_key().to_bytes(), submitted.clone());
+    let account = account_strkey(&key);
+    let secret_key = stellar_strkey::ed25519::PrivateKey(key.to_bytes()).to_string();
+
+    let cli = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_payments-cli"))
+            .args(["--rpc-url", &url, "--contract", &contract_strkey()])
+            .args(["--output", "json"])
+            .env("PAYMENTS_SECRET_KEY", &secret_key)
+            .args(args)
+            .output()
+            .expect("failed to run payments-cli");
+        (
+            output.status.code(),
+            serde_json::from_slice::<Value>(&output.stdout).ok(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        )
+    };
+
+    // Reads are only simulated
+    let (_, business, _) = cli(&["get-business", "--name", "acme"]);
+    assert_eq!(business.unwrap()["default_fee_basis_points"], 250);
+    assert!(submitted.lock().unwrap().is_empty());
+
+    let (_, request, stderr) = cli(&[
+        "create-request",
+        "--business",
+        "acme",
+        "--amount",
+        "1000",
+        "--payer",
+        &account,
+        "--requester",
+        &account,
+    ]);
+    let request = request.unwrap_or_else(|| panic!("{stderr}"));
+    assert_eq!(request["id"], 1);
+    assert_eq!(request["status"], "pending");
+    assert_eq!(*submitted.lock().unwrap(), ["create_payment_request"]);
+
+    // Contract errors surface the same way as in the sandbox
+    let (code, _, stderr) = cli(&["get-request", "--payment-id", "9"]);
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("PaymentNotFound (#2)"), "{stderr}");
+
+    // Sandbox-only commands are refused
+    let (code, _, stderr) = cli(&["new-address"]);
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("--dry-run"), "{stderr}");
+}
@@ -0,0 +1,212 @@
+//! Typed wrappers for every entrypoint of the payment request contract.
+//!
+//! Each wrapper takes the same arguments as the contract function and
+//! returns an [`Invocation`] that can be built, simulated or submitted.
+
+use std::collections::BTreeMap;
+
+use stellar_xdr::curr::{
+    ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef, ScSpecTypeResult,
+};
+
+use crate::{error::ContractError, scval::ScType, types::*, Invocation, PaymentsClient};
+
+/// How a contract function's return type maps to the value the client hands
+/// back. `Result<T, Error>` functions return `T` and surface the error as
+/// [`crate::ClientError::Contract`].
+pub trait ContractReturn {
+    type Output: ScType;
+
+    /// Outputs of the function in the contract spec
+    fn outputs() -> Vec<ScSpecTypeDef>;
+}
+
+impl<T: ScType> ContractReturn for T {
+    type Output = T;
+
+    fn outputs() -> Vec<ScSpecTypeDef> {
+        vec![T::spec()]
+    }
+}
+
+impl<T: ScType> ContractReturn for Result<T, ContractError> {
+    type Output = T;
+
+    fn outputs() -> Vec<ScSpecTypeDef> {
+        vec![ScSpecTypeDef::Result(Box::new(ScSpecTypeResult {
+            ok_type: Box::new(T::spec()),
+            error_type: Box::new(ScSpecTypeDef::Error),
+        }))]
+    }
+}
+
+/// Marker for functions without a return value
+pub struct NoReturn;
+
+impl ContractReturn for NoReturn {
+    type Output = ();
+
+    fn outputs() -> Vec<ScSpecTypeDef> {
+        Vec::new()
+    }
+}
+
+macro_rules! returns {
+    () => {
+        NoReturn
+    };
+    ($ret:ty) => {
+        $ret
+    };
+}
+
+fn function_spec(
+    name: &str,
+    inputs: Vec<(&str, ScSpecTypeDef)>,
+    outputs: Vec<ScSpecTypeDef>,
+) -> ScSpecEntry {
+    let inputs: Vec<ScSpecFunctionInputV0> = inputs
+        .into_iter()
+        .map(|(input, type_)| ScSpecFunctionInputV0 {
+            doc: Default::default(),
+            name: input.try_into().expect("argument names fit in the spec"),
+            type_,
+        })
+        .collect();
+    ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+        doc: Default::default(),
+        name: stellar_xdr::curr::ScSymbol(name.try_into().expect("function names fit in the spec")),
+        inputs: inputs
+            .try_into()
+            .expect("contract functions take at most 10 arguments"),
+        outputs: outputs
+            .try_into()
+            .expect("contract functions return at most one value"),
+    })
+}
+
+macro_rules! entrypoints {
+    ($( fn $name:ident ( $( $arg:ident : $ty:ty ),* $(,)? ) $( -> $ret:ty )? ; )*) => {
+        // Arguments are borrowed exactly as declared, like the SDK's
+        // generated clients
+        #[allow(clippy::ptr_arg, clippy::too_many_arguments)]
+        impl PaymentsClient {
+            $(
+                pub fn $name(
+                    &self,
+                    $( $arg: &$ty, )*
+                ) -> Invocation<'_, <returns!($($ret)?) as ContractReturn>::Output> {
+                    self.invocation(stringify!($name), vec![ $( $arg.to_scval(), )* ])
+                }
+            )*
+        }
+
+        /// Spec entries of every entrypoint the client wraps, without doc
+        /// comments.
+        pub fn function_specs() -> Vec<ScSpecEntry> {
+            vec![
+                $(
+                    function_spec(
+                        stringify!($name),
+                        vec![ $( (stringify!($arg), <$ty as ScType>::spec()), )* ],
+                        <returns!($($ret)?) as ContractReturn>::outputs(),
+                    ),
+                )*
+            ]
+        }
+    };
+}
+
+entrypoints! {
+    // Setup and businesses
+    fn initialize(owner: Address, default_fee_percentage: u32, native_token: Address) -> Result<(), ContractError>;
+    fn register_business(business_name: String, business_owner: Address, fee_recipient: Address, fee_percentage: u32) -> Result<(), ContractError>;
+    fn update_business_status(business_name: String, is_active: bool, caller: Address) -> Result<(), ContractError>;
+    fn set_fee_basis(business_name: String, fee_basis: FeeBasis, caller: Address) -> Result<(), ContractError>;
+    fn get_business_config(business_name: String) -> Result<BusinessConfig, ContractError>;
+
+    // Payment requests
+    fn create_payment_request(amount: i128, business_name: String, description: String, denomination: String, authorized_addresses: Vec<Address>, requester: Address, custom_fee_percentage: Option<u32>) -> Result<u64, ContractError>;
+    fn create_open_payment_request(business_name: String, description: String, denomination: String, authorized_addresses: Vec<Address>, requester: Address, custom_fee_percentage: Option<u32>, bounds: AmountBounds) -> Result<u64, ContractError>;
+    fn create_tippable_request(amount: i128, business_name: String, description: String, denomination: String, authorized_addresses: Vec<Address>, requester: Address, custom_fee_percentage: Option<u32>) -> Result<u64, ContractError>;
+    fn create_invoice_request(business_name: String, description: String, denomination: String, authorized_addresses: Vec<Address>, requester: Address, custom_fee_percentage: Option<u32>, invoice: Invoice) -> Result<u64, ContractError>;
+    fn create_fiat_payment_request(fiat_amount: i128, business_name: String, description: String, denomination: String, authorized_addresses: Vec<Address>, requester: Address, custom_fee_percentage: Option<u32>) -> Result<u64, ContractError>;
+    fn cancel_payment_request(payment_id: u64, caller: Address) -> Result<(), ContractError>;
+    fn set_payment_splits(payment_id: u64, splits: Vec<SplitShare>, caller: Address) -> Result<(), ContractError>;
+    fn get_payment_request(payment_id: u64) -> Result<PaymentRequest, ContractError>;
+    fn get_payment_by_merchant_ref(business_name: String, merchant_reference: String) -> Result<PaymentRequest, ContractError>;
+    fn get_invoice(payment_id: u64) -> Result<Invoice, ContractError>;
+    fn get_invoice_totals(payment_id: u64) -> Result<InvoiceTotals, ContractError>;
+    fn get_fiat_quote(payment_id: u64) -> Result<FiatQuote, ContractError>;
+    fn get_payment_contributions(payment_id: u64) -> BTreeMap<Address, i128>;
+    fn get_payment_history(address: Address) -> PaymentHistory;
+
+    // Payments
+    fn execute_payment(payment_id: u64, payer: Address, token_address: Address) -> Result<(), ContractError>;
+    fn execute_partial_payment(payment_id: u64, payer: Address, token_address: Address, amount: i128) -> Result<(), ContractError>;
+    fn execute_open_payment(payment_id: u64, payer: Address, token_address: Address, amount: i128) -> Result<(), ContractError>;
+    fn execute_payment_with_tip(payment_id: u64, payer: Address, token_address: Address, tip: i128) -> Result<(), ContractError>;
+    fn execute_xlm_payment(payment_id: u64, payer: Address) -> Result<(), ContractError>;
+    fn execute_swap_payment(payment_id: u64, payer: Address, source_token: Address, receive_token: Address, max_source_amount: i128, deadline: u64) -> Result<i128, ContractError>;
+    fn set_swap_adapter(caller: Address, adapter: Address) -> Result<(), ContractError>;
+    fn get_swap_adapter() -> Option<Address>;
+
+    // Signed payment intents
+    fn set_intent_key(payer: Address, public_key: [u8; 32]);
+    fn get_intent_key(payer: Address) -> Option<[u8; 32]>;
+    fn get_intent_nonce(payer: Address) -> u64;
+    fn execute_payment_with_signature(intent: PaymentIntent, signature: [u8; 64]) -> Result<(), ContractError>;
+
+    // Refunds and disputes
+    fn refund_payment(payment_id: u64, payer: Address, amount: i128) -> Result<(), ContractError>;
+    fn get_payment_refunds(payment_id: u64) -> BTreeMap<Address, i128>;
+    fn set_dispute_config(caller: Address, config: DisputeConfig) -> Result<(), ContractError>;
+    fn get_dispute_config() -> Option<DisputeConfig>;
+    fn open_dispute(payment_id: u64, payer: Address, evidence_hash: [u8; 32]) -> Result<(), ContractError>;
+    fn respond_to_dispute(payment_id: u64, payer: Address, evidence_hash: [u8; 32]) -> Result<(), ContractError>;
+    fn resolve_dispute(payment_id: u64, payer: Address, outcome: DisputeOutcome) -> Result<(), ContractError>;
+    fn finalize_dispute(payment_id: u64, payer: Address) -> Result<(), ContractError>;
+    fn pay_dispute_refund(payment_id: u64, payer: Address, caller: Address) -> Result<(), ContractError>;
+    fn get_dispute(payment_id: u64, payer: Address) -> Result<Dispute, ContractError>;
+
+    // Spending limits
+    fn set_spending_limits(caller: Address, limits: SpendingLimits) -> Result<(), ContractError>;
+    fn set_business_limits(business_name: String, limits: SpendingLimits, caller: Address) -> Result<(), ContractError>;
+    fn set_limit_exemption(caller: Address, payer: Address, exempt: bool) -> Result<(), ContractError>;
+    fn get_spending_limits(business_name: String) -> SpendingLimits;
+    fn get_payer_volume(payer: Address, window: u64) -> i128;
+    fn get_business_volume(business_name: String, window: u64) -> i128;
+
+    // Compliance screening
+    fn set_compliance_config(caller: Address, config: ComplianceConfig) -> Result<(), ContractError>;
+    fn get_compliance_config() -> Option<ComplianceConfig>;
+    fn set_denylisted(caller: Address, address: Address, listed: bool) -> Result<(), ContractError>;
+    fn set_allowlisted(caller: Address, address: Address, listed: bool) -> Result<(), ContractError>;
+    fn check_compliance(address: Address) -> bool;
+    fn get_blocked_payment(payment_id: u64) -> Option<BlockedPayment>;
+
+    // Price oracle
+    fn set_oracle_config(caller: Address, config: OracleConfig) -> Result<(), ContractError>;
+    fn get_oracle_config() -> Option<OracleConfig>;
+
+    // Settlement
+    fn set_settlement_config(business_name: String, config: SettlementConfig, caller: Address) -> Result<(), ContractError>;
+    fn get_settlement_config(business_name: String) -> Option<SettlementConfig>;
+    fn get_merchant_balance(business_name: String, token_address: Address) -> i128;
+    fn get_held_proceeds(payment_id: u64) -> i128;
+    fn release_proceeds(payment_id: u64) -> Result<i128, ContractError>;
+    fn get_unsettled_payments(business_name: String, token_address: Address) -> Vec<u64>;
+    fn settle(business_name: String, token_address: Address) -> Result<u64, ContractError>;
+    fn get_settlement(settlement_id: u64) -> Result<SettlementRecord, ContractError>;
+
+    // Streams
+    fn create_stream(sender: Address, recipient: Address, token_address: Address, deposit: i128, start_time: u64, cliff_time: u64, end_time: u64) -> Result<u64, ContractError>;
+    fn withdraw_from_stream(stream_id: u64) -> Result<i128, ContractError>;
+    fn cancel_stream(stream_id: u64) -> Result<i128, ContractError>;
+    fn get_stream(stream_id: u64) -> Result<Stream, ContractError>;
+    fn get_stream_balance(stream_id: u64) -> Result<i128, ContractError>;
+
+    // Anchor ramps
+    fn record_ramp(payment_id: u64, caller: Address, record: RampRecord) -> Result<(), ContractError>;
+    fn get_ramps(payment_id: u64) -> Vec<RampRecord>;
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+pub use payment_core::Error as ContractError;
+
+use crate::scval::DecodeError;
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The contract returned one of its `Error` codes
+    Contract(ContractError),
+    /// The invocation failed in the host for a reason other than a contract error
+    Host(String),
+    /// The network rejected or failed the submitted transaction
+    Transaction {
+        hash: String,
+        status: String,
+    },
+    /// The transaction was not included before polling gave up
+    Timeout {
+        hash: String,
+    },
+    /// JSON-RPC error object returned by the server
+    Rpc {
+        code: i64,
+        message: String,
+    },
+    Http(String),
+    /// The RPC server answered with something the client cannot use
+    Protocol(String),
+    AccountNotFound(String),
+    Xdr(stellar_xdr::curr::Error),
+    Decode(DecodeError),
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::Contract(error) => {
+                write!(f, "contract error: {error:?} (#{})", *error as u32)
+            }
+            ClientError::Host(message) => write!(f, "invocation failed: {message}"),
+            ClientError::Transaction { hash, status } => {
+                write!(f, "transaction {hash} failed with status {status}")
+            }
+            ClientError::Timeout { hash } => write!(f, "transaction {hash} was not confirmed"),
+            ClientError::Rpc { code, message } => write!(f, "rpc error {code}: {message}"),
+            ClientError::Http(message) => write!(f, "http error: {message}"),
+            ClientError::Protocol(message) => write!(f, "unexpected rpc response: {message}"),
+            ClientError::AccountNotFound(account) => write!(f, "account {account} not found"),
+            ClientError::Xdr(error) => write!(f, "xdr error: {error}"),
+            ClientError::Decode(error) => write!(f, "unexpected return value: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<stellar_xdr::curr::Error> for ClientError {
+    fn from(error: stellar_xdr::curr::Error) -> ClientError {
+        ClientError::Xdr(error)
+    }
+}
+
+impl From<DecodeError> for ClientError {
+    fn from(error: DecodeError) -> ClientError {
+        ClientError::Decode(error)
+    }
+}
+
+/// Map a contract error code to the contract's `Error` enum.
+pub fn contract_error(code: u32) -> Option<ContractError> {
+    ContractError::try_from(soroban_sdk::InvokeError::Contract(code)).ok()
+}
+
+/// The contract error code in a failed simulation's error string, where it
+/// shows up as `Error(Contract, #<code>)`. Useful for contracts with error
+/// enums of their own.
+pub fn contract_error_code(message: &str) -> Option<u32> {
+    const MARKER: &str = "Error(Contract, #";
+
+    message.find(MARKER).and_then(|start| {
+        let digits = &message[start + MARKER.len()..];
+        let end = digits.find(|c: char| !c.is_ascii_digit())?;
+        digits[..end].parse().ok()
+    })
+}
+
+/// Classify the error string RPC returns for a failed simulation.
+pub(crate) fn simulation_error(message: &str) -> ClientError {
+    match contract_error_code(message).and_then(contract_error) {
+        Some(error) => ClientError::Contract(error),
+        None => ClientError::Host(message.lines().next().unwrap_or_default().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_simulation_error() {
+        let message = "HostError: Error(Contract, #2)\n\nEvent log (newest first):\n   0: ...";
+        assert!(matches!(
+            simulation_error(message),
+            ClientError::Contract(ContractError::PaymentNotFound)
+        ));
+        assert!(matches!(
+            simulation_error("HostError: Error(Contract, #9999)"),
+            ClientError::Host(_)
+        ));
+        assert!(matches!(
+            simulation_error("HostError: Error(Budget, ExceededLimit)"),
+            ClientError::Host(_)
+        ));
+    }
+}
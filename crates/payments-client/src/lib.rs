@@ -0,0 +1,225 @@
+//! Typed client for backend services calling the payment request contract
+//! through Soroban RPC.
+//!
+//! Entrypoints are wrapped one-to-one with their contract signatures, and the
+//! contract's `#[contracttype]` values are mirrored as plain Rust types that
+//! decode from `ScVal`. The wrappers are checked against the contract's own
+//! spec in the test suite.
+//!
+//! ```no_run
+//! # use payments_client::{PaymentsClient, types::Address};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! # let source = [0u8; 32];
+//! let contract: Address = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE".parse()?;
+//! let client = PaymentsClient::new(
+//!     "https://soroban-testnet.stellar.org",
+//!     contract,
+//!     "Test SDF Network ; September 2015",
+//! );
+//! let request = client.get_payment_request(&1).simulate(&source)?.result;
+//! println!("{:?}", request.status);
+//! # Ok(())
+//! # }
+//! ```
+
+mod contract;
+mod error;
+pub mod rpc;
+mod scval;
+pub mod transaction;
+pub mod types;
+
+use std::{marker::PhantomData, thread, time::Duration};
+
+use ed25519_dalek::SigningKey;
+use stellar_xdr::curr::{
+    HostFunction, InvokeContractArgs, Limits, ReadXdr, ScSymbol, ScVal, Transaction,
+    TransactionEnvelope, TransactionMeta,
+};
+
+pub use contract::{function_specs, ContractReturn, NoReturn};
+pub use error::{contract_error, contract_error_code, ClientError, ContractError};
+pub use rpc::RpcClient;
+pub use scval::{DecodeError, ScType, Symbol, Udt};
+pub use types::type_specs;
+
+pub struct PaymentsClient {
+    rpc: RpcClient,
+    contract: types::Address,
+    network_passphrase: String,
+    poll_interval: Duration,
+    poll_attempts: u32,
+}
+
+impl PaymentsClient {
+    pub fn new(
+        rpc_url: &str,
+        contract: types::Address,
+        network_passphrase: &str,
+    ) -> PaymentsClient {
+        PaymentsClient {
+            rpc: RpcClient::new(rpc_url),
+            contract,
+            network_passphrase: network_passphrase.to_string(),
+            poll_interval: Duration::from_secs(1),
+            poll_attempts: 30,
+        }
+    }
+
+    /// How often and how many times to ask for the result of a submitted
+    /// transaction
+    pub fn with_polling(mut self, interval: Duration, attempts: u32) -> PaymentsClient {
+        self.poll_interval = interval;
+        self.poll_attempts = attempts;
+        self
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn contract(&self) -> &types::Address {
+        &self.contract
+    }
+
+    pub fn network_passphrase(&self) -> &str {
+        &self.network_passphrase
+    }
+
+    /// Send a signed transaction and wait until it is applied. Returns the
+    /// transaction hash and its base64 result meta.
+    pub fn send(&self, envelope: &TransactionEnvelope) -> Result<(String, String), ClientError> {
+        let sent = self.rpc.send_transaction(envelope)?;
+        if !matches!(sent.status.as_str(), "PENDING" | "DUPLICATE") {
+            return Err(ClientError::Transaction {
+                hash: sent.hash,
+                status: sent.status,
+            });
+        }
+
+        for attempt in 0..self.poll_attempts {
+            if attempt > 0 {
+                thread::sleep(self.poll_interval);
+            }
+            let response = self.rpc.get_transaction(&sent.hash)?;
+            match response.status.as_str() {
+                "NOT_FOUND" => continue,
+                "SUCCESS" => {
+                    let meta = response.result_meta_xdr.ok_or_else(|| {
+                        ClientError::Protocol("successful transaction without meta".to_string())
+                    })?;
+                    return Ok((sent.hash, meta));
+                }
+                _ => {
+                    return Err(ClientError::Transaction {
+                        hash: sent.hash,
+                        status: response.status,
+                    })
+                }
+            }
+        }
+        Err(ClientError::Timeout { hash: sent.hash })
+    }
+
+    fn invocation<T>(&self, function: &'static str, args: Vec<ScVal>) -> Invocation<'_, T> {
+        Invocation {
+            client: self,
+            function,
+            args,
+            result: PhantomData,
+        }
+    }
+}
+
+/// A contract call that has not been sent yet.
+pub struct Invocation<'a, T> {
+    client: &'a PaymentsClient,
+    function: &'static str,
+    args: Vec<ScVal>,
+    result: PhantomData<fn() -> T>,
+}
+
+/// Outcome of simulating an invocation.
+#[derive(Clone, Debug)]
+pub struct Simulation<T> {
+    /// Value the contract returned during simulation
+    pub result: T,
+    /// The transaction with resources, fee and authorizations attached,
+    /// ready to be signed
+    pub transaction: Transaction,
+    pub latest_ledger: u32,
+}
+
+impl<T: ScType> Invocation<'_, T> {
+    pub fn host_function(&self) -> HostFunction {
+        HostFunction::InvokeContract(InvokeContractArgs {
+            contract_address: self.client.contract.to_sc_address(),
+            function_name: ScSymbol(
+                self.function
+                    .try_into()
+                    .expect("function names are symbols"),
+            ),
+            args: self
+                .args
+                .clone()
+                .try_into()
+                .expect("contract functions take at most 10 arguments"),
+        })
+    }
+
+    /// Build the unsimulated transaction for `source` using the account's next
+    /// sequence number
+    pub fn build(&self, source: &[u8; 32], sequence: i64) -> Transaction {
+        transaction::invoke_transaction(source, sequence, self.host_function())
+    }
+
+    /// Simulate the call with `source` (an ed25519 public key) as the
+    /// transaction source. Read-only entrypoints only need this.
+    pub fn simulate(&self, source: &[u8; 32]) -> Result<Simulation<T>, ClientError> {
+        let rpc = &self.client.rpc;
+        let sequence = rpc.get_account_sequence(source)? + 1;
+        let transaction = self.build(source, sequence);
+        let simulation = rpc.simulate_transaction(&transaction::unsigned(transaction.clone()))?;
+
+        if let Some(message) = &simulation.error {
+            return Err(error::simulation_error(message));
+        }
+        let value = simulation
+            .results
+            .first()
+            .ok_or_else(|| ClientError::Protocol("simulation returned no result".to_string()))?;
+        let result = T::from_scval(&ScVal::from_xdr_base64(&value.xdr, Limits::none())?)?;
+
+        Ok(Simulation {
+            result,
+            transaction: transaction::assemble(transaction, &simulation)?,
+            latest_ledger: simulation.latest_ledger,
+        })
+    }
+
+    /// Simulate, sign with `key` and submit the call, then wait for the
+    /// result. Authorization from addresses other than the signer must be
+    /// given separately.
+    pub fn submit(&self, key: &SigningKey) -> Result<T, ClientError> {
+        let client = self.client;
+        let simulation = self.simulate(&key.verifying_key().to_bytes())?;
+        let envelope = transaction::sign(simulation.transaction, &client.network_passphrase, key)?;
+
+        let (_, meta) = client.send(&envelope)?;
+        T::from_scval(&return_value(&meta)?).map_err(ClientError::from)
+    }
+}
+
+fn return_value(meta: &str) -> Result<ScVal, ClientError> {
+    match TransactionMeta::from_xdr_base64(meta, Limits::none())? {
+        TransactionMeta::V3(meta) => meta
+            .soroban_meta
+            .map(|soroban| soroban.return_value)
+            .ok_or_else(|| {
+                ClientError::Protocol("transaction meta has no return value".to_string())
+            }),
+        _ => Err(ClientError::Protocol(
+            "expected soroban transaction meta".to_string(),
+        )),
+    }
+}
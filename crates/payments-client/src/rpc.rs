@@ -0,0 +1,168 @@
+//! Minimal blocking Soroban RPC client covering the methods the contract
+//! client needs.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use stellar_xdr::curr::{
+    AccountId, LedgerEntryData, LedgerKey, LedgerKeyAccount, Limits, PublicKey, ReadXdr,
+    TransactionEnvelope, Uint256, WriteXdr,
+};
+
+use crate::error::ClientError;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateResult {
+    #[serde(default)]
+    pub auth: Vec<String>,
+    pub xdr: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateTransactionResponse {
+    #[serde(default)]
+    pub transaction_data: String,
+    #[serde(default, deserialize_with = "string_number")]
+    pub min_resource_fee: i64,
+    #[serde(default)]
+    pub results: Vec<SimulateResult>,
+    pub latest_ledger: u32,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendTransactionResponse {
+    pub status: String,
+    pub hash: String,
+    pub error_result_xdr: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTransactionResponse {
+    pub status: String,
+    pub result_meta_xdr: Option<String>,
+    pub ledger: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct LedgerEntryResult {
+    xdr: String,
+}
+
+#[derive(Deserialize)]
+struct GetLedgerEntriesResponse {
+    #[serde(default)]
+    entries: Vec<LedgerEntryResult>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+// RPC encodes 64-bit fees as strings
+fn string_number<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    let value = Value::deserialize(deserializer)?;
+    match &value {
+        Value::String(s) => s.parse().map_err(serde::de::Error::custom),
+        Value::Number(n) => n
+            .as_i64()
+            .ok_or_else(|| serde::de::Error::custom("fee out of range")),
+        _ => Err(serde::de::Error::custom("expected a number")),
+    }
+}
+
+pub struct RpcClient {
+    url: String,
+    agent: ureq::Agent,
+    next_id: AtomicU64,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> RpcClient {
+        RpcClient {
+            url: url.to_string(),
+            agent: ureq::Agent::new(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, ClientError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let body = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let response: RpcResponse<T> = self
+            .agent
+            .post(&self.url)
+            .send_json(body)
+            .map_err(|e| ClientError::Http(e.to_string()))?
+            .into_json()
+            .map_err(|e| ClientError::Http(e.to_string()))?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(ClientError::Rpc {
+                code: error.code,
+                message: error.message,
+            }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(ClientError::Protocol(format!(
+                "{method} returned no result"
+            ))),
+        }
+    }
+
+    /// Current sequence number of an account
+    pub fn get_account_sequence(&self, account: &[u8; 32]) -> Result<i64, ClientError> {
+        let key = LedgerKey::Account(LedgerKeyAccount {
+            account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(*account))),
+        });
+        let response: GetLedgerEntriesResponse = self.request(
+            "getLedgerEntries",
+            json!({ "keys": [key.to_xdr_base64(Limits::none())?] }),
+        )?;
+        let entry = response.entries.first().ok_or_else(|| {
+            ClientError::AccountNotFound(stellar_strkey::ed25519::PublicKey(*account).to_string())
+        })?;
+        match LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none())? {
+            LedgerEntryData::Account(account) => Ok(account.seq_num.0),
+            _ => Err(ClientError::Protocol(
+                "expected an account entry".to_string(),
+            )),
+        }
+    }
+
+    pub fn simulate_transaction(
+        &self,
+        envelope: &TransactionEnvelope,
+    ) -> Result<SimulateTransactionResponse, ClientError> {
+        self.request(
+            "simulateTransaction",
+            json!({ "transaction": envelope.to_xdr_base64(Limits::none())? }),
+        )
+    }
+
+    pub fn send_transaction(
+        &self,
+        envelope: &TransactionEnvelope,
+    ) -> Result<SendTransactionResponse, ClientError> {
+        self.request(
+            "sendTransaction",
+            json!({ "transaction": envelope.to_xdr_base64(Limits::none())? }),
+        )
+    }
+
+    pub fn get_transaction(&self, hash: &str) -> Result<GetTransactionResponse, ClientError> {
+        self.request("getTransaction", json!({ "hash": hash }))
+    }
+}
@@ -0,0 +1,555 @@
+use std::{collections::BTreeMap, fmt};
+
+use stellar_xdr::curr::{
+    Int128Parts, ScBytes, ScMap, ScMapEntry, ScSpecTypeBytesN, ScSpecTypeDef, ScSpecTypeMap,
+    ScSpecTypeOption, ScSpecTypeTuple, ScSpecTypeVec, ScString, ScSymbol, ScVal, ScVec,
+};
+
+/// An `ScVal` did not have the shape the contract interface promises.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodeError {
+    pub expected: &'static str,
+    pub found: String,
+}
+
+impl DecodeError {
+    pub(crate) fn new(expected: &'static str, found: &ScVal) -> DecodeError {
+        DecodeError {
+            expected,
+            found: format!("{found:?}"),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A Rust type with a fixed `ScVal` encoding, matching how the contract's
+/// `#[contracttype]` values are laid out on the ledger.
+pub trait ScType: Sized {
+    /// Type of the value in the contract spec
+    fn spec() -> ScSpecTypeDef;
+
+    fn to_scval(&self) -> ScVal;
+
+    fn from_scval(value: &ScVal) -> Result<Self, DecodeError>;
+}
+
+impl ScType for () {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::Tuple(Box::new(ScSpecTypeTuple {
+            value_types: Default::default(),
+        }))
+    }
+
+    fn to_scval(&self) -> ScVal {
+        ScVal::Void
+    }
+
+    fn from_scval(value: &ScVal) -> Result<(), DecodeError> {
+        match value {
+            ScVal::Void => Ok(()),
+            other => Err(DecodeError::new("void", other)),
+        }
+    }
+}
+
+impl ScType for bool {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::Bool
+    }
+
+    fn to_scval(&self) -> ScVal {
+        ScVal::Bool(*self)
+    }
+
+    fn from_scval(value: &ScVal) -> Result<bool, DecodeError> {
+        match value {
+            ScVal::Bool(v) => Ok(*v),
+            other => Err(DecodeError::new("bool", other)),
+        }
+    }
+}
+
+impl ScType for u32 {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::U32
+    }
+
+    fn to_scval(&self) -> ScVal {
+        ScVal::U32(*self)
+    }
+
+    fn from_scval(value: &ScVal) -> Result<u32, DecodeError> {
+        match value {
+            ScVal::U32(v) => Ok(*v),
+            other => Err(DecodeError::new("u32", other)),
+        }
+    }
+}
+
+impl ScType for u64 {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::U64
+    }
+
+    fn to_scval(&self) -> ScVal {
+        ScVal::U64(*self)
+    }
+
+    fn from_scval(value: &ScVal) -> Result<u64, DecodeError> {
+        match value {
+            ScVal::U64(v) => Ok(*v),
+            other => Err(DecodeError::new("u64", other)),
+        }
+    }
+}
+
+impl ScType for i128 {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::I128
+    }
+
+    fn to_scval(&self) -> ScVal {
+        ScVal::I128(Int128Parts {
+            hi: (*self >> 64) as i64,
+            lo: *self as u64,
+        })
+    }
+
+    fn from_scval(value: &ScVal) -> Result<i128, DecodeError> {
+        match value {
+            ScVal::I128(parts) => Ok(((parts.hi as i128) << 64) | parts.lo as i128),
+            other => Err(DecodeError::new("i128", other)),
+        }
+    }
+}
+
+impl ScType for String {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::String
+    }
+
+    fn to_scval(&self) -> ScVal {
+        ScVal::String(ScString(
+            self.as_str()
+                .try_into()
+                .expect("string exceeds the XDR length limit"),
+        ))
+    }
+
+    fn from_scval(value: &ScVal) -> Result<String, DecodeError> {
+        match value {
+            ScVal::String(s) => {
+                s.0.to_utf8_string()
+                    .map_err(|_| DecodeError::new("UTF-8 string", value))
+            }
+            other => Err(DecodeError::new("string", other)),
+        }
+    }
+}
+
+/// A contract `Symbol`, such as the status of a processor payment record
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(pub String);
+
+impl ScType for Symbol {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::Symbol
+    }
+
+    fn to_scval(&self) -> ScVal {
+        symbol(&self.0)
+    }
+
+    fn from_scval(value: &ScVal) -> Result<Symbol, DecodeError> {
+        match value {
+            ScVal::Symbol(s) => s
+                .0
+                .to_utf8_string()
+                .map(Symbol)
+                .map_err(|_| DecodeError::new("UTF-8 symbol", value)),
+            other => Err(DecodeError::new("symbol", other)),
+        }
+    }
+}
+
+impl<const N: usize> ScType for [u8; N] {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::BytesN(ScSpecTypeBytesN { n: N as u32 })
+    }
+
+    fn to_scval(&self) -> ScVal {
+        ScVal::Bytes(ScBytes(
+            self.to_vec()
+                .try_into()
+                .expect("fixed size bytes fit in XDR"),
+        ))
+    }
+
+    fn from_scval(value: &ScVal) -> Result<[u8; N], DecodeError> {
+        match value {
+            ScVal::Bytes(bytes) => bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| DecodeError::new("fixed length bytes", value)),
+            other => Err(DecodeError::new("bytes", other)),
+        }
+    }
+}
+
+// `None` is encoded as void, `Some` as the bare value
+impl<T: ScType> ScType for Option<T> {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+            value_type: Box::new(T::spec()),
+        }))
+    }
+
+    fn to_scval(&self) -> ScVal {
+        match self {
+            Some(value) => value.to_scval(),
+            None => ScVal::Void,
+        }
+    }
+
+    fn from_scval(value: &ScVal) -> Result<Option<T>, DecodeError> {
+        match value {
+            ScVal::Void => Ok(None),
+            other => T::from_scval(other).map(Some),
+        }
+    }
+}
+
+impl<T: ScType> ScType for Vec<T> {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+            element_type: Box::new(T::spec()),
+        }))
+    }
+
+    fn to_scval(&self) -> ScVal {
+        vec_scval(self.iter().map(ScType::to_scval).collect())
+    }
+
+    fn from_scval(value: &ScVal) -> Result<Vec<T>, DecodeError> {
+        match value {
+            ScVal::Vec(Some(items)) => items.iter().map(T::from_scval).collect(),
+            other => Err(DecodeError::new("vec", other)),
+        }
+    }
+}
+
+impl<K: ScType + Ord, V: ScType> ScType for BTreeMap<K, V> {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::Map(Box::new(ScSpecTypeMap {
+            key_type: Box::new(K::spec()),
+            value_type: Box::new(V::spec()),
+        }))
+    }
+
+    fn to_scval(&self) -> ScVal {
+        map_scval(
+            self.iter()
+                .map(|(k, v)| (k.to_scval(), v.to_scval()))
+                .collect(),
+        )
+    }
+
+    fn from_scval(value: &ScVal) -> Result<BTreeMap<K, V>, DecodeError> {
+        match value {
+            ScVal::Map(Some(entries)) => entries
+                .iter()
+                .map(|entry| Ok((K::from_scval(&entry.key)?, V::from_scval(&entry.val)?)))
+                .collect(),
+            other => Err(DecodeError::new("map", other)),
+        }
+    }
+}
+
+pub(crate) fn symbol(name: &str) -> ScVal {
+    ScVal::Symbol(ScSymbol(
+        name.try_into().expect("symbols are at most 32 characters"),
+    ))
+}
+
+pub(crate) fn vec_scval(items: Vec<ScVal>) -> ScVal {
+    ScVal::Vec(Some(ScVec(items.try_into().expect("vec fits in XDR"))))
+}
+
+// Maps must be sorted by key; the host rejects unsorted maps
+pub(crate) fn map_scval(mut entries: Vec<(ScVal, ScVal)>) -> ScVal {
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    let entries: Vec<ScMapEntry> = entries
+        .into_iter()
+        .map(|(key, val)| ScMapEntry { key, val })
+        .collect();
+    ScVal::Map(Some(ScMap(entries.try_into().expect("map fits in XDR"))))
+}
+
+pub(crate) fn struct_field<'a>(value: &'a ScVal, name: &str) -> Result<&'a ScVal, DecodeError> {
+    let ScVal::Map(Some(entries)) = value else {
+        return Err(DecodeError::new("struct", value));
+    };
+    entries
+        .iter()
+        .find(|entry| matches!(&entry.key, ScVal::Symbol(s) if s.0.as_slice() == name.as_bytes()))
+        .map(|entry| &entry.val)
+        .ok_or_else(|| DecodeError::new("struct field", value))
+}
+
+// Enum values are a vec holding the variant name followed by its fields
+pub(crate) fn enum_variant(value: &ScVal) -> Result<(String, &[ScVal]), DecodeError> {
+    match value {
+        ScVal::Vec(Some(items)) => match items.split_first() {
+            Some((ScVal::Symbol(name), fields)) => name
+                .0
+                .to_utf8_string()
+                .map(|name| (name, fields))
+                .map_err(|_| DecodeError::new("enum variant", value)),
+            _ => Err(DecodeError::new("enum variant", value)),
+        },
+        other => Err(DecodeError::new("enum", other)),
+    }
+}
+
+/// Declare a client-side mirror of a `#[contracttype]` struct. Fields are
+/// encoded as a map keyed by field name, like the SDK does.
+macro_rules! contract_struct {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident {
+            $( $(#[$field_attr:meta])* pub $field:ident : $ty:ty, )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct $name {
+            $( $(#[$field_attr])* pub $field: $ty, )*
+        }
+
+        impl $crate::scval::ScType for $name {
+            fn spec() -> stellar_xdr::curr::ScSpecTypeDef {
+                $crate::scval::udt_spec(stringify!($name))
+            }
+
+            fn to_scval(&self) -> stellar_xdr::curr::ScVal {
+                $crate::scval::map_scval(vec![
+                    $( ($crate::scval::symbol(stringify!($field)), self.$field.to_scval()), )*
+                ])
+            }
+
+            fn from_scval(
+                value: &stellar_xdr::curr::ScVal,
+            ) -> Result<$name, $crate::scval::DecodeError> {
+                Ok($name {
+                    $( $field: <$ty>::from_scval($crate::scval::struct_field(value, stringify!($field))?)?, )*
+                })
+            }
+        }
+
+        impl $crate::scval::Udt for $name {
+            fn spec_entry() -> stellar_xdr::curr::ScSpecEntry {
+                $crate::scval::struct_spec(
+                    stringify!($name),
+                    vec![ $( (stringify!($field), <$ty as $crate::scval::ScType>::spec()), )* ],
+                )
+            }
+        }
+    };
+}
+
+/// Declare a client-side mirror of a `#[contracttype]` enum whose variants
+/// are unit-like or wrap a single value.
+macro_rules! contract_enum {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $( $(#[$variant_attr:meta])* $variant:ident $( ($inner:ty) )?, )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum $name {
+            $( $(#[$variant_attr])* $variant $( ($inner) )?, )*
+        }
+
+        impl $crate::scval::ScType for $name {
+            fn spec() -> stellar_xdr::curr::ScSpecTypeDef {
+                $crate::scval::udt_spec(stringify!($name))
+            }
+
+            fn to_scval(&self) -> stellar_xdr::curr::ScVal {
+                let mut items = vec![$crate::scval::symbol(self.variant_name())];
+                match self {
+                    $( $name::$variant { .. } => {
+                        items.extend($crate::scval::enum_fields!(self, $name::$variant $( ($inner) )?));
+                    } )*
+                }
+                $crate::scval::vec_scval(items)
+            }
+
+            fn from_scval(
+                value: &stellar_xdr::curr::ScVal,
+            ) -> Result<$name, $crate::scval::DecodeError> {
+                let (variant, fields) = $crate::scval::enum_variant(value)?;
+                match variant.as_str() {
+                    $( stringify!($variant) => {
+                        $crate::scval::enum_decode!(value, fields, $name::$variant $( ($inner) )?)
+                    } )*
+                    _ => Err($crate::scval::DecodeError::new(stringify!($name), value)),
+                }
+            }
+        }
+
+        impl $name {
+            fn variant_name(&self) -> &'static str {
+                match self {
+                    $( $name::$variant { .. } => stringify!($variant), )*
+                }
+            }
+        }
+
+        impl $crate::scval::Udt for $name {
+            fn spec_entry() -> stellar_xdr::curr::ScSpecEntry {
+                $crate::scval::union_spec(
+                    stringify!($name),
+                    vec![ $( (stringify!($variant), vec![ $( <$inner as $crate::scval::ScType>::spec() )? ]), )* ],
+                )
+            }
+        }
+    };
+}
+
+macro_rules! enum_fields {
+    ($value:expr, $name:ident :: $variant:ident) => {
+        Vec::<stellar_xdr::curr::ScVal>::new()
+    };
+    ($value:expr, $name:ident :: $variant:ident ($inner:ty)) => {
+        match $value {
+            $name::$variant(inner) => vec![inner.to_scval()],
+            _ => unreachable!(),
+        }
+    };
+}
+
+macro_rules! enum_decode {
+    ($value:expr, $fields:expr, $name:ident :: $variant:ident) => {
+        match $fields {
+            [] => Ok($name::$variant),
+            _ => Err($crate::scval::DecodeError::new(
+                stringify!($variant),
+                $value,
+            )),
+        }
+    };
+    ($value:expr, $fields:expr, $name:ident :: $variant:ident ($inner:ty)) => {
+        match $fields {
+            [inner] => Ok($name::$variant(<$inner>::from_scval(inner)?)),
+            _ => Err($crate::scval::DecodeError::new(
+                stringify!($variant),
+                $value,
+            )),
+        }
+    };
+}
+
+pub(crate) use {contract_enum, contract_struct, enum_decode, enum_fields};
+
+/// A user-defined type declared in the contract spec.
+pub trait Udt {
+    /// The type's entry in the contract spec, without doc comments
+    fn spec_entry() -> stellar_xdr::curr::ScSpecEntry;
+}
+
+pub(crate) fn udt_spec(name: &str) -> ScSpecTypeDef {
+    ScSpecTypeDef::Udt(stellar_xdr::curr::ScSpecTypeUdt {
+        name: name.try_into().expect("type names fit in the spec"),
+    })
+}
+
+pub(crate) fn struct_spec(
+    name: &str,
+    fields: Vec<(&str, ScSpecTypeDef)>,
+) -> stellar_xdr::curr::ScSpecEntry {
+    use stellar_xdr::curr::{ScSpecEntry, ScSpecUdtStructFieldV0, ScSpecUdtStructV0};
+
+    let fields: Vec<ScSpecUdtStructFieldV0> = fields
+        .into_iter()
+        .map(|(field, type_)| ScSpecUdtStructFieldV0 {
+            doc: Default::default(),
+            name: field.try_into().expect("field names fit in the spec"),
+            type_,
+        })
+        .collect();
+    ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+        doc: Default::default(),
+        lib: Default::default(),
+        name: name.try_into().expect("type names fit in the spec"),
+        fields: fields.try_into().expect("struct fields fit in the spec"),
+    })
+}
+
+pub(crate) fn union_spec(
+    name: &str,
+    cases: Vec<(&str, Vec<ScSpecTypeDef>)>,
+) -> stellar_xdr::curr::ScSpecEntry {
+    use stellar_xdr::curr::{
+        ScSpecEntry, ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0,
+        ScSpecUdtUnionV0,
+    };
+
+    let cases: Vec<ScSpecUdtUnionCaseV0> = cases
+        .into_iter()
+        .map(|(case, types)| {
+            let name = case.try_into().expect("case names fit in the spec");
+            if types.is_empty() {
+                ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                    doc: Default::default(),
+                    name,
+                })
+            } else {
+                ScSpecUdtUnionCaseV0::TupleV0(ScSpecUdtUnionCaseTupleV0 {
+                    doc: Default::default(),
+                    name,
+                    type_: types.try_into().expect("case fields fit in the spec"),
+                })
+            }
+        })
+        .collect();
+    ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+        doc: Default::default(),
+        lib: Default::default(),
+        name: name.try_into().expect("type names fit in the spec"),
+        cases: cases.try_into().expect("union cases fit in the spec"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_i128_round_trip() {
+        for value in [0, 1, -1, i64::MAX as i128 + 1, i128::MIN, i128::MAX] {
+            assert_eq!(i128::from_scval(&value.to_scval()), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_decode_mismatch() {
+        assert_eq!(
+            u64::from_scval(&ScVal::U32(1)),
+            Err(DecodeError {
+                expected: "u64",
+                found: "U32(1)".into()
+            })
+        );
+        assert_eq!(Option::<u32>::from_scval(&ScVal::Void), Ok(None));
+    }
+}
@@ -0,0 +1,126 @@
+//! Building, assembling and signing `InvokeHostFunction` transactions.
+
+use ed25519_dalek::{Signer, SigningKey};
+use sha2::{Digest, Sha256};
+use stellar_xdr::curr::{
+    DecoratedSignature, Hash, HostFunction, InvokeHostFunctionOp, Limits, Memo, MuxedAccount,
+    Operation, OperationBody, Preconditions, ReadXdr, SequenceNumber, SignatureHint,
+    SorobanAuthorizationEntry, SorobanTransactionData, Transaction, TransactionEnvelope,
+    TransactionExt, TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
+    TransactionV1Envelope, Uint256, VecM, WriteXdr,
+};
+
+use crate::{error::ClientError, rpc::SimulateTransactionResponse};
+
+/// Inclusion fee offered on top of the resource fee, in stroops
+pub const BASE_FEE: u32 = 100;
+
+/// A transaction with a single `InvokeHostFunction` operation and no
+/// resources attached, as sent to `simulateTransaction`.
+pub fn invoke_transaction(source: &[u8; 32], sequence: i64, function: HostFunction) -> Transaction {
+    let operation = Operation {
+        source_account: None,
+        body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+            host_function: function,
+            auth: VecM::default(),
+        }),
+    };
+    Transaction {
+        source_account: MuxedAccount::Ed25519(Uint256(*source)),
+        fee: BASE_FEE,
+        seq_num: SequenceNumber(sequence),
+        cond: Preconditions::None,
+        memo: Memo::None,
+        operations: vec![operation].try_into().expect("one operation fits"),
+        ext: TransactionExt::V0,
+    }
+}
+
+/// Attach the footprint, resource fee and authorizations returned by a
+/// simulation. Authorizations recorded for the source account are signed by
+/// the envelope signature; other signers must sign their entries separately.
+pub fn assemble(
+    mut transaction: Transaction,
+    simulation: &SimulateTransactionResponse,
+) -> Result<Transaction, ClientError> {
+    let data =
+        SorobanTransactionData::from_xdr_base64(&simulation.transaction_data, Limits::none())?;
+    let auth = simulation
+        .results
+        .first()
+        .map(|result| {
+            result
+                .auth
+                .iter()
+                .map(|entry| SorobanAuthorizationEntry::from_xdr_base64(entry, Limits::none()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    let operation = transaction
+        .operations
+        .first()
+        .cloned()
+        .ok_or_else(|| ClientError::Protocol("transaction has no operation".to_string()))?;
+    let OperationBody::InvokeHostFunction(mut invoke) = operation.body else {
+        return Err(ClientError::Protocol(
+            "expected an InvokeHostFunction operation".to_string(),
+        ));
+    };
+    invoke.auth = auth.try_into()?;
+
+    let fee = u32::try_from(simulation.min_resource_fee)
+        .ok()
+        .and_then(|fee| fee.checked_add(BASE_FEE))
+        .ok_or_else(|| ClientError::Protocol("resource fee out of range".to_string()))?;
+
+    transaction.operations = vec![Operation {
+        source_account: operation.source_account,
+        body: OperationBody::InvokeHostFunction(invoke),
+    }]
+    .try_into()?;
+    transaction.fee = fee;
+    transaction.ext = TransactionExt::V1(data);
+    Ok(transaction)
+}
+
+pub fn network_id(network_passphrase: &str) -> Hash {
+    Hash(Sha256::digest(network_passphrase.as_bytes()).into())
+}
+
+/// Hash the network signs, also used as the transaction ID
+pub fn hash(transaction: &Transaction, network_passphrase: &str) -> Result<[u8; 32], ClientError> {
+    let payload = TransactionSignaturePayload {
+        network_id: network_id(network_passphrase),
+        tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(transaction.clone()),
+    };
+    Ok(Sha256::digest(payload.to_xdr(Limits::none())?).into())
+}
+
+pub fn unsigned(transaction: Transaction) -> TransactionEnvelope {
+    TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx: transaction,
+        signatures: VecM::default(),
+    })
+}
+
+pub fn sign(
+    transaction: Transaction,
+    network_passphrase: &str,
+    key: &SigningKey,
+) -> Result<TransactionEnvelope, ClientError> {
+    let signature = key.sign(&hash(&transaction, network_passphrase)?);
+    let public_key = key.verifying_key().to_bytes();
+    let hint: [u8; 4] = public_key[28..]
+        .try_into()
+        .expect("hint is the last four bytes");
+    let signature = DecoratedSignature {
+        hint: SignatureHint(hint),
+        signature: signature.to_bytes().to_vec().try_into()?,
+    };
+    Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+        tx: transaction,
+        signatures: vec![signature].try_into()?,
+    }))
+}
@@ -0,0 +1,421 @@
+//! Client-side mirrors of the contract's `#[contracttype]` values.
+
+use std::{fmt, str::FromStr};
+
+use stellar_strkey::{ed25519, Contract, Strkey};
+use stellar_xdr::curr::{AccountId, Hash, PublicKey, ScAddress, ScSpecTypeDef, ScVal, Uint256};
+
+use crate::scval::{contract_enum, contract_struct, DecodeError, ScType, Symbol};
+
+/// An account (`G...`) or contract (`C...`) address.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Address {
+    Account([u8; 32]),
+    Contract([u8; 32]),
+}
+
+impl Address {
+    pub fn to_sc_address(&self) -> ScAddress {
+        match self {
+            Address::Account(key) => {
+                ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(*key))))
+            }
+            Address::Contract(id) => ScAddress::Contract(Hash(*id)),
+        }
+    }
+
+    pub fn from_sc_address(address: &ScAddress) -> Address {
+        match address {
+            ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key)))) => {
+                Address::Account(*key)
+            }
+            ScAddress::Contract(Hash(id)) => Address::Contract(*id),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Account(key) => write!(f, "{}", ed25519::PublicKey(*key)),
+            Address::Contract(id) => write!(f, "{}", Contract(*id)),
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = stellar_strkey::DecodeError;
+
+    fn from_str(s: &str) -> Result<Address, Self::Err> {
+        match Strkey::from_string(s)? {
+            Strkey::PublicKeyEd25519(key) => Ok(Address::Account(key.0)),
+            Strkey::Contract(id) => Ok(Address::Contract(id.0)),
+            _ => Err(stellar_strkey::DecodeError::Invalid),
+        }
+    }
+}
+
+impl ScType for Address {
+    fn spec() -> ScSpecTypeDef {
+        ScSpecTypeDef::Address
+    }
+
+    fn to_scval(&self) -> ScVal {
+        ScVal::Address(self.to_sc_address())
+    }
+
+    fn from_scval(value: &ScVal) -> Result<Address, DecodeError> {
+        match value {
+            ScVal::Address(address) => Ok(Address::from_sc_address(address)),
+            other => Err(DecodeError::new("address", other)),
+        }
+    }
+}
+
+contract_struct! {
+    pub struct PaymentRequest {
+        pub id: u64,
+        pub amount: i128,
+        pub business_name: String,
+        pub description: String,
+        pub denomination: String,
+        pub authorized_addresses: Vec<Address>,
+        pub requester: Address,
+        pub timestamp: u64,
+        pub status: PaymentStatus,
+        pub fee_percentage: u32, // Basis points (100 = 1%)
+        pub amount_paid: i128,
+        pub kind: RequestKind,
+        pub tip_amount: i128,
+        pub splits: Vec<SplitShare>,
+    }
+}
+
+contract_enum! {
+    pub enum RequestKind {
+        Fixed,
+        OpenAmount(AmountBounds),
+        Tippable,
+        FiatPriced,
+    }
+}
+
+contract_struct! {
+    pub struct AmountBounds {
+        pub min_amount: Option<i128>,
+        pub max_amount: Option<i128>,
+    }
+}
+
+contract_enum! {
+    pub enum FeeBasis {
+        BaseOnly,
+        Total,
+    }
+}
+
+contract_enum! {
+    pub enum PaymentStatus {
+        Pending,
+        Authorized,
+        Completed,
+        Failed,
+        Cancelled,
+        PartiallyPaid,
+        Refunded,
+    }
+}
+
+contract_struct! {
+    pub struct PaymentHistory {
+        pub total_payments: u64,
+        pub total_amount: i128,
+        pub last_payment_id: u64,
+    }
+}
+
+contract_struct! {
+    pub struct BusinessConfig {
+        pub name: String,
+        pub owner: Address,
+        pub fee_recipient: Address,
+        pub default_fee_percentage: u32,
+        pub is_active: bool,
+        pub fee_basis: FeeBasis,
+    }
+}
+
+contract_struct! {
+    pub struct SplitShare {
+        pub recipient: Address,
+        pub share_basis_points: u32,
+    }
+}
+
+contract_struct! {
+    pub struct LineItem {
+        pub sku: String,
+        pub quantity: u32,
+        pub unit_price: i128,
+    }
+}
+
+contract_struct! {
+    pub struct Invoice {
+        pub line_items: Vec<LineItem>,
+        pub tax_basis_points: u32,
+        pub discount: i128,
+        pub due_date: u64,
+        pub merchant_reference: String,
+    }
+}
+
+contract_struct! {
+    pub struct InvoiceTotals {
+        pub subtotal: i128,
+        pub discount: i128,
+        pub tax: i128,
+        pub total: i128,
+    }
+}
+
+contract_enum! {
+    pub enum ComplianceProvider {
+        Disabled,
+        OnContract,
+        External(Address),
+    }
+}
+
+contract_struct! {
+    pub struct ComplianceConfig {
+        pub provider: ComplianceProvider,
+        pub require_allowlist: bool,
+    }
+}
+
+contract_struct! {
+    pub struct BlockedPayment {
+        pub payer: Address,
+        pub blocked_address: Address,
+        pub ledger: u32,
+        pub timestamp: u64,
+    }
+}
+
+contract_enum! {
+    pub enum DisputeStatus {
+        Open,
+        Responded,
+        Resolved(DisputeOutcome),
+    }
+}
+
+contract_enum! {
+    pub enum DisputeOutcome {
+        Refund,
+        PartialRefund(i128),
+        Rejected,
+    }
+}
+
+contract_struct! {
+    pub struct DisputeConfig {
+        pub arbiter: Address,
+        pub response_period: u64,
+        pub ruling_period: u64,
+        pub hold_period: u64,
+    }
+}
+
+contract_struct! {
+    pub struct Dispute {
+        pub payment_id: u64,
+        pub payer: Address,
+        pub disputed_amount: i128,
+        pub payer_evidence: [u8; 32],
+        pub merchant_evidence: [u8; 32],
+        pub opened_at: u64,
+        pub response_deadline: u64,
+        pub ruling_deadline: u64,
+        pub status: DisputeStatus,
+        pub refund_amount: i128,
+        pub refund_paid: i128,
+        pub escrowed_amount: i128,
+    }
+}
+
+contract_struct! {
+    pub struct PaymentIntent {
+        pub payment_id: u64,
+        pub payer: Address,
+        pub token_address: Address,
+        pub amount: i128,
+        pub nonce: u64,
+        pub expiry: u64,
+    }
+}
+
+contract_struct! {
+    pub struct SpendingLimits {
+        pub max_payment_amount: Option<i128>,
+        pub payer_daily_limit: Option<i128>,
+        pub payer_weekly_limit: Option<i128>,
+        pub business_daily_limit: Option<i128>,
+        pub business_weekly_limit: Option<i128>,
+    }
+}
+
+contract_struct! {
+    pub struct OracleConfig {
+        pub oracle: Address,
+        pub denomination: String,
+        pub max_age: u64,
+        pub max_deviation_bps: u32,
+    }
+}
+
+contract_struct! {
+    pub struct FiatQuote {
+        pub fiat_amount: i128,
+        pub token_address: Address,
+        pub price: i128,
+        pub price_timestamp: u64,
+        pub token_amount: i128,
+    }
+}
+
+contract_struct! {
+    pub struct SettlementConfig {
+        pub enabled: bool,
+        pub payout_address: Address,
+        pub interval: u64,
+        pub threshold: i128,
+    }
+}
+
+contract_struct! {
+    pub struct SettlementRecord {
+        pub id: u64,
+        pub business_name: String,
+        pub token_address: Address,
+        pub payout_address: Address,
+        pub amount: i128,
+        pub payment_ids: Vec<u64>,
+        pub timestamp: u64,
+    }
+}
+
+contract_enum! {
+    pub enum StreamStatus {
+        Active,
+        Cancelled,
+        Depleted,
+    }
+}
+
+contract_struct! {
+    pub struct Stream {
+        pub id: u64,
+        pub sender: Address,
+        pub recipient: Address,
+        pub token_address: Address,
+        pub deposit: i128,
+        pub withdrawn: i128,
+        pub start_time: u64,
+        pub cliff_time: u64,
+        pub end_time: u64,
+        pub status: StreamStatus,
+    }
+}
+
+contract_enum! {
+    pub enum RampKind {
+        Deposit,
+        Withdrawal,
+        Send,
+    }
+}
+
+contract_enum! {
+    pub enum RampStatus {
+        Pending,
+        Completed,
+        Failed,
+    }
+}
+
+contract_struct! {
+    pub struct RampRecord {
+        pub kind: RampKind,
+        pub anchor: String,
+        pub anchor_id: String,
+        pub token_address: Address,
+        pub amount: i128,
+        pub status: RampStatus,
+        pub stellar_transaction_id: String,
+        pub recorded_by: Address,
+        pub updated_at: u64,
+    }
+}
+
+// Records of the payment processor contract, for reading its payments
+// alongside payment requests
+
+contract_struct! {
+    pub struct PaymentDetails {
+        pub amount: i128,
+        pub sender: Address,
+        pub recipient: Address,
+        pub token_address: Address,
+        pub business_name: String,
+        pub customer_name: String,
+        pub order_id: String,
+    }
+}
+
+contract_struct! {
+    pub struct PaymentRecord {
+        pub payment_id: u64,
+        pub details: PaymentDetails,
+        pub timestamp: u64,
+        pub status: Symbol,
+    }
+}
+
+/// Spec entries of every payment request contract type the client mirrors.
+pub fn type_specs() -> Vec<stellar_xdr::curr::ScSpecEntry> {
+    use crate::scval::Udt;
+
+    vec![
+        PaymentRequest::spec_entry(),
+        RequestKind::spec_entry(),
+        AmountBounds::spec_entry(),
+        FeeBasis::spec_entry(),
+        PaymentStatus::spec_entry(),
+        PaymentHistory::spec_entry(),
+        BusinessConfig::spec_entry(),
+        SplitShare::spec_entry(),
+        LineItem::spec_entry(),
+        Invoice::spec_entry(),
+        InvoiceTotals::spec_entry(),
+        ComplianceProvider::spec_entry(),
+        ComplianceConfig::spec_entry(),
+        BlockedPayment::spec_entry(),
+        DisputeStatus::spec_entry(),
+        DisputeOutcome::spec_entry(),
+        DisputeConfig::spec_entry(),
+        Dispute::spec_entry(),
+        PaymentIntent::spec_entry(),
+        SpendingLimits::spec_entry(),
+        OracleConfig::spec_entry(),
+        FiatQuote::spec_entry(),
+        SettlementConfig::spec_entry(),
+        SettlementRecord::spec_entry(),
+        StreamStatus::spec_entry(),
+        Stream::spec_entry(),
+        RampKind::spec_entry(),
+        RampStatus::spec_entry(),
+        RampRecord::spec_entry(),
+    ]
+}
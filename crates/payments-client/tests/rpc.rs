@@ -0,0 +1,500 @@
+//! Drive the client against a mock RPC server that executes invocations on
+//! the real contract in a sandbox `Env`.
+
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use ed25519_dalek::{Signature, SigningKey, Verifier, VerifyingKey};
+use payment_requests::{PaymentContract, PaymentContractClient};
+use payments_client::{transaction, types::*, ClientError, ContractError, PaymentsClient, ScType};
+use serde_json::{json, Value};
+use soroban_sdk::{
+    testutils::Address as _, token::StellarAssetClient, Env, IntoVal, Symbol, TryFromVal, Val,
+};
+use stellar_xdr::curr::{
+    AccountEntry, AccountEntryExt, AccountId, ExtensionPoint, HostFunction, LedgerEntryChanges,
+    LedgerEntryData, LedgerFootprint, Limits, OperationBody, PublicKey, ReadXdr, ScVal,
+    SequenceNumber, SorobanResources, SorobanTransactionData, SorobanTransactionMeta,
+    SorobanTransactionMetaExt, Thresholds, TransactionEnvelope, TransactionMeta, TransactionMetaV3,
+    Uint256, WriteXdr,
+};
+
+const PASSPHRASE: &str = "Test SDF Network ; September 2015";
+const CONTRACT_ID: [u8; 32] = [7; 32];
+const RESOURCE_FEE: i64 = 5_000;
+
+struct Fixture {
+    owner: Address,
+    payer: Address,
+    token: Address,
+}
+
+#[derive(Default)]
+struct Recorded {
+    methods: Vec<String>,
+    submitted: Vec<TransactionEnvelope>,
+}
+
+struct MockRpc {
+    url: String,
+    fixture: Fixture,
+    recorded: Arc<Mutex<Recorded>>,
+}
+
+fn address(env: &Env, address: &soroban_sdk::Address) -> Address {
+    let val: Val = address.into_val(env);
+    Address::from_scval(&ScVal::try_from_val(env, &val).unwrap()).unwrap()
+}
+
+fn contract_address(env: &Env) -> soroban_sdk::Address {
+    let val = Val::try_from_val(env, &Address::Contract(CONTRACT_ID).to_scval()).unwrap();
+    soroban_sdk::Address::try_from_val(env, &val).unwrap()
+}
+
+// Simulations run on a throwaway copy of the ledger
+fn fork(env: &Env, seed: u8) -> Env {
+    let fork = Env::from_snapshot(env.to_snapshot());
+    fork.host().set_base_prng_seed([seed; 32]).unwrap();
+    fork.mock_all_auths();
+    fork.register_contract(Some(&contract_address(&fork)), PaymentContract);
+    fork
+}
+
+// Run the invocation, rendering failures the way RPC does
+fn invoke(env: &Env, function: &HostFunction) -> Result<ScVal, String> {
+    let HostFunction::InvokeContract(call) = function else {
+        return Err("unsupported host function".to_string());
+    };
+    let contract = soroban_sdk::Address::try_from_val(
+        env,
+        &Val::try_from_val(env, &ScVal::Address(call.contract_address.clone())).unwrap(),
+    )
+    .unwrap();
+    let mut args = soroban_sdk::Vec::<Val>::new(env);
+    for arg in call.args.iter() {
+        args.push_back(Val::try_from_val(env, arg).unwrap());
+    }
+    let function = Symbol::new(env, &call.function_name.0.to_utf8_string().unwrap());
+
+    match env.try_invoke_contract::<Val, soroban_sdk::Error>(&contract, &function, args) {
+        Ok(Ok(value)) => Ok(ScVal::try_from_val(env, &value).unwrap()),
+        Err(Ok(error)) => Err(format!("HostError: {error:?}\n\nEvent log (newest first):")),
+        other => Err(format!("HostError: {other:?}")),
+    }
+}
+
+fn host_function(envelope: &TransactionEnvelope) -> HostFunction {
+    let TransactionEnvelope::Tx(envelope) = envelope else {
+        panic!("expected a v1 envelope");
+    };
+    match &envelope.tx.operations[0].body {
+        OperationBody::InvokeHostFunction(op) => op.host_function.clone(),
+        other => panic!("unexpected operation {other:?}"),
+    }
+}
+
+fn account_entry(key: [u8; 32]) -> String {
+    let entry = AccountEntry {
+        account_id: AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(key))),
+        balance: 100_000_000,
+        seq_num: SequenceNumber(41),
+        num_sub_entries: 0,
+        inflation_dest: None,
+        flags: 0,
+        home_domain: Default::default(),
+        thresholds: Thresholds([1, 0, 0, 0]),
+        signers: Default::default(),
+        ext: AccountEntryExt::V0,
+    };
+    LedgerEntryData::Account(entry)
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+}
+
+fn transaction_data() -> String {
+    SorobanTransactionData {
+        ext: ExtensionPoint::V0,
+        resources: SorobanResources {
+            footprint: LedgerFootprint {
+                read_only: Default::default(),
+                read_write: Default::default(),
+            },
+            instructions: 1_000_000,
+            read_bytes: 1_000,
+            write_bytes: 1_000,
+        },
+        resource_fee: RESOURCE_FEE,
+    }
+    .to_xdr_base64(Limits::none())
+    .unwrap()
+}
+
+fn result_meta(value: ScVal) -> String {
+    TransactionMeta::V3(TransactionMetaV3 {
+        ext: ExtensionPoint::V0,
+        tx_changes_before: LedgerEntryChanges(Default::default()),
+        operations: Default::default(),
+        tx_changes_after: LedgerEntryChanges(Default::default()),
+        soroban_meta: Some(SorobanTransactionMeta {
+            ext: SorobanTransactionMetaExt::V0,
+            events: Default::default(),
+            return_value: value,
+            diagnostic_events: Default::default(),
+        }),
+    })
+    .to_xdr_base64(Limits::none())
+    .unwrap()
+}
+
+struct Server {
+    env: Env,
+    account: [u8; 32],
+    forks: u8,
+    // Transaction hash to its result and how often it was polled
+    transactions: HashMap<String, (Result<ScVal, String>, u32)>,
+}
+
+impl Server {
+    fn handle(&mut self, method: &str, params: &Value) -> Value {
+        match method {
+            "getLedgerEntries" => {
+                let key = stellar_xdr::curr::LedgerKey::from_xdr_base64(
+                    params["keys"][0].as_str().unwrap(),
+                    Limits::none(),
+                )
+                .unwrap();
+                let known = matches!(
+                    &key,
+                    stellar_xdr::curr::LedgerKey::Account(account)
+                        if account.account_id.0 == PublicKey::PublicKeyTypeEd25519(Uint256(self.account))
+                );
+                let entries: Vec<Value> = if known {
+                    vec![json!({ "xdr": account_entry(self.account) })]
+                } else {
+                    vec![]
+                };
+                json!({ "result": { "entries": entries, "latestLedger": 100 } })
+            }
+            "simulateTransaction" => {
+                let envelope = TransactionEnvelope::from_xdr_base64(
+                    params["transaction"].as_str().unwrap(),
+                    Limits::none(),
+                )
+                .unwrap();
+                self.forks += 1;
+                let env = fork(&self.env, self.forks);
+                let result = match invoke(&env, &host_function(&envelope)) {
+                    Ok(value) => json!({
+                        "transactionData": transaction_data(),
+                        "minResourceFee": RESOURCE_FEE.to_string(),
+                        "results": [{ "auth": [], "xdr": value.to_xdr_base64(Limits::none()).unwrap() }],
+                        "latestLedger": 100,
+                    }),
+                    Err(error) => json!({ "error": error, "latestLedger": 100 }),
+                };
+                json!({ "result": result })
+            }
+            "sendTransaction" => {
+                let envelope = TransactionEnvelope::from_xdr_base64(
+                    params["transaction"].as_str().unwrap(),
+                    Limits::none(),
+                )
+                .unwrap();
+                let TransactionEnvelope::Tx(signed) = &envelope else {
+                    unreachable!()
+                };
+                let hash = transaction::hash(&signed.tx, PASSPHRASE).unwrap();
+                let signature = Signature::from_slice(&signed.signatures[0].signature).unwrap();
+                if VerifyingKey::from_bytes(&self.account)
+                    .unwrap()
+                    .verify(&hash, &signature)
+                    .is_err()
+                {
+                    return json!({ "error": { "code": -32602, "message": "bad signature" } });
+                }
+                let hash: String = hash.iter().map(|b| format!("{b:02x}")).collect();
+                let result = invoke(&self.env, &host_function(&envelope));
+                self.transactions.insert(hash.clone(), (result, 0));
+                json!({ "result": { "status": "PENDING", "hash": hash, "latestLedger": 100 } })
+            }
+            "getTransaction" => {
+                let (result, polls) = self
+                    .transactions
+                    .get_mut(params["hash"].as_str().unwrap())
+                    .unwrap();
+                *polls += 1;
+                // The first poll happens before the transaction is included
+                let response = match (*polls, result) {
+                    (1, _) => json!({ "status": "NOT_FOUND", "latestLedger": 100 }),
+                    (_, Ok(value)) => json!({
+                        "status": "SUCCESS",
+                        "ledger": 101,
+                        "resultMetaXdr": result_meta(value.clone()),
+                    }),
+                    (_, Err(_)) => json!({ "status": "FAILED", "ledger": 101 }),
+                };
+                json!({ "result": response })
+            }
+            other => {
+                json!({ "error": { "code": -32601, "message": format!("unknown method {other}") } })
+            }
+        }
+    }
+}
+
+fn spawn(account: [u8; 32]) -> MockRpc {
+    let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+    let recorded = Arc::new(Mutex::new(Recorded::default()));
+    let (fixture_tx, fixture_rx) = mpsc::channel();
+
+    let log = recorded.clone();
+    thread::spawn(move || {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract = contract_address(&env);
+        env.register_contract(Some(&contract), PaymentContract);
+        let client = PaymentContractClient::new(&env, &contract);
+
+        let owner = soroban_sdk::Address::generate(&env);
+        let payer = soroban_sdk::Address::generate(&env);
+        let token = env
+            .register_stellar_asset_contract_v2(owner.clone())
+            .address();
+        StellarAssetClient::new(&env, &token).mint(&payer, &10_000);
+        client.initialize(&owner, &100, &token);
+        client.register_business(
+            &soroban_sdk::String::from_str(&env, "acme"),
+            &owner,
+            &owner,
+            &250,
+        );
+        fixture_tx
+            .send(Fixture {
+                owner: address(&env, &owner),
+                payer: address(&env, &payer),
+                token: address(&env, &token),
+            })
+            .unwrap();
+
+        let mut state = Server {
+            env,
+            account,
+            forks: 0,
+            transactions: HashMap::new(),
+        };
+        for mut request in server.incoming_requests() {
+            let mut body = String::new();
+            request.as_reader().read_to_string(&mut body).unwrap();
+            let body: Value = serde_json::from_str(&body).unwrap();
+            let method = body["method"].as_str().unwrap();
+            log.lock().unwrap().methods.push(method.to_string());
+            if method == "sendTransaction" {
+                let envelope = TransactionEnvelope::from_xdr_base64(
+                    body["params"]["transaction"].as_str().unwrap(),
+                    Limits::none(),
+                )
+                .unwrap();
+                log.lock().unwrap().submitted.push(envelope);
+            }
+
+            let mut response = state.handle(method, &body["params"]);
+            response["jsonrpc"] = json!("2.0");
+            response["id"] = body["id"].clone();
+            let header = tiny_http::Header::from_bytes("Content-Type", "application/json").unwrap();
+            request
+                .respond(tiny_http::Response::from_string(response.to_string()).with_header(header))
+                .unwrap();
+        }
+    });
+
+    MockRpc {
+        url,
+        fixture: fixture_rx.recv().unwrap(),
+        recorded,
+    }
+}
+
+fn signer() -> SigningKey {
+    SigningKey::from_bytes(&[1; 32])
+}
+
+fn client(rpc: &MockRpc) -> PaymentsClient {
+    PaymentsClient::new(&rpc.url, Address::Contract(CONTRACT_ID), PASSPHRASE)
+        .with_polling(Duration::from_millis(1), 5)
+}
+
+#[test]
+fn test_simulate_decodes_contract_values() {
+    let key = signer();
+    let source = key.verifying_key().to_bytes();
+    let rpc = spawn(source);
+    let client = client(&rpc);
+    let Fixture { owner, payer, .. } = &rpc.fixture;
+
+    let business = client
+        .get_business_config(&"acme".to_string())
+        .simulate(&source)
+        .unwrap();
+    assert_eq!(
+        business.result,
+        BusinessConfig {
+            name: "acme".into(),
+            owner: *owner,
+            fee_recipient: *owner,
+            default_fee_percentage: 250,
+            is_active: true,
+            fee_basis: FeeBasis::Total,
+        }
+    );
+
+    // Simulated writes return the value without changing the ledger
+    let created = client
+        .create_open_payment_request(
+            &"acme".to_string(),
+            &"Donation".to_string(),
+            &"USDC".to_string(),
+            &vec![*payer],
+            owner,
+            &None,
+            &AmountBounds {
+                min_amount: Some(5),
+                max_amount: None,
+            },
+        )
+        .simulate(&source)
+        .unwrap();
+    assert_eq!(created.result, 1);
+    assert_eq!(created.latest_ledger, 100);
+    assert_eq!(
+        created.transaction.fee,
+        transaction::BASE_FEE + RESOURCE_FEE as u32
+    );
+    assert_eq!(created.transaction.seq_num.0, 42);
+
+    let history = client.get_payment_history(payer).simulate(&source).unwrap();
+    assert_eq!(
+        history.result,
+        PaymentHistory {
+            total_payments: 0,
+            total_amount: 0,
+            last_payment_id: 0,
+        }
+    );
+    assert!(client
+        .get_payment_contributions(&1)
+        .simulate(&source)
+        .unwrap()
+        .result
+        .is_empty());
+}
+
+#[test]
+fn test_contract_errors_are_mapped() {
+    let key = signer();
+    let source = key.verifying_key().to_bytes();
+    let rpc = spawn(source);
+    let client = client(&rpc);
+
+    let error = client
+        .get_payment_request(&99)
+        .simulate(&source)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ClientError::Contract(ContractError::PaymentNotFound)
+    ));
+
+    let error = client
+        .register_business(
+            &"acme".to_string(),
+            &rpc.fixture.owner,
+            &rpc.fixture.owner,
+            &20_000,
+        )
+        .submit(&key)
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ClientError::Contract(ContractError::InvalidFeePercentage)
+    ));
+    assert!(rpc.recorded.lock().unwrap().submitted.is_empty());
+
+    // Unknown source accounts cannot build a transaction
+    let error = client.get_swap_adapter().simulate(&[9; 32]).unwrap_err();
+    assert!(matches!(error, ClientError::AccountNotFound(_)));
+}
+
+#[test]
+fn test_submit_signs_and_waits_for_result() {
+    let key = signer();
+    let source = key.verifying_key().to_bytes();
+    let rpc = spawn(source);
+    let client = client(&rpc);
+    let Fixture {
+        owner,
+        payer,
+        token,
+    } = &rpc.fixture;
+
+    let payment_id = client
+        .create_payment_request(
+            &1_000,
+            &"acme".to_string(),
+            &"Order 17".to_string(),
+            &"USDC".to_string(),
+            &vec![*payer],
+            owner,
+            &None,
+        )
+        .submit(&key)
+        .unwrap();
+    assert_eq!(payment_id, 1);
+
+    client
+        .execute_payment(&payment_id, payer, token)
+        .submit(&key)
+        .unwrap();
+
+    let request = client
+        .get_payment_request(&payment_id)
+        .simulate(&source)
+        .unwrap()
+        .result;
+    assert_eq!(request.status, PaymentStatus::Completed);
+    assert_eq!(request.amount_paid, 1_000);
+    assert_eq!(request.kind, RequestKind::Fixed);
+    assert_eq!(request.authorized_addresses, vec![*payer]);
+    assert_eq!(
+        client
+            .get_payment_contributions(&payment_id)
+            .simulate(&source)
+            .unwrap()
+            .result
+            .get(payer),
+        Some(&1_000)
+    );
+
+    let recorded = rpc.recorded.lock().unwrap();
+    assert_eq!(recorded.submitted.len(), 2);
+    let TransactionEnvelope::Tx(envelope) = &recorded.submitted[0] else {
+        unreachable!()
+    };
+    assert!(matches!(
+        envelope.tx.ext,
+        stellar_xdr::curr::TransactionExt::V1(_)
+    ));
+    // Each submission was polled until it was found
+    assert_eq!(
+        recorded
+            .methods
+            .iter()
+            .filter(|method| *method == "getTransaction")
+            .count(),
+        4
+    );
+}
@@ -0,0 +1,213 @@
+//! The client's wrappers and types must match the contract interface.
+
+use payment_requests::PaymentContract;
+use payments_client::{function_specs, type_specs};
+use stellar_xdr::curr::{Limits, ReadXdr, ScSpecEntry, ScSpecUdtUnionCaseV0};
+
+// Docs and the library name do not affect the encoding; struct fields are
+// compared by name
+fn normalize(entry: ScSpecEntry) -> ScSpecEntry {
+    match entry {
+        ScSpecEntry::FunctionV0(mut function) => {
+            function.doc = Default::default();
+            let mut inputs = function.inputs.to_vec();
+            for input in &mut inputs {
+                input.doc = Default::default();
+            }
+            function.inputs = inputs.try_into().unwrap();
+            ScSpecEntry::FunctionV0(function)
+        }
+        ScSpecEntry::UdtStructV0(mut udt) => {
+            udt.doc = Default::default();
+            udt.lib = Default::default();
+            let mut fields = udt.fields.to_vec();
+            for field in &mut fields {
+                field.doc = Default::default();
+            }
+            fields.sort_by(|a, b| a.name.cmp(&b.name));
+            udt.fields = fields.try_into().unwrap();
+            ScSpecEntry::UdtStructV0(udt)
+        }
+        ScSpecEntry::UdtUnionV0(mut udt) => {
+            udt.doc = Default::default();
+            udt.lib = Default::default();
+            let mut cases = udt.cases.to_vec();
+            for case in &mut cases {
+                match case {
+                    ScSpecUdtUnionCaseV0::VoidV0(case) => case.doc = Default::default(),
+                    ScSpecUdtUnionCaseV0::TupleV0(case) => case.doc = Default::default(),
+                }
+            }
+            udt.cases = cases.try_into().unwrap();
+            ScSpecEntry::UdtUnionV0(udt)
+        }
+        other => other,
+    }
+}
+
+fn decode(xdr: &[u8]) -> ScSpecEntry {
+    normalize(ScSpecEntry::from_xdr(xdr, Limits::none()).unwrap())
+}
+
+fn name(entry: &ScSpecEntry) -> String {
+    match entry {
+        ScSpecEntry::FunctionV0(function) => function.name.to_utf8_string().unwrap(),
+        ScSpecEntry::UdtStructV0(udt) => udt.name.to_utf8_string().unwrap(),
+        ScSpecEntry::UdtUnionV0(udt) => udt.name.to_utf8_string().unwrap(),
+        other => panic!("unexpected spec entry {other:?}"),
+    }
+}
+
+fn assert_matches(client: Vec<ScSpecEntry>, contract: Vec<ScSpecEntry>) {
+    assert_eq!(client.len(), contract.len());
+    for expected in contract {
+        let wrapped = client
+            .iter()
+            .find(|entry| name(entry) == name(&expected))
+            .unwrap_or_else(|| panic!("client is missing {}", name(&expected)));
+        assert_eq!(
+            normalize(wrapped.clone()),
+            expected,
+            "{} differs",
+            name(&expected)
+        );
+    }
+}
+
+#[test]
+fn test_entrypoints_match_contract() {
+    let contract = [
+        &PaymentContract::spec_xdr_initialize()[..],
+        &PaymentContract::spec_xdr_register_business(),
+        &PaymentContract::spec_xdr_update_business_status(),
+        &PaymentContract::spec_xdr_set_fee_basis(),
+        &PaymentContract::spec_xdr_get_business_config(),
+        &PaymentContract::spec_xdr_create_payment_request(),
+        &PaymentContract::spec_xdr_create_open_payment_request(),
+        &PaymentContract::spec_xdr_create_tippable_request(),
+        &PaymentContract::spec_xdr_create_invoice_request(),
+        &PaymentContract::spec_xdr_create_fiat_payment_request(),
+        &PaymentContract::spec_xdr_cancel_payment_request(),
+        &PaymentContract::spec_xdr_set_payment_splits(),
+        &PaymentContract::spec_xdr_get_payment_request(),
+        &PaymentContract::spec_xdr_get_payment_by_merchant_ref(),
+        &PaymentContract::spec_xdr_get_invoice(),
+        &PaymentContract::spec_xdr_get_invoice_totals(),
+        &PaymentContract::spec_xdr_get_fiat_quote(),
+        &PaymentContract::spec_xdr_get_payment_contributions(),
+        &PaymentContract::spec_xdr_get_payment_history(),
+        &PaymentContract::spec_xdr_execute_payment(),
+        &PaymentContract::spec_xdr_execute_partial_payment(),
+        &PaymentContract::spec_xdr_execute_open_payment(),
+        &PaymentContract::spec_xdr_execute_payment_with_tip(),
+        &PaymentContract::spec_xdr_execute_xlm_payment(),
+        &PaymentContract::spec_xdr_execute_swap_payment(),
+        &PaymentContract::spec_xdr_set_swap_adapter(),
+        &PaymentContract::spec_xdr_get_swap_adapter(),
+        &PaymentContract::spec_xdr_set_intent_key(),
+        &PaymentContract::spec_xdr_get_intent_key(),
+        &PaymentContract::spec_xdr_get_intent_nonce(),
+        &PaymentContract::spec_xdr_execute_payment_with_signature(),
+        &PaymentContract::spec_xdr_refund_payment(),
+        &PaymentContract::spec_xdr_get_payment_refunds(),
+        &PaymentContract::spec_xdr_set_dispute_config(),
+        &PaymentContract::spec_xdr_get_dispute_config(),
+        &PaymentContract::spec_xdr_open_dispute(),
+        &PaymentContract::spec_xdr_respond_to_dispute(),
+        &PaymentContract::spec_xdr_resolve_dispute(),
+        &PaymentContract::spec_xdr_finalize_dispute(),
+        &PaymentContract::spec_xdr_pay_dispute_refund(),
+        &PaymentContract::spec_xdr_get_dispute(),
+        &PaymentContract::spec_xdr_set_spending_limits(),
+        &PaymentContract::spec_xdr_set_business_limits(),
+        &PaymentContract::spec_xdr_set_limit_exemption(),
+        &PaymentContract::spec_xdr_get_spending_limits(),
+        &PaymentContract::spec_xdr_get_payer_volume(),
+        &PaymentContract::spec_xdr_get_business_volume(),
+        &PaymentContract::spec_xdr_set_compliance_config(),
+        &PaymentContract::spec_xdr_get_compliance_config(),
+        &PaymentContract::spec_xdr_set_denylisted(),
+        &PaymentContract::spec_xdr_set_allowlisted(),
+        &PaymentContract::spec_xdr_check_compliance(),
+        &PaymentContract::spec_xdr_get_blocked_payment(),
+        &PaymentContract::spec_xdr_set_oracle_config(),
+        &PaymentContract::spec_xdr_get_oracle_config(),
+        &PaymentContract::spec_xdr_set_settlement_config(),
+        &PaymentContract::spec_xdr_get_settlement_config(),
+        &PaymentContract::spec_xdr_get_merchant_balance(),
+        &PaymentContract::spec_xdr_get_held_proceeds(),
+        &PaymentContract::spec_xdr_release_proceeds(),
+        &PaymentContract::spec_xdr_get_unsettled_payments(),
+        &PaymentContract::spec_xdr_settle(),
+        &PaymentContract::spec_xdr_get_settlement(),
+        &PaymentContract::spec_xdr_create_stream(),
+        &PaymentContract::spec_xdr_withdraw_from_stream(),
+        &PaymentContract::spec_xdr_cancel_stream(),
+        &PaymentContract::spec_xdr_get_stream(),
+        &PaymentContract::spec_xdr_get_stream_balance(),
+        &PaymentContract::spec_xdr_record_ramp(),
+        &PaymentContract::spec_xdr_get_ramps(),
+    ];
+    assert_matches(
+        function_specs(),
+        contract.iter().map(|xdr| decode(xdr)).collect(),
+    );
+}
+
+#[test]
+fn test_types_match_contract() {
+    use payment_core::*;
+
+    let contract = [
+        &PaymentRequest::spec_xdr()[..],
+        &RequestKind::spec_xdr(),
+        &AmountBounds::spec_xdr(),
+        &FeeBasis::spec_xdr(),
+        &PaymentStatus::spec_xdr(),
+        &PaymentHistory::spec_xdr(),
+        &payment_requests::BusinessConfig::spec_xdr(),
+        &SplitShare::spec_xdr(),
+        &LineItem::spec_xdr(),
+        &Invoice::spec_xdr(),
+        &InvoiceTotals::spec_xdr(),
+        &ComplianceProvider::spec_xdr(),
+        &ComplianceConfig::spec_xdr(),
+        &BlockedPayment::spec_xdr(),
+        &DisputeStatus::spec_xdr(),
+        &DisputeOutcome::spec_xdr(),
+        &DisputeConfig::spec_xdr(),
+        &Dispute::spec_xdr(),
+        &PaymentIntent::spec_xdr(),
+        &SpendingLimits::spec_xdr(),
+        &OracleConfig::spec_xdr(),
+        &FiatQuote::spec_xdr(),
+        &SettlementConfig::spec_xdr(),
+        &SettlementRecord::spec_xdr(),
+        &StreamStatus::spec_xdr(),
+        &Stream::spec_xdr(),
+        &RampKind::spec_xdr(),
+        &RampStatus::spec_xdr(),
+        &RampRecord::spec_xdr(),
+    ];
+    assert_matches(
+        type_specs(),
+        contract.iter().map(|xdr| decode(xdr)).collect(),
+    );
+}
+
+#[test]
+fn test_processor_records_match_contract() {
+    use payments_client::{types, Udt};
+
+    let contract = [
+        &payment_core::PaymentDetails::spec_xdr()[..],
+        &payment_core::PaymentRecord::spec_xdr(),
+    ];
+    assert_matches(
+        vec![
+            types::PaymentDetails::spec_entry(),
+            types::PaymentRecord::spec_entry(),
+        ],
+        contract.iter().map(|xdr| decode(xdr)).collect(),
+    );
+}